@@ -0,0 +1,164 @@
+//! Storage trait.
+//!
+//! `where` and `rm` reach the inventory through [`Storage`] rather than the
+//! `db` functions, so they do not depend on SQLite. It is a first step:
+//! every other command still calls `db` on an SQLite connection, and SQLite
+//! is the only implementation.
+
+use anyhow::Result;
+use rusqlite::Connection;
+use std::path::Path;
+
+use crate::db;
+use crate::model::Item;
+
+/// The item operations a backend provides.
+///
+/// Names and paths follow the same rules as the `db` functions: a reference
+/// is a name, a `/`-separated path or `#ID`, and `\/` escapes a slash inside
+/// a name.
+///
+/// The trait covers only what `invy where` and `invy rm` need; it grows as
+/// more commands move onto it.
+pub trait Storage {
+    /// The item a reference (name, path or `#ID`) points to. Errors if a
+    /// name is ambiguous.
+    fn resolve_item(&self, reference: &str) -> Result<Option<Item>>;
+
+    /// The item at a `/`-separated path.
+    fn item_at_path(&self, path: &str) -> Result<Option<Item>>;
+
+    /// Every item with exactly this name, wherever it is.
+    fn items_named(&self, name: &str) -> Result<Vec<Item>>;
+
+    /// Names from the root down to the item, inclusive.
+    fn item_path(&self, id: i64) -> Result<Vec<String>>;
+
+    /// Items directly inside a container, or at the root for `None`.
+    fn children(&self, container_id: Option<i64>) -> Result<Vec<Item>>;
+
+    /// Delete an item. Its contents move to the root, with the new location
+    /// recorded.
+    fn delete_item(&self, id: i64) -> Result<()>;
+
+    /// Note that an item was looked up (see `invy optimize`), unless the
+    /// store cannot be written right now.
+    fn record_lookup(&self, id: i64) -> Result<()>;
+
+    /// How many times an item was looked up, and when it last was.
+    fn lookups(&self, id: i64) -> Result<(i64, Option<String>)>;
+
+    /// Run `f` in a write transaction, committed if it returns `Ok`.
+    fn transaction(&self, f: &mut dyn FnMut(&dyn Storage) -> Result<()>) -> Result<()>;
+}
+
+/// Open the SQLite database at `path` (default location when `None`).
+pub fn open(path: Option<&Path>) -> Result<Box<dyn Storage>> {
+    Ok(Box::new(db::open(path)?))
+}
+
+/// Run `f` in a write transaction on `store` and return its value.
+pub fn with_tx<T>(store: &dyn Storage, f: impl FnOnce(&dyn Storage) -> Result<T>) -> Result<T> {
+    let mut f = Some(f);
+    let mut value = None;
+    store.transaction(&mut |tx| {
+        if let Some(f) = f.take() {
+            value = Some(f(tx)?);
+        }
+        Ok(())
+    })?;
+    Ok(value.expect("transaction ran its closure"))
+}
+
+impl Storage for Connection {
+    fn resolve_item(&self, reference: &str) -> Result<Option<Item>> {
+        db::resolve_item(self, reference)
+    }
+
+    fn item_at_path(&self, path: &str) -> Result<Option<Item>> {
+        db::get_item_by_path(self, path)
+    }
+
+    fn items_named(&self, name: &str) -> Result<Vec<Item>> {
+        db::find_items_by_exact_name(self, name)
+    }
+
+    fn item_path(&self, id: i64) -> Result<Vec<String>> {
+        db::get_item_path(self, id)
+    }
+
+    fn children(&self, container_id: Option<i64>) -> Result<Vec<Item>> {
+        match container_id {
+            Some(id) => db::list_items_in_container(self, id),
+            None => db::list_root_items(self),
+        }
+    }
+
+    fn delete_item(&self, id: i64) -> Result<()> {
+        let children = db::list_items_in_container(self, id)?;
+        // ON DELETE SET NULL orphans the children to the root
+        db::delete_item(self, id)?;
+        for child in &children {
+            db::record_location(self, child.id, None)?;
+        }
+        Ok(())
+    }
+
+    fn record_lookup(&self, id: i64) -> Result<()> {
+        db::record_lookup(self, id)
+    }
+
+    fn lookups(&self, id: i64) -> Result<(i64, Option<String>)> {
+        db::lookup_stats(self, id)
+    }
+
+    fn transaction(&self, f: &mut dyn FnMut(&dyn Storage) -> Result<()>) -> Result<()> {
+        db::with_tx(self, |tx| f(&**tx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn test_store() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let conn = db::open(Some(&dir.path().join("invy.db"))).expect("open");
+        (dir, conn)
+    }
+
+    #[test]
+    fn sqlite_resolves_paths_and_children() {
+        let (_dir, conn) = test_store();
+        let garage = db::insert_item(&conn, "garage", None, None).unwrap();
+        let hammer = db::insert_item(&conn, "hammer", Some("16oz"), Some(garage.id)).unwrap();
+        let store: &dyn Storage = &conn;
+
+        assert_eq!(store.item_path(hammer.id).unwrap(), ["garage", "hammer"]);
+        assert_eq!(
+            store.item_at_path("garage/hammer").unwrap().map(|i| i.id),
+            Some(hammer.id)
+        );
+        assert_eq!(store.children(None).unwrap().len(), 1);
+
+        store.delete_item(garage.id).unwrap();
+        let roots = store.children(None).unwrap();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].id, hammer.id);
+    }
+
+    #[test]
+    fn transaction_rolls_back_on_error() {
+        let (_dir, conn) = test_store();
+        db::insert_item(&conn, "hammer", None, None).unwrap();
+        let store: &dyn Storage = &conn;
+        let result: Result<()> = with_tx(store, |tx| {
+            let hammer = tx.resolve_item("hammer")?.expect("hammer");
+            tx.delete_item(hammer.id)?;
+            Err(anyhow!("boom"))
+        });
+        assert!(result.is_err());
+        assert_eq!(store.items_named("hammer").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,88 @@
+//! Keyword rules that pick a container for a new item (the `[classify]`
+//! config table).
+//!
+//! See SPEC.md#invy-add-name
+
+use std::collections::HashMap;
+
+/// A rule that matched: the keyword found and the container it files into.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Suggestion<'a> {
+    pub keyword: &'a str,
+    pub container: &'a str,
+}
+
+/// The rule whose keyword appears in `text`, ignoring case.
+///
+/// A keyword matches at the start of a word, so `batter` matches
+/// "AA batteries" but `tery` does not. When several keywords match, the
+/// longest wins (ties go to the one first in alphabetical order), so
+/// `usb cable` beats `cable`.
+pub fn suggest<'a>(rules: &'a HashMap<String, String>, text: &str) -> Option<Suggestion<'a>> {
+    let text = text.to_lowercase();
+    rules
+        .iter()
+        .filter(|(keyword, container)| {
+            let keyword = keyword.trim().to_lowercase();
+            !keyword.is_empty() && !container.trim().is_empty() && starts_word(&text, &keyword)
+        })
+        .max_by(|(a, _), (b, _)| {
+            a.trim()
+                .chars()
+                .count()
+                .cmp(&b.trim().chars().count())
+                .then_with(|| b.cmp(a))
+        })
+        .map(|(keyword, container)| Suggestion { keyword, container })
+}
+
+/// Whether `needle` occurs in `text` where a word begins.
+fn starts_word(text: &str, needle: &str) -> bool {
+    text.match_indices(needle).any(|(index, _)| {
+        text[..index]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn matches_at_word_starts() {
+        let rules = rules(&[("batter", "electronics-bin")]);
+        let hit = suggest(&rules, "AA Batteries").unwrap();
+        assert_eq!(hit.container, "electronics-bin");
+        assert_eq!(hit.keyword, "batter");
+        assert!(suggest(&rules, "flatterbatter").is_none());
+        assert!(suggest(&rules, "pancake mix (batter)").is_some());
+    }
+
+    #[test]
+    fn longest_keyword_wins() {
+        let rules = rules(&[
+            ("cable", "cables"),
+            ("usb cable", "electronics-bin"),
+            ("usb", "usb-box"),
+        ]);
+        let hit = suggest(&rules, "usb cable, 2m").unwrap();
+        assert_eq!(hit.container, "electronics-bin");
+        assert_eq!(suggest(&rules, "hdmi cable").unwrap().container, "cables");
+    }
+
+    #[test]
+    fn ties_and_empty_rules() {
+        let rules = rules(&[("case", "shelf"), ("bits", "toolbox"), ("", "x")]);
+        assert_eq!(suggest(&rules, "bits case").unwrap().keyword, "bits");
+        assert!(suggest(&rules, "hammer").is_none());
+    }
+}
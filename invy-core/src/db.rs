@@ -0,0 +1,4033 @@
+//! Database layer for invy.
+//!
+//! Provides SQLite connection management, migrations, and CRUD operations.
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{
+    params, Connection, DatabaseName, ErrorCode, OptionalExtension, Transaction,
+    TransactionBehavior,
+};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::fuzzy;
+use crate::model::{
+    ConditionKind, Consumption, ContainerStats, ExportRow, Item, ItemKind, Location, NewItem,
+    Share, Stats, StorageConditions, SyncItem, Todo, ViewInfo, ViewStatus,
+};
+use crate::notice;
+use crate::quantity::Quantity;
+use crate::strict;
+use crate::timespec;
+
+/// Get the default database path.
+///
+/// `~/.invy.db` on Unix; `%LOCALAPPDATA%\invy\invy.db` on Windows, where
+/// dotfiles in the profile directory are unusual and may be roamed.
+pub fn default_db_path() -> Result<PathBuf> {
+    let dirs = directories::BaseDirs::new()
+        .ok_or_else(|| anyhow!("Could not determine home directory"))?;
+
+    if cfg!(windows) {
+        Ok(dirs.data_local_dir().join("invy").join("invy.db"))
+    } else {
+        Ok(dirs.home_dir().join(".invy.db"))
+    }
+}
+
+/// Resolve the database path, creating the default location's directory.
+pub fn resolve_path(path: Option<&Path>) -> Result<PathBuf> {
+    match path {
+        Some(p) => Ok(p.to_path_buf()),
+        None => {
+            let p = default_db_path()?;
+            if let Some(dir) = p.parent() {
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create directory {:?}", dir))?;
+            }
+            Ok(p)
+        }
+    }
+}
+
+/// Open a database connection, creating and migrating if necessary.
+pub fn open(path: Option<&Path>) -> Result<Connection> {
+    let conn = connect(path)?;
+    init(&conn)?;
+    Ok(conn)
+}
+
+/// Open a database connection without running migrations.
+///
+/// Used by `invy db` so the schema can be inspected and migrated explicitly.
+pub fn open_unmigrated(path: Option<&Path>) -> Result<Connection> {
+    let conn = connect(path)?;
+    configure(&conn)?;
+    Ok(conn)
+}
+
+/// Open the database file, unlocking it if encrypted.
+fn connect(path: Option<&Path>) -> Result<Connection> {
+    let db_path = resolve_path(path)?;
+
+    let conn = Connection::open(&db_path)
+        .with_context(|| format!("Failed to open database at {:?}", db_path))?;
+
+    #[cfg(feature = "encryption")]
+    crate::encryption::unlock(&conn)?;
+
+    Ok(conn)
+}
+
+/// Create a new database at `path`, optionally encrypted.
+///
+/// Fails if a database already exists there.
+pub fn create(path: &Path, encrypted: bool) -> Result<Connection> {
+    if path.exists() {
+        return Err(anyhow!("database already exists at {}", path.display()));
+    }
+    if encrypted && !cfg!(feature = "encryption") {
+        return Err(anyhow!(
+            "this build has no encryption support (rebuild with --features encryption)"
+        ));
+    }
+
+    let conn = Connection::open(path)
+        .with_context(|| format!("Failed to create database at {:?}", path))?;
+
+    #[cfg(feature = "encryption")]
+    if encrypted {
+        crate::encryption::key_new(&conn)?;
+    }
+
+    init(&conn)?;
+    Ok(conn)
+}
+
+/// Prepare a freshly opened connection for use.
+///
+/// Shared by `open` and the in-memory connections used in unit tests.
+fn init(conn: &Connection) -> Result<()> {
+    configure(conn)?;
+    retry_on_busy(|| migrate_to(conn, LATEST_SCHEMA_VERSION))
+}
+
+/// How long SQLite waits on a locked database before reporting busy.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Attempts made by `retry_on_busy` before giving up.
+const BUSY_RETRIES: u32 = 5;
+
+/// Apply per-connection settings for safe concurrent use.
+///
+/// WAL lets readers proceed while another process writes, the busy timeout
+/// makes writers queue instead of failing with `database is locked`, and
+/// foreign keys are off by default in SQLite, which would silently disable
+/// `ON DELETE SET NULL` orphaning.
+fn configure(conn: &Connection) -> Result<()> {
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    retry_on_busy(|| {
+        // In-memory databases report "memory" and ignore the request
+        let _: String =
+            conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))?;
+        Ok(())
+    })
+    .context("Failed to configure database")
+}
+
+/// Retry an operation that failed because the database was busy or locked.
+///
+/// The busy timeout covers ordinary lock waits, but SQLite reports busy
+/// immediately in some cases (e.g. switching journal mode or upgrading a
+/// read transaction), so those are retried with exponential backoff.
+pub fn retry_on_busy<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut delay = Duration::from_millis(50);
+    for _ in 1..BUSY_RETRIES {
+        match op() {
+            Err(err) if is_busy(&err) => {
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    op()
+}
+
+fn is_busy(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>(),
+            Some(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+        )
+    })
+}
+
+/// Schema migrations, applied in order. Migration `i` brings the schema to
+/// version `i + 1`, recorded in `PRAGMA user_version`.
+///
+/// Databases created before versioning report version 0 but already have the
+/// `items` table, so the statements of migration 1 must be safe to re-run.
+const MIGRATIONS: &[&str] = &[
+    // 1: items and their indexes
+    r#"
+    CREATE TABLE IF NOT EXISTS items (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        description TEXT,
+        container_id INTEGER REFERENCES items(id) ON DELETE SET NULL,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_items_name ON items(name);
+    CREATE INDEX IF NOT EXISTS idx_items_container ON items(container_id);
+    CREATE UNIQUE INDEX IF NOT EXISTS idx_items_name_container
+        ON items(name, COALESCE(container_id, 0));
+    "#,
+    // 2: settings and container content hashes
+    r#"
+    CREATE TABLE IF NOT EXISTS settings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS container_hashes (
+        item_id INTEGER PRIMARY KEY REFERENCES items(id) ON DELETE CASCADE,
+        hash TEXT NOT NULL,
+        recorded_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    "#,
+    // 3: item quantities and the consumption log
+    r#"
+    ALTER TABLE items ADD COLUMN quantity INTEGER NOT NULL DEFAULT 1;
+
+    CREATE TABLE consumptions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id INTEGER REFERENCES items(id) ON DELETE SET NULL,
+        name TEXT NOT NULL,
+        path TEXT NOT NULL,
+        quantity INTEGER NOT NULL,
+        consumed_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+
+    CREATE INDEX idx_consumptions_consumed_at ON consumptions(consumed_at);
+    "#,
+    // 4: location history, seeded with each item's current container
+    r#"
+    CREATE TABLE item_locations (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+        container_id INTEGER REFERENCES items(id) ON DELETE SET NULL,
+        path TEXT NOT NULL,
+        moved_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+
+    CREATE INDEX idx_item_locations_item ON item_locations(item_id);
+
+    WITH RECURSIVE chain(item_id, container_id, ancestor_id, path) AS (
+        SELECT id, container_id, container_id, '' FROM items
+        UNION ALL
+        SELECT chain.item_id, chain.container_id, parent.container_id,
+               parent.name || CASE WHEN chain.path = '' THEN '' ELSE '/' || chain.path END
+        FROM chain JOIN items AS parent ON parent.id = chain.ancestor_id
+    )
+    INSERT INTO item_locations (item_id, container_id, path, moved_at)
+    SELECT chain.item_id, chain.container_id, chain.path, items.updated_at
+    FROM chain JOIN items ON items.id = chain.item_id
+    WHERE chain.ancestor_id IS NULL;
+    "#,
+    // 5: quantity left after each consumption (NULL for older entries), and
+    // location history that outlives deleted items so stats can count them
+    r#"
+    ALTER TABLE consumptions ADD COLUMN remaining INTEGER;
+
+    CREATE TABLE item_locations_new (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id INTEGER NOT NULL,
+        container_id INTEGER REFERENCES items(id) ON DELETE SET NULL,
+        path TEXT NOT NULL,
+        moved_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    INSERT INTO item_locations_new (id, item_id, container_id, path, moved_at)
+        SELECT id, item_id, container_id, path, moved_at FROM item_locations;
+    DROP TABLE item_locations;
+    ALTER TABLE item_locations_new RENAME TO item_locations;
+
+    CREATE INDEX idx_item_locations_item ON item_locations(item_id);
+    "#,
+    // 6: read-only share tokens scoped to a subtree
+    r#"
+    CREATE TABLE shares (
+        token TEXT PRIMARY KEY,
+        item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        expires_at TEXT
+    );
+    "#,
+    // 7: optional units for quantities (NULL for a plain count)
+    r#"
+    ALTER TABLE items ADD COLUMN unit TEXT;
+    ALTER TABLE consumptions ADD COLUMN unit TEXT;
+    "#,
+    // 8: checklists of items a container must hold
+    r#"
+    CREATE TABLE checklist_entries (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+        name TEXT NOT NULL COLLATE NOCASE,
+        UNIQUE (item_id, name)
+    );
+    "#,
+    // 9: container cycles are rejected by the database itself, whatever code
+    // path writes container_id. Any cycle already present (impossible
+    // through invy, but not through other tools) is broken first by moving
+    // its lowest-ID member to root.
+    r#"
+    WITH RECURSIVE
+        reachable(id) AS (
+            SELECT id FROM items WHERE container_id IS NULL
+            UNION
+            SELECT items.id FROM items JOIN reachable ON items.container_id = reachable.id
+        ),
+        up(item_id, ancestor_id) AS (
+            SELECT id, container_id FROM items WHERE id NOT IN reachable
+            UNION
+            SELECT up.item_id, items.container_id
+            FROM up JOIN items ON items.id = up.ancestor_id
+        ),
+        looped(id) AS (SELECT item_id FROM up WHERE item_id = ancestor_id)
+    UPDATE items SET container_id = NULL
+    WHERE id IN looped
+      AND id = (SELECT MIN(ancestor_id) FROM up
+                WHERE item_id = items.id AND ancestor_id IN looped);
+
+    CREATE TRIGGER items_no_cycle_insert
+    BEFORE INSERT ON items
+    WHEN NEW.container_id = NEW.id
+    BEGIN
+        SELECT RAISE(ABORT, 'an item cannot contain itself');
+    END;
+
+    CREATE TRIGGER items_no_cycle_update
+    BEFORE UPDATE OF container_id ON items
+    WHEN NEW.container_id IS NOT NULL AND NEW.id IN (
+        WITH RECURSIVE up(id) AS (
+            SELECT NEW.container_id
+            UNION
+            SELECT items.container_id FROM items JOIN up ON items.id = up.id
+        )
+        SELECT id FROM up
+    )
+    BEGIN
+        SELECT RAISE(ABORT, 'an item cannot be moved into itself or its own contents');
+    END;
+    "#,
+    // 10: names of items in other languages
+    r#"
+    CREATE TABLE item_names (
+        item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+        lang TEXT NOT NULL,
+        name TEXT NOT NULL,
+        PRIMARY KEY (item_id, lang)
+    );
+    CREATE INDEX idx_item_names_name ON item_names(name COLLATE NOCASE);
+    "#,
+    // 11: temperature and humidity of containers and what items need
+    r#"
+    CREATE TABLE storage_conditions (
+        item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+        kind TEXT NOT NULL CHECK (kind IN ('provides', 'requires')),
+        temp_min INTEGER,
+        temp_max INTEGER,
+        humidity_min INTEGER,
+        humidity_max INTEGER,
+        note TEXT,
+        PRIMARY KEY (item_id, kind)
+    );
+    "#,
+    // 12: stable random (version 4) UUIDs for external references, assigned
+    // by a trigger so items inserted by any code path or tool get one
+    r#"
+    ALTER TABLE items ADD COLUMN uuid TEXT;
+
+    UPDATE items SET uuid = lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' ||
+            substr(hex(randomblob(2)), 2) || '-' ||
+            substr('89ab', 1 + abs(random() % 4), 1) ||
+            substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)));
+
+    CREATE UNIQUE INDEX idx_items_uuid ON items(uuid);
+
+    CREATE TRIGGER items_assign_uuid
+    AFTER INSERT ON items
+    WHEN NEW.uuid IS NULL
+    BEGIN
+        UPDATE items SET uuid = lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' ||
+            substr(hex(randomblob(2)), 2) || '-' ||
+            substr('89ab', 1 + abs(random() % 4), 1) ||
+            substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)))
+        WHERE id = NEW.id;
+    END;
+    "#,
+    // 13: maintenance tasks about items
+    r#"
+    CREATE TABLE todos (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+        text TEXT NOT NULL,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        done_at TEXT
+    );
+
+    CREATE INDEX idx_todos_item ON todos(item_id);
+    "#,
+    // 14: item weights in grams (NULL when unknown)
+    r#"
+    ALTER TABLE items ADD COLUMN weight_g INTEGER;
+    "#,
+    // 15: other names items are known by
+    r#"
+    CREATE TABLE aliases (
+        item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+        alias TEXT NOT NULL COLLATE NOCASE,
+        PRIMARY KEY (item_id, alias)
+    );
+    CREATE INDEX idx_aliases_alias ON aliases(alias COLLATE NOCASE);
+    "#,
+    // 16: what items are (plain items, containers, locations); items that
+    // already hold something become containers
+    r#"
+    ALTER TABLE items ADD COLUMN kind TEXT NOT NULL DEFAULT 'item'
+        CHECK (kind IN ('item', 'container', 'location'));
+    UPDATE items SET kind = 'container'
+    WHERE id IN (SELECT container_id FROM items WHERE container_id IS NOT NULL);
+    "#,
+    // 17: when audits last confirmed items are where they are recorded
+    r#"
+    CREATE TABLE verifications (
+        item_id INTEGER PRIMARY KEY REFERENCES items(id) ON DELETE CASCADE,
+        verified_at TEXT NOT NULL
+    );
+    "#,
+    // 18: low-stock thresholds, in their own unit (see invy shopping)
+    r#"
+    ALTER TABLE items ADD COLUMN min_quantity INTEGER;
+    ALTER TABLE items ADD COLUMN min_unit TEXT;
+    "#,
+    // 19: expiry dates (see invy expiring)
+    r#"
+    ALTER TABLE items ADD COLUMN expires_at TEXT;
+    "#,
+    // 20: boxes unpacked after a move (see invy unpacked)
+    r#"
+    CREATE TABLE unpacked (
+        item_id INTEGER PRIMARY KEY REFERENCES items(id) ON DELETE CASCADE,
+        unpacked_at TEXT NOT NULL
+    );
+    "#,
+    // 21: groups of identical containers added together (see invy add --count)
+    r#"
+    CREATE TABLE item_groups (
+        item_id INTEGER PRIMARY KEY REFERENCES items(id) ON DELETE CASCADE,
+        name TEXT NOT NULL COLLATE NOCASE
+    );
+    CREATE INDEX idx_item_groups_name ON item_groups(name);
+    "#,
+    // 22: change log for invy sync: every insert, update and delete of an
+    // item, by UUID, whatever code path made it. New items get their UUID
+    // from items_assign_uuid, whose update is what gets logged.
+    r#"
+    CREATE TABLE sync_changes (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        uuid TEXT NOT NULL,
+        deleted INTEGER NOT NULL DEFAULT 0,
+        changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+
+    CREATE TABLE sync_peers (
+        replica TEXT PRIMARY KEY,
+        lines INTEGER NOT NULL
+    );
+
+    CREATE TRIGGER items_sync_insert
+    AFTER INSERT ON items
+    WHEN NEW.uuid IS NOT NULL
+    BEGIN
+        INSERT INTO sync_changes (uuid) VALUES (NEW.uuid);
+    END;
+
+    CREATE TRIGGER items_sync_update
+    AFTER UPDATE ON items
+    WHEN NEW.uuid IS NOT NULL
+    BEGIN
+        INSERT INTO sync_changes (uuid) VALUES (NEW.uuid);
+    END;
+
+    CREATE TRIGGER items_sync_delete
+    AFTER DELETE ON items
+    WHEN OLD.uuid IS NOT NULL
+    BEGIN
+        INSERT INTO sync_changes (uuid, deleted) VALUES (OLD.uuid, 1);
+    END;
+    "#,
+    // 23: items looked up with invy where and invy show, for invy optimize
+    r#"
+    CREATE TABLE item_lookups (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+        looked_up_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+
+    CREATE INDEX idx_item_lookups_item ON item_lookups(item_id);
+    "#,
+    // 24: each item's full path (names escaped as in join_path), kept up to
+    // date by triggers so reading a path is one lookup instead of a walk
+    r#"
+    CREATE TABLE item_paths (
+        item_id INTEGER PRIMARY KEY REFERENCES items(id) ON DELETE CASCADE,
+        path TEXT NOT NULL
+    );
+
+    CREATE INDEX idx_item_paths_path ON item_paths(path);
+
+    WITH RECURSIVE paths(id, path) AS (
+        SELECT id, replace(replace(name, '\', '\\'), '/', '\/')
+        FROM items WHERE container_id IS NULL
+        UNION ALL
+        SELECT items.id, paths.path || '/' || replace(replace(items.name, '\', '\\'), '/', '\/')
+        FROM items JOIN paths ON items.container_id = paths.id
+    )
+    INSERT INTO item_paths (item_id, path) SELECT id, path FROM paths;
+
+    CREATE TRIGGER items_path_insert
+    AFTER INSERT ON items
+    BEGIN
+        INSERT INTO item_paths (item_id, path) VALUES (
+            NEW.id,
+            COALESCE((SELECT path FROM item_paths WHERE item_id = NEW.container_id) || '/', '')
+                || replace(replace(NEW.name, '\', '\\'), '/', '\/')
+        );
+    END;
+
+    -- Contents first, while the item's own row still holds the old path
+    CREATE TRIGGER items_path_update
+    AFTER UPDATE OF name, container_id ON items
+    BEGIN
+        UPDATE item_paths SET path =
+            COALESCE((SELECT path FROM item_paths WHERE item_id = NEW.container_id) || '/', '')
+                || replace(replace(NEW.name, '\', '\\'), '/', '\/')
+                || substr(path, length((SELECT path FROM item_paths WHERE item_id = NEW.id)) + 1)
+        WHERE substr(path, 1, length((SELECT path FROM item_paths WHERE item_id = NEW.id)) + 1)
+              = (SELECT path FROM item_paths WHERE item_id = NEW.id) || '/';
+        UPDATE item_paths SET path =
+            COALESCE((SELECT path FROM item_paths WHERE item_id = NEW.container_id) || '/', '')
+                || replace(replace(NEW.name, '\', '\\'), '/', '\/')
+        WHERE item_id = NEW.id;
+    END;
+    "#,
+    // 25: when each synced item was deleted here, kept after the change log
+    // is cleared so an older change received later cannot bring it back
+    r#"
+    CREATE TABLE sync_tombstones (
+        uuid TEXT PRIMARY KEY,
+        deleted_at TEXT NOT NULL
+    );
+
+    INSERT INTO sync_tombstones (uuid, deleted_at)
+    SELECT uuid, MAX(changed_at) FROM sync_changes WHERE deleted = 1 GROUP BY uuid;
+
+    CREATE TRIGGER items_sync_tombstone
+    AFTER DELETE ON items
+    WHEN OLD.uuid IS NOT NULL
+    BEGIN
+        INSERT OR REPLACE INTO sync_tombstones (uuid, deleted_at)
+        VALUES (OLD.uuid, datetime('now'));
+    END;
+    "#,
+];
+
+/// Schema version produced by running every migration.
+pub const LATEST_SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Get the schema version recorded in the database.
+pub fn schema_version(conn: &Connection) -> Result<u32> {
+    let version = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    Ok(version)
+}
+
+/// Run `f` inside a write transaction, committing only if it succeeds.
+///
+/// Any error rolls back every statement `f` executed, so a failing command
+/// never leaves partial changes behind (e.g. a half-created container path).
+/// The transaction is `IMMEDIATE`: the write lock is taken up front and waits
+/// on the busy timeout instead of failing halfway through.
+pub fn with_tx<T>(conn: &Connection, f: impl FnOnce(&Transaction) -> Result<T>) -> Result<T> {
+    let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    let value = f(&tx)?;
+    tx.commit()?;
+    Ok(value)
+}
+
+/// Migrate the schema forward to `target`.
+///
+/// Each step runs in its own transaction together with the version bump,
+/// re-reading the version under the write lock so concurrent processes
+/// never apply the same migration twice. Downgrades are refused because
+/// migrations are not reversible.
+pub fn migrate_to(conn: &Connection, target: u32) -> Result<()> {
+    if target > LATEST_SCHEMA_VERSION {
+        return Err(anyhow!(
+            "unknown schema version {} (latest is {})",
+            target,
+            LATEST_SCHEMA_VERSION
+        ));
+    }
+
+    let current = schema_version(conn)?;
+    if target < current {
+        return Err(anyhow!(
+            "cannot downgrade schema from version {} to {}",
+            current,
+            target
+        ));
+    }
+    if current == target {
+        return Ok(());
+    }
+
+    loop {
+        let done = with_tx(conn, |tx| {
+            let version = schema_version(tx)?;
+            if version >= target {
+                return Ok(true);
+            }
+
+            tx.execute_batch(&format!(
+                "{}\nPRAGMA user_version = {};",
+                MIGRATIONS[version as usize],
+                version + 1
+            ))
+            .with_context(|| format!("Failed to run migration {}", version + 1))?;
+            Ok(false)
+        })?;
+        if done {
+            return Ok(());
+        }
+    }
+}
+
+/// Convenience views for external reporting tools, installed on demand by
+/// `invy views install`. Each entry is the exact `CREATE VIEW` statement;
+/// SQLite keeps the text verbatim, which is how staleness is detected.
+/// Views may only depend on views listed before them.
+const VIEWS: &[(&str, &str)] = &[
+    (
+        "items_with_paths",
+        r"CREATE VIEW items_with_paths AS
+WITH RECURSIVE paths(id, path, depth) AS (
+    SELECT id, replace(replace(name, '\', '\\'), '/', '\/'), 0
+    FROM items WHERE container_id IS NULL
+    UNION ALL
+    SELECT i.id, p.path || '/' || replace(replace(i.name, '\', '\\'), '/', '\/'),
+           p.depth + 1
+    FROM items i JOIN paths p ON i.container_id = p.id
+)
+SELECT i.id, i.name, i.description, i.container_id, p.path, p.depth,
+       i.quantity, i.created_at, i.updated_at
+FROM items i JOIN paths p ON p.id = i.id",
+    ),
+    (
+        "container_totals",
+        "CREATE VIEW container_totals AS
+WITH RECURSIVE descendants(container_id, id, quantity) AS (
+    SELECT container_id, id, quantity FROM items WHERE container_id IS NOT NULL
+    UNION ALL
+    SELECT d.container_id, i.id, i.quantity
+    FROM items i JOIN descendants d ON i.container_id = d.id
+)
+SELECT p.id, p.path,
+       (SELECT COUNT(*) FROM items c WHERE c.container_id = p.id) AS child_count,
+       COUNT(d.id) AS descendant_count,
+       SUM(d.quantity) AS total_quantity
+FROM items_with_paths p JOIN descendants d ON d.container_id = p.id
+GROUP BY p.id",
+    ),
+    (
+        "expiring_soon",
+        "CREATE VIEW expiring_soon AS
+SELECT p.id, p.name, p.path, i.expires_at,
+       CAST(julianday(i.expires_at) - julianday(date('now')) AS INTEGER) AS days_left
+FROM items i JOIN items_with_paths p ON p.id = i.id
+WHERE i.expires_at <= date('now', '+30 days')
+ORDER BY i.expires_at, i.id",
+    ),
+];
+
+/// Create or replace every convenience view.
+pub fn install_views(conn: &Connection) -> Result<()> {
+    // Drop dependents first so replacing a view never breaks another
+    for (name, _) in VIEWS.iter().rev() {
+        conn.execute_batch(&format!("DROP VIEW IF EXISTS {}", name))?;
+    }
+    for (name, sql) in VIEWS {
+        conn.execute_batch(sql)
+            .with_context(|| format!("Failed to create view {}", name))?;
+    }
+    Ok(())
+}
+
+/// Check each convenience view against the definition this version ships.
+pub fn view_status(conn: &Connection) -> Result<Vec<ViewInfo>> {
+    let mut views = Vec::new();
+    for (name, sql) in VIEWS {
+        let installed: Option<String> = conn
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'view' AND name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let status = match installed {
+            None => ViewStatus::Missing,
+            // A view over a column a migration removed only fails when used
+            Some(_)
+                if conn
+                    .prepare(&format!("SELECT * FROM {} LIMIT 0", name))
+                    .is_err() =>
+            {
+                ViewStatus::Broken
+            }
+            Some(installed) if installed != *sql => ViewStatus::Stale,
+            Some(_) => ViewStatus::Ok,
+        };
+        views.push(ViewInfo {
+            name: name.to_string(),
+            status,
+        });
+    }
+    Ok(views)
+}
+
+/// Write a consistent copy of the database to `path`, which must not exist.
+///
+/// Cannot run inside a transaction.
+pub fn backup_to(conn: &Connection, path: &Path) -> Result<()> {
+    let target = path
+        .to_str()
+        .ok_or_else(|| anyhow!("backup path {:?} is not valid UTF-8", path))?;
+    retry_on_busy(|| Ok(conn.execute("VACUUM INTO ?1", params![target])?))
+        .with_context(|| format!("Failed to back up to {:?}", path))?;
+    Ok(())
+}
+
+/// Count all items, or 0 if the items table does not exist yet.
+pub fn count_items(conn: &Connection) -> Result<i64> {
+    let has_items: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'items')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_items {
+        return Ok(0);
+    }
+    let count = conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))?;
+    Ok(count)
+}
+
+/// Gather inventory-wide counts for `invy stats`.
+pub fn stats(conn: &Connection) -> Result<Stats> {
+    let stats = conn.query_row(
+        "SELECT
+            (SELECT COUNT(*) FROM items),
+            (SELECT COUNT(DISTINCT container_id) FROM items WHERE container_id IS NOT NULL),
+            (SELECT COUNT(*) FROM items WHERE container_id IS NULL),
+            (SELECT COALESCE(SUM(CASE WHEN unit IS NULL THEN quantity ELSE 1 END), 0)
+             FROM consumptions)",
+        [],
+        |row| {
+            Ok(Stats {
+                item_count: row.get(0)?,
+                container_count: row.get(1)?,
+                root_count: row.get(2)?,
+                consumed_count: row.get(3)?,
+            })
+        },
+    )?;
+    Ok(stats)
+}
+
+/// Get the database journal mode (e.g. `delete`, `wal`).
+pub fn journal_mode(conn: &Connection) -> Result<String> {
+    let mode = conn.pragma_query_value(None, "journal_mode", |row| row.get(0))?;
+    Ok(mode)
+}
+
+/// Problems reported by `PRAGMA integrity_check`; empty when the file is
+/// sound.
+pub fn integrity_check(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let messages = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(messages.into_iter().filter(|m| m != "ok").collect())
+}
+
+/// One item from each container cycle: the member with the lowest ID,
+/// whose move to root breaks the cycle.
+///
+/// The schema rejects cycles (migration 9), so they only appear when another
+/// tool writes to a database without those triggers.
+pub fn find_cycles(conn: &Connection) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "WITH RECURSIVE
+             reachable(id) AS (
+                 SELECT id FROM items WHERE container_id IS NULL
+                 UNION
+                 SELECT items.id FROM items JOIN reachable ON items.container_id = reachable.id
+             ),
+             up(item_id, ancestor_id) AS (
+                 SELECT id, container_id FROM items WHERE id NOT IN reachable
+                 UNION
+                 SELECT up.item_id, items.container_id
+                 FROM up JOIN items ON items.id = up.ancestor_id
+             ),
+             looped(id) AS (SELECT item_id FROM up WHERE item_id = ancestor_id)
+         SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items
+         WHERE id IN looped
+           AND id = (SELECT MIN(ancestor_id) FROM up
+                     WHERE item_id = items.id AND ancestor_id IN looped)
+         ORDER BY id",
+    )?;
+    let items = stmt
+        .query_map([], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Items whose `container_id` refers to an item that no longer exists.
+pub fn find_dangling_containers(conn: &Connection) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items
+         WHERE container_id IS NOT NULL AND container_id NOT IN (SELECT id FROM items)
+         ORDER BY id",
+    )?;
+    let items = stmt
+        .query_map([], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Items with the same name as an earlier item in the same container, which
+/// names must be unique within.
+pub fn find_duplicate_names(conn: &Connection) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items
+         WHERE EXISTS (
+             SELECT 1 FROM items AS other
+             WHERE other.name = items.name
+               AND other.container_id IS items.container_id
+               AND other.id < items.id
+         )
+         ORDER BY id",
+    )?;
+    let items = stmt
+        .query_map([], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// A name not yet used in a container: `name` itself, or else `name (2)`,
+/// `name (3)`, ...
+pub fn free_name(conn: &Connection, name: &str, container_id: Option<i64>) -> Result<String> {
+    if !name_exists_in_container(conn, name, container_id)? {
+        return Ok(name.to_string());
+    }
+    let mut n = 2;
+    loop {
+        let candidate = format!("{} ({})", name, n);
+        if !name_exists_in_container(conn, &candidate, container_id)? {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+/// Insert a new item into the database.
+pub fn insert_item(
+    conn: &Connection,
+    name: &str,
+    description: Option<&str>,
+    container_id: Option<i64>,
+) -> Result<Item> {
+    conn.execute(
+        "INSERT INTO items (name, description, container_id) VALUES (?1, ?2, ?3)",
+        params![name, description, container_id],
+    )
+    .with_context(|| format!("Failed to insert item '{}'", name))?;
+
+    let id = conn.last_insert_rowid();
+    record_location(conn, id, container_id)?;
+    get_item_by_id(conn, id)?.ok_or_else(|| anyhow!("Failed to retrieve inserted item"))
+}
+
+/// Insert a new item of kind container, e.g. one created by `add --in`.
+pub fn insert_container(conn: &Connection, name: &str, container_id: Option<i64>) -> Result<Item> {
+    let mut item = insert_item(conn, name, None, container_id)?;
+    set_item_kind(conn, item.id, ItemKind::Container)?;
+    item.kind = ItemKind::Container;
+    Ok(item)
+}
+
+/// Run `f` in a transaction of its own, or inside the caller's if one is
+/// already open (e.g. a command's [`with_tx`]).
+fn in_tx<T>(conn: &Connection, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    if conn.is_autocommit() {
+        with_tx(conn, |tx| f(tx))
+    } else {
+        f(conn)
+    }
+}
+
+/// Insert many items at once, in order, returning them as inserted.
+///
+/// Runs in a single transaction and reuses its statements, which makes
+/// loading thousands of rows (e.g. `invy import`) far faster than calling
+/// [`insert_item`] for each. Containers must already exist or come earlier
+/// in `items`; any failure inserts nothing.
+pub fn insert_items(conn: &Connection, items: &[NewItem]) -> Result<Vec<Item>> {
+    in_tx(conn, |conn| {
+        let mut insert = conn.prepare_cached(
+            "INSERT INTO items (name, description, container_id, quantity, unit, uuid, kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )?;
+        let mut locate = conn.prepare_cached(
+            "INSERT INTO item_locations (item_id, container_id, path) VALUES (?1, ?2, ?3)",
+        )?;
+        let mut select = conn.prepare_cached(
+            "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind FROM items WHERE id = ?1",
+        )?;
+        // Most rows share a handful of containers
+        let mut paths: HashMap<i64, String> = HashMap::new();
+
+        let mut inserted = Vec::with_capacity(items.len());
+        for item in items {
+            insert
+                .execute(params![
+                    item.name,
+                    item.description,
+                    item.container_id,
+                    item.quantity.amount,
+                    item.quantity.unit,
+                    item.uuid,
+                    item.kind.as_str()
+                ])
+                .with_context(|| format!("Failed to insert item '{}'", item.name))?;
+            let id = conn.last_insert_rowid();
+
+            let path = match item.container_id {
+                Some(container_id) => match paths.get(&container_id) {
+                    Some(path) => path.clone(),
+                    None => {
+                        let path = join_path(&get_item_path(conn, container_id)?);
+                        paths.insert(container_id, path.clone());
+                        path
+                    }
+                },
+                None => String::new(),
+            };
+            locate.execute(params![id, item.container_id, path])?;
+            inserted.push(select.query_row(params![id], item_from_row)?);
+        }
+        Ok(inserted)
+    })
+}
+
+/// Move many items into one container in a single transaction, recording
+/// each move in the items' location history as [`move_item`] does.
+pub fn move_items(
+    conn: &Connection,
+    item_ids: &[i64],
+    new_container_id: Option<i64>,
+) -> Result<()> {
+    in_tx(conn, |conn| {
+        let path = match new_container_id {
+            Some(id) => join_path(&get_item_path(conn, id)?),
+            None => String::new(),
+        };
+        let mut update = conn.prepare_cached(
+            "UPDATE items SET container_id = ?1, updated_at = datetime('now')
+             WHERE id = ?2 AND container_id IS NOT ?1",
+        )?;
+        let mut locate = conn.prepare_cached(
+            "INSERT INTO item_locations (item_id, container_id, path) VALUES (?1, ?2, ?3)",
+        )?;
+        for &id in item_ids {
+            if update.execute(params![new_container_id, id])? > 0 {
+                locate.execute(params![id, new_container_id, path])?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Delete many items (and, through the foreign key, their contents) in a
+/// single transaction.
+pub fn delete_items(conn: &Connection, item_ids: &[i64]) -> Result<()> {
+    in_tx(conn, |conn| {
+        let mut delete = conn.prepare_cached("DELETE FROM items WHERE id = ?1")?;
+        for &id in item_ids {
+            delete.execute(params![id])?;
+        }
+        Ok(())
+    })
+}
+
+/// Map a row selected as `id, name, description, container_id, created_at,
+/// updated_at, quantity, unit, uuid, kind` to an `Item`.
+fn item_from_row(row: &rusqlite::Row) -> rusqlite::Result<Item> {
+    Ok(Item {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        container_id: row.get(3)?,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+        quantity: row.get(6)?,
+        unit: row.get(7)?,
+        uuid: row.get(8)?,
+        kind: ItemKind::from_db(&row.get::<_, String>(9)?),
+    })
+}
+
+/// Get an item by ID.
+pub fn get_item_by_id(conn: &Connection, id: i64) -> Result<Option<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind FROM items WHERE id = ?1",
+    )?;
+
+    let item = stmt.query_row(params![id], item_from_row).optional()?;
+
+    Ok(item)
+}
+
+/// Get an item by name. Returns error if ambiguous (multiple matches).
+pub fn get_item_by_name(conn: &Connection, name: &str) -> Result<Option<Item>> {
+    // Real names win, so adding an alias never breaks a working reference
+    let mut items = find_items_by_exact_name(conn, name)?;
+    if items.is_empty() {
+        items = find_items_by_alias(conn, name)?;
+    }
+
+    match items.len() {
+        0 => Ok(None),
+        1 => Ok(Some(items.into_iter().next().unwrap())),
+        _ => {
+            // Prefer the single match inside the current context, if any;
+            // strict mode does not guess
+            let context = if strict::enabled() {
+                None
+            } else {
+                get_context(conn)?
+            };
+            if let Some(context) = context {
+                let mut in_context = Vec::new();
+                for item in &items {
+                    if is_ancestor(conn, context.id, item.id)? {
+                        in_context.push(item);
+                    }
+                }
+                if let [item] = in_context[..] {
+                    notice::emit(&format!(
+                        "note: '{}' is ambiguous; using {} from current context",
+                        name,
+                        join_path(&get_item_path(conn, item.id)?)
+                    ));
+                    return Ok(Some(item.clone()));
+                }
+            }
+
+            let paths: Vec<String> = items
+                .iter()
+                .map(|i| join_path(&get_item_path(conn, i.id).unwrap_or_default()))
+                .collect();
+            Err(strict::ambiguous(format!(
+                "'{}' is ambiguous. Use full path: {}",
+                name,
+                paths.join(", ")
+            )))
+        }
+    }
+}
+
+/// Get the one item whose description contains `text` (case-insensitive).
+/// Returns error if several descriptions match.
+pub fn get_item_by_description(conn: &Connection, text: &str) -> Result<Option<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items WHERE description LIKE '%' || ?1 || '%' ESCAPE '\\' COLLATE NOCASE
+         ORDER BY name COLLATE NOCASE, id",
+    )?;
+    let mut items = stmt
+        .query_map(params![escape_like(text)], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if items.len() > 1 {
+        let paths: Vec<String> = items
+            .iter()
+            .map(|i| join_path(&get_item_path(conn, i.id).unwrap_or_default()))
+            .collect();
+        return Err(strict::ambiguous(format!(
+            "description '{}' matches more than one item. Use full path: {}",
+            text,
+            paths.join(", ")
+        )));
+    }
+    Ok(items.pop())
+}
+
+/// Escape `%`, `_` and `\` so `text` matches literally in a `LIKE` pattern
+/// with `ESCAPE '\'`.
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Find items by exact name (may return multiple if in different containers).
+pub fn find_items_by_exact_name(conn: &Connection, name: &str) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items WHERE name = ?1",
+    )?;
+
+    let items = stmt
+        .query_map(params![name], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// Get item by path (e.g., "garage/toolbox/hammer"). A `/` inside a name is
+/// written `\/` (e.g. "office/A\/V cables").
+pub fn get_item_by_path(conn: &Connection, path: &str) -> Result<Option<Item>> {
+    let parts = split_path(path);
+
+    if parts.is_empty() {
+        return Ok(None);
+    }
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items JOIN item_paths ON item_paths.item_id = items.id
+         WHERE item_paths.path = ?1",
+    )?;
+    let item = stmt
+        .query_row(params![join_path(&parts)], item_from_row)
+        .optional()?;
+
+    Ok(item)
+}
+
+/// Fail if `item` was updated since the caller read it, i.e. its
+/// `updated_at` no longer equals `expected` (optimistic concurrency for
+/// `--expected-updated-at`). `None` skips the check.
+pub fn ensure_unchanged(item: &Item, expected: Option<&str>) -> Result<()> {
+    match expected.map(str::trim) {
+        Some(expected) if expected != item.updated_at => Err(anyhow!(
+            "conflict: '{}' was updated at {}, not {}. Read it again and retry",
+            item.name,
+            item.updated_at,
+            expected
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Resolve an item reference: `#ID`, `uuid:<prefix>`, a name, or a path.
+pub fn resolve_item(conn: &Connection, reference: &str) -> Result<Option<Item>> {
+    if let Some(id) = parse_id_ref(reference) {
+        get_item_by_id(conn, id)
+    } else if let Some(prefix) = parse_uuid_ref(reference) {
+        get_item_by_uuid_prefix(conn, &prefix)
+    } else if is_path(reference) {
+        get_item_by_path(conn, reference)
+    } else {
+        get_item_by_name(conn, &unescape_name(reference))
+    }
+}
+
+/// Whether a reference contains a `/` path separator, as opposed to only
+/// escaped `\/` inside a name.
+pub fn is_path(reference: &str) -> bool {
+    let mut escaped = false;
+    for c in reference.chars() {
+        match c {
+            '/' if !escaped => return true,
+            '\\' if !escaped => escaped = true,
+            _ => escaped = false,
+        }
+    }
+    false
+}
+
+/// Split a path on unescaped `/`, unescaping each name and dropping empty
+/// segments.
+pub fn split_path(path: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('/' | '\\')) => {
+                part.extend(chars.next());
+            }
+            '/' => parts.push(std::mem::take(&mut part)),
+            c => part.push(c),
+        }
+    }
+    parts.push(part);
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// A single name as typed in a reference, with `\/` and `\\` unescaped.
+pub fn unescape_name(name: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('/' | '\\')) => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Escape a name for use in a path: `/` becomes `\/` and `\` becomes `\\`.
+pub fn escape_name(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('/', "\\/")
+}
+
+/// Join names into a path that `split_path` turns back into the same names.
+pub fn join_path(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| escape_name(name))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The ID in an `#123` reference.
+pub fn parse_id_ref(reference: &str) -> Option<i64> {
+    let digits = reference.strip_prefix('#')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// The lowercase prefix in a `uuid:3f2a` reference.
+pub fn parse_uuid_ref(reference: &str) -> Option<String> {
+    let prefix = reference.strip_prefix("uuid:")?.to_ascii_lowercase();
+    if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_hexdigit() || b == b'-') {
+        return None;
+    }
+    Some(prefix)
+}
+
+/// Get the item whose UUID starts with `prefix`. Returns error if the prefix
+/// matches more than one item.
+pub fn get_item_by_uuid_prefix(conn: &Connection, prefix: &str) -> Result<Option<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items WHERE uuid LIKE ?1 || '%' LIMIT 2",
+    )?;
+    let mut items = stmt
+        .query_map(params![prefix], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if items.len() > 1 {
+        return Err(strict::ambiguous(format!(
+            "uuid prefix '{}' matches more than one item (use more characters)",
+            prefix
+        )));
+    }
+    Ok(items.pop())
+}
+
+/// Recompute every stored path from the hierarchy, e.g. after `invy doctor
+/// --fix` repaired containers the triggers could not follow.
+pub fn rebuild_item_paths(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        r#"
+        DELETE FROM item_paths;
+        WITH RECURSIVE paths(id, path) AS (
+            SELECT id, replace(replace(name, '\', '\\'), '/', '\/')
+            FROM items WHERE container_id IS NULL
+            UNION ALL
+            SELECT items.id, paths.path || '/' || replace(replace(items.name, '\', '\\'), '/', '\/')
+            FROM items JOIN paths ON items.container_id = paths.id
+        )
+        INSERT INTO item_paths (item_id, path) SELECT id, path FROM paths;
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Get the path to an item as a vector of names (from root to item).
+///
+/// Read from the `item_paths` table the triggers maintain; empty if the
+/// item does not exist.
+pub fn get_item_path(conn: &Connection, item_id: i64) -> Result<Vec<String>> {
+    let path: Option<String> = conn
+        .prepare_cached("SELECT path FROM item_paths WHERE item_id = ?1")?
+        .query_row(params![item_id], |row| row.get(0))
+        .optional()?;
+    Ok(path.map(|path| split_path(&path)).unwrap_or_default())
+}
+
+/// IDs of the containers above an item, nearest first.
+pub fn get_ancestor_ids(conn: &Connection, item_id: i64) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare_cached(
+        "WITH RECURSIVE up(id, container_id, depth) AS (
+             SELECT id, container_id, 0 FROM items WHERE id = ?1
+             UNION ALL
+             SELECT items.id, items.container_id, up.depth + 1
+             FROM items JOIN up ON items.id = up.container_id
+         )
+         SELECT id FROM up WHERE depth > 0 ORDER BY depth",
+    )?;
+    let ids = stmt
+        .query_map(params![item_id], |row| row.get(0))?
+        .collect::<Result<Vec<i64>, _>>()?;
+    Ok(ids)
+}
+
+/// Like [`get_item_path`], using each item's name in `lang` where it has one.
+pub fn get_item_path_in(
+    conn: &Connection,
+    item_id: i64,
+    lang: Option<&str>,
+) -> Result<Vec<String>> {
+    let Some(lang) = lang else {
+        return get_item_path(conn, item_id);
+    };
+    let mut stmt = conn.prepare_cached(
+        "WITH RECURSIVE up(id, name, container_id, depth) AS (
+             SELECT id, name, container_id, 0 FROM items WHERE id = ?1
+             UNION ALL
+             SELECT items.id, items.name, items.container_id, up.depth + 1
+             FROM items JOIN up ON items.id = up.container_id
+         )
+         SELECT COALESCE(item_names.name, up.name) FROM up
+         LEFT JOIN item_names ON item_names.item_id = up.id AND item_names.lang = ?2
+         ORDER BY up.depth DESC",
+    )?;
+    let path = stmt
+        .query_map(params![item_id, lang], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(path)
+}
+
+/// Search items by name, description or a name in another language
+/// (case-insensitive substring match).
+pub fn search_items(conn: &Connection, query: &str) -> Result<Vec<Item>> {
+    let pattern = format!("%{}%", query);
+
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items
+         WHERE name LIKE ?1 COLLATE NOCASE
+            OR description LIKE ?1 COLLATE NOCASE
+            OR id IN (SELECT item_id FROM item_names WHERE name LIKE ?1 COLLATE NOCASE)
+            OR id IN (SELECT item_id FROM aliases WHERE alias LIKE ?1 COLLATE NOCASE)
+         ORDER BY name COLLATE NOCASE, id",
+    )?;
+
+    let items = stmt
+        .query_map(params![pattern], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// List items at root level (no container).
+pub fn list_root_items(conn: &Connection) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items WHERE container_id IS NULL",
+    )?;
+
+    let items = stmt
+        .query_map([], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// List items in a specific container.
+pub fn list_items_in_container(conn: &Connection, container_id: i64) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items WHERE container_id = ?1",
+    )?;
+
+    let items = stmt
+        .query_map(params![container_id], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// List items that hold at least one other item, by name.
+pub fn list_containers(conn: &Connection) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items
+         WHERE id IN (SELECT container_id FROM items)
+         ORDER BY name COLLATE NOCASE, id",
+    )?;
+
+    let items = stmt
+        .query_map([], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// List items that have a checklist (see `invy check`), by name.
+pub fn list_checklist_containers(conn: &Connection) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items
+         WHERE id IN (SELECT item_id FROM checklist_entries)
+         ORDER BY name COLLATE NOCASE, id",
+    )?;
+
+    let items = stmt
+        .query_map([], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// List all items recursively.
+pub fn list_all_items(conn: &Connection) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind FROM items",
+    )?;
+
+    let items = stmt
+        .query_map([], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// Ordering for `invy list`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// Alphabetical, case-insensitive
+    #[default]
+    Name,
+    /// Oldest first
+    Created,
+    /// Least recently updated first
+    Updated,
+    /// Fewest direct children first
+    Children,
+}
+
+impl SortKey {
+    /// `ORDER BY` clause for a query over `items`. Ties fall back to name,
+    /// then id, so the order is stable.
+    fn order_by(self, reverse: bool) -> String {
+        let key = match self {
+            SortKey::Name => "items.name COLLATE NOCASE",
+            SortKey::Created => "items.created_at",
+            SortKey::Updated => "items.updated_at",
+            SortKey::Children => {
+                "(SELECT COUNT(*) FROM items AS c WHERE c.container_id = items.id)"
+            }
+        };
+        let dir = if reverse { "DESC" } else { "ASC" };
+        format!("ORDER BY {key} {dir}, items.name COLLATE NOCASE {dir}, items.id {dir}")
+    }
+}
+
+/// List the direct children of a container (root items when `None`) in the
+/// given order.
+pub fn list_items_sorted(
+    conn: &Connection,
+    container_id: Option<i64>,
+    sort: SortKey,
+    reverse: bool,
+) -> Result<Vec<Item>> {
+    let sql = format!(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items WHERE container_id IS ?1 {}",
+        sort.order_by(reverse)
+    );
+    let mut stmt = conn.prepare(&sql)?;
+
+    let items = stmt
+        .query_map(params![container_id], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// List all items in the given order.
+pub fn list_all_items_sorted(conn: &Connection, sort: SortKey, reverse: bool) -> Result<Vec<Item>> {
+    let sql = format!(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items {}",
+        sort.order_by(reverse)
+    );
+    let mut stmt = conn.prepare(&sql)?;
+
+    let items = stmt
+        .query_map([], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+/// Totals over the contents of container `item_id`, in one recursive query.
+///
+/// `None` when the item holds nothing.
+pub fn container_stats(conn: &Connection, item_id: i64) -> Result<Option<ContainerStats>> {
+    let path = join_path(&get_item_path(conn, item_id)?);
+    let (direct_children, descendants, total_quantity, last_activity, deepest_path) = conn
+        .query_row(
+            r#"
+            WITH RECURSIVE tree(id, depth, path, quantity, unit, updated_at) AS (
+                SELECT id, 0, ?2, quantity, unit, updated_at FROM items WHERE id = ?1
+                UNION ALL
+                SELECT items.id, tree.depth + 1,
+                       tree.path || '/' || replace(replace(items.name, '\', '\\'), '/', '\/'),
+                       items.quantity, items.unit, items.updated_at
+                FROM items JOIN tree ON items.container_id = tree.id
+            )
+            SELECT
+                (SELECT COUNT(*) FROM tree WHERE depth = 1),
+                (SELECT COUNT(*) FROM tree WHERE depth > 0),
+                (SELECT COALESCE(SUM(quantity), 0) FROM tree WHERE depth > 0 AND unit IS NULL),
+                (SELECT MAX(updated_at) FROM tree),
+                (SELECT path FROM tree ORDER BY depth DESC, path LIMIT 1)
+            "#,
+            params![item_id, path],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            },
+        )?;
+    if descendants == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(ContainerStats {
+        direct_children,
+        descendants,
+        total_quantity,
+        last_activity,
+        deepest_path: split_path(&deepest_path),
+    }))
+}
+
+/// Count children of an item.
+pub fn count_children(conn: &Connection, item_id: i64) -> Result<i64> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM items WHERE container_id = ?1",
+        params![item_id],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// Count items at the root, outside any container.
+pub fn count_root_items(conn: &Connection) -> Result<i64> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM items WHERE container_id IS NULL",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// Number of direct children of every item that has any, in one query;
+/// items missing from the map hold nothing.
+pub fn child_counts(conn: &Connection) -> Result<HashMap<i64, i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT container_id, COUNT(*) FROM items
+         WHERE container_id IS NOT NULL GROUP BY container_id",
+    )?;
+    let counts = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(counts)
+}
+
+/// Update an item's name.
+pub fn update_item_name(conn: &Connection, item_id: i64, new_name: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET name = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![new_name, item_id],
+    )?;
+    Ok(())
+}
+
+/// Set what an item is.
+pub fn set_item_kind(conn: &Connection, item_id: i64, kind: ItemKind) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET kind = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![kind.as_str(), item_id],
+    )?;
+    Ok(())
+}
+
+/// Replace an item's UUID, e.g. with the one it had in an imported file.
+pub fn set_item_uuid(conn: &Connection, item_id: i64, uuid: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET uuid = ?1 WHERE id = ?2",
+        params![uuid, item_id],
+    )
+    .with_context(|| format!("Failed to set uuid '{}'", uuid))?;
+    Ok(())
+}
+
+/// Update an item's description.
+pub fn update_item_description(
+    conn: &Connection,
+    item_id: i64,
+    new_description: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET description = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![new_description, item_id],
+    )?;
+    Ok(())
+}
+
+/// Set an item's quantity and unit.
+pub fn update_item_quantity(conn: &Connection, item_id: i64, quantity: &Quantity) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET quantity = ?1, unit = ?2, updated_at = datetime('now') WHERE id = ?3",
+        params![quantity.amount, quantity.unit, item_id],
+    )?;
+    Ok(())
+}
+
+/// Set or clear an item's own weight in grams.
+pub fn update_item_weight(conn: &Connection, item_id: i64, grams: Option<i64>) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET weight_g = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![grams, item_id],
+    )?;
+    Ok(())
+}
+
+/// Set (or clear, with `None`) the amount below which an item needs
+/// restocking.
+pub fn update_item_min_quantity(
+    conn: &Connection,
+    item_id: i64,
+    min: Option<&Quantity>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET min_quantity = ?1, min_unit = ?2, updated_at = datetime('now')
+         WHERE id = ?3",
+        params![
+            min.map(|q| q.amount),
+            min.and_then(|q| q.unit.as_deref()),
+            item_id
+        ],
+    )?;
+    Ok(())
+}
+
+/// The amount below which an item needs restocking, if set.
+pub fn get_min_quantity(conn: &Connection, item_id: i64) -> Result<Option<Quantity>> {
+    let min = conn.query_row(
+        "SELECT min_quantity, min_unit FROM items WHERE id = ?1",
+        params![item_id],
+        |row| {
+            Ok(row.get::<_, Option<i64>>(0)?.map(|amount| Quantity {
+                amount,
+                unit: row.get(1).ok().flatten(),
+            }))
+        },
+    )?;
+    Ok(min)
+}
+
+/// Every item with a restocking threshold, with that threshold.
+pub fn list_min_quantities(conn: &Connection) -> Result<Vec<(Item, Quantity)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind, min_quantity, min_unit
+         FROM items WHERE min_quantity IS NOT NULL",
+    )?;
+    let items = stmt
+        .query_map([], |row| {
+            let min = Quantity {
+                amount: row.get(10)?,
+                unit: row.get(11)?,
+            };
+            Ok((item_from_row(row)?, min))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Set (or clear, with `None`) the day an item expires (`YYYY-MM-DD`).
+pub fn update_item_expiry(conn: &Connection, item_id: i64, expires_at: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE items SET expires_at = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![expires_at, item_id],
+    )?;
+    Ok(())
+}
+
+/// The day an item expires, if set.
+pub fn get_expiry(conn: &Connection, item_id: i64) -> Result<Option<String>> {
+    let expires_at = conn.query_row(
+        "SELECT expires_at FROM items WHERE id = ?1",
+        params![item_id],
+        |row| row.get(0),
+    )?;
+    Ok(expires_at)
+}
+
+/// Items expiring on or before `until` (`YYYY-MM-DD`), including those
+/// already expired, soonest first, with their expiry dates.
+pub fn list_expiring(conn: &Connection, until: &str) -> Result<Vec<(Item, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind, expires_at
+         FROM items WHERE expires_at <= ?1
+         ORDER BY expires_at, id",
+    )?;
+    let items = stmt
+        .query_map(params![until], |row| {
+            Ok((item_from_row(row)?, row.get(10)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Total weight in grams of every item together with everything inside it.
+///
+/// An item's own weight is the one set with `invy edit --weight`, or else
+/// its quantity when that is a mass (`2 kg` of flour). Items with no known
+/// weight anywhere in their subtree are left out.
+pub fn subtree_weights(conn: &Connection) -> Result<HashMap<i64, i64>> {
+    let mut stmt = conn.prepare("SELECT id, container_id, weight_g, quantity, unit FROM items")?;
+    let rows = stmt
+        .query_map([], |row| {
+            let quantity = Quantity {
+                amount: row.get(3)?,
+                unit: row.get(4)?,
+            };
+            let weight: Option<i64> = row.get(2)?;
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<i64>>(1)?,
+                weight.or_else(|| quantity.grams()),
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let parents: HashMap<i64, Option<i64>> =
+        rows.iter().map(|&(id, parent, _)| (id, parent)).collect();
+    let mut totals = HashMap::new();
+    for &(id, _, weight) in &rows {
+        let Some(weight) = weight else {
+            continue;
+        };
+        // Cycles are rejected by triggers, so every chain reaches the root
+        let mut current = Some(id);
+        while let Some(item_id) = current {
+            *totals.entry(item_id).or_insert(0) += weight;
+            current = parents.get(&item_id).copied().flatten();
+        }
+    }
+    Ok(totals)
+}
+
+/// Move an item to a new container.
+///
+/// Records the new container in the item's location history unless the item
+/// is already there.
+pub fn move_item(conn: &Connection, item_id: i64, new_container_id: Option<i64>) -> Result<()> {
+    let changed = conn.execute(
+        "UPDATE items SET container_id = ?1, updated_at = datetime('now')
+         WHERE id = ?2 AND container_id IS NOT ?1",
+        params![new_container_id, item_id],
+    )?;
+    if changed > 0 {
+        record_location(conn, item_id, new_container_id)?;
+    }
+    Ok(())
+}
+
+/// Append an entry to an item's location history.
+///
+/// The container path is stored as text so entries stay readable after the
+/// container is renamed or removed.
+pub fn record_location(conn: &Connection, item_id: i64, container_id: Option<i64>) -> Result<()> {
+    let path = match container_id {
+        Some(id) => join_path(&get_item_path(conn, id)?),
+        None => String::new(),
+    };
+    conn.execute(
+        "INSERT INTO item_locations (item_id, container_id, path) VALUES (?1, ?2, ?3)",
+        params![item_id, container_id, path],
+    )?;
+    Ok(())
+}
+
+/// List the location history of every item, oldest first within each item.
+pub fn list_all_locations(conn: &Connection) -> Result<Vec<(i64, Location)>> {
+    let mut stmt = conn.prepare(
+        "SELECT item_id, container_id, path, moved_at FROM item_locations
+         ORDER BY item_id, moved_at, id",
+    )?;
+
+    let locations = stmt
+        .query_map([], |row| {
+            let path: String = row.get(2)?;
+            Ok((
+                row.get(0)?,
+                Location {
+                    container_id: row.get(1)?,
+                    path: split_path(&path),
+                    moved_at: row.get(3)?,
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(locations)
+}
+
+/// List the containers an item has lived in, newest first.
+pub fn list_locations(conn: &Connection, item_id: i64) -> Result<Vec<Location>> {
+    let mut stmt = conn.prepare(
+        "SELECT container_id, path, moved_at FROM item_locations
+         WHERE item_id = ?1
+         ORDER BY moved_at DESC, id DESC",
+    )?;
+
+    let locations = stmt
+        .query_map(params![item_id], |row| {
+            let path: String = row.get(1)?;
+            Ok(Location {
+                container_id: row.get(0)?,
+                path: split_path(&path),
+                moved_at: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(locations)
+}
+
+/// Copy an item into a container, optionally copying its whole subtree.
+///
+/// Returns the newly created top-level copy. The subtree is snapshotted
+/// before any inserts so copying into a descendant cannot recurse forever.
+pub fn copy_item(
+    conn: &Connection,
+    item_id: i64,
+    new_container_id: Option<i64>,
+    recursive: bool,
+) -> Result<Item> {
+    let item = get_item_by_id(conn, item_id)?
+        .ok_or_else(|| anyhow!("item with id {} not found", item_id))?;
+
+    let children = if recursive {
+        list_items_in_container(conn, item_id)?
+    } else {
+        Vec::new()
+    };
+
+    let mut copy = insert_item(
+        conn,
+        &item.name,
+        item.description.as_deref(),
+        new_container_id,
+    )?;
+    if item.quantity != copy.quantity || item.unit != copy.unit {
+        update_item_quantity(conn, copy.id, &item.measure())?;
+        copy.quantity = item.quantity;
+        copy.unit = item.unit.clone();
+    }
+    if item.kind != copy.kind {
+        set_item_kind(conn, copy.id, item.kind)?;
+        copy.kind = item.kind;
+    }
+
+    conn.execute(
+        "INSERT INTO item_names (item_id, lang, name)
+         SELECT ?2, lang, name FROM item_names WHERE item_id = ?1",
+        params![item.id, copy.id],
+    )?;
+    conn.execute(
+        "UPDATE items SET (weight_g, min_quantity, min_unit, expires_at) =
+            (SELECT weight_g, min_quantity, min_unit, expires_at FROM items WHERE id = ?1)
+         WHERE id = ?2",
+        params![item.id, copy.id],
+    )?;
+
+    for child in children {
+        copy_item(conn, child.id, Some(copy.id), true)?;
+    }
+
+    Ok(copy)
+}
+
+/// Count all descendants of an item (children, grandchildren, ...).
+pub fn count_descendants(conn: &Connection, item_id: i64) -> Result<i64> {
+    let count = conn.query_row(
+        "WITH RECURSIVE below(id) AS (
+             SELECT id FROM items WHERE container_id = ?1
+             UNION ALL
+             SELECT items.id FROM items JOIN below ON items.container_id = below.id
+         )
+         SELECT COUNT(*) FROM below",
+        params![item_id],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// Get a stored setting value.
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
+    let value = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value)
+}
+
+/// Store a setting value, replacing any previous one.
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = ?2",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Remove a stored setting.
+pub fn delete_setting(conn: &Connection, key: &str) -> Result<()> {
+    conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?;
+    Ok(())
+}
+
+/// Settings key holding the current container context (an item ID).
+const CONTEXT_KEY: &str = "context";
+
+/// Get the current container context set with `invy cd`.
+///
+/// Returns `None` when unset or when the container has since been removed.
+pub fn get_context(conn: &Connection) -> Result<Option<Item>> {
+    match get_setting(conn, CONTEXT_KEY)?.and_then(|v| v.parse().ok()) {
+        Some(id) => get_item_by_id(conn, id),
+        None => Ok(None),
+    }
+}
+
+/// Set (or clear, with `None`) the current container context.
+pub fn set_context(conn: &Connection, item_id: Option<i64>) -> Result<()> {
+    match item_id {
+        Some(id) => set_setting(conn, CONTEXT_KEY, &id.to_string()),
+        None => delete_setting(conn, CONTEXT_KEY),
+    }
+}
+
+/// Settings keys holding the running audit: when it started and, unless it
+/// covers the whole inventory, the audited container's ID.
+const AUDIT_STARTED_KEY: &str = "audit.started_at";
+const AUDIT_CONTAINER_KEY: &str = "audit.container";
+
+/// Start an audit of a container (the whole inventory with `None`),
+/// replacing any running one. Returns when it started.
+pub fn start_audit(conn: &Connection, container_id: Option<i64>) -> Result<String> {
+    let started_at = timespec::now();
+    set_setting(conn, AUDIT_STARTED_KEY, &started_at)?;
+    match container_id {
+        Some(id) => set_setting(conn, AUDIT_CONTAINER_KEY, &id.to_string())?,
+        None => delete_setting(conn, AUDIT_CONTAINER_KEY)?,
+    }
+    Ok(started_at)
+}
+
+/// The running audit: its container ID (`None` for the whole inventory) and
+/// when it started.
+pub fn get_audit(conn: &Connection) -> Result<Option<(Option<i64>, String)>> {
+    let Some(started_at) = get_setting(conn, AUDIT_STARTED_KEY)? else {
+        return Ok(None);
+    };
+    let container_id = get_setting(conn, AUDIT_CONTAINER_KEY)?.and_then(|v| v.parse().ok());
+    Ok(Some((container_id, started_at)))
+}
+
+/// Record that an item was found where it is recorded.
+pub fn verify_item(conn: &Connection, item_id: i64) -> Result<String> {
+    let verified_at = timespec::now();
+    conn.execute(
+        "INSERT INTO verifications (item_id, verified_at) VALUES (?1, ?2)
+         ON CONFLICT(item_id) DO UPDATE SET verified_at = ?2",
+        params![item_id, verified_at],
+    )?;
+    Ok(verified_at)
+}
+
+/// When an item was last verified by an audit, if ever.
+pub fn get_verified_at(conn: &Connection, item_id: i64) -> Result<Option<String>> {
+    let verified_at = conn
+        .query_row(
+            "SELECT verified_at FROM verifications WHERE item_id = ?1",
+            params![item_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(verified_at)
+}
+
+/// When each verified item was last verified, by item ID.
+pub fn list_verifications(conn: &Connection) -> Result<HashMap<i64, String>> {
+    let mut stmt = conn.prepare("SELECT item_id, verified_at FROM verifications")?;
+    let verifications = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(verifications)
+}
+
+/// Mark a box as unpacked, keeping the time it was first marked.
+pub fn mark_unpacked(conn: &Connection, item_id: i64) -> Result<String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO unpacked (item_id, unpacked_at) VALUES (?1, ?2)",
+        params![item_id, timespec::now()],
+    )?;
+    let unpacked_at = conn.query_row(
+        "SELECT unpacked_at FROM unpacked WHERE item_id = ?1",
+        params![item_id],
+        |row| row.get(0),
+    )?;
+    Ok(unpacked_at)
+}
+
+/// Mark a box as packed again. Returns whether it was marked unpacked.
+pub fn unmark_unpacked(conn: &Connection, item_id: i64) -> Result<bool> {
+    let removed = conn.execute("DELETE FROM unpacked WHERE item_id = ?1", params![item_id])?;
+    Ok(removed > 0)
+}
+
+/// Put an item in a group of identical containers, replacing any group it
+/// was in.
+pub fn set_item_group(conn: &Connection, item_id: i64, group: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO item_groups (item_id, name) VALUES (?1, ?2)",
+        params![item_id, group],
+    )?;
+    Ok(())
+}
+
+/// The group an item was added with, if any.
+pub fn get_item_group(conn: &Connection, item_id: i64) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT name FROM item_groups WHERE item_id = ?1",
+            params![item_id],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// IDs of the items in a group (names ignore case).
+pub fn list_group_members(conn: &Connection, group: &str) -> Result<HashSet<i64>> {
+    let mut stmt = conn.prepare("SELECT item_id FROM item_groups WHERE name = ?1")?;
+    let members = stmt
+        .query_map(params![group], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(members)
+}
+
+/// Get an item by its full UUID.
+pub fn get_item_by_uuid(conn: &Connection, uuid: &str) -> Result<Option<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items WHERE uuid = ?1",
+    )?;
+    Ok(stmt.query_row(params![uuid], item_from_row).optional()?)
+}
+
+/// Highest change log ID so far (0 when the log is empty).
+pub fn last_sync_change(conn: &Connection) -> Result<i64> {
+    Ok(
+        conn.query_row("SELECT COALESCE(MAX(id), 0) FROM sync_changes", [], |row| {
+            row.get(0)
+        })?,
+    )
+}
+
+/// The latest logged change of each item, oldest first: its UUID, whether it
+/// was a deletion, and when it happened.
+pub fn pending_sync_changes(conn: &Connection) -> Result<Vec<(String, bool, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT uuid, deleted, changed_at FROM sync_changes
+         WHERE id IN (SELECT MAX(id) FROM sync_changes GROUP BY uuid)
+         ORDER BY id",
+    )?;
+    let changes = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(changes)
+}
+
+/// Forget logged changes after `after` (through `through`, when given).
+pub fn clear_sync_changes(conn: &Connection, after: i64, through: Option<i64>) -> Result<()> {
+    conn.execute(
+        "DELETE FROM sync_changes WHERE id > ?1 AND id <= COALESCE(?2, id)",
+        params![after, through],
+    )?;
+    Ok(())
+}
+
+/// Log a change to every item, so the next sync sends the whole inventory.
+pub fn log_all_items_for_sync(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT INTO sync_changes (uuid) SELECT uuid FROM items WHERE uuid IS NOT NULL ORDER BY id",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Log a change to one item, so the next sync sends it.
+pub fn log_sync_change(conn: &Connection, uuid: &str) -> Result<()> {
+    conn.execute("INSERT INTO sync_changes (uuid) VALUES (?1)", params![uuid])?;
+    Ok(())
+}
+
+/// Everything sync carries about an item, with its depth (0 at root) and
+/// `updated_at`.
+pub fn sync_item(conn: &Connection, uuid: &str) -> Result<Option<(SyncItem, i64, String)>> {
+    let item = conn
+        .query_row(
+            "SELECT i.name, i.description, parent.uuid, i.kind, i.quantity, i.unit, i.weight_g,
+                    i.min_quantity, i.min_unit, i.expires_at, i.created_at, i.updated_at, i.id
+             FROM items AS i LEFT JOIN items AS parent ON parent.id = i.container_id
+             WHERE i.uuid = ?1",
+            params![uuid],
+            |row| {
+                let item = SyncItem {
+                    name: row.get(0)?,
+                    description: row.get(1)?,
+                    container: row.get(2)?,
+                    kind: ItemKind::from_db(&row.get::<_, String>(3)?),
+                    quantity: row.get(4)?,
+                    unit: row.get(5)?,
+                    weight_g: row.get(6)?,
+                    min_quantity: row.get(7)?,
+                    min_unit: row.get(8)?,
+                    expires_at: row.get(9)?,
+                    created_at: row.get(10)?,
+                };
+                Ok((item, row.get::<_, String>(11)?, row.get::<_, i64>(12)?))
+            },
+        )
+        .optional()?;
+    let Some((item, updated_at, id)) = item else {
+        return Ok(None);
+    };
+    let depth = get_item_path(conn, id)?.len() as i64 - 1;
+    Ok(Some((item, depth, updated_at)))
+}
+
+/// Insert a row of an `invy export` file as it was exported, keeping its
+/// ID, UUID and timestamps (see `--from-export`).
+pub fn insert_export_row(
+    conn: &Connection,
+    row: &ExportRow,
+    container_id: Option<i64>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO items (id, name, description, container_id, quantity, unit, uuid,
+                            created_at, updated_at, kind)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            row.id,
+            row.name,
+            row.description,
+            container_id,
+            row.quantity,
+            row.unit,
+            row.uuid,
+            row.created_at,
+            row.updated_at,
+            row.kind.as_str()
+        ],
+    )
+    .with_context(|| format!("Failed to load '{}'", row.path))?;
+    record_location(conn, row.id, container_id)
+}
+
+/// Write an item received from another machine: update the item with this
+/// UUID (`id`), or insert it. `updated_at` is kept as the sender had it. The
+/// caller checks names and containment first. Returns the item's ID.
+pub fn write_sync_item(
+    conn: &Connection,
+    id: Option<i64>,
+    uuid: &str,
+    item: &SyncItem,
+    container_id: Option<i64>,
+    updated_at: &str,
+) -> Result<i64> {
+    let id = match id {
+        Some(id) => {
+            let moved =
+                get_item_by_id(conn, id)?.is_some_and(|old| old.container_id != container_id);
+            conn.execute(
+                "UPDATE items SET name = ?1, description = ?2, container_id = ?3 WHERE id = ?4",
+                params![item.name, item.description, container_id, id],
+            )?;
+            if moved {
+                record_location(conn, id, container_id)?;
+            }
+            id
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO items (name, description, container_id, uuid, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    item.name,
+                    item.description,
+                    container_id,
+                    uuid,
+                    item.created_at
+                ],
+            )?;
+            let id = conn.last_insert_rowid();
+            record_location(conn, id, container_id)?;
+            id
+        }
+    };
+    conn.execute(
+        "UPDATE items SET kind = ?1, quantity = ?2, unit = ?3, weight_g = ?4, min_quantity = ?5,
+                          min_unit = ?6, expires_at = ?7, updated_at = ?8
+         WHERE id = ?9",
+        params![
+            item.kind.as_str(),
+            item.quantity,
+            item.unit,
+            item.weight_g,
+            item.min_quantity,
+            item.min_unit,
+            item.expires_at,
+            updated_at,
+            id
+        ],
+    )?;
+    Ok(id)
+}
+
+/// When the item with this UUID was deleted here, if it was.
+pub fn sync_tombstone(conn: &Connection, uuid: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT deleted_at FROM sync_tombstones WHERE uuid = ?1",
+            params![uuid],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Record when the item with this UUID was deleted, as the machine that
+/// deleted it says.
+pub fn set_sync_tombstone(conn: &Connection, uuid: &str, deleted_at: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_tombstones (uuid, deleted_at) VALUES (?1, ?2)",
+        params![uuid, deleted_at],
+    )?;
+    Ok(())
+}
+
+/// How many lines of another machine's sync file were already applied.
+pub fn sync_peer_lines(conn: &Connection, replica: &str) -> Result<usize> {
+    let lines: Option<i64> = conn
+        .query_row(
+            "SELECT lines FROM sync_peers WHERE replica = ?1",
+            params![replica],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(lines.unwrap_or(0) as usize)
+}
+
+/// Record how many lines of another machine's sync file are applied.
+pub fn set_sync_peer_lines(conn: &Connection, replica: &str, lines: usize) -> Result<()> {
+    conn.execute(
+        "INSERT INTO sync_peers (replica, lines) VALUES (?1, ?2)
+         ON CONFLICT(replica) DO UPDATE SET lines = excluded.lines",
+        params![replica, lines as i64],
+    )?;
+    Ok(())
+}
+
+/// When each unpacked box was marked unpacked, by item ID.
+pub fn list_unpacked(conn: &Connection) -> Result<HashMap<i64, String>> {
+    let mut stmt = conn.prepare("SELECT item_id, unpacked_at FROM unpacked")?;
+    let unpacked = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(unpacked)
+}
+
+/// Compute a stable SHA-256 hash of a container's contents.
+///
+/// Covers the relative path, description, quantity with unit and kind of
+/// every descendant, sorted so the result does not depend on insertion
+/// order. The container's own name and description are excluded, so
+/// relabelling a box does not change it.
+pub fn hash_contents(conn: &Connection, item_id: i64) -> Result<String> {
+    let mut stmt = conn.prepare(
+        r"WITH RECURSIVE below(id, path, description, quantity, unit, kind) AS (
+             SELECT id, replace(replace(name, '\', '\\'), '/', '\/'), description, quantity,
+                    unit, kind
+             FROM items WHERE container_id = ?1
+             UNION ALL
+             SELECT items.id,
+                    below.path || '/' || replace(replace(items.name, '\', '\\'), '/', '\/'),
+                    items.description, items.quantity, items.unit, items.kind
+             FROM items JOIN below ON items.container_id = below.id
+         )
+         SELECT path || char(9) || COALESCE(description, '') || char(9) || quantity
+                || COALESCE(unit, '') || char(9) || kind
+         FROM below",
+    )?;
+    let mut lines = stmt
+        .query_map(params![item_id], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    lines.sort();
+
+    let mut hasher = Sha256::new();
+    for line in &lines {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    let digest = hasher.finalize();
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Record the current content hash for a container.
+pub fn record_hash(conn: &Connection, item_id: i64, hash: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO container_hashes (item_id, hash) VALUES (?1, ?2)
+         ON CONFLICT(item_id) DO UPDATE SET hash = ?2, recorded_at = datetime('now')",
+        params![item_id, hash],
+    )?;
+    Ok(())
+}
+
+/// Get the last recorded hash (and when it was recorded) for a container.
+pub fn get_recorded_hash(conn: &Connection, item_id: i64) -> Result<Option<(String, String)>> {
+    let recorded = conn
+        .query_row(
+            "SELECT hash, recorded_at FROM container_hashes WHERE item_id = ?1",
+            params![item_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(recorded)
+}
+
+/// List the IDs of all containers with a recorded hash.
+pub fn list_hashed_items(conn: &Connection) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare("SELECT item_id FROM container_hashes ORDER BY item_id")?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ids)
+}
+
+/// Add a name to a container's checklist. Returns false if it was already
+/// listed (names compare case-insensitively).
+pub fn add_checklist_entry(conn: &Connection, item_id: i64, name: &str) -> Result<bool> {
+    let added = conn.execute(
+        "INSERT OR IGNORE INTO checklist_entries (item_id, name) VALUES (?1, ?2)",
+        params![item_id, name],
+    )?;
+    Ok(added > 0)
+}
+
+/// Remove a name from a container's checklist. Returns false if it was not
+/// listed.
+pub fn remove_checklist_entry(conn: &Connection, item_id: i64, name: &str) -> Result<bool> {
+    let removed = conn.execute(
+        "DELETE FROM checklist_entries WHERE item_id = ?1 AND name = ?2",
+        params![item_id, name],
+    )?;
+    Ok(removed > 0)
+}
+
+/// A container's checklist, in the order entries were added.
+pub fn list_checklist(conn: &Connection, item_id: i64) -> Result<Vec<String>> {
+    let mut stmt =
+        conn.prepare("SELECT name FROM checklist_entries WHERE item_id = ?1 ORDER BY id")?;
+    let names = stmt
+        .query_map(params![item_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names)
+}
+
+/// Set an item's name in another language, replacing any previous one.
+pub fn set_item_name(conn: &Connection, item_id: i64, lang: &str, name: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO item_names (item_id, lang, name) VALUES (?1, ?2, ?3)
+         ON CONFLICT (item_id, lang) DO UPDATE SET name = excluded.name",
+        params![item_id, lang, name],
+    )?;
+    Ok(())
+}
+
+/// Remove an item's name in another language. Returns the removed name.
+pub fn remove_item_name(conn: &Connection, item_id: i64, lang: &str) -> Result<Option<String>> {
+    let name = get_item_name(conn, item_id, lang)?;
+    conn.execute(
+        "DELETE FROM item_names WHERE item_id = ?1 AND lang = ?2",
+        params![item_id, lang],
+    )?;
+    Ok(name)
+}
+
+/// An item's name in `lang`, if it has one.
+pub fn get_item_name(conn: &Connection, item_id: i64, lang: &str) -> Result<Option<String>> {
+    let name = conn
+        .query_row(
+            "SELECT name FROM item_names WHERE item_id = ?1 AND lang = ?2",
+            params![item_id, lang],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(name)
+}
+
+/// Show `item` under its name in `lang`, if it has one.
+pub fn localize_item(conn: &Connection, item: &mut Item, lang: Option<&str>) -> Result<()> {
+    let Some(lang) = lang else {
+        return Ok(());
+    };
+    if let Some(name) = get_item_name(conn, item.id, lang)? {
+        item.name = name;
+    }
+    Ok(())
+}
+
+/// An item's names in other languages as (lang, name), by language.
+pub fn list_item_names(conn: &Connection, item_id: i64) -> Result<Vec<(String, String)>> {
+    let mut stmt =
+        conn.prepare("SELECT lang, name FROM item_names WHERE item_id = ?1 ORDER BY lang")?;
+    let names = stmt
+        .query_map(params![item_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names)
+}
+
+/// Items known by `alias` (case-insensitive).
+pub fn find_items_by_alias(conn: &Connection, alias: &str) -> Result<Vec<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items
+         WHERE id IN (SELECT item_id FROM aliases WHERE alias = ?1)
+         ORDER BY id",
+    )?;
+    let items = stmt
+        .query_map(params![alias], item_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Give an item another name. Returns false if it already had it.
+pub fn add_alias(conn: &Connection, item_id: i64, alias: &str) -> Result<bool> {
+    let added = conn.execute(
+        "INSERT OR IGNORE INTO aliases (item_id, alias) VALUES (?1, ?2)",
+        params![item_id, alias],
+    )?;
+    Ok(added > 0)
+}
+
+/// Remove one of an item's aliases. Returns false if it did not have it.
+pub fn remove_alias(conn: &Connection, item_id: i64, alias: &str) -> Result<bool> {
+    let removed = conn.execute(
+        "DELETE FROM aliases WHERE item_id = ?1 AND alias = ?2",
+        params![item_id, alias],
+    )?;
+    Ok(removed > 0)
+}
+
+/// An item's aliases, alphabetically.
+pub fn list_aliases(conn: &Connection, item_id: i64) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT alias FROM aliases WHERE item_id = ?1 ORDER BY alias")?;
+    let aliases = stmt
+        .query_map(params![item_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(aliases)
+}
+
+/// Record storage conditions, replacing any of the same kind for the item.
+pub fn set_storage_conditions(conn: &Connection, conditions: &StorageConditions) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO storage_conditions
+            (item_id, kind, temp_min, temp_max, humidity_min, humidity_max, note)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            conditions.item_id,
+            conditions.kind.as_str(),
+            conditions.temp_min,
+            conditions.temp_max,
+            conditions.humidity_min,
+            conditions.humidity_max,
+            conditions.note,
+        ],
+    )?;
+    Ok(())
+}
+
+/// An item's storage conditions of one kind, if recorded.
+pub fn get_storage_conditions(
+    conn: &Connection,
+    item_id: i64,
+    kind: ConditionKind,
+) -> Result<Option<StorageConditions>> {
+    Ok(query_storage_conditions(
+        conn,
+        "WHERE item_id = ?1 AND kind = ?2",
+        params![item_id, kind.as_str()],
+    )?
+    .pop())
+}
+
+/// Remove all storage conditions of an item. Returns how many were removed.
+pub fn remove_storage_conditions(conn: &Connection, item_id: i64) -> Result<usize> {
+    let removed = conn.execute(
+        "DELETE FROM storage_conditions WHERE item_id = ?1",
+        params![item_id],
+    )?;
+    Ok(removed)
+}
+
+/// Every recorded storage condition, containers first.
+pub fn list_storage_conditions(conn: &Connection) -> Result<Vec<StorageConditions>> {
+    let mut all = query_storage_conditions(conn, "", [])?;
+    all.sort_by(|a, b| (a.kind.as_str(), &a.path).cmp(&(b.kind.as_str(), &b.path)));
+    Ok(all)
+}
+
+fn query_storage_conditions(
+    conn: &Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<StorageConditions>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT item_id, kind, temp_min, temp_max, humidity_min, humidity_max, note
+         FROM storage_conditions {}",
+        filter
+    ))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            let kind: String = row.get(1)?;
+            Ok(StorageConditions {
+                item_id: row.get(0)?,
+                path: Vec::new(),
+                kind: if kind == "requires" {
+                    ConditionKind::Requires
+                } else {
+                    ConditionKind::Provides
+                },
+                temp_min: row.get(2)?,
+                temp_max: row.get(3)?,
+                humidity_min: row.get(4)?,
+                humidity_max: row.get(5)?,
+                note: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rows.into_iter()
+        .map(|conditions| {
+            let path = get_item_path(conn, conditions.item_id)?;
+            Ok(StorageConditions { path, ..conditions })
+        })
+        .collect()
+}
+
+/// Add an open task about an item.
+pub fn insert_todo(conn: &Connection, item_id: i64, text: &str) -> Result<Todo> {
+    conn.execute(
+        "INSERT INTO todos (item_id, text) VALUES (?1, ?2)",
+        params![item_id, text],
+    )?;
+    get_todo(conn, conn.last_insert_rowid())?
+        .ok_or_else(|| anyhow!("Failed to retrieve inserted todo"))
+}
+
+/// Get a task by ID.
+pub fn get_todo(conn: &Connection, id: i64) -> Result<Option<Todo>> {
+    Ok(query_todos(conn, "WHERE id = ?1", params![id])?.pop())
+}
+
+/// Mark a task done now.
+pub fn complete_todo(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE todos SET done_at = datetime('now') WHERE id = ?1",
+        params![id],
+    )?;
+    Ok(())
+}
+
+/// Every task, oldest first; finished ones only with `include_done`.
+pub fn list_todos(conn: &Connection, include_done: bool) -> Result<Vec<Todo>> {
+    let filter = if include_done {
+        "ORDER BY id"
+    } else {
+        "WHERE done_at IS NULL ORDER BY id"
+    };
+    query_todos(conn, filter, [])
+}
+
+/// Number of open tasks about an item.
+pub fn count_open_todos(conn: &Connection, item_id: i64) -> Result<i64> {
+    let count = conn.query_row(
+        "SELECT COUNT(*) FROM todos WHERE item_id = ?1 AND done_at IS NULL",
+        params![item_id],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+fn query_todos(
+    conn: &Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<Todo>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, item_id, text, created_at, done_at FROM todos {}",
+        filter
+    ))?;
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok(Todo {
+                id: row.get(0)?,
+                item_id: row.get(1)?,
+                path: Vec::new(),
+                text: row.get(2)?,
+                created_at: row.get(3)?,
+                done_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rows.into_iter()
+        .map(|todo| {
+            let path = get_item_path(conn, todo.item_id)?;
+            Ok(Todo { path, ..todo })
+        })
+        .collect()
+}
+
+/// Find an item with the given name (case-insensitive) anywhere below a
+/// container, preferring the shallowest match.
+pub fn find_descendant_by_name(
+    conn: &Connection,
+    container_id: i64,
+    name: &str,
+) -> Result<Option<i64>> {
+    let id = conn
+        .query_row(
+            "WITH RECURSIVE below(id, name, depth) AS (
+                 SELECT id, name, 1 FROM items WHERE container_id = ?1
+                 UNION ALL
+                 SELECT items.id, items.name, below.depth + 1
+                 FROM items JOIN below ON items.container_id = below.id
+             )
+             SELECT id FROM below WHERE name = ?2 COLLATE NOCASE
+             ORDER BY depth, id LIMIT 1",
+            params![container_id, name],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(id)
+}
+
+/// Append an entry to the consumption log.
+///
+/// The name and path are copied so the entry outlives the item itself.
+pub fn record_consumption(
+    conn: &Connection,
+    item: &Item,
+    path: &[String],
+    taken: &Quantity,
+    remaining: i64,
+) -> Result<Consumption> {
+    conn.execute(
+        "INSERT INTO consumptions (item_id, name, path, quantity, remaining, unit)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            item.id,
+            item.name,
+            join_path(path),
+            taken.amount,
+            remaining,
+            taken.unit
+        ],
+    )?;
+
+    let id = conn.last_insert_rowid();
+    let consumed_at = conn.query_row(
+        "SELECT consumed_at FROM consumptions WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )?;
+    Ok(Consumption {
+        id,
+        item_id: Some(item.id),
+        name: item.name.clone(),
+        path: path.to_vec(),
+        quantity: taken.amount,
+        unit: taken.unit.clone(),
+        remaining: Some(remaining),
+        consumed_at,
+    })
+}
+
+/// List consumption log entries, newest first, optionally since a cutoff
+/// timestamp (`YYYY-MM-DD HH:MM:SS`, UTC).
+pub fn list_consumptions(conn: &Connection, since: Option<&str>) -> Result<Vec<Consumption>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, item_id, name, path, quantity, remaining, consumed_at, unit
+         FROM consumptions
+         WHERE ?1 IS NULL OR consumed_at >= ?1
+         ORDER BY consumed_at DESC, id DESC",
+    )?;
+
+    let entries = stmt
+        .query_map(params![since], |row| {
+            let path: String = row.get(3)?;
+            Ok(Consumption {
+                id: row.get(0)?,
+                item_id: row.get(1)?,
+                name: row.get(2)?,
+                path: split_path(&path),
+                quantity: row.get(4)?,
+                unit: row.get(7)?,
+                remaining: row.get(5)?,
+                consumed_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(entries)
+}
+
+/// Create a share token for an item's subtree.
+///
+/// `expires_at` is a SQLite timestamp, or `None` for a share that never
+/// expires.
+pub fn create_share(conn: &Connection, item_id: i64, expires_at: Option<&str>) -> Result<Share> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow!("Failed to generate share token: {}", e))?;
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    conn.execute(
+        "INSERT INTO shares (token, item_id, expires_at) VALUES (?1, ?2, ?3)",
+        params![token, item_id, expires_at],
+    )?;
+
+    list_shares(conn)?
+        .into_iter()
+        .find(|share| share.token == token)
+        .ok_or_else(|| anyhow!("Failed to retrieve created share"))
+}
+
+/// List all share tokens, newest first, including expired ones.
+pub fn list_shares(conn: &Connection) -> Result<Vec<Share>> {
+    let mut stmt = conn.prepare(
+        "SELECT token, item_id, created_at, expires_at,
+                expires_at IS NOT NULL AND expires_at <= datetime('now')
+         FROM shares
+         ORDER BY created_at DESC, rowid DESC",
+    )?;
+
+    let shares = stmt
+        .query_map([], |row| {
+            Ok(Share {
+                token: row.get(0)?,
+                item_id: row.get(1)?,
+                path: Vec::new(),
+                created_at: row.get(2)?,
+                expires_at: row.get(3)?,
+                expired: row.get(4)?,
+                url: None,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    shares
+        .into_iter()
+        .map(|share| {
+            let path = get_item_path(conn, share.item_id)?;
+            Ok(Share { path, ..share })
+        })
+        .collect()
+}
+
+/// Delete a share token. Returns whether it existed.
+pub fn delete_share(conn: &Connection, token: &str) -> Result<bool> {
+    let deleted = conn.execute("DELETE FROM shares WHERE token = ?1", params![token])?;
+    Ok(deleted > 0)
+}
+
+/// Delete share tokens past their expiry. Returns the number removed.
+pub fn delete_expired_shares(conn: &Connection) -> Result<usize> {
+    let deleted = conn.execute(
+        "DELETE FROM shares
+         WHERE expires_at IS NOT NULL AND expires_at <= datetime('now')",
+        [],
+    )?;
+    Ok(deleted)
+}
+
+/// Delete an item by ID.
+pub fn delete_item(conn: &Connection, item_id: i64) -> Result<()> {
+    conn.execute("DELETE FROM items WHERE id = ?1", params![item_id])?;
+    Ok(())
+}
+
+/// Check if an item is an ancestor of another item.
+pub fn is_ancestor(conn: &Connection, potential_ancestor_id: i64, item_id: i64) -> Result<bool> {
+    let found = conn.query_row(
+        "WITH RECURSIVE up(id) AS (
+             SELECT ?2
+             UNION
+             SELECT items.container_id FROM items JOIN up ON items.id = up.id
+             WHERE items.container_id IS NOT NULL
+         )
+         SELECT EXISTS (SELECT 1 FROM up WHERE id = ?1)",
+        params![potential_ancestor_id, item_id],
+        |row| row.get(0),
+    )?;
+    Ok(found)
+}
+
+/// Check if a name exists in a container.
+pub fn name_exists_in_container(
+    conn: &Connection,
+    name: &str,
+    container_id: Option<i64>,
+) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM items WHERE name = ?1 AND container_id IS ?2",
+        params![name, container_id],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Note that an item was looked up (see `invy optimize`).
+///
+/// Lookups are made by commands that otherwise only read, so this is best
+/// effort: a read-only database, or one another process is writing to, is
+/// left alone instead of failing the command or waiting for the lock.
+pub fn record_lookup(conn: &Connection, item_id: i64) -> Result<()> {
+    if conn.is_readonly(DatabaseName::Main)? {
+        return Ok(());
+    }
+    conn.busy_timeout(Duration::ZERO)?;
+    let result = conn.execute(
+        "INSERT INTO item_lookups (item_id) VALUES (?1)",
+        params![item_id],
+    );
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    match result {
+        Err(rusqlite::Error::SqliteFailure(e, _))
+            if matches!(
+                e.code,
+                ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked | ErrorCode::ReadOnly
+            ) =>
+        {
+            Ok(())
+        }
+        result => result.map(|_| ()).map_err(Into::into),
+    }
+}
+
+/// How many times an item was looked up, and when it last was.
+pub fn lookup_stats(conn: &Connection, item_id: i64) -> Result<(i64, Option<String>)> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*), MAX(looked_up_at) FROM item_lookups WHERE item_id = ?1",
+        params![item_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?)
+}
+
+/// How often each item was used since `since`: looked up, moved (not
+/// counting where it was first put) or consumed. Items never used are
+/// absent.
+pub fn usage_counts(conn: &Connection, since: &str) -> Result<HashMap<i64, i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT item_id, COUNT(*) FROM (
+             SELECT item_id FROM item_lookups WHERE looked_up_at >= ?1
+             UNION ALL
+             SELECT item_id FROM item_locations
+             WHERE moved_at >= ?1
+               AND id NOT IN (SELECT MIN(id) FROM item_locations GROUP BY item_id)
+             UNION ALL
+             SELECT item_id FROM consumptions WHERE consumed_at >= ?1 AND item_id IS NOT NULL
+         )
+         GROUP BY item_id",
+    )?;
+    let counts = stmt
+        .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(counts)
+}
+
+/// The item with this name directly in a container (at root when `None`).
+pub fn get_item_in_container(
+    conn: &Connection,
+    name: &str,
+    container_id: Option<i64>,
+) -> Result<Option<Item>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, container_id, created_at, updated_at, quantity, unit, uuid, kind
+         FROM items WHERE name = ?1 AND container_id IS ?2",
+    )?;
+    Ok(stmt
+        .query_row(params![name, container_id], item_from_row)
+        .optional()?)
+}
+
+/// Create each missing level of `path`, anchored at the root, as a
+/// container (for `invy mkdir`). Returns the item at the end of the path
+/// and the levels that were created, outermost first.
+pub fn create_container_path(conn: &Connection, path: &str) -> Result<(Item, Vec<Item>)> {
+    let mut current: Option<Item> = None;
+    let mut created = Vec::new();
+    for part in split_path(path) {
+        let container_id = current.as_ref().map(|item| item.id);
+        current = Some(match get_item_in_container(conn, &part, container_id)? {
+            Some(item) => item,
+            None => {
+                let item = insert_container(conn, &part, container_id)?;
+                created.push(item.clone());
+                item
+            }
+        });
+    }
+    let item = current.ok_or_else(|| anyhow!("container path is empty"))?;
+    Ok((item, created))
+}
+
+/// Get or create a container by name (at root level).
+#[allow(dead_code)]
+pub fn get_or_create_container(conn: &Connection, name: &str) -> Result<Item> {
+    // First try to find existing
+    if let Some(item) = get_item_by_path(conn, name)? {
+        return Ok(item);
+    }
+
+    // Create new container
+    insert_item(conn, name, None, None)
+}
+
+/// The warning for an item put inside a plain item rather than a container
+/// or location (see `ItemKind`).
+pub fn plain_container_warning(container: &str) -> String {
+    format!(
+        "'{}' is a plain item, not a container; mark it with `invy edit '{}' --kind container`",
+        container,
+        escape_name(container)
+    )
+}
+
+/// How a container that things are put in is found: whether a missing one
+/// is created, and whether strict mode refuses to guess.
+#[derive(Debug, Clone, Copy)]
+pub struct Placement {
+    /// Create missing containers (off with `--no-create`)
+    pub auto_create: bool,
+    /// Refuse to create containers or to put things in plain items
+    /// (`--strict`)
+    pub strict: bool,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            auto_create: true,
+            strict: false,
+        }
+    }
+}
+
+/// Check a container things are about to be put in. A plain item is refused
+/// in strict mode; otherwise the warning to show is returned.
+pub fn check_container_kind(container: &Item, placement: Placement) -> Result<Option<String>> {
+    if container.kind.holds_items() {
+        return Ok(None);
+    }
+    let warning = plain_container_warning(&container.name);
+    strict::check_when(placement.strict, || warning.clone())?;
+    Ok(Some(warning))
+}
+
+/// Resolve (or create) the destination container, rejecting a move of
+/// `item` into itself or its descendants. `what` names the thing being
+/// moved in that error. `None` is the root. Moving into a plain item is
+/// refused in strict mode and otherwise warned about as a notice.
+pub fn resolve_destination(
+    conn: &Connection,
+    destination: &str,
+    item: &Item,
+    what: &str,
+    placement: Placement,
+) -> Result<Option<i64>> {
+    if destination == "/" || destination == "root" {
+        return Ok(None);
+    }
+
+    let container = resolve_or_create_container(conn, destination, placement)?;
+
+    // Check for circular reference
+    if container.id == item.id || is_ancestor(conn, item.id, container.id)? {
+        return Err(anyhow!(
+            "cannot move {} into itself or its descendants",
+            what
+        ));
+    }
+    if let Some(warning) = check_container_kind(&container, placement)? {
+        notice::emit(&format!("Warning: {}", warning));
+    }
+
+    Ok(Some(container.id))
+}
+
+/// A destination as shown in errors: `(root)` for the root.
+pub fn destination_label(destination: &str) -> String {
+    if destination == "/" || destination == "root" {
+        "(root)".to_string()
+    } else {
+        destination.to_string()
+    }
+}
+
+/// The existing container whose path (for a path reference) or name is
+/// closest to `reference`, as a path.
+fn closest_container(conn: &Connection, reference: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT items.name, item_paths.path FROM items
+         JOIN item_paths ON item_paths.item_id = items.id
+         WHERE items.kind != 'item' OR items.id IN (SELECT container_id FROM items)",
+    )?;
+    let containers = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let closest = if is_path(reference) {
+        let wanted = join_path(&split_path(reference));
+        fuzzy::closest(&wanted, containers.iter().map(|(_, path)| path.as_str()))
+    } else {
+        let wanted = unescape_name(reference);
+        fuzzy::closest(&wanted, containers.iter().map(|(name, _)| name.as_str())).and_then(|name| {
+            containers
+                .iter()
+                .find(|(other, _)| other == name)
+                .map(|(_, path)| path.as_str())
+        })
+    };
+    Ok(closest.map(String::from))
+}
+
+/// Resolve a container reference, creating it if necessary. Without
+/// `auto_create` a missing container is an error naming the closest
+/// existing one.
+pub fn resolve_or_create_container(
+    conn: &Connection,
+    reference: &str,
+    placement: Placement,
+) -> Result<Item> {
+    // An ID or UUID names an existing item; there is nothing to create
+    if parse_id_ref(reference).is_some() || parse_uuid_ref(reference).is_some() {
+        return resolve_item(conn, reference)?
+            .ok_or_else(|| anyhow!("container '{}' not found", reference));
+    }
+
+    // First try to resolve existing; strict mode and --no-create report an
+    // ambiguous name instead of creating yet another container
+    let Placement {
+        auto_create,
+        strict,
+    } = placement;
+    match resolve_item(conn, reference) {
+        Ok(Some(item)) => return Ok(item),
+        Err(err) if strict || !auto_create => return Err(err),
+        _ => {}
+    }
+    strict::check_when(strict, || {
+        format!(
+            "container '{}' not found; create it first with `invy add --kind container`",
+            reference
+        )
+    })?;
+    if !auto_create {
+        let mut message = format!("container '{}' not found", reference);
+        if let Some(path) = closest_container(conn, reference)? {
+            message.push_str(&format!("; did you mean '{}'?", path));
+        }
+        return Err(anyhow!(message));
+    }
+
+    // If it's a path, we need to create the hierarchy
+    if is_path(reference) {
+        Ok(create_container_path(conn, reference)?.0)
+    } else {
+        // Simple name - create at root
+        insert_container(conn, &unescape_name(reference), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("in-memory database");
+        init(&conn).expect("init");
+        conn
+    }
+
+    /// Build garage/toolbox/hammer and return (garage, toolbox, hammer).
+    fn seed(conn: &Connection) -> (Item, Item, Item) {
+        let garage = insert_item(conn, "garage", None, None).unwrap();
+        let toolbox = insert_item(conn, "toolbox", None, Some(garage.id)).unwrap();
+        let hammer = insert_item(conn, "hammer", Some("claw"), Some(toolbox.id)).unwrap();
+        (garage, toolbox, hammer)
+    }
+
+    #[test]
+    fn views_report_paths_and_totals() {
+        let conn = test_conn();
+        let (garage, _, hammer) = seed(&conn);
+        update_item_quantity(&conn, hammer.id, &Quantity::count(3)).unwrap();
+
+        assert!(view_status(&conn)
+            .unwrap()
+            .iter()
+            .all(|v| v.status == ViewStatus::Missing));
+        install_views(&conn).unwrap();
+        assert!(view_status(&conn)
+            .unwrap()
+            .iter()
+            .all(|v| v.status == ViewStatus::Ok));
+
+        let path: String = conn
+            .query_row(
+                "SELECT path FROM items_with_paths WHERE id = ?1",
+                [hammer.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(path, "garage/toolbox/hammer");
+        let (descendants, total): (i64, i64) = conn
+            .query_row(
+                "SELECT descendant_count, total_quantity FROM container_totals WHERE id = ?1",
+                [garage.id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((descendants, total), (2, 4));
+
+        update_item_expiry(&conn, hammer.id, Some("2000-01-01")).unwrap();
+        let (name, days_left): (String, i64) = conn
+            .query_row("SELECT name, days_left FROM expiring_soon", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(name, "hammer");
+        assert!(days_left < 0);
+
+        // A definition from another version is stale until reinstalled
+        conn.execute_batch(
+            "DROP VIEW container_totals;
+             CREATE VIEW container_totals AS SELECT id FROM items;",
+        )
+        .unwrap();
+        let status = view_status(&conn).unwrap();
+        assert_eq!(status[1].status, ViewStatus::Stale);
+        install_views(&conn).unwrap();
+        assert_eq!(view_status(&conn).unwrap()[1].status, ViewStatus::Ok);
+    }
+
+    #[test]
+    fn fresh_database_is_at_latest_version() {
+        let conn = test_conn();
+        assert_eq!(schema_version(&conn).unwrap(), LATEST_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn migrate_stepwise_and_refuse_downgrade() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert_eq!(count_items(&conn).unwrap(), 0);
+
+        migrate_to(&conn, 1).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), 1);
+        assert!(get_setting(&conn, "context").is_err());
+
+        migrate_to(&conn, LATEST_SCHEMA_VERSION).unwrap();
+        assert!(get_setting(&conn, "context").unwrap().is_none());
+
+        assert!(migrate_to(&conn, 1).is_err());
+        assert!(migrate_to(&conn, LATEST_SCHEMA_VERSION + 1).is_err());
+    }
+
+    #[test]
+    fn unversioned_legacy_schema_migrates() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.execute("INSERT INTO items (name) VALUES ('hammer')", [])
+            .unwrap();
+
+        init(&conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), LATEST_SCHEMA_VERSION);
+        assert_eq!(count_items(&conn).unwrap(), 1);
+    }
+
+    #[test]
+    fn with_tx_commits_on_success() {
+        let conn = test_conn();
+        with_tx(&conn, |tx| insert_item(tx, "hammer", None, None)).unwrap();
+        assert_eq!(count_items(&conn).unwrap(), 1);
+    }
+
+    #[test]
+    fn with_tx_rolls_back_on_error() {
+        let conn = test_conn();
+        let result: Result<()> = with_tx(&conn, |tx| {
+            resolve_or_create_container(tx, "garage/shelf/bin", Placement::default())?;
+            Err(anyhow!("boom"))
+        });
+        assert!(result.is_err());
+        assert_eq!(count_items(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn insert_items_writes_all_or_nothing() {
+        let conn = test_conn();
+        let (_, toolbox, hammer) = seed(&conn);
+
+        let nails = NewItem {
+            quantity: Quantity::parse("250g").unwrap(),
+            ..NewItem::new("nails", Some(toolbox.id))
+        };
+        let items = insert_items(&conn, &[nails, NewItem::new("saw", Some(toolbox.id))]).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].unit.as_deref(), Some("g"));
+        assert!(items[1].uuid.is_some());
+        assert_eq!(
+            list_locations(&conn, items[1].id).unwrap()[0].path,
+            ["garage", "toolbox"]
+        );
+
+        // The second row clashes with the hammer, so the first is not kept
+        let clash = [
+            NewItem::new("drill", None),
+            NewItem::new(&hammer.name, Some(toolbox.id)),
+        ];
+        assert!(insert_items(&conn, &clash).is_err());
+        assert_eq!(count_items(&conn).unwrap(), 5);
+
+        // Inside a caller's transaction the rows commit with it
+        with_tx(&conn, |tx| insert_items(tx, &[NewItem::new("drill", None)])).unwrap();
+        assert_eq!(count_items(&conn).unwrap(), 6);
+    }
+
+    #[test]
+    fn move_and_delete_items_in_bulk() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+        let saw = insert_item(&conn, "saw", None, Some(toolbox.id)).unwrap();
+
+        move_items(&conn, &[hammer.id, saw.id], Some(garage.id)).unwrap();
+        assert_eq!(list_items_in_container(&conn, toolbox.id).unwrap().len(), 0);
+        assert_eq!(list_locations(&conn, saw.id).unwrap().len(), 2);
+
+        delete_items(&conn, &[hammer.id, saw.id]).unwrap();
+        assert_eq!(count_items(&conn).unwrap(), 2);
+    }
+
+    #[test]
+    fn consumption_outlives_deleted_item() {
+        let conn = test_conn();
+        let (_, _, hammer) = seed(&conn);
+        let path = get_item_path(&conn, hammer.id).unwrap();
+
+        record_consumption(&conn, &hammer, &path, &Quantity::count(1), 0).unwrap();
+        delete_item(&conn, hammer.id).unwrap();
+
+        let entries = list_consumptions(&conn, None).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].item_id, None);
+        assert_eq!(entries[0].path, ["garage", "toolbox", "hammer"]);
+        assert!(list_consumptions(&conn, Some("2999-01-01 00:00:00"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn stored_paths_keep_slashes_in_names() {
+        let conn = test_conn();
+        let (garage, _, hammer) = seed(&conn);
+        let acdc = insert_item(&conn, "AC/DC", None, Some(garage.id)).unwrap();
+
+        move_item(&conn, hammer.id, Some(acdc.id)).unwrap();
+        let locations = list_locations(&conn, hammer.id).unwrap();
+        assert_eq!(locations[0].path, ["garage", "AC/DC"]);
+
+        let hammer = get_item_by_id(&conn, hammer.id).unwrap().unwrap();
+        let path = get_item_path(&conn, hammer.id).unwrap();
+        record_consumption(&conn, &hammer, &path, &Quantity::count(1), 0).unwrap();
+        let entries = list_consumptions(&conn, None).unwrap();
+        assert_eq!(entries[0].path, ["garage", "AC/DC", "hammer"]);
+
+        let flat = insert_item(&conn, "flat", None, None).unwrap();
+        insert_item(&conn, "a/b", None, Some(flat.id)).unwrap();
+        let nested = insert_item(&conn, "nested", None, None).unwrap();
+        let a = insert_item(&conn, "a", None, Some(nested.id)).unwrap();
+        insert_item(&conn, "b", None, Some(a.id)).unwrap();
+        assert_ne!(
+            hash_contents(&conn, flat.id).unwrap(),
+            hash_contents(&conn, nested.id).unwrap()
+        );
+    }
+
+    #[test]
+    fn moves_are_recorded_in_location_history() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+
+        move_item(&conn, hammer.id, Some(garage.id)).unwrap();
+        move_item(&conn, hammer.id, Some(garage.id)).unwrap();
+        move_item(&conn, hammer.id, None).unwrap();
+
+        let paths: Vec<String> = list_locations(&conn, hammer.id)
+            .unwrap()
+            .into_iter()
+            .map(|l| l.path.join("/"))
+            .collect();
+        assert_eq!(paths, ["", "garage", "garage/toolbox"]);
+        assert_eq!(list_locations(&conn, toolbox.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn location_history_is_seeded_from_current_containers() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 3).unwrap();
+        conn.execute_batch(
+            "INSERT INTO items (id, name, container_id) VALUES (1, 'garage', NULL);
+             INSERT INTO items (id, name, container_id) VALUES (2, 'toolbox', 1);
+             INSERT INTO items (id, name, container_id) VALUES (3, 'hammer', 2);",
+        )
+        .unwrap();
+        migrate_to(&conn, 4).unwrap();
+
+        let hammer = list_locations(&conn, 3).unwrap();
+        assert_eq!(hammer.len(), 1);
+        assert_eq!(hammer[0].path, ["garage", "toolbox"]);
+        assert!(list_locations(&conn, 1).unwrap()[0].path.is_empty());
+    }
+
+    #[test]
+    fn shares_expire_and_follow_their_item() {
+        let conn = test_conn();
+        let (garage, toolbox, _) = seed(&conn);
+
+        let live = create_share(&conn, toolbox.id, Some("2999-01-01 00:00:00")).unwrap();
+        let old = create_share(&conn, garage.id, Some("2000-01-01 00:00:00")).unwrap();
+        assert_eq!(live.token.len(), 32);
+        assert_ne!(live.token, old.token);
+        assert_eq!(live.path, ["garage", "toolbox"]);
+        assert!(!live.expired);
+        assert!(old.expired);
+
+        delete_item(&conn, toolbox.id).unwrap();
+        let remaining = list_shares(&conn).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert!(delete_share(&conn, &old.token).unwrap());
+        assert!(!delete_share(&conn, &old.token).unwrap());
+    }
+
+    #[test]
+    fn foreign_keys_orphan_children_on_delete() {
+        let conn = test_conn();
+        let (_, toolbox, hammer) = seed(&conn);
+
+        delete_item(&conn, toolbox.id).unwrap();
+        let hammer = get_item_by_id(&conn, hammer.id).unwrap().unwrap();
+        assert_eq!(hammer.container_id, None);
+    }
+
+    #[test]
+    fn concurrent_writer_waits_for_lock() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("test.db");
+        let first = open(Some(&path)).unwrap();
+        let second = open(Some(&path)).unwrap();
+        assert_eq!(journal_mode(&first).unwrap(), "wal");
+
+        first.execute_batch("BEGIN IMMEDIATE").unwrap();
+        insert_item(&first, "hammer", None, None).unwrap();
+
+        let writer =
+            std::thread::spawn(move || insert_item(&second, "saw", None, None).map(|item| item.id));
+        std::thread::sleep(Duration::from_millis(200));
+        first.execute_batch("COMMIT").unwrap();
+
+        assert!(writer.join().unwrap().is_ok());
+        assert_eq!(count_items(&first).unwrap(), 2);
+    }
+
+    #[test]
+    fn path_resolves_full_path() {
+        let conn = test_conn();
+        let (_, _, hammer) = seed(&conn);
+
+        let found = get_item_by_path(&conn, "garage/toolbox/hammer").unwrap();
+        assert_eq!(found.map(|i| i.id), Some(hammer.id));
+    }
+
+    #[test]
+    fn path_ignores_empty_segments() {
+        let conn = test_conn();
+        let (_, toolbox, hammer) = seed(&conn);
+
+        let found = get_item_by_path(&conn, "garage//toolbox///hammer").unwrap();
+        assert_eq!(found.map(|i| i.id), Some(hammer.id));
+
+        let found = get_item_by_path(&conn, "/garage/toolbox").unwrap();
+        assert_eq!(found.map(|i| i.id), Some(toolbox.id));
+    }
+
+    #[test]
+    fn path_ignores_trailing_slash() {
+        let conn = test_conn();
+        let (_, toolbox, _) = seed(&conn);
+
+        let found = get_item_by_path(&conn, "garage/toolbox/").unwrap();
+        assert_eq!(found.map(|i| i.id), Some(toolbox.id));
+    }
+
+    #[test]
+    fn path_of_only_slashes_is_none() {
+        let conn = test_conn();
+        seed(&conn);
+
+        assert!(get_item_by_path(&conn, "/").unwrap().is_none());
+        assert!(get_item_by_path(&conn, "///").unwrap().is_none());
+    }
+
+    #[test]
+    fn path_is_anchored_at_root() {
+        let conn = test_conn();
+        seed(&conn);
+
+        // "toolbox" lives inside garage, so a path starting at it does not match
+        assert!(get_item_by_path(&conn, "toolbox/hammer").unwrap().is_none());
+    }
+
+    #[test]
+    fn path_with_ambiguous_intermediate_uses_full_path() {
+        let conn = test_conn();
+        let garage = insert_item(&conn, "garage", None, None).unwrap();
+        let attic = insert_item(&conn, "attic", None, None).unwrap();
+        let box_a = insert_item(&conn, "box", None, Some(garage.id)).unwrap();
+        let box_b = insert_item(&conn, "box", None, Some(attic.id)).unwrap();
+        let tape_a = insert_item(&conn, "tape", None, Some(box_a.id)).unwrap();
+        let tape_b = insert_item(&conn, "tape", None, Some(box_b.id)).unwrap();
+
+        let a = get_item_by_path(&conn, "garage/box/tape").unwrap();
+        let b = get_item_by_path(&conn, "attic/box/tape").unwrap();
+        assert_eq!(a.map(|i| i.id), Some(tape_a.id));
+        assert_eq!(b.map(|i| i.id), Some(tape_b.id));
+
+        // The bare intermediate name is ambiguous
+        let err = resolve_item(&conn, "box").unwrap_err();
+        assert!(err.to_string().contains("ambiguous"));
+        assert!(err.to_string().contains("garage/box"));
+        assert!(err.to_string().contains("attic/box"));
+    }
+
+    #[test]
+    fn path_missing_segment_is_none() {
+        let conn = test_conn();
+        seed(&conn);
+
+        assert!(get_item_by_path(&conn, "garage/shelf/hammer")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn context_breaks_ambiguity() {
+        let conn = test_conn();
+        let garage = insert_item(&conn, "garage", None, None).unwrap();
+        let attic = insert_item(&conn, "attic", None, None).unwrap();
+        let box_a = insert_item(&conn, "box", None, Some(garage.id)).unwrap();
+        insert_item(&conn, "box", None, Some(attic.id)).unwrap();
+
+        set_context(&conn, Some(garage.id)).unwrap();
+        let found = resolve_item(&conn, "box").unwrap();
+        assert_eq!(found.map(|i| i.id), Some(box_a.id));
+
+        // Outside any single match the error remains
+        set_context(&conn, None).unwrap();
+        assert!(resolve_item(&conn, "box").is_err());
+    }
+
+    #[test]
+    fn context_of_removed_item_is_ignored() {
+        let conn = test_conn();
+        let garage = insert_item(&conn, "garage", None, None).unwrap();
+        set_context(&conn, Some(garage.id)).unwrap();
+        delete_item(&conn, garage.id).unwrap();
+
+        assert!(get_context(&conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn resolve_bare_name_unique() {
+        let conn = test_conn();
+        let (_, _, hammer) = seed(&conn);
+
+        let found = resolve_item(&conn, "hammer").unwrap();
+        assert_eq!(found.map(|i| i.id), Some(hammer.id));
+        assert!(resolve_item(&conn, "wrench").unwrap().is_none());
+    }
+
+    #[test]
+    fn item_path_walks_to_root() {
+        let conn = test_conn();
+        let (_, _, hammer) = seed(&conn);
+
+        assert_eq!(
+            get_item_path(&conn, hammer.id).unwrap(),
+            vec!["garage", "toolbox", "hammer"]
+        );
+    }
+
+    #[test]
+    fn subtree_queries_cover_every_level() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+        set_item_name(&conn, toolbox.id, "de", "Werkzeugkasten").unwrap();
+
+        assert_eq!(
+            get_item_path_in(&conn, hammer.id, Some("de")).unwrap(),
+            vec!["garage", "Werkzeugkasten", "hammer"]
+        );
+        assert_eq!(
+            get_ancestor_ids(&conn, hammer.id).unwrap(),
+            [toolbox.id, garage.id]
+        );
+        assert_eq!(count_descendants(&conn, garage.id).unwrap(), 2);
+        assert_eq!(count_descendants(&conn, hammer.id).unwrap(), 0);
+        assert!(get_item_path(&conn, 999).unwrap().is_empty());
+    }
+
+    #[test]
+    fn child_counts_match_count_children() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+        insert_item(&conn, "saw", None, Some(toolbox.id)).unwrap();
+
+        let counts = child_counts(&conn).unwrap();
+        for item in [&garage, &toolbox, &hammer] {
+            let expected = count_children(&conn, item.id).unwrap();
+            assert_eq!(counts.get(&item.id).copied().unwrap_or(0), expected);
+        }
+        assert_eq!(counts[&toolbox.id], 2);
+    }
+
+    #[test]
+    fn stored_paths_follow_renames_moves_and_deletes() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+        let shed = insert_item(&conn, "shed", None, None).unwrap();
+
+        update_item_name(&conn, toolbox.id, "A/V box").unwrap();
+        assert_eq!(
+            get_item_path(&conn, hammer.id).unwrap(),
+            vec!["garage", "A/V box", "hammer"]
+        );
+        assert_eq!(
+            get_item_by_path(&conn, "garage/A\\/V box/hammer")
+                .unwrap()
+                .map(|i| i.id),
+            Some(hammer.id)
+        );
+
+        move_item(&conn, toolbox.id, Some(shed.id)).unwrap();
+        assert_eq!(
+            get_item_path(&conn, hammer.id).unwrap(),
+            vec!["shed", "A/V box", "hammer"]
+        );
+        assert!(get_item_by_path(&conn, "garage/A\\/V box")
+            .unwrap()
+            .is_none());
+
+        // Deleting a container orphans its contents to the root
+        delete_item(&conn, shed.id).unwrap();
+        assert_eq!(
+            get_item_path(&conn, hammer.id).unwrap(),
+            vec!["A/V box", "hammer"]
+        );
+        assert_eq!(get_item_path(&conn, garage.id).unwrap(), vec!["garage"]);
+    }
+
+    #[test]
+    fn ancestor_checks() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+        let kitchen = insert_item(&conn, "kitchen", None, None).unwrap();
+
+        assert!(is_ancestor(&conn, garage.id, hammer.id).unwrap());
+        assert!(is_ancestor(&conn, toolbox.id, hammer.id).unwrap());
+        assert!(is_ancestor(&conn, hammer.id, hammer.id).unwrap());
+        assert!(!is_ancestor(&conn, hammer.id, garage.id).unwrap());
+        assert!(!is_ancestor(&conn, kitchen.id, hammer.id).unwrap());
+    }
+
+    #[test]
+    fn unique_index_rejects_duplicate_at_root() {
+        let conn = test_conn();
+        insert_item(&conn, "garage", None, None).unwrap();
+
+        assert!(insert_item(&conn, "garage", None, None).is_err());
+    }
+
+    #[test]
+    fn unique_index_rejects_duplicate_in_container() {
+        let conn = test_conn();
+        let (_, toolbox, _) = seed(&conn);
+
+        assert!(insert_item(&conn, "hammer", None, Some(toolbox.id)).is_err());
+    }
+
+    #[test]
+    fn unique_index_allows_same_name_in_different_containers() {
+        let conn = test_conn();
+        let (garage, _, _) = seed(&conn);
+
+        assert!(insert_item(&conn, "hammer", None, Some(garage.id)).is_ok());
+        assert!(insert_item(&conn, "hammer", None, None).is_ok());
+    }
+
+    #[test]
+    fn name_exists_checks_container_scope() {
+        let conn = test_conn();
+        let (garage, toolbox, _) = seed(&conn);
+
+        assert!(name_exists_in_container(&conn, "hammer", Some(toolbox.id)).unwrap());
+        assert!(!name_exists_in_container(&conn, "hammer", Some(garage.id)).unwrap());
+        assert!(name_exists_in_container(&conn, "garage", None).unwrap());
+    }
+
+    #[test]
+    fn resolve_or_create_builds_missing_levels() {
+        let conn = test_conn();
+        seed(&conn);
+
+        let bin =
+            resolve_or_create_container(&conn, "garage/shelf/bin", Placement::default()).unwrap();
+        assert_eq!(
+            get_item_path(&conn, bin.id).unwrap(),
+            vec!["garage", "shelf", "bin"]
+        );
+
+        // Existing levels are reused rather than duplicated
+        let again =
+            resolve_or_create_container(&conn, "garage/shelf/bin", Placement::default()).unwrap();
+        assert_eq!(again.id, bin.id);
+    }
+
+    #[test]
+    fn lookups_skip_read_only_databases() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invy.db");
+        let conn = open(Some(&path)).unwrap();
+        let hammer = insert_item(&conn, "hammer", None, None).unwrap();
+        record_lookup(&conn, hammer.id).unwrap();
+
+        let read_only =
+            Connection::open_with_flags(&path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+        record_lookup(&read_only, hammer.id).unwrap();
+        let lookups: i64 = conn
+            .query_row("SELECT COUNT(*) FROM item_lookups", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(lookups, 1);
+    }
+
+    #[test]
+    fn description_lookup_matches_wildcards_literally() {
+        let conn = test_conn();
+        let drill = insert_item(&conn, "drill", Some("charged 100%"), None).unwrap();
+        insert_item(&conn, "saw", Some("charged 1005 times"), None).unwrap();
+        insert_item(&conn, "plane", Some("a_b"), None).unwrap();
+
+        let found = get_item_by_description(&conn, "100%").unwrap().unwrap();
+        assert_eq!(found.id, drill.id);
+        assert!(get_item_by_description(&conn, "a%b").unwrap().is_none());
+        assert!(get_item_by_description(&conn, "a_c").unwrap().is_none());
+    }
+
+    #[test]
+    fn content_hash_ignores_order_and_container_label() {
+        let conn = test_conn();
+        let a = insert_item(&conn, "box-a", Some("blue"), None).unwrap();
+        let b = insert_item(&conn, "box-b", Some("red"), None).unwrap();
+        insert_item(&conn, "tape", None, Some(a.id)).unwrap();
+        insert_item(&conn, "glue", Some("wood"), Some(a.id)).unwrap();
+        insert_item(&conn, "glue", Some("wood"), Some(b.id)).unwrap();
+        insert_item(&conn, "tape", None, Some(b.id)).unwrap();
+
+        assert_eq!(
+            hash_contents(&conn, a.id).unwrap(),
+            hash_contents(&conn, b.id).unwrap()
+        );
+    }
+
+    #[test]
+    fn content_hash_changes_with_contents() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+        let before = hash_contents(&conn, garage.id).unwrap();
+
+        update_item_description(&conn, hammer.id, Some("ball peen")).unwrap();
+        let after_desc = hash_contents(&conn, garage.id).unwrap();
+        assert_ne!(before, after_desc);
+
+        update_item_quantity(&conn, hammer.id, &Quantity::count(2)).unwrap();
+        let after_qty = hash_contents(&conn, garage.id).unwrap();
+        assert_ne!(after_desc, after_qty);
+
+        set_item_kind(&conn, hammer.id, ItemKind::Container).unwrap();
+        let after_kind = hash_contents(&conn, garage.id).unwrap();
+        assert_ne!(after_qty, after_kind);
+
+        move_item(&conn, hammer.id, Some(garage.id)).unwrap();
+        assert_ne!(after_kind, hash_contents(&conn, garage.id).unwrap());
+        assert_ne!(before, hash_contents(&conn, toolbox.id).unwrap());
+    }
+
+    #[test]
+    fn database_rejects_container_cycles() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+
+        let err = move_item(&conn, garage.id, Some(hammer.id)).unwrap_err();
+        assert!(err.to_string().contains("its own contents"), "{err}");
+        assert!(move_item(&conn, toolbox.id, Some(toolbox.id)).is_err());
+        assert!(conn
+            .execute(
+                "INSERT INTO items (id, name, container_id) VALUES (99, 'loop', 99)",
+                [],
+            )
+            .is_err());
+
+        // Moving within the tree is still fine
+        move_item(&conn, hammer.id, Some(garage.id)).unwrap();
+    }
+
+    #[test]
+    fn migration_breaks_existing_cycles() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 8).unwrap();
+        conn.execute_batch(
+            "INSERT INTO items (id, name, container_id) VALUES
+                 (1, 'a', NULL), (2, 'b', 3), (3, 'c', 4), (4, 'd', 2), (5, 'e', 4);",
+        )
+        .unwrap();
+
+        migrate_to(&conn, LATEST_SCHEMA_VERSION).unwrap();
+        let parents: Vec<(i64, Option<i64>)> = conn
+            .prepare("SELECT id, container_id FROM items ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            parents,
+            [
+                (1, None),
+                (2, None),
+                (3, Some(4)),
+                (4, Some(2)),
+                (5, Some(4))
+            ]
+        );
+    }
+
+    #[test]
+    fn migration_marks_items_holding_others_as_containers() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 15).unwrap();
+        conn.execute_batch(
+            "INSERT INTO items (id, name, container_id) VALUES
+                 (1, 'garage', NULL), (2, 'toolbox', 1), (3, 'hammer', 2), (4, 'saw', NULL);",
+        )
+        .unwrap();
+
+        migrate_to(&conn, LATEST_SCHEMA_VERSION).unwrap();
+        let kinds: Vec<ItemKind> = (1..=4)
+            .map(|id| get_item_by_id(&conn, id).unwrap().unwrap().kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ItemKind::Container,
+                ItemKind::Container,
+                ItemKind::Item,
+                ItemKind::Item
+            ]
+        );
+
+        let shelf =
+            resolve_or_create_container(&conn, "attic/shelf", Placement::default()).unwrap();
+        assert_eq!(shelf.kind, ItemKind::Container);
+        let attic = get_item_by_path(&conn, "attic").unwrap().unwrap();
+        assert_eq!(attic.kind, ItemKind::Container);
+    }
+
+    #[test]
+    fn checklist_matches_nested_items_by_name() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+
+        assert!(add_checklist_entry(&conn, garage.id, "Hammer").unwrap());
+        assert!(!add_checklist_entry(&conn, garage.id, "hammer").unwrap());
+        add_checklist_entry(&conn, garage.id, "saw").unwrap();
+        assert_eq!(list_checklist(&conn, garage.id).unwrap(), ["Hammer", "saw"]);
+
+        assert_eq!(
+            find_descendant_by_name(&conn, garage.id, "HAMMER").unwrap(),
+            Some(hammer.id)
+        );
+        assert_eq!(
+            find_descendant_by_name(&conn, garage.id, "saw").unwrap(),
+            None
+        );
+        // The container itself does not count
+        assert_eq!(
+            find_descendant_by_name(&conn, toolbox.id, "toolbox").unwrap(),
+            None
+        );
+
+        assert!(remove_checklist_entry(&conn, garage.id, "SAW").unwrap());
+        assert_eq!(list_checklist(&conn, garage.id).unwrap(), ["Hammer"]);
+    }
+
+    #[test]
+    fn id_refs_resolve_by_id() {
+        let conn = test_conn();
+        let (_, _, hammer) = seed(&conn);
+
+        assert_eq!(parse_id_ref("#3"), Some(3));
+        assert_eq!(parse_id_ref("#"), None);
+        assert_eq!(parse_id_ref("#-3"), None);
+        assert_eq!(parse_id_ref("#3a"), None);
+        assert_eq!(parse_id_ref("3"), None);
+        let found = resolve_item(&conn, &format!("#{}", hammer.id)).unwrap();
+        assert_eq!(found.map(|i| i.id), Some(hammer.id));
+        assert!(resolve_item(&conn, "#999").unwrap().is_none());
+    }
+
+    #[test]
+    fn escaped_slashes_stay_in_names() {
+        let conn = test_conn();
+        let office = insert_item(&conn, "office", None, None).unwrap();
+        let cables = insert_item(&conn, "A/V cables", None, Some(office.id)).unwrap();
+
+        assert!(is_path("office/A\\/V cables"));
+        assert!(!is_path("A\\/V cables"));
+        assert!(is_path("back\\\\/slash"));
+        assert_eq!(split_path("office/A\\/V cables"), ["office", "A/V cables"]);
+        assert_eq!(split_path("/a\\\\/b/"), ["a\\", "b"]);
+        assert_eq!(unescape_name("A\\/V"), "A/V");
+        assert_eq!(unescape_name("C:\\temp"), "C:\\temp");
+        let names = vec!["a\\b".to_string(), "A/V".to_string()];
+        assert_eq!(split_path(&join_path(&names)), names);
+
+        for reference in ["A\\/V cables", "office/A\\/V cables"] {
+            let found = resolve_item(&conn, reference).unwrap();
+            assert_eq!(found.map(|i| i.id), Some(cables.id));
+        }
+        assert!(resolve_item(&conn, "A/V cables").unwrap().is_none());
+    }
+
+    #[test]
+    fn uuid_refs_resolve_by_prefix() {
+        let conn = test_conn();
+        let (garage, _, hammer) = seed(&conn);
+        let uuid = hammer.uuid.clone().unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert_ne!(garage.uuid, hammer.uuid);
+
+        assert_eq!(parse_uuid_ref("uuid:3F2A"), Some("3f2a".to_string()));
+        assert_eq!(parse_uuid_ref("uuid:"), None);
+        assert_eq!(parse_uuid_ref("uuid:3%"), None);
+        assert_eq!(parse_uuid_ref("3f2a"), None);
+        let found = resolve_item(&conn, &format!("uuid:{}", uuid.to_uppercase())).unwrap();
+        assert_eq!(found.map(|i| i.id), Some(hammer.id));
+        assert!(resolve_item(&conn, "uuid:-").unwrap().is_none());
+
+        // Renames keep the UUID
+        update_item_name(&conn, hammer.id, "mallet").unwrap();
+        let renamed = resolve_item(&conn, &format!("uuid:{}", &uuid[..8]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(renamed.name, "mallet");
+    }
+
+    #[test]
+    fn container_stats_cover_the_subtree() {
+        let conn = test_conn();
+        let (garage, toolbox, hammer) = seed(&conn);
+        update_item_quantity(&conn, hammer.id, &Quantity::count(3)).unwrap();
+        let cable = insert_item(&conn, "A/V cable", None, Some(toolbox.id)).unwrap();
+        update_item_quantity(&conn, cable.id, &Quantity::parse("2m").unwrap()).unwrap();
+        insert_item(&conn, "shelf", None, Some(garage.id)).unwrap();
+
+        let stats = container_stats(&conn, garage.id).unwrap().unwrap();
+        assert_eq!(stats.direct_children, 2);
+        assert_eq!(stats.descendants, 4);
+        assert_eq!(stats.total_quantity, 5);
+        assert_eq!(stats.deepest_path, ["garage", "toolbox", "A/V cable"]);
+        assert!(container_stats(&conn, hammer.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn item_names_are_searched_and_localize_paths() {
+        let conn = test_conn();
+        let (garage, _, hammer) = seed(&conn);
+        set_item_name(&conn, garage.id, "es", "garaje").unwrap();
+        set_item_name(&conn, hammer.id, "es", "mazo").unwrap();
+        set_item_name(&conn, hammer.id, "es", "martillo").unwrap();
+
+        let found = search_items(&conn, "MARTIL").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, hammer.id);
+        assert_eq!(
+            get_item_path_in(&conn, hammer.id, Some("es")).unwrap(),
+            ["garaje", "toolbox", "martillo"]
+        );
+        assert_eq!(
+            get_item_path_in(&conn, hammer.id, Some("fr")).unwrap(),
+            get_item_path(&conn, hammer.id).unwrap()
+        );
+
+        assert_eq!(
+            remove_item_name(&conn, hammer.id, "es").unwrap().as_deref(),
+            Some("martillo")
+        );
+        assert!(list_item_names(&conn, hammer.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn aliases_resolve_after_real_names() {
+        let conn = test_conn();
+        let (garage, _, hammer) = seed(&conn);
+        assert!(add_alias(&conn, hammer.id, "Mallet").unwrap());
+        assert!(!add_alias(&conn, hammer.id, "mallet").unwrap());
+
+        let found = resolve_item(&conn, "MALLET").unwrap().unwrap();
+        assert_eq!(found.id, hammer.id);
+        assert_eq!(search_items(&conn, "mall").unwrap()[0].id, hammer.id);
+
+        // An item actually named like the alias wins
+        let mallet = insert_item(&conn, "mallet", None, Some(garage.id)).unwrap();
+        assert_eq!(
+            resolve_item(&conn, "mallet").unwrap().unwrap().id,
+            mallet.id
+        );
+
+        assert_eq!(list_aliases(&conn, hammer.id).unwrap(), ["Mallet"]);
+        assert!(remove_alias(&conn, hammer.id, "mallet").unwrap());
+        assert!(list_aliases(&conn, hammer.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn copy_recursive_duplicates_subtree() {
+        let conn = test_conn();
+        let (garage, _, _) = seed(&conn);
+        let attic = insert_item(&conn, "attic", None, None).unwrap();
+
+        set_item_kind(&conn, garage.id, ItemKind::Location).unwrap();
+
+        let copy = copy_item(&conn, garage.id, Some(attic.id), true).unwrap();
+        assert_eq!(copy.kind, ItemKind::Location);
+        assert_eq!(count_descendants(&conn, copy.id).unwrap(), 2);
+        assert!(get_item_by_path(&conn, "attic/garage/toolbox/hammer")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn item_groups_ignore_case_and_follow_deletes() {
+        let conn = test_conn();
+        let bin1 = insert_item(&conn, "bin-1", None, None).unwrap();
+        let bin2 = insert_item(&conn, "bin-2", None, None).unwrap();
+        set_item_group(&conn, bin1.id, "Bins").unwrap();
+        set_item_group(&conn, bin2.id, "bins").unwrap();
+
+        assert_eq!(
+            list_group_members(&conn, "BINS").unwrap(),
+            HashSet::from([bin1.id, bin2.id])
+        );
+        assert_eq!(get_item_group(&conn, bin1.id).unwrap().unwrap(), "Bins");
+
+        delete_item(&conn, bin1.id).unwrap();
+        assert_eq!(
+            list_group_members(&conn, "bins").unwrap(),
+            HashSet::from([bin2.id])
+        );
+    }
+}
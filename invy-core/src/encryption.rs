@@ -0,0 +1,71 @@
+//! SQLCipher database encryption.
+//!
+//! Only compiled with the `encryption` cargo feature. The passphrase comes
+//! from `INVY_PASSPHRASE` or, failing that, an interactive prompt.
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::Connection;
+
+/// Environment variable holding the database passphrase.
+pub const PASSPHRASE_ENV: &str = "INVY_PASSPHRASE";
+
+/// Read the passphrase from the environment or prompt for it.
+///
+/// With `confirm`, the prompt asks twice and requires both entries to match.
+pub fn passphrase(confirm: bool) -> Result<String> {
+    if let Ok(pass) = std::env::var(PASSPHRASE_ENV) {
+        if !pass.is_empty() {
+            return Ok(pass);
+        }
+    }
+
+    crate::strict::check(|| {
+        format!(
+            "{} is not set and strict mode never prompts",
+            PASSPHRASE_ENV
+        )
+    })?;
+    let pass =
+        rpassword::prompt_password("Database passphrase: ").context("Failed to read passphrase")?;
+    if pass.is_empty() {
+        return Err(anyhow!("passphrase must not be empty"));
+    }
+    if confirm {
+        let again = rpassword::prompt_password("Confirm passphrase: ")
+            .context("Failed to read passphrase")?;
+        if again != pass {
+            return Err(anyhow!("passphrases do not match"));
+        }
+    }
+    Ok(pass)
+}
+
+/// Set the key on a connection to a new, empty database.
+pub fn key_new(conn: &Connection) -> Result<()> {
+    let pass = passphrase(true)?;
+    conn.pragma_update(None, "key", &pass)
+        .context("Failed to set database key")?;
+    Ok(())
+}
+
+/// Unlock an encrypted database; plaintext databases are left untouched.
+pub fn unlock(conn: &Connection) -> Result<()> {
+    if is_readable(conn) {
+        return Ok(());
+    }
+
+    let pass = passphrase(false)?;
+    conn.pragma_update(None, "key", &pass)
+        .context("Failed to set database key")?;
+    if !is_readable(conn) {
+        return Err(anyhow!("wrong passphrase or not an invy database"));
+    }
+    Ok(())
+}
+
+fn is_readable(conn: &Connection) -> bool {
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .is_ok()
+}
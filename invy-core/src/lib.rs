@@ -0,0 +1,29 @@
+//! invy-core - inventory storage and queries behind the invy CLI.
+//!
+//! Items live in a tree of containers in a SQLite database. Functions here
+//! take a connection, return typed values and never print; presenting
+//! results is up to the caller. The `invy` binary is one such caller:
+//!
+//! ```no_run
+//! let conn = invy_core::db::open(None)?;
+//! for item in invy_core::db::search_items(&conn, "drill")? {
+//!     let path = invy_core::db::get_item_path(&conn, item.id)?;
+//!     println!("{}", invy_core::db::join_path(&path));
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! See SPEC.md for the behavior these functions implement.
+
+pub mod backend;
+pub mod classify;
+pub mod db;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+pub mod fuzzy;
+pub mod model;
+pub mod notice;
+pub mod pathglob;
+pub mod quantity;
+pub mod strict;
+pub mod timespec;
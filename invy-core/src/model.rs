@@ -0,0 +1,822 @@
+//! Data models for invy.
+
+use serde::{Deserialize, Serialize};
+
+use crate::quantity::Quantity;
+
+/// An item in the inventory.
+///
+/// Items can be standalone or nested inside containers.
+/// A container is just an item that has other items inside it; `kind` says
+/// what the item is meant to be.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub id: i64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub quantity: i64,
+    /// Unit of `quantity` (e.g. `m`, `g`); none for a plain count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Stable identifier that survives renames, moves and re-imports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    #[serde(default)]
+    pub kind: ItemKind,
+}
+
+/// What an item is: a thing, something that holds things, or a place.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    /// A plain thing (e.g. a hammer)
+    #[default]
+    Item,
+    /// Something that holds items (e.g. a box or drawer)
+    Container,
+    /// A place (e.g. a room or shelf)
+    Location,
+}
+
+impl ItemKind {
+    /// Name stored in the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ItemKind::Item => "item",
+            ItemKind::Container => "container",
+            ItemKind::Location => "location",
+        }
+    }
+
+    /// Parse a name stored in the database; unknown names are plain items.
+    pub fn from_db(kind: &str) -> Self {
+        match kind {
+            "container" => ItemKind::Container,
+            "location" => ItemKind::Location,
+            _ => ItemKind::Item,
+        }
+    }
+
+    /// Whether items are meant to be put inside.
+    pub fn holds_items(self) -> bool {
+        self != ItemKind::Item
+    }
+}
+
+impl std::str::FromStr for ItemKind {
+    type Err = anyhow::Error;
+
+    /// Parse `item`, `container` or `location`, ignoring case.
+    fn from_str(kind: &str) -> anyhow::Result<Self> {
+        match kind.to_lowercase().as_str() {
+            "item" => Ok(ItemKind::Item),
+            "container" => Ok(ItemKind::Container),
+            "location" => Ok(ItemKind::Location),
+            _ => Err(anyhow::anyhow!(
+                "invalid kind '{}'; use item, container or location",
+                kind
+            )),
+        }
+    }
+}
+
+/// An item to be created by `db::insert_items`.
+#[derive(Debug, Clone)]
+pub struct NewItem {
+    pub name: String,
+    pub description: Option<String>,
+    pub container_id: Option<i64>,
+    pub quantity: Quantity,
+    /// UUID to keep, e.g. from an imported file; a new one is assigned if none
+    pub uuid: Option<String>,
+    pub kind: ItemKind,
+}
+
+impl NewItem {
+    /// A plain item with a count of 1 and no description.
+    pub fn new(name: impl Into<String>, container_id: Option<i64>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            container_id,
+            quantity: Quantity::count(1),
+            uuid: None,
+            kind: ItemKind::Item,
+        }
+    }
+}
+
+/// An item with its full path and child count for display purposes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemWithPath {
+    pub id: i64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub path: Vec<String>,
+    #[serde(default)]
+    pub kind: ItemKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub child_count: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub quantity: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// Open tasks about the item (see `invy todo`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_todos: Option<i64>,
+    /// Weight in grams including contents, if any is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight_g: Option<i64>,
+    /// Containers climbed from the item `invy near` searched around
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<usize>,
+    /// When an audit last confirmed the item is where it is recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<String>,
+    /// Restock when the quantity drops below this, in `min_unit` (see
+    /// `invy shopping`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_quantity: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_unit: Option<String>,
+    /// Day the item expires (`YYYY-MM-DD`; see `invy expiring`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locations: Option<Vec<Location>>,
+    /// Other names the item is known by (see `invy alias`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aliases: Option<Vec<String>>,
+    /// Group of identical containers it was added with (see `invy add
+    /// --count`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Totals over a container's contents (show --json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ContainerStats>,
+}
+
+impl Item {
+    /// The item's quantity together with its unit.
+    pub fn measure(&self) -> Quantity {
+        Quantity {
+            amount: self.quantity,
+            unit: self.unit.clone(),
+        }
+    }
+
+    /// Convert to ItemWithPath with the given path and child count.
+    pub fn with_path(self, path: Vec<String>, child_count: Option<i64>) -> ItemWithPath {
+        ItemWithPath {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            path,
+            kind: self.kind,
+            child_count,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            quantity: self.quantity,
+            unit: self.unit,
+            uuid: self.uuid,
+            open_todos: None,
+            weight_g: None,
+            distance: None,
+            verified_at: None,
+            min_quantity: None,
+            min_unit: None,
+            expires_at: None,
+            locations: None,
+            aliases: None,
+            group: None,
+            stats: None,
+        }
+    }
+}
+
+/// Item for list display (with child count).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListItem {
+    pub id: i64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub child_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// Open tasks about the item, if any (see `invy todo`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_todos: Option<i64>,
+    /// Weight in grams including contents, if any is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight_g: Option<i64>,
+    /// A container too heavy to lift safely (see `invy heavy`)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub heavy: bool,
+    /// Used to color rows by age; not part of the output
+    #[serde(skip)]
+    pub updated_at: String,
+    /// Extra columns for `list --long`
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub details: Option<ListDetails>,
+}
+
+/// Path and timestamps shown by `list --long`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListDetails {
+    pub path: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Item {
+    /// Convert into ListItem with child count.
+    pub fn into_list_item(self, child_count: i64) -> ListItem {
+        ListItem {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            child_count,
+            uuid: self.uuid,
+            open_todos: None,
+            weight_g: None,
+            heavy: false,
+            updated_at: self.updated_at,
+            details: None,
+        }
+    }
+
+    /// Convert into ListItem with child count, path and timestamps.
+    pub fn into_long_list_item(self, child_count: i64, path: Vec<String>) -> ListItem {
+        ListItem {
+            details: Some(ListDetails {
+                path,
+                created_at: self.created_at.clone(),
+                updated_at: self.updated_at.clone(),
+            }),
+            ..self.into_list_item(child_count)
+        }
+    }
+}
+
+/// Item with nested children for tree display.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeItem {
+    pub id: i64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub child_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// Used to color rows by age; not part of the output
+    #[serde(skip)]
+    pub updated_at: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeItem>,
+}
+
+/// Result of hashing (or verifying) a container's contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerHash {
+    pub id: i64,
+    pub path: Vec<String>,
+    pub hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recorded_at: Option<String>,
+    pub status: HashStatus,
+}
+
+/// Outcome of a hash or verify run for one container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashStatus {
+    /// Hash was computed and stored
+    Recorded,
+    /// Contents match the stored hash
+    Ok,
+    /// Contents differ from the stored hash
+    Changed,
+    /// No hash has been stored for this container
+    Unrecorded,
+}
+
+/// One item renamed (or to be renamed, in a dry run) by `invy rename`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamedItem {
+    pub id: i64,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// Items whose names match or nearly match (for `invy dupes`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DupeGroup {
+    pub kind: DupeKind,
+    pub items: Vec<ItemWithPath>,
+}
+
+/// How the names in a duplicate group relate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DupeKind {
+    /// Same name, ignoring case
+    Exact,
+    /// Names a few edits apart
+    Similar,
+}
+
+/// Temperature and humidity a container provides, or an item needs (for
+/// `invy storage`). Unset bounds are unknown or don't matter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConditions {
+    pub item_id: i64,
+    pub path: Vec<String>,
+    pub kind: ConditionKind,
+    /// Degrees Celsius
+    pub temp_min: Option<i64>,
+    pub temp_max: Option<i64>,
+    /// Relative humidity in percent
+    pub humidity_min: Option<i64>,
+    pub humidity_max: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Whether storage conditions describe a container or an item's needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConditionKind {
+    /// What a container is like (e.g. an attic reaching 40°C)
+    Provides,
+    /// What an item needs (e.g. chocolate below 30°C)
+    Requires,
+}
+
+impl ConditionKind {
+    /// Name stored in the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConditionKind::Provides => "provides",
+            ConditionKind::Requires => "requires",
+        }
+    }
+}
+
+/// An item stored somewhere unsuitable (for `invy storage-check`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageIssue {
+    pub item_id: i64,
+    pub path: Vec<String>,
+    /// Nearest container with recorded conditions
+    pub container: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_note: Option<String>,
+    /// e.g. "reaches 40°C, needs at most 30°C"
+    pub problems: Vec<String>,
+}
+
+/// A suggested reorganization (for `invy optimize`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizeSuggestion {
+    pub item_id: i64,
+    pub path: Vec<String>,
+    /// Lookups, moves and consumptions in the period analyzed
+    pub uses: i64,
+    /// Containers above the item; 0 at root
+    pub depth: usize,
+    /// e.g. "used 7 times, 3 levels deep in attic"
+    pub reason: String,
+    /// Where to move it; none when no better place is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<Vec<String>>,
+}
+
+/// An item's name in another language (see `invy i18n`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Translation {
+    pub item_id: i64,
+    pub path: Vec<String>,
+    /// Language code, e.g. `es` or `pt-br`
+    pub lang: String,
+    pub name: String,
+}
+
+/// Another name an item is known by (see `invy alias`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alias {
+    pub item_id: i64,
+    pub path: Vec<String>,
+    pub alias: String,
+}
+
+/// Progress of a stocktake (see `invy audit`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// Audited container; `None` when auditing the whole inventory
+    pub container_id: Option<i64>,
+    /// Path of the audited container (empty for the whole inventory)
+    pub path: Vec<String>,
+    pub started_at: String,
+    /// Items checked since the audit started
+    pub checked: usize,
+    /// Every item inside the audited container
+    pub total: usize,
+    /// Items not checked yet, with when they were last verified
+    pub unchecked: Vec<ItemWithPath>,
+}
+
+/// An item running low (see `invy shopping`). Amounts are in `unit`, the
+/// finer of the item's and its threshold's units.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShoppingItem {
+    pub id: i64,
+    pub name: String,
+    pub path: Vec<String>,
+    /// How much there is
+    pub quantity: i64,
+    /// The restocking threshold
+    pub min_quantity: i64,
+    /// How much to buy to get back to the threshold
+    pub need: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+/// How far unpacking has come after a move (see `invy unpacking-status`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnpackingStatus {
+    /// Containers counted as boxes
+    pub boxes: usize,
+    pub unpacked_boxes: usize,
+    /// Plain items (not containers or locations)
+    pub items: usize,
+    /// Items no longer inside any packed box
+    pub unpacked_items: usize,
+    /// Boxes still packed, sorted by path
+    pub packed: Vec<PackedBox>,
+}
+
+/// A box not marked unpacked yet, with the items still inside it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackedBox {
+    pub id: i64,
+    pub name: String,
+    pub path: Vec<String>,
+    /// Plain items inside, at any depth
+    pub items: usize,
+}
+
+/// A maintenance task about an item (see `invy todo`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Todo {
+    pub id: i64,
+    pub item_id: i64,
+    pub path: Vec<String>,
+    pub text: String,
+    pub created_at: String,
+    /// When the task was marked done; null while open
+    pub done_at: Option<String>,
+}
+
+/// A container checked against its checklist (for `invy check`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistReport {
+    pub container: Vec<String>,
+    pub entries: Vec<ChecklistEntry>,
+    pub missing: usize,
+}
+
+/// One required item and where it was found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistEntry {
+    pub name: String,
+    pub present: bool,
+    /// Path of the matching item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<Vec<String>>,
+}
+
+/// Totals over everything inside a container (for show --json).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerStats {
+    /// Items directly inside
+    pub direct_children: i64,
+    /// Items at any depth inside
+    pub descendants: i64,
+    /// Sum of the quantities of descendants counted in pieces (no unit)
+    pub total_quantity: i64,
+    /// Most recent `updated_at` of the container or anything inside it
+    pub last_activity: String,
+    /// Full path of the most deeply nested item inside
+    pub deepest_path: Vec<String>,
+}
+
+/// A container an item has lived in (for show --locations-history).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<i64>,
+    /// Path of the container at the time; empty for root
+    pub path: Vec<String>,
+    pub moved_at: String,
+}
+
+/// An entry in the consumption log (for consume and consumed commands).
+///
+/// Entries keep the item's name and path so they remain meaningful after a
+/// fully consumed item has been archived out of the inventory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Consumption {
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<i64>,
+    pub name: String,
+    pub path: Vec<String>,
+    pub quantity: i64,
+    /// Unit of `quantity` and `remaining`; none for a plain count
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Quantity left afterwards; 0 means the item was archived. Unknown for
+    /// entries logged before this was tracked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<i64>,
+    pub consumed_at: String,
+}
+
+/// Inventory-wide counts (for `invy stats`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stats {
+    pub item_count: i64,
+    /// Items that currently hold at least one other item
+    pub container_count: i64,
+    pub root_count: i64,
+    /// Total quantity recorded in the consumption log
+    pub consumed_count: i64,
+}
+
+/// Data-quality counts over a set of items (for `invy stats --health`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthScore {
+    pub items: i64,
+    /// Items with a description
+    pub described: i64,
+    /// Items neither changed nor confirmed by an audit recently
+    pub stale: i64,
+    /// Items whose name is also used somewhere else
+    pub ambiguous: i64,
+    /// 0-100: the average share of described, fresh and unambiguous items
+    pub score: i64,
+}
+
+/// Data quality of a top-level container's contents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerHealth {
+    pub path: Vec<String>,
+    #[serde(flatten)]
+    pub health: HealthScore,
+}
+
+/// Data-quality report (for `invy stats --health`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    #[serde(flatten)]
+    pub health: HealthScore,
+    /// Age after which unchanged, unaudited items count as stale
+    pub stale_after: String,
+    /// Score of the previous run, and when it was
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_score: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_at: Option<String>,
+    /// Lowest score first
+    pub containers: Vec<ContainerHealth>,
+}
+
+/// Activity within one period (for `invy stats --series`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SeriesPoint {
+    pub period: String,
+    /// Items created in the period that are still in the inventory
+    pub added: i64,
+    /// Items consumed away (archived) in the period
+    pub archived: i64,
+    /// `added - archived`
+    pub net: i64,
+    pub moved: i64,
+    /// Quantity consumed in the period
+    pub consumed: i64,
+}
+
+/// Moves into and out of one container within one period (for
+/// `invy stats --churn`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChurnPoint {
+    pub period: String,
+    /// Container path at the time of the move; empty for root
+    pub container: Vec<String>,
+    pub moved_in: i64,
+    pub moved_out: i64,
+}
+
+/// A read-only share token scoped to one item's subtree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Share {
+    pub token: String,
+    pub item_id: i64,
+    pub path: Vec<String>,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    pub expired: bool,
+    /// Link served by `invy serve`; filled in by the share command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// One item as a flat, typed row (for `invy export`).
+///
+/// Paths are `/`-joined strings so the row loads directly into a table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRow {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub container_id: Option<i64>,
+    /// Path of the containing item; empty at root
+    pub container: String,
+    pub path: String,
+    /// Nesting level; root items are 0
+    pub depth: i64,
+    pub quantity: i64,
+    /// Unit of `quantity`; null for a plain count
+    pub unit: Option<String>,
+    pub child_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Stable identifier; give it back to `invy import` to keep it
+    pub uuid: Option<String>,
+    /// Missing from exports made before it was added, which read as items
+    #[serde(default)]
+    pub kind: ItemKind,
+}
+
+/// Validation result for an import file (for `invy import`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Data rows read, excluding the header
+    pub rows: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    /// Items written; 0 when validation failed or with --report-only
+    pub imported: usize,
+    pub issues: Vec<ImportIssue>,
+}
+
+/// A problem found by `invy doctor`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorIssue {
+    /// Which check found it: `integrity`, `cycle`, `dangling` or `duplicate`
+    pub check: String,
+    /// Item concerned, if the problem is about one
+    pub item_id: Option<i64>,
+    pub message: String,
+    /// What `--fix` did about it; null when not repaired
+    pub fix: Option<String>,
+}
+
+/// A problem found while validating one import row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportIssue {
+    pub severity: Severity,
+    /// 1-based line in the file; the header is line 1
+    pub line: u64,
+    /// Header name of the offending column, if the issue is about one value
+    pub column: Option<String>,
+    pub value: Option<String>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+/// How serious an import issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Blocks the import
+    Error,
+    /// The row is imported or skipped as described
+    Warning,
+}
+
+/// A convenience SQL view and whether it matches this version (for
+/// `invy views`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewInfo {
+    pub name: String,
+    pub status: ViewStatus,
+}
+
+/// State of an installed convenience view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewStatus {
+    /// Installed and up to date
+    Ok,
+    /// Not installed
+    Missing,
+    /// Installed by another version of invy
+    Stale,
+    /// Installed but no longer valid against the schema
+    Broken,
+}
+
+/// Outcome of one scheduled job (for `invy cron run`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronRun {
+    pub job: String,
+    pub status: CronStatus,
+    /// What the job did, why it failed, or when it last ran
+    pub detail: String,
+    /// Last successful run, including this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<String>,
+}
+
+/// Whether a scheduled job ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CronStatus {
+    Ran,
+    /// Not due yet
+    Skipped,
+    Failed,
+}
+
+/// Storage-level information about a database (for `invy db info`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbInfo {
+    pub path: String,
+    pub schema_version: u32,
+    pub latest_schema_version: u32,
+    pub item_count: i64,
+    pub size_bytes: u64,
+    pub journal_mode: String,
+}
+
+/// One line of a machine's sync file: the state of an item after a change,
+/// or its deletion (see `invy sync`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncEntry {
+    pub uuid: String,
+    /// When the change was made: the item's `updated_at`, or when it was
+    /// deleted
+    pub at: String,
+    /// The item as it was after the change; none for a deletion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item: Option<SyncItem>,
+}
+
+/// Everything `invy sync` carries about an item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncItem {
+    pub name: String,
+    pub description: Option<String>,
+    /// UUID of the containing item; none at root
+    pub container: Option<String>,
+    pub kind: ItemKind,
+    pub quantity: i64,
+    pub unit: Option<String>,
+    pub weight_g: Option<i64>,
+    pub min_quantity: Option<i64>,
+    pub min_unit: Option<String>,
+    pub expires_at: Option<String>,
+    pub created_at: String,
+}
+
+/// What one `invy sync` did.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// This machine's name in the sync folder
+    pub replica: String,
+    /// Changes written to this machine's sync file
+    pub sent: usize,
+    /// Changes read from other machines' files
+    pub received: usize,
+    /// Received changes that were applied (the rest were older than the
+    /// local copy)
+    pub applied: usize,
+    /// Received changes that could not be applied as they were
+    pub conflicts: Vec<String>,
+}
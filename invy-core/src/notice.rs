@@ -0,0 +1,22 @@
+//! Notes for the user about guesses made along the way, such as which of
+//! several items with one name was picked.
+//!
+//! The library never prints; a program that wants the notes shown installs
+//! a handler once at startup (the CLI prints them on stderr). Without one
+//! they are dropped.
+
+use std::sync::OnceLock;
+
+static HANDLER: OnceLock<fn(&str)> = OnceLock::new();
+
+/// Send every later notice to `handler`. Only the first call has an effect.
+pub fn set_handler(handler: fn(&str)) {
+    let _ = HANDLER.set(handler);
+}
+
+/// Pass a notice to the installed handler, if any.
+pub fn emit(message: &str) {
+    if let Some(handler) = HANDLER.get() {
+        handler(message);
+    }
+}
@@ -0,0 +1,208 @@
+//! Glob patterns matched against item paths, for commands that share part of
+//! the inventory (`invy export --include garage/** --exclude **/private*`).
+//!
+//! Patterns are `/`-separated like paths, and `\/` is a slash inside a name.
+//! Within a segment `*` matches any run of characters and `?` a single
+//! character; a segment that is exactly `**` matches any number of segments,
+//! including none. Matching ignores case.
+//!
+//! `name_captures` matches a single name and returns what each `*` and `?`
+//! matched, for `invy rename` templates.
+
+use anyhow::{anyhow, Result};
+
+use crate::db;
+
+/// One compiled pattern.
+#[derive(Debug, Clone)]
+struct Pattern {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    /// `**`: zero or more whole segments
+    AnyDepth,
+    /// A single name, possibly with `*` and `?`
+    Name(Vec<char>),
+}
+
+impl Pattern {
+    fn parse(pattern: &str) -> Result<Self> {
+        let segments: Vec<Segment> = db::split_path(pattern)
+            .into_iter()
+            .map(|segment| match segment.as_str() {
+                "**" => Segment::AnyDepth,
+                _ => Segment::Name(segment.to_lowercase().chars().collect()),
+            })
+            .collect();
+        if segments.is_empty() {
+            return Err(anyhow!("empty path pattern '{}'", pattern));
+        }
+        Ok(Self { segments })
+    }
+
+    fn matches(&self, path: &[String]) -> bool {
+        matches_segments(&self.segments, path)
+    }
+}
+
+fn matches_segments(segments: &[Segment], path: &[String]) -> bool {
+    match segments.split_first() {
+        None => path.is_empty(),
+        Some((Segment::AnyDepth, rest)) => {
+            (0..=path.len()).any(|skip| matches_segments(rest, &path[skip..]))
+        }
+        Some((Segment::Name(glob), rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                let name: Vec<char> = name.to_lowercase().chars().collect();
+                matches_name(glob, &name) && matches_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match one name against a segment glob (`*` and `?`).
+fn matches_name(glob: &[char], name: &[char]) -> bool {
+    match glob.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| matches_name(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && matches_name(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_name(rest, &name[1..]),
+    }
+}
+
+/// Match a whole name against a glob, ignoring case. On a match, returns the
+/// text each `*` and `?` matched, in order (`*` as little as possible).
+pub fn name_captures(glob: &str, name: &str) -> Option<Vec<String>> {
+    let glob: Vec<char> = glob.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let mut captures = Vec::new();
+    capture_name(&glob, &name, &mut captures).then_some(captures)
+}
+
+fn capture_name(glob: &[char], name: &[char], captures: &mut Vec<String>) -> bool {
+    match glob.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|len| {
+            captures.push(name[..len].iter().collect());
+            capture_name(rest, &name[len..], captures) || {
+                captures.pop();
+                false
+            }
+        }),
+        Some(('?', rest)) => match name.split_first() {
+            Some((c, name_rest)) => {
+                captures.push(c.to_string());
+                capture_name(rest, name_rest, captures) || {
+                    captures.pop();
+                    false
+                }
+            }
+            None => false,
+        },
+        Some((c, rest)) => match name.split_first() {
+            Some((n, name_rest)) if c.to_lowercase().eq(n.to_lowercase()) => {
+                capture_name(rest, name_rest, captures)
+            }
+            _ => false,
+        },
+    }
+}
+
+/// `--include` / `--exclude` patterns applied together.
+///
+/// A pattern that matches a container also covers everything inside it, so
+/// `--exclude garage/private` drops the whole subtree. An item is kept when
+/// it falls under some include pattern (or there are none) and under no
+/// exclude pattern.
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl PathFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let compile = |patterns: &[String]| -> Result<Vec<Pattern>> {
+            patterns.iter().map(|p| Pattern::parse(p)).collect()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Whether the item at `path` (root first, one name per segment) is kept.
+    pub fn matches(&self, path: &[String]) -> bool {
+        let covered = |patterns: &[Pattern]| {
+            (1..=path.len()).any(|len| patterns.iter().any(|p| p.matches(&path[..len])))
+        };
+        (self.include.is_empty() || covered(&self.include)) && !covered(&self.exclude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Vec<String> {
+        db::split_path(path)
+    }
+
+    fn filter(include: &[&str], exclude: &[&str]) -> PathFilter {
+        let strings =
+            |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        PathFilter::new(&strings(include), &strings(exclude)).unwrap()
+    }
+
+    #[test]
+    fn stars_stay_within_a_segment() {
+        let pattern = Pattern::parse("garage/tool*").unwrap();
+        assert!(pattern.matches(&path("garage/toolbox")));
+        assert!(pattern.matches(&path("Garage/Tools")));
+        assert!(!pattern.matches(&path("garage/shelf/toolbox")));
+        assert!(Pattern::parse("garage/?ox")
+            .unwrap()
+            .matches(&path("garage/box")));
+    }
+
+    #[test]
+    fn double_star_spans_any_depth() {
+        let pattern = Pattern::parse("**/private*").unwrap();
+        assert!(pattern.matches(&path("private")));
+        assert!(pattern.matches(&path("office/desk/private letters")));
+        assert!(!pattern.matches(&path("office/desk")));
+        assert!(Pattern::parse("garage/**")
+            .unwrap()
+            .matches(&path("garage")));
+    }
+
+    #[test]
+    fn escaped_slashes_match_inside_names() {
+        let pattern = Pattern::parse(r"office/A\/V*").unwrap();
+        assert!(pattern.matches(&[String::from("office"), String::from("A/V cables")]));
+    }
+
+    #[test]
+    fn name_captures_return_wildcard_text() {
+        assert_eq!(
+            name_captures("box*-?", "Box 12-a"),
+            Some(vec![" 12".to_string(), "a".to_string()])
+        );
+        assert_eq!(name_captures("BOX*", "box"), Some(vec![String::new()]));
+        assert_eq!(name_captures("box*", "crate"), None);
+        assert_eq!(name_captures("box", "boxes"), None);
+    }
+
+    #[test]
+    fn patterns_cover_subtrees() {
+        let filter = filter(&["garage"], &["**/private*"]);
+        assert!(filter.matches(&path("garage/toolbox/hammer")));
+        assert!(!filter.matches(&path("office/stapler")));
+        assert!(!filter.matches(&path("garage/private box/letters")));
+        assert!(PathFilter::default().matches(&path("anything")));
+        assert!(PathFilter::new(&["/".to_string()], &[]).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! Quantities with optional units, e.g. `3`, `3 m` or `250g`.
+//!
+//! Amounts are whole numbers in their unit. Units in the same family (length,
+//! mass, volume) convert exactly into the finer unit, so `3 m` minus `30 cm`
+//! is `270 cm`. Any other unit (`pair`, `roll`) only matches itself, and a
+//! plain count has no unit.
+
+use anyhow::{anyhow, Result};
+use std::fmt;
+
+/// Convertible units: (name, family, size in the family's smallest unit).
+const UNITS: &[(&str, &str, i64)] = &[
+    ("mm", "length", 1),
+    ("cm", "length", 10),
+    ("m", "length", 1_000),
+    ("km", "length", 1_000_000),
+    ("mg", "mass", 1),
+    ("g", "mass", 1_000),
+    ("kg", "mass", 1_000_000),
+    ("ml", "volume", 1),
+    ("l", "volume", 1_000),
+];
+
+/// An amount, optionally in a unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quantity {
+    pub amount: i64,
+    pub unit: Option<String>,
+}
+
+impl Quantity {
+    /// A plain count.
+    pub fn count(amount: i64) -> Self {
+        Quantity { amount, unit: None }
+    }
+
+    /// Parse `3`, `3m` or `3 m`. The amount must be at least 1.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let invalid = || {
+            anyhow!(
+                "invalid quantity '{}'. Use a whole number, optionally with a unit, like 3 or 250g",
+                spec
+            )
+        };
+
+        let unit_start = spec
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(spec.len());
+        let (amount, unit) = spec.split_at(unit_start);
+        let amount: i64 = amount.parse().map_err(|_| invalid())?;
+        let unit = unit.trim();
+
+        if amount < 1 {
+            return Err(anyhow!("quantity must be at least 1"));
+        }
+        if !unit.is_empty() && !unit.chars().all(char::is_alphabetic) {
+            return Err(invalid());
+        }
+
+        Ok(Quantity {
+            amount,
+            unit: (!unit.is_empty()).then(|| normalize_unit(unit)),
+        })
+    }
+
+    /// Subtract `other`, converting both into the finer unit when they differ.
+    /// Returns `(taken, remaining)` in that common unit; `remaining` may be
+    /// negative when taking more than there is.
+    pub fn subtract(&self, other: &Quantity) -> Result<(Quantity, Quantity)> {
+        let unit = common_unit(self.unit.as_deref(), other.unit.as_deref()).ok_or_else(|| {
+            anyhow!(
+                "units do not match: cannot subtract {} from {}",
+                describe_unit(other.unit.as_deref()),
+                describe_unit(self.unit.as_deref())
+            )
+        })?;
+        let have = self.to_unit(unit.as_deref()).expect("common unit converts");
+        let take = other
+            .to_unit(unit.as_deref())
+            .expect("common unit converts");
+        Ok((
+            take.clone(),
+            Quantity {
+                amount: have.amount - take.amount,
+                unit,
+            },
+        ))
+    }
+
+    /// Express this quantity in `unit`, if that is exact.
+    pub fn to_unit(&self, unit: Option<&str>) -> Option<Quantity> {
+        if self.unit.as_deref() == unit {
+            return Some(self.clone());
+        }
+        let (from_family, from_size) = lookup(self.unit.as_deref()?)?;
+        let (to_family, to_size) = lookup(unit?)?;
+        if from_family != to_family || (self.amount * from_size) % to_size != 0 {
+            return None;
+        }
+        Some(Quantity {
+            amount: self.amount * from_size / to_size,
+            unit: unit.map(String::from),
+        })
+    }
+
+    /// The amount in grams, if the unit is a mass (`mg` rounds down).
+    pub fn grams(&self) -> Option<i64> {
+        match lookup(self.unit.as_deref()?)? {
+            ("mass", size) => Some(self.amount * size / 1_000),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a weight such as `500g` or `20kg` into grams.
+pub fn parse_weight(spec: &str) -> Result<i64> {
+    Quantity::parse(spec)
+        .ok()
+        .and_then(|quantity| quantity.grams())
+        .filter(|&grams| grams > 0)
+        .ok_or_else(|| {
+            anyhow!(
+                "invalid weight '{}'. Use a mass like 500g or 20kg",
+                spec.trim()
+            )
+        })
+}
+
+/// Format a weight in grams: `850 g`, `12.5 kg`.
+pub fn format_weight(grams: i64) -> String {
+    if grams < 1_000 {
+        return format!("{} g", grams);
+    }
+    let tenths = (grams + 50) / 100;
+    if tenths % 10 == 0 {
+        format!("{} kg", tenths / 10)
+    } else {
+        format!("{}.{} kg", tenths / 10, tenths % 10)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.unit {
+            Some(unit) => write!(f, "{} {}", self.amount, unit),
+            None => write!(f, "{}", self.amount),
+        }
+    }
+}
+
+/// Canonical spelling of a unit: `L` becomes `l`, `pairs` stays as written.
+fn normalize_unit(unit: &str) -> String {
+    let lower = unit.to_lowercase();
+    if lookup(&lower).is_some() {
+        lower
+    } else {
+        unit.to_string()
+    }
+}
+
+fn lookup(unit: &str) -> Option<(&'static str, i64)> {
+    UNITS
+        .iter()
+        .find(|(name, _, _)| *name == unit)
+        .map(|&(_, family, size)| (family, size))
+}
+
+/// The finer of two compatible units, or `None` if they cannot be combined.
+fn common_unit(a: Option<&str>, b: Option<&str>) -> Option<Option<String>> {
+    if a == b {
+        return Some(a.map(String::from));
+    }
+    let (family_a, size_a) = lookup(a?)?;
+    let (family_b, size_b) = lookup(b?)?;
+    if family_a != family_b {
+        return None;
+    }
+    Some(Some(if size_a <= size_b { a? } else { b? }.to_string()))
+}
+
+fn describe_unit(unit: Option<&str>) -> String {
+    match unit.and_then(|u| lookup(u).map(|(family, _)| (u, family))) {
+        Some((unit, family)) => format!("{} ({})", unit, family),
+        None => match unit {
+            Some(unit) => unit.to_string(),
+            None => "a plain count".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(spec: &str) -> Quantity {
+        Quantity::parse(spec).unwrap()
+    }
+
+    #[test]
+    fn parses_amounts_and_units() {
+        assert_eq!(q("3"), Quantity::count(3));
+        assert_eq!(q("3m").to_string(), "3 m");
+        assert_eq!(q("250 g").to_string(), "250 g");
+        assert_eq!(q("2L").to_string(), "2 l");
+        assert_eq!(q("2 pairs").to_string(), "2 pairs");
+        for spec in ["", "m", "0", "-3", "1.5m", "3 m2"] {
+            assert!(Quantity::parse(spec).is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn subtracts_in_the_finer_unit() {
+        let (taken, left) = q("3m").subtract(&q("30cm")).unwrap();
+        assert_eq!(
+            (taken.to_string(), left.to_string()),
+            ("30 cm".into(), "270 cm".into())
+        );
+
+        let (_, left) = q("250cm").subtract(&q("1m")).unwrap();
+        assert_eq!(left.to_string(), "150 cm");
+        assert_eq!(left.to_unit(Some("m")), None);
+        assert_eq!(q("300cm").to_unit(Some("m")), Some(q("3m")));
+
+        let (_, left) = q("4").subtract(&q("1")).unwrap();
+        assert_eq!(left, Quantity::count(3));
+    }
+
+    #[test]
+    fn mismatched_units_are_rejected() {
+        for (have, take) in [("3m", "30g"), ("3", "1m"), ("2 pairs", "1m"), ("1l", "1")] {
+            assert!(q(have).subtract(&q(take)).is_err(), "{have} - {take}");
+        }
+    }
+
+    #[test]
+    fn weights_are_grams() {
+        assert_eq!(q("2kg").grams(), Some(2_000));
+        assert_eq!(q("1500mg").grams(), Some(1));
+        assert_eq!(q("3m").grams(), None);
+        assert_eq!(q("3").grams(), None);
+
+        assert_eq!(parse_weight("20kg").unwrap(), 20_000);
+        assert_eq!(parse_weight(" 500 g ").unwrap(), 500);
+        for spec in ["20", "2m", "100mg", "kg"] {
+            assert!(parse_weight(spec).is_err(), "{spec}");
+        }
+
+        assert_eq!(format_weight(850), "850 g");
+        assert_eq!(format_weight(12_500), "12.5 kg");
+        assert_eq!(format_weight(20_000), "20 kg");
+        assert_eq!(format_weight(19_980), "20 kg");
+    }
+}
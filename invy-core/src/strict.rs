@@ -0,0 +1,66 @@
+//! Strict mode (`--strict`) for scripts.
+//!
+//! In strict mode invy never guesses: containers are not auto-created,
+//! nothing prompts, no "did you mean" suggestions are printed, ambiguous
+//! names are not settled by the current context, and what would be a
+//! warning fails the command instead. Such failures are `Violation` errors,
+//! which exit with `EXIT_CODE` so scripts can tell them from other errors.
+//!
+//! The mode is process-wide because it changes name resolution deep inside
+//! `db`, which every command goes through. Where containers are found or
+//! created it is passed in explicitly instead, with `db::Placement`.
+//!
+//! See SPEC.md#strict-mode
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit code of a command refused in strict mode.
+pub const EXIT_CODE: i32 = 3;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn strict mode on for the rest of the process.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether strict mode is on.
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Something strict mode refuses to guess about or tolerate.
+#[derive(Debug)]
+pub struct Violation(pub String);
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (strict mode)", self.0)
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// The error for an ambiguous reference: a `Violation` in strict mode, so
+/// scripts can tell it apart, and a plain error otherwise.
+pub fn ambiguous(message: String) -> anyhow::Error {
+    if enabled() {
+        return Violation(message).into();
+    }
+    anyhow::Error::msg(message)
+}
+
+/// In strict mode, fail with `message`, which would otherwise be a warning.
+pub fn check(message: impl FnOnce() -> String) -> anyhow::Result<()> {
+    check_when(enabled(), message)
+}
+
+/// Like [`check`], for callers that are told whether strict mode is on
+/// (see `db::Placement`).
+pub fn check_when(strict: bool, message: impl FnOnce() -> String) -> anyhow::Result<()> {
+    if strict {
+        return Err(Violation(message()).into());
+    }
+    Ok(())
+}
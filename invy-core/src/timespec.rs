@@ -0,0 +1,256 @@
+//! Time specifications for filters such as `--since 30d`.
+//!
+//! A spec is either a relative duration (`12h`, `30d`, `2w`, `6m`, `1y`)
+//! counted back from now (or forward, for expiries), or an absolute date (`2024-01-15`) or timestamp
+//! (`2024-01-15 10:30:00`). Timestamps are UTC to match SQLite's
+//! `datetime('now')`.
+//!
+//! Also provides the date filters shared by `find` and `list`, and the
+//! calendar periods used to bucket timestamps in `invy stats`.
+
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Months, NaiveDate, NaiveDateTime, Utc};
+
+/// Timestamp format used by SQLite's `datetime()`.
+const SQLITE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Resolve a time spec in the past (`30d` = 30 days ago) to a SQLite
+/// timestamp (`YYYY-MM-DD HH:MM:SS`).
+pub fn parse_since(spec: &str) -> Result<String> {
+    parse(spec, Direction::Past)
+}
+
+/// Resolve a time spec in the future (`7d` = 7 days from now) to a SQLite
+/// timestamp.
+pub fn parse_until(spec: &str) -> Result<String> {
+    parse(spec, Direction::Future)
+}
+
+/// Resolve a date or a time spec in the future (`6m` = six months from now)
+/// to the day it falls on (`YYYY-MM-DD`), as used for expiry dates.
+pub fn parse_date(spec: &str) -> Result<String> {
+    parse_until(spec).map(|timestamp| timestamp[..10].to_string())
+}
+
+/// Today's date (`YYYY-MM-DD`, UTC).
+pub fn today() -> String {
+    Utc::now().date_naive().format("%Y-%m-%d").to_string()
+}
+
+/// Days from `from` to `to`, both `YYYY-MM-DD`; negative when `to` is
+/// earlier.
+pub fn days_between(from: &str, to: &str) -> Option<i64> {
+    let from = NaiveDate::parse_from_str(from, "%Y-%m-%d").ok()?;
+    let to = NaiveDate::parse_from_str(to, "%Y-%m-%d").ok()?;
+    Some((to - from).num_days())
+}
+
+/// The current time as a SQLite timestamp.
+pub fn now() -> String {
+    Utc::now().naive_utc().format(SQLITE_FORMAT).to_string()
+}
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Past,
+    Future,
+}
+
+fn parse(spec: &str, direction: Direction) -> Result<String> {
+    let now = Utc::now().naive_utc();
+    resolve(spec.trim(), now, direction)
+        .map(|t| t.format(SQLITE_FORMAT).to_string())
+        .ok_or_else(|| {
+            anyhow!(
+                "invalid time '{}'. Use a duration like 30d, 2w, 6m or a date like 2024-01-15",
+                spec
+            )
+        })
+}
+
+/// Filter on item timestamps (`--created-after`, `--created-before`,
+/// `--updated-since`), resolved to SQLite timestamps.
+#[derive(Debug, Clone, Default)]
+pub struct DateFilter {
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub updated_since: Option<String>,
+}
+
+impl DateFilter {
+    /// Resolve the filter flags; each accepts an age (`7d`) or a date.
+    pub fn parse(
+        created_after: Option<&str>,
+        created_before: Option<&str>,
+        updated_since: Option<&str>,
+    ) -> Result<Self> {
+        Ok(DateFilter {
+            created_after: created_after.map(parse_since).transpose()?,
+            created_before: created_before.map(parse_since).transpose()?,
+            updated_since: updated_since.map(parse_since).transpose()?,
+        })
+    }
+
+    /// Whether no bound is set, so every item matches.
+    pub fn is_empty(&self) -> bool {
+        self.created_after.is_none()
+            && self.created_before.is_none()
+            && self.updated_since.is_none()
+    }
+
+    /// Check an item's timestamps. Lower bounds are inclusive, the
+    /// `created_before` bound is exclusive.
+    pub fn matches(&self, created_at: &str, updated_at: &str) -> bool {
+        self.created_after
+            .as_deref()
+            .is_none_or(|t| created_at >= t)
+            && self
+                .created_before
+                .as_deref()
+                .is_none_or(|t| created_at < t)
+            && self
+                .updated_since
+                .as_deref()
+                .is_none_or(|t| updated_at >= t)
+    }
+}
+
+/// Calendar period for bucketing timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    /// Label of the period containing a SQLite timestamp, e.g. `2024-01`
+    /// for a month or `2024-W03` for an ISO week. Labels sort chronologically.
+    pub fn bucket(self, timestamp: &str) -> String {
+        let Ok(datetime) = NaiveDateTime::parse_from_str(timestamp, SQLITE_FORMAT) else {
+            return timestamp.to_string();
+        };
+        let pattern = match self {
+            Period::Day => "%Y-%m-%d",
+            Period::Week => "%G-W%V",
+            Period::Month => "%Y-%m",
+            Period::Year => "%Y",
+        };
+        datetime.format(pattern).to_string()
+    }
+}
+
+fn resolve(spec: &str, now: NaiveDateTime, direction: Direction) -> Option<NaiveDateTime> {
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(spec, SQLITE_FORMAT) {
+        return Some(datetime);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0);
+    }
+
+    let unit_start = spec.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = spec.split_at(unit_start);
+    let amount: u32 = amount.parse().ok()?;
+
+    let (duration, months) = match unit {
+        "h" => (Duration::hours(amount.into()), None),
+        "d" => (Duration::days(amount.into()), None),
+        "w" => (Duration::weeks(amount.into()), None),
+        "m" => (Duration::zero(), Some(Months::new(amount))),
+        "y" => (Duration::zero(), Some(Months::new(amount.checked_mul(12)?))),
+        _ => return None,
+    };
+
+    match (direction, months) {
+        (Direction::Past, Some(months)) => now.checked_sub_months(months),
+        (Direction::Past, None) => now.checked_sub_signed(duration),
+        (Direction::Future, Some(months)) => now.checked_add_months(months),
+        (Direction::Future, None) => now.checked_add_signed(duration),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2024-03-31 12:00:00", SQLITE_FORMAT).unwrap()
+    }
+
+    fn at(spec: &str) -> Option<String> {
+        resolve(spec, now(), Direction::Past).map(|t| t.format(SQLITE_FORMAT).to_string())
+    }
+
+    #[test]
+    fn relative_ages_count_back_from_now() {
+        assert_eq!(at("12h").as_deref(), Some("2024-03-31 00:00:00"));
+        assert_eq!(at("30d").as_deref(), Some("2024-03-01 12:00:00"));
+        assert_eq!(at("2w").as_deref(), Some("2024-03-17 12:00:00"));
+        assert_eq!(at("1m").as_deref(), Some("2024-02-29 12:00:00"));
+        assert_eq!(at("1y").as_deref(), Some("2023-03-31 12:00:00"));
+    }
+
+    #[test]
+    fn future_durations_count_forward() {
+        let until = |spec| {
+            resolve(spec, now(), Direction::Future).map(|t| t.format(SQLITE_FORMAT).to_string())
+        };
+        assert_eq!(until("7d").as_deref(), Some("2024-04-07 12:00:00"));
+        assert_eq!(until("1m").as_deref(), Some("2024-04-30 12:00:00"));
+        assert_eq!(until("2024-12-24").as_deref(), Some("2024-12-24 00:00:00"));
+    }
+
+    #[test]
+    fn absolute_dates_and_timestamps() {
+        assert_eq!(at("2024-01-15").as_deref(), Some("2024-01-15 00:00:00"));
+        assert_eq!(
+            at("2024-01-15 10:30:00").as_deref(),
+            Some("2024-01-15 10:30:00")
+        );
+    }
+
+    #[test]
+    fn periods_bucket_timestamps() {
+        let ts = "2024-01-01 09:15:00";
+        assert_eq!(Period::Day.bucket(ts), "2024-01-01");
+        assert_eq!(Period::Week.bucket(ts), "2024-W01");
+        assert_eq!(Period::Month.bucket(ts), "2024-01");
+        assert_eq!(Period::Year.bucket(ts), "2024");
+        // ISO weeks can belong to the neighbouring year
+        assert_eq!(Period::Week.bucket("2021-01-01 00:00:00"), "2020-W53");
+    }
+
+    #[test]
+    fn date_filter_bounds() {
+        let filter = DateFilter::parse(Some("2024-01-01"), Some("2024-02-01"), None).unwrap();
+        assert!(filter.matches("2024-01-01 00:00:00", "2024-01-01 00:00:00"));
+        assert!(filter.matches("2024-01-31 23:59:59", "2024-01-31 23:59:59"));
+        assert!(!filter.matches("2024-02-01 00:00:00", "2024-02-01 00:00:00"));
+        assert!(!filter.matches("2023-12-31 23:59:59", "2024-03-01 00:00:00"));
+
+        let filter = DateFilter::parse(None, None, Some("2024-06-01")).unwrap();
+        assert!(filter.matches("2020-01-01 00:00:00", "2024-06-02 00:00:00"));
+        assert!(!filter.matches("2020-01-01 00:00:00", "2024-05-31 00:00:00"));
+        assert!(DateFilter::default().is_empty());
+    }
+
+    #[test]
+    fn dates_count_days() {
+        assert_eq!(parse_date("2024-01-15").unwrap(), "2024-01-15");
+        assert_eq!(parse_date("2024-01-15 10:30:00").unwrap(), "2024-01-15");
+        assert_eq!(parse_date("0d").unwrap(), today());
+        assert_eq!(days_between("2024-02-28", "2024-03-01"), Some(2));
+        assert_eq!(days_between("2024-03-01", "2024-02-28"), Some(-2));
+        assert_eq!(days_between("2024-03-01", "soon"), None);
+    }
+
+    #[test]
+    fn invalid_specs_are_rejected() {
+        for spec in ["", "d", "30", "30x", "-3d", "2024-13-01"] {
+            assert_eq!(at(spec), None, "{spec}");
+        }
+        assert!(parse_since("soon").is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! Export file encryption with age.
+//!
+//! invy does not implement age itself; it runs the `age` program (or the one
+//! `INVY_AGE` names), so any age-compatible tool such as rage works too.
+//!
+//! See SPEC.md#invy-export
+
+use anyhow::{anyhow, Context, Result};
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Program run to encrypt and decrypt, unless `INVY_AGE` names another.
+const AGE: &str = "age";
+
+fn program() -> OsString {
+    std::env::var_os("INVY_AGE").unwrap_or_else(|| AGE.into())
+}
+
+/// Encrypt `plaintext` to every recipient (`age1...` or SSH public keys).
+pub fn encrypt(plaintext: &[u8], recipients: &[String]) -> Result<Vec<u8>> {
+    if recipients.is_empty() {
+        return Err(anyhow!("--encrypt needs at least one --recipient"));
+    }
+    let mut command = Command::new(program());
+    command.arg("--encrypt");
+    for recipient in recipients {
+        command.arg("--recipient").arg(recipient);
+    }
+    run(command, Some(plaintext))
+}
+
+/// Decrypt an age file, with the keys in `identity` when given (age asks for
+/// the passphrase of a passphrase-encrypted file itself).
+pub fn decrypt(file: &Path, identity: Option<&Path>) -> Result<Vec<u8>> {
+    if !file.is_file() {
+        return Err(anyhow!("file '{}' not found", file.display()));
+    }
+    let mut command = Command::new(program());
+    command.arg("--decrypt");
+    if let Some(identity) = identity {
+        command.arg("--identity").arg(identity);
+    }
+    command.arg(file);
+    run(command, None)
+}
+
+/// Run age, feeding it `input` on stdin, and return what it wrote to stdout.
+fn run(mut command: Command, input: Option<&[u8]>) -> Result<Vec<u8>> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    command
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::inherit()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to run {} (install age or set INVY_AGE)", program))?;
+    // Feed stdin from another thread so a large export cannot deadlock with
+    // age waiting for its stdout to be read
+    let writer = input.map(|input| {
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = input.to_vec();
+        std::thread::spawn(move || stdin.write_all(&input))
+    });
+    let output = child.wait_with_output()?;
+    let written = writer.map(|writer| writer.join().expect("stdin writer panicked"));
+    // A failed write is usually age exiting early (a bad recipient, say);
+    // its own message says why, where ours would only say "broken pipe"
+    if !output.status.success() {
+        return Err(anyhow!(
+            "age failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    if let Some(written) = written {
+        written.with_context(|| format!("Failed to write to {}", program))?;
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,469 @@
+//! Machine interface: `invy --api`, newline-delimited JSON-RPC over stdin
+//! and stdout, and the item operations it shares with `invy serve`.
+//!
+//! Each operation takes its parameters as a JSON object and returns what the
+//! matching command prints with `--json`. Failures are `Error`s with a code
+//! that each transport maps to its own (JSON-RPC codes, HTTP statuses);
+//! anything else failed one of the commands' own checks.
+//!
+//! See SPEC.md#machine-mode
+
+use anyhow::Result;
+use rusqlite::Connection;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::commands::add;
+use crate::commands::edit::{self, EditChanges};
+use crate::commands::{mv, rm, show};
+use crate::db::{self, SortKey};
+use crate::model::{Item, ItemKind, ItemWithPath};
+use crate::output::{self, Format};
+use crate::quantity::Quantity;
+use crate::timespec;
+
+/// What kind of failure an `Error` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    /// Missing, unknown or malformed parameters
+    InvalidParams,
+    /// The item or container referred to does not exist
+    NotFound,
+    /// One of the commands' own checks failed (name taken, strict mode, a
+    /// stale `expected_updated_at`)
+    Failed,
+}
+
+/// A failure caused by the request rather than by the inventory.
+#[derive(Debug)]
+pub struct Error {
+    pub code: Code,
+    pub message: String,
+}
+
+impl Error {
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Error {
+            code: Code::InvalidParams,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(what: &str) -> Self {
+        Error {
+            code: Code::NotFound,
+            message: format!("{} not found", what),
+        }
+    }
+
+    /// Mark a command's error as a failed check, unless it already has a
+    /// code or came from the database or the file system, which is not the
+    /// request's fault.
+    pub fn failed(err: anyhow::Error) -> anyhow::Error {
+        let internal = err
+            .chain()
+            .any(|cause| cause.is::<rusqlite::Error>() || cause.is::<io::Error>());
+        if internal || err.is::<Error>() {
+            return err;
+        }
+        Error {
+            code: Code::Failed,
+            message: format!("{:#}", err),
+        }
+        .into()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// JSON-RPC error codes (see <https://www.jsonrpc.org/specification>).
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// A command's own check failed (name taken, invalid value, strict mode)
+const COMMAND_FAILED: i64 = -32000;
+const NOT_FOUND: i64 = -32001;
+
+/// Answer JSON-RPC requests, one per line on stdin, until it closes.
+///
+/// # Arguments
+/// * `default_container` - Container `add` uses when given none
+/// * `placement` - Whether missing containers are created, and strict mode
+/// * `db_path` - Optional custom database path
+pub fn run(
+    default_container: Option<&str>,
+    placement: db::Placement,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = respond(&conn, &line, default_container, placement);
+        writeln!(stdout, "{}", response)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// The response to one request line.
+fn respond(
+    conn: &Connection,
+    line: &str,
+    default_container: Option<&str>,
+    placement: db::Placement,
+) -> Value {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(err) => return error_response(Value::Null, PARSE_ERROR, &err.to_string()),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return error_response(id, INVALID_REQUEST, "request needs a \"method\" string");
+    };
+    let params = match request.get("params") {
+        None | Some(Value::Null) => Map::new(),
+        Some(Value::Object(params)) => params.clone(),
+        Some(_) => {
+            return error_response(id, INVALID_PARAMS, "\"params\" must be an object");
+        }
+    };
+
+    let Some(result) = call(conn, method, &params, default_container, placement) else {
+        let message = format!(
+            "unknown method '{}'; expected one of {}",
+            method,
+            METHODS.join(", ")
+        );
+        return error_response(id, METHOD_NOT_FOUND, &message);
+    };
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => {
+            let code = match err.downcast_ref::<Error>().map(|err| err.code) {
+                Some(Code::InvalidParams) => INVALID_PARAMS,
+                Some(Code::NotFound) => NOT_FOUND,
+                Some(Code::Failed) | None => COMMAND_FAILED,
+            };
+            error_response(id, code, &format!("{:#}", err))
+        }
+    }
+}
+
+/// A JSON-RPC error response.
+pub fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// Methods `call` knows.
+const METHODS: &[&str] = &["list", "find", "show", "add", "edit", "mv", "rm"];
+
+/// Run `method`, or `None` if there is no such method. `item` names the item
+/// for `show`, `edit`, `mv` and `rm`; the other parameters are each operation's.
+/// `placement` decides whether `add`, `edit` and `mv` create missing
+/// containers.
+pub fn call(
+    conn: &Connection,
+    method: &str,
+    params: &Map<String, Value>,
+    default_container: Option<&str>,
+    placement: db::Placement,
+) -> Option<Result<Value>> {
+    let with_item = |params: &Map<String, Value>| -> Result<(String, Map<String, Value>)> {
+        let mut params = params.clone();
+        let item = match params.remove("item") {
+            Some(Value::String(item)) => item,
+            Some(Value::Number(id)) => format!("#{}", id),
+            _ => return Err(Error::invalid("'item' (a name, path or #ID) is required").into()),
+        };
+        Ok((item, params))
+    };
+    Some(match method {
+        "list" => list(conn, params),
+        "find" => find(conn, params),
+        "show" => with_item(params).and_then(|(item, params)| {
+            fields(&params, &[])?;
+            show(conn, &item)
+        }),
+        "add" => add(conn, params, default_container, placement),
+        "edit" => {
+            with_item(params).and_then(|(item, params)| edit(conn, &item, &params, placement))
+        }
+        "mv" => {
+            with_item(params).and_then(|(item, params)| move_to(conn, &item, &params, placement))
+        }
+        "rm" => with_item(params).and_then(|(item, params)| {
+            fields(&params, &[])?;
+            remove(conn, &item)
+        }),
+        _ => return None,
+    })
+}
+
+/// The contents of a container (`in`), or the root items, by name.
+pub fn list(conn: &Connection, params: &Map<String, Value>) -> Result<Value> {
+    fields(params, &["in", "limit", "offset"])?;
+    let container_id = match text(params, "in")?.as_deref() {
+        None | Some("" | "/") => None,
+        Some(container_ref) => Some(
+            db::resolve_item(conn, container_ref)
+                .map_err(Error::failed)?
+                .ok_or_else(|| Error::not_found(&format!("container '{}'", container_ref)))?
+                .id,
+        ),
+    };
+    let items = db::list_items_sorted(conn, container_id, SortKey::Name, false)?;
+    let (items, page) = paginate(items, params)?;
+    let items = with_paths(conn, items)?;
+    to_json(|w| output::print_items(w, &items, page, Format::Json))
+}
+
+/// Items whose name, description or alias contains `query`, as `invy find`
+/// finds them.
+pub fn find(conn: &Connection, params: &Map<String, Value>) -> Result<Value> {
+    fields(params, &["query", "limit", "offset"])?;
+    let query = text(params, "query")?
+        .filter(|query| !query.is_empty())
+        .ok_or_else(|| Error::invalid("'query' is required"))?;
+    let items = db::search_items(conn, &query)?;
+    let (items, page) = paginate(items, params)?;
+    let items = with_paths(conn, items)?;
+    to_json(|w| output::print_items(w, &items, page, Format::Json))
+}
+
+/// What `invy show --json` prints about an item.
+pub fn show(conn: &Connection, item_ref: &str) -> Result<Value> {
+    let item = db::resolve_item(conn, item_ref)
+        .map_err(Error::failed)?
+        .ok_or_else(|| Error::not_found(&format!("item '{}'", item_ref)))?;
+    let mut details = show::details(conn, item, None)?;
+    details.stats = db::container_stats(conn, details.id)?;
+    to_json(|w| output::print_item(w, &details, Format::Json))
+}
+
+/// Add an item, as `invy add` does.
+pub fn add(
+    conn: &Connection,
+    params: &Map<String, Value>,
+    default_container: Option<&str>,
+    placement: db::Placement,
+) -> Result<Value> {
+    fields(
+        params,
+        &[
+            "name",
+            "description",
+            "container",
+            "quantity",
+            "kind",
+            "expires_at",
+        ],
+    )?;
+    let name = text(params, "name")?
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| Error::invalid("'name' is required"))?;
+    let description = text(params, "description")?.filter(|desc| !desc.is_empty());
+    let container = text(params, "container")?;
+    let container = container.as_deref().or(default_container);
+    let quantity = Quantity::parse(text(params, "quantity")?.as_deref().unwrap_or("1"))
+        .map_err(Error::failed)?;
+    let kind = match text(params, "kind")? {
+        Some(kind) => parse_kind(&kind)?,
+        None => ItemKind::Item,
+    };
+    let expires = text(params, "expires_at")?
+        .map(|expires| timespec::parse_date(&expires))
+        .transpose()
+        .map_err(Error::failed)?;
+
+    let details = add::Details {
+        desc: description.as_deref(),
+        quantity: &quantity,
+        kind,
+        expires: expires.as_deref(),
+    };
+    let (item, _) = db::with_tx(conn, |tx| {
+        add::insert(tx, &name, &details, container, placement)
+    })
+    .map_err(Error::failed)?;
+    to_json(|w| output::print_added(w, &item, Format::Json))
+}
+
+/// Change an item, as `invy edit` does. `null` clears a field; a `null`
+/// container moves the item to root.
+pub fn edit(
+    conn: &Connection,
+    item_ref: &str,
+    params: &Map<String, Value>,
+    placement: db::Placement,
+) -> Result<Value> {
+    fields(
+        params,
+        &[
+            "name",
+            "description",
+            "container",
+            "kind",
+            "weight",
+            "min_quantity",
+            "expires_at",
+            "expected_updated_at",
+        ],
+    )?;
+    db::resolve_item(conn, item_ref)
+        .map_err(Error::failed)?
+        .ok_or_else(|| Error::not_found(&format!("item '{}'", item_ref)))?;
+    let name = text(params, "name")?;
+    let description = text(params, "description")?;
+    let container = text(params, "container")?.map(|c| if c.is_empty() { "/".into() } else { c });
+    let weight = text(params, "weight")?;
+    let min_qty = text(params, "min_quantity")?;
+    let expires = text(params, "expires_at")?;
+    let expected_updated_at = text(params, "expected_updated_at")?;
+    let kind = text(params, "kind")?
+        .map(|kind| parse_kind(&kind))
+        .transpose()?;
+
+    let changes = EditChanges {
+        name: name.as_deref(),
+        desc: description.as_deref(),
+        weight: weight.as_deref(),
+        min_qty: min_qty.as_deref(),
+        expires: expires.as_deref(),
+        container: container.as_deref(),
+        kind,
+        ..EditChanges::default()
+    };
+    let updated = edit::update(
+        conn,
+        item_ref,
+        &changes,
+        expected_updated_at.as_deref(),
+        placement,
+    )
+    .map_err(Error::failed)?;
+    to_json(|w| output::print_item(w, &updated, Format::Json))
+}
+
+/// Move an item into the `to` container, as `invy mv` does.
+pub fn move_to(
+    conn: &Connection,
+    item_ref: &str,
+    params: &Map<String, Value>,
+    placement: db::Placement,
+) -> Result<Value> {
+    fields(params, &["to", "expected_updated_at"])?;
+    let destination = text(params, "to")?
+        .filter(|to| !to.is_empty())
+        .ok_or_else(|| Error::invalid("'to' (a container, or \"/\" for root) is required"))?;
+    let expected_updated_at = text(params, "expected_updated_at")?;
+    db::resolve_item(conn, item_ref)
+        .map_err(Error::failed)?
+        .ok_or_else(|| Error::not_found(&format!("item '{}'", item_ref)))?;
+    let (item, old_path) = db::with_tx(conn, |tx| {
+        mv::move_item(
+            tx,
+            item_ref,
+            &destination,
+            expected_updated_at.as_deref(),
+            placement,
+        )
+    })
+    .map_err(Error::failed)?;
+    to_json(|w| output::print_moved(w, &item, &old_path, Format::Json))
+}
+
+/// Remove an item, as `invy rm` does.
+pub fn remove(conn: &Connection, item_ref: &str) -> Result<Value> {
+    db::resolve_item(conn, item_ref)
+        .map_err(Error::failed)?
+        .ok_or_else(|| Error::not_found(&format!("item '{}'", item_ref)))?;
+    let (id, name, orphaned) = rm::remove(conn, item_ref).map_err(Error::failed)?;
+    to_json(|w| output::print_removed(w, id, &name, &orphaned, Format::Json))
+}
+
+/// What `print` writes, as a JSON value.
+pub fn to_json(print: impl FnOnce(&mut Vec<u8>) -> Result<()>) -> Result<Value> {
+    let mut out = Vec::new();
+    print(&mut out)?;
+    Ok(serde_json::from_slice(&out)?)
+}
+
+/// Items with their paths and child counts, as listings show them.
+pub fn with_paths(conn: &Connection, items: Vec<Item>) -> Result<Vec<ItemWithPath>> {
+    let child_counts = db::child_counts(conn)?;
+    items
+        .into_iter()
+        .map(|item| {
+            let path = db::get_item_path(conn, item.id)?;
+            let child_count = child_counts.get(&item.id).copied().unwrap_or(0);
+            Ok(item.with_path(path, Some(child_count)))
+        })
+        .collect()
+}
+
+/// Apply the `limit` and `offset` parameters.
+fn paginate(
+    items: Vec<Item>,
+    params: &Map<String, Value>,
+) -> Result<(Vec<Item>, Option<output::Page>)> {
+    let number = |name: &str| -> Result<Option<usize>> {
+        match text(params, name)? {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| Error::invalid(format!("'{}' must be a number", name)).into()),
+            None => Ok(None),
+        }
+    };
+    let offset = number("offset")?.unwrap_or(0);
+    Ok(output::paginate(items, offset, number("limit")?))
+}
+
+/// Reject any parameter not in `allowed`.
+pub fn fields(params: &Map<String, Value>, allowed: &[&str]) -> Result<()> {
+    if let Some(key) = params.keys().find(|key| !allowed.contains(&key.as_str())) {
+        let expected = if allowed.is_empty() {
+            "none here".to_string()
+        } else {
+            format!("one of {}", allowed.join(", "))
+        };
+        return Err(
+            Error::invalid(format!("unknown field '{}'; expected {}", key, expected)).into(),
+        );
+    }
+    Ok(())
+}
+
+/// A parameter as the text the matching flag takes: `None` when absent, `""`
+/// for `null` (which clears it) and numbers as written.
+fn text(params: &Map<String, Value>, key: &str) -> Result<Option<String>> {
+    match params.get(key) {
+        None => Ok(None),
+        Some(Value::Null) => Ok(Some(String::new())),
+        Some(Value::String(text)) => Ok(Some(text.clone())),
+        Some(Value::Number(number)) => Ok(Some(number.to_string())),
+        Some(_) => Err(Error::invalid(format!("'{}' must be a string", key)).into()),
+    }
+}
+
+fn parse_kind(kind: &str) -> Result<ItemKind> {
+    kind.parse()
+        .map_err(|err: anyhow::Error| Error::invalid(err.to_string()).into())
+}
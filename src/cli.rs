@@ -2,18 +2,28 @@
 //!
 //! See SPEC.md for full behavioral specification.
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::commands::check::SaveAs;
+use crate::commands::export::ExportTarget;
+use crate::commands::import::ImportSource;
+use crate::commands::storage::ConditionArgs;
+use crate::db::SortKey;
+use crate::model::ItemKind;
+use crate::output::{ColorChoice, Format};
+use crate::timespec::{DateFilter, Period};
+
 /// A CLI tool for tracking home inventory with hierarchical containers.
 ///
 /// See SPEC.md for full documentation.
 #[derive(Parser, Debug)]
 #[command(name = "invy")]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, arg_required_else_help = true)]
 pub struct Cli {
+    /// The command to run; `None` only with `--api`
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
 
     /// Output as JSON
     #[arg(short, long, global = true)]
@@ -23,13 +33,75 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub csv: bool,
 
-    /// Use custom database file
+    /// Output as Markdown tables (list and find) or bullets (list -r)
     #[arg(long, global = true)]
+    pub markdown: bool,
+
+    /// Print only the IDs of affected items, one per line
+    #[arg(short, long, global = true, conflicts_with_all = ["json", "csv", "markdown"])]
+    pub quiet: bool,
+
+    /// Default output format (overridden by --json/--csv/--markdown)
+    #[arg(long, global = true, env = "INVY_FORMAT", value_enum)]
+    pub format: Option<Format>,
+
+    /// Use custom database file
+    #[arg(long, global = true, env = "INVY_DB")]
     pub db: Option<PathBuf>,
+
+    /// Run against a throwaway copy of the database (a tutorial inventory if there is none)
+    #[arg(long, global = true)]
+    pub sandbox: bool,
+
+    /// Read an `invy export` JSON file instead of the database (read-only
+    /// commands only)
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "sandbox")]
+    pub from_export: Option<PathBuf>,
+
+    /// Use custom config file (default: ~/.config/invy/config.toml)
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Show item names in this language where one is set (see `invy i18n`)
+    #[arg(long, global = true, env = "INVY_LANG", value_name = "CODE")]
+    pub lang: Option<String>,
+
+    /// Color list rows by age: auto, always or never (default: auto)
+    #[arg(long, global = true, value_enum)]
+    pub color: Option<ColorChoice>,
+
+    /// Never guess: no auto-created containers, prompts or suggestions, and
+    /// warnings are errors (for scripts)
+    #[arg(
+        long,
+        global = true,
+        env = "INVY_STRICT",
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub strict: bool,
+
+    /// Fail instead of creating a missing destination container (e.g. for
+    /// `add --in` or `mv`), naming the closest existing one
+    #[arg(long, global = true)]
+    pub no_create: bool,
+
+    /// Answer JSON-RPC requests, one per line on stdin, instead of running
+    /// a command (for editor plugins and scripts; see SPEC.md#machine-mode)
+    #[arg(long, conflicts_with_all = ["json", "csv", "markdown", "quiet", "format"])]
+    pub api: bool,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
+    /// Create a new, empty inventory database
+    ///
+    /// See SPEC.md#invy-init
+    Init {
+        /// Encrypt the database with a passphrase (requires the `encryption` feature)
+        #[arg(long)]
+        encrypted: bool,
+    },
+
     /// Add a new item to the inventory
     ///
     /// See SPEC.md#invy-add-name
@@ -41,9 +113,55 @@ pub enum Commands {
         #[arg(short, long)]
         desc: Option<String>,
 
-        /// Container to place item in (auto-creates if needed)
+        /// Write the description in $VISUAL or $EDITOR
+        #[arg(long, conflicts_with = "desc")]
+        editor: bool,
+
+        /// Container to place item in (auto-creates if needed; "/" for root)
         #[arg(short = 'i', long = "in")]
         container: Option<String>,
+
+        /// How much of the item there is, optionally with a unit (3, 3m, 250g)
+        #[arg(long, default_value = "1")]
+        qty: String,
+
+        /// What the item is: a plain item, a container (box, drawer) or a
+        /// location (room, shelf)
+        #[arg(long, value_enum, default_value = "item")]
+        kind: ItemKind,
+
+        /// Day the item expires: a date (2025-06-30) or from now (6m)
+        #[arg(long, value_name = "DATE")]
+        expires: Option<String>,
+
+        /// Add this many identical containers, numbered NAME-1, NAME-2, ...
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=1000))]
+        count: Option<u32>,
+
+        /// Group the numbered containers join, for `list --group` (default:
+        /// the name)
+        #[arg(long, value_name = "NAME", requires = "count")]
+        group: Option<String>,
+    },
+
+    /// Create a container path, making each missing level
+    ///
+    /// See SPEC.md#invy-mkdir-path
+    Mkdir {
+        /// Container path to create (e.g. garage/shelf2/bin-a)
+        path: String,
+    },
+
+    /// Print how many items a container holds
+    ///
+    /// See SPEC.md#invy-count-container
+    Count {
+        /// Container to count in (default: root)
+        container: Option<String>,
+
+        /// Count everything inside, not just direct children
+        #[arg(short, long)]
+        recursive: bool,
     },
 
     /// Search for items by name or description
@@ -51,7 +169,53 @@ pub enum Commands {
     /// See SPEC.md#invy-find-query
     Find {
         /// Search term (substring match, case-insensitive)
-        query: String,
+        #[arg(required_unless_present_any = ["missing_child", "incomplete"])]
+        query: Option<String>,
+
+        /// Find containers with no item of this name inside (instead of searching)
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["query", "incomplete"])]
+        missing_child: Option<String>,
+
+        /// Find containers missing something on their checklist (see `invy check`)
+        #[arg(long, conflicts_with = "query")]
+        incomplete: bool,
+
+        /// Only look below this container; with --missing-child, check its
+        /// direct children
+        #[arg(short = 'i', long = "in", value_name = "CONTAINER")]
+        within: Option<String>,
+
+        /// Only find items under paths matching this glob, e.g. 'garage/**'
+        /// (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        path: Vec<String>,
+
+        /// Leave out items under paths matching this glob, e.g. '**/attic'
+        /// (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        not_path: Vec<String>,
+
+        #[command(flatten)]
+        dates: DateArgs,
+
+        #[command(flatten)]
+        page: PageArgs,
+
+        #[command(flatten)]
+        save: SaveArgs,
+
+        /// Print each item through a template, e.g. '{{ name }} :: {{ path }}',
+        /// or the name of one in [templates]
+        #[arg(long, value_name = "TEMPLATE")]
+        template: Option<String>,
+
+        /// Only print the number of matches; exits with code 1 when there
+        /// are none
+        #[arg(
+            long,
+            conflicts_with_all = ["limit", "offset", "template", "save_as_checklist", "save_as_kit"]
+        )]
+        count: bool,
     },
 
     /// List items, optionally within a specific container
@@ -62,14 +226,75 @@ pub enum Commands {
         container: Option<String>,
 
         /// List all descendants recursively
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with_all = ["limit", "offset"])]
         recursive: bool,
+
+        /// Order items by name, created, updated or children (default: name)
+        #[arg(long, value_enum)]
+        sort: Option<SortKey>,
+
+        /// Reverse the sort order
+        #[arg(long)]
+        reverse: bool,
+
+        /// Include IDs, paths and timestamps
+        #[arg(short, long, conflicts_with = "recursive")]
+        long: bool,
+
+        /// Only list containers and locations
+        #[arg(long)]
+        containers_only: bool,
+
+        /// Only list containers and locations with nothing inside, at any
+        /// depth, with their paths
+        #[arg(long)]
+        empty: bool,
+
+        /// Only list the members of a group added with `add --count`
+        #[arg(long, value_name = "NAME")]
+        group: Option<String>,
+
+        /// Print each item through a template, e.g. '{{ name }} :: {{ path }}',
+        /// or the name of one in [templates]
+        #[arg(long, value_name = "TEMPLATE")]
+        template: Option<String>,
+
+        #[command(flatten)]
+        dates: DateArgs,
+
+        #[command(flatten)]
+        page: PageArgs,
+
+        #[command(flatten)]
+        save: SaveArgs,
     },
 
     /// Show detailed information about a specific item
     ///
     /// See SPEC.md#invy-show-item
     Show {
+        #[command(flatten)]
+        item: ItemArg,
+
+        /// Refer to the item by text in its description, which must match
+        /// only one item
+        #[arg(long, value_name = "TEXT", group = "ItemArg")]
+        by_desc: Option<String>,
+
+        /// Also list every container the item has lived in
+        #[arg(long)]
+        locations_history: bool,
+
+        /// Print each item through a template, e.g. '{{ name }} :: {{ path }}',
+        /// or the name of one in [templates]
+        #[arg(long, value_name = "TEMPLATE")]
+        template: Option<String>,
+    },
+
+    /// Print only the full path of an item (one line per match)
+    ///
+    /// See SPEC.md#invy-where-item
+    Where {
         /// Item name or path
         item: String,
     },
@@ -77,28 +302,511 @@ pub enum Commands {
     /// Move an item to a different container
     ///
     /// See SPEC.md#invy-mv-item-destination
+    #[command(visible_alias = "file")]
     Mv {
-        /// Item to move
+        /// Item to move (name, path or #ID); `container/*` moves its contents.
+        /// With --id, give only the destination
+        #[arg(value_name = "ITEM")]
+        item: String,
+
+        /// Target container (use "/" for root)
+        destination: Option<String>,
+
+        /// Refer to the item by ID (same as #ID)
+        #[arg(long)]
+        id: Option<i64>,
+
+        /// Move everything inside the item, leaving the item itself in place
+        #[arg(long)]
+        contents: bool,
+
+        /// Fail if the item was updated since this timestamp (its updated_at)
+        #[arg(long, value_name = "TIMESTAMP")]
+        expected_updated_at: Option<String>,
+    },
+
+    /// Rename the items in a container whose names match a pattern
+    ///
+    /// See SPEC.md#invy-rename
+    Rename {
+        /// Name glob; each `*` and `?` is captured as {1}, {2}, ...
+        #[arg(long, value_name = "GLOB")]
+        matching: String,
+
+        /// New name, e.g. "moving-box-{n:02}" ({n} counter, {1} capture, {name})
+        #[arg(long)]
+        template: String,
+
+        /// Container whose contents are renamed (default: root)
+        #[arg(short = 'i', long = "in", value_name = "CONTAINER")]
+        container: Option<String>,
+
+        /// First value of {n}
+        #[arg(long, default_value_t = 1, value_name = "N")]
+        start: i64,
+
+        /// Show the new names without renaming anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// List items in the default container awaiting placement
+    ///
+    /// See SPEC.md#invy-triage
+    Triage,
+
+    /// List items with the same or nearly the same name across containers
+    ///
+    /// See SPEC.md#invy-dupes
+    Dupes {
+        /// Edits allowed between similar names (default: 1 per 5 characters, at most 2)
+        #[arg(long, value_name = "N", conflicts_with = "exact")]
+        max_distance: Option<usize>,
+
+        /// Only report identical names
+        #[arg(long)]
+        exact: bool,
+    },
+
+    /// List containers too heavy to lift safely, heaviest first
+    ///
+    /// See SPEC.md#invy-heavy
+    Heavy {
+        /// Weight threshold, e.g. 20kg (default: heavy_over from the config, or 20kg)
+        #[arg(long, value_name = "WEIGHT")]
+        over: Option<String>,
+    },
+
+    /// Suggest moving things to where they would be easier to find
+    ///
+    /// See SPEC.md#invy-optimize
+    Optimize {
+        /// Only count uses in this period, e.g. 30d
+        #[arg(long, default_value = "90d")]
+        since: String,
+
+        /// Uses that make an item frequently used
+        #[arg(long, value_name = "N", default_value_t = 3)]
+        min_uses: i64,
+
+        /// Deepest level a frequently used item should sit at (root items are 0)
+        #[arg(long, value_name = "N", default_value_t = 2)]
+        max_depth: usize,
+
+        /// Print the moves as a shell script of invy mv commands
+        #[arg(long)]
+        script: bool,
+    },
+
+    /// List items that have expired or expire soon, soonest first
+    ///
+    /// See SPEC.md#invy-expiring
+    Expiring {
+        /// How far ahead to look (e.g. 30d, 2w, 6m, or a date)
+        #[arg(long, default_value = "30d")]
+        within: String,
+    },
+
+    /// List items below their minimum quantity, with how much to buy
+    ///
+    /// See SPEC.md#invy-shopping
+    Shopping {
+        /// Only include items below this container
+        #[arg(short = 'i', long = "in", value_name = "CONTAINER")]
+        within: Option<String>,
+    },
+
+    /// List items physically close to another one, nearest first
+    ///
+    /// See SPEC.md#invy-near-item
+    Near {
+        /// Item to search around
+        item: String,
+
+        /// How many containers up to look (1: the same container)
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        radius: u32,
+    },
+
+    /// Set the current container context used to break name ambiguity
+    ///
+    /// See SPEC.md#invy-cd-container
+    Cd {
+        /// Container to focus on (omit or use "/" to clear)
+        container: Option<String>,
+    },
+
+    /// Show the current container context
+    ///
+    /// See SPEC.md#invy-pwd
+    Pwd,
+
+    /// Copy an item (or a whole container tree) into another container
+    ///
+    /// See SPEC.md#invy-cp-item-destination
+    Cp {
+        /// Item to copy
         item: String,
 
         /// Target container (use "/" for root)
         destination: String,
+
+        /// Copy the item's contents recursively
+        #[arg(short, long)]
+        recursive: bool,
+    },
+
+    /// Verify a container holds everything on its checklist
+    ///
+    /// See SPEC.md#invy-check-container
+    Check {
+        /// Container to check (e.g. a first-aid kit)
+        container: String,
+
+        /// Add a required item to the checklist (repeatable)
+        #[arg(long, value_name = "NAME")]
+        require: Vec<String>,
+
+        /// Remove an item from the checklist (repeatable)
+        #[arg(long, value_name = "NAME")]
+        unrequire: Vec<String>,
+    },
+
+    /// Give items names in other languages
+    ///
+    /// See SPEC.md#invy-i18n
+    I18n {
+        #[command(subcommand)]
+        command: I18nCommands,
+    },
+
+    /// Give items other names that show, find and other commands accept
+    ///
+    /// See SPEC.md#invy-alias
+    Alias {
+        #[command(subcommand)]
+        command: AliasCommands,
+    },
+
+    /// Take stock of a container: check off what is really there
+    ///
+    /// See SPEC.md#invy-audit
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+
+    /// Mark boxes as unpacked after a move
+    ///
+    /// See SPEC.md#invy-unpacked
+    Unpacked {
+        /// Boxes that are unpacked
+        #[arg(required = true)]
+        boxes: Vec<String>,
+
+        /// Mark them as still packed instead
+        #[arg(long)]
+        undo: bool,
+    },
+
+    /// Report how many boxes and items are still packed after a move
+    ///
+    /// See SPEC.md#invy-unpacking-status
+    UnpackingStatus {
+        /// Only count what is inside this container or location
+        #[arg(short = 'i', long = "in", value_name = "CONTAINER")]
+        within: Option<String>,
+    },
+
+    /// Record temperature and humidity of containers and what items need
+    ///
+    /// See SPEC.md#invy-storage
+    Storage {
+        #[command(subcommand)]
+        command: StorageCommands,
+    },
+
+    /// Report items stored somewhere too hot, cold, damp or dry for them
+    ///
+    /// See SPEC.md#invy-storage-check
+    StorageCheck,
+
+    /// Keep maintenance tasks next to the items they are about
+    ///
+    /// See SPEC.md#invy-todo
+    Todo {
+        #[command(subcommand)]
+        command: TodoCommands,
+    },
+
+    /// Hash a container's contents, or verify them against the stored hash
+    ///
+    /// See SPEC.md#invy-hash-container
+    Hash {
+        /// Container to hash (optional with --verify: checks all recorded)
+        container: Option<String>,
+
+        /// Compare against the last recorded hash instead of recording
+        #[arg(long)]
+        verify: bool,
     },
 
     /// Remove an item from the inventory
     ///
     /// See SPEC.md#invy-rm-item
     Rm {
-        /// Item to remove
+        #[command(flatten)]
+        item: ItemArg,
+    },
+
+    /// Use up some (or all) of an item, keeping a record in the consumption log
+    ///
+    /// See SPEC.md#invy-consume-item
+    Consume {
+        /// Item to consume
         item: String,
+
+        /// How much to consume, optionally with a unit (2, 30cm)
+        #[arg(long, default_value = "1")]
+        qty: String,
+    },
+
+    /// Report what has been consumed
+    ///
+    /// See SPEC.md#invy-consumed
+    Consumed {
+        /// Only include consumption since this age or date (e.g. 30d, 2024-01-15)
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Show inventory statistics, optionally as a time series for plotting
+    ///
+    /// See SPEC.md#invy-stats
+    Stats {
+        /// Show additions, archivals, moves and consumption per period
+        #[arg(long, conflicts_with = "churn")]
+        series: bool,
+
+        /// Show moves into and out of each container per period
+        #[arg(long, conflicts_with = "health")]
+        churn: bool,
+
+        /// Score data quality (descriptions, stale records, ambiguous names)
+        /// overall and per top-level container
+        #[arg(long, conflicts_with = "series")]
+        health: bool,
+
+        /// With --health, items neither changed nor audited for this long
+        /// are stale
+        #[arg(long, value_name = "AGE", default_value = "1y", requires = "health")]
+        stale_after: String,
+
+        /// Bucket size for --series and --churn
+        #[arg(long, value_enum, default_value = "month")]
+        period: Period,
+
+        /// Only include activity since this age or date (e.g. 1y, 2024-01-01)
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Create and manage read-only share links for a container
+    ///
+    /// See SPEC.md#invy-share
+    Share {
+        #[command(subcommand)]
+        command: ShareCommands,
+    },
+
+    /// Import items from a CSV file
+    ///
+    /// See SPEC.md#invy-import-file
+    Import {
+        /// CSV file with path, description and quantity columns
+        file: PathBuf,
+
+        /// Program that wrote the file
+        #[arg(long, value_enum, default_value = "invy")]
+        from: ImportSource,
+
+        /// Only validate the file and print the report
+        #[arg(long)]
+        report_only: bool,
+
+        /// The file is age-encrypted (e.g. by `invy export --encrypt`)
+        #[arg(long)]
+        decrypt: bool,
+
+        /// age identity file holding the key to decrypt with
+        #[arg(short, long, value_name = "FILE", requires = "decrypt")]
+        identity: Option<PathBuf>,
+    },
+
+    /// Export the whole inventory as flat rows
+    ///
+    /// See SPEC.md#invy-export
+    Export {
+        /// Only export this container and what is inside it
+        container: Option<String>,
+
+        /// Layout to produce
+        #[arg(long, value_enum, default_value = "json")]
+        target: ExportTarget,
+
+        /// File (json, ical) or directory (datasette, markdown-vault) to write;
+        /// json and ical default to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only export items under paths matching this glob (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        include: Vec<String>,
+
+        /// Leave out items under paths matching this glob (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        exclude: Vec<String>,
+
+        /// Encrypt the json export with age
+        #[arg(long, requires = "recipient")]
+        encrypt: bool,
+
+        /// age public key (`age1...`) or SSH public key to encrypt to
+        /// (repeatable)
+        #[arg(short, long, value_name = "KEY", requires = "encrypt")]
+        recipient: Vec<String>,
+    },
+
+    /// Update invy to the latest signed release
+    ///
+    /// See SPEC.md#invy-self-update
+    #[cfg(feature = "self-update")]
+    SelfUpdate {
+        /// Only check whether an update is available
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Answer find, show and add from a Telegram chat
+    ///
+    /// See SPEC.md#invy-bot
+    #[cfg(feature = "bot")]
+    Bot {
+        /// Bot token from @BotFather (default: `[bot] telegram_token`)
+        #[arg(long, env = "INVY_TELEGRAM_TOKEN", hide_env_values = true)]
+        telegram_token: Option<String>,
+
+        /// Telegram user ID allowed to use the bot (repeatable; adds to
+        /// `[bot] allow`)
+        #[arg(long = "allow", value_name = "USER_ID")]
+        allow: Vec<i64>,
+
+        /// Answer waiting messages and exit instead of polling forever
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// Offer inventory tools to AI assistants over the Model Context Protocol
+    ///
+    /// See SPEC.md#invy-mcp
+    Mcp,
+
+    /// Answer a JSON API over HTTP, for phone-friendly frontends
+    ///
+    /// See SPEC.md#invy-serve
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Port to listen on (0 picks a free one)
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// Address to listen on; use 0.0.0.0 to serve other devices
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+
+        /// Require `Authorization: Bearer <token>` on API requests
+        #[arg(long, env = "INVY_SERVE_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+    },
+
+    /// Share one inventory between machines through a synced folder
+    ///
+    /// See SPEC.md#invy-sync
+    Sync {
+        /// Folder every machine sees, e.g. in Dropbox or Syncthing
+        dir: PathBuf,
+    },
+
+    /// Push notifications through ntfy or a Telegram bot
+    ///
+    /// See SPEC.md#invy-notify-test
+    #[cfg(feature = "notify")]
+    Notify {
+        #[command(subcommand)]
+        command: NotifyCommands,
+    },
+
+    /// Read text from photos of labels or receipts
+    ///
+    /// See SPEC.md#invy-photo-ocr
+    #[cfg(feature = "ocr")]
+    Photo {
+        #[command(subcommand)]
+        command: PhotoCommands,
+    },
+
+    /// Run periodic jobs from the config file's [cron] schedules
+    ///
+    /// See SPEC.md#invy-cron-run
+    Cron {
+        #[command(subcommand)]
+        command: CronCommands,
+    },
+
+    /// Copy the config file to or from another machine
+    ///
+    /// See SPEC.md#invy-config
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Inspect and maintain the database itself
+    ///
+    /// See SPEC.md#invy-db
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
+
+    /// Check the database for corruption and broken containment
+    ///
+    /// See SPEC.md#invy-doctor
+    Doctor {
+        /// Repair what can be repaired
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Install SQL views for external reporting tools
+    ///
+    /// See SPEC.md#invy-views
+    Views {
+        #[command(subcommand)]
+        command: ViewsCommands,
     },
 
     /// Edit an existing item's name or description
     ///
     /// See SPEC.md#invy-edit-item
     Edit {
-        /// Item to edit
-        item: String,
+        /// Items to edit (name, path or #ID); several get the same change
+        #[arg(value_name = "ITEM", required_unless_present = "id")]
+        items: Vec<String>,
+
+        /// Refer to an item by ID (same as #ID; repeatable)
+        #[arg(long)]
+        id: Vec<i64>,
 
         /// New name
         #[arg(short, long)]
@@ -107,5 +815,426 @@ pub enum Commands {
         /// New description (use "" to clear)
         #[arg(short, long)]
         desc: Option<String>,
+
+        /// Edit the description in $VISUAL or $EDITOR
+        #[arg(long, conflicts_with = "desc")]
+        editor: bool,
+
+        /// Add text to the end of the description, after "; "
+        #[arg(long, value_name = "TEXT", conflicts_with_all = ["desc", "editor"])]
+        append_desc: Option<String>,
+
+        /// Weight of the item itself, e.g. 2kg (use "" to clear)
+        #[arg(long)]
+        weight: Option<String>,
+
+        /// Restock when the quantity drops below this, e.g. 2 or 500g (use ""
+        /// to clear; see `invy shopping`)
+        #[arg(long, value_name = "QTY")]
+        min_qty: Option<String>,
+
+        /// Day the item expires: a date (2025-06-30) or from now (6m) (use ""
+        /// to clear)
+        #[arg(long, value_name = "DATE")]
+        expires: Option<String>,
+
+        /// Move the item into this container (use "/" for root)
+        #[arg(short = 'i', long = "in", value_name = "CONTAINER")]
+        container: Option<String>,
+
+        /// Mark the item as a plain item, a container or a location
+        #[arg(long, value_enum)]
+        kind: Option<ItemKind>,
+
+        /// Fail if the item was updated since this timestamp (its updated_at)
+        #[arg(long, value_name = "TIMESTAMP")]
+        expected_updated_at: Option<String>,
+    },
+}
+
+/// The item a command acts on, by name, path or ID. Commands can offer other
+/// ways to name it by adding arguments to the `ItemArg` group.
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct ItemArg {
+    /// Item name, path or #ID
+    #[arg(value_name = "ITEM")]
+    pub item: Option<String>,
+
+    /// Refer to the item by ID (same as #ID)
+    #[arg(long)]
+    pub id: Option<i64>,
+}
+
+impl ItemArg {
+    /// The reference to resolve: the name or path, or `#ID`.
+    pub fn to_ref(&self) -> String {
+        match self.id {
+            Some(id) => format!("#{}", id),
+            None => self.item.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Timestamp filters shared by `find` and `list`.
+#[derive(Args, Debug)]
+pub struct DateArgs {
+    /// Only items created on or after this age or date (e.g. 7d, 2024-01-15)
+    #[arg(long)]
+    pub created_after: Option<String>,
+
+    /// Only items created before this age or date
+    #[arg(long)]
+    pub created_before: Option<String>,
+
+    /// Only items updated since this age or date
+    #[arg(long)]
+    pub updated_since: Option<String>,
+}
+
+impl DateArgs {
+    /// Resolve the flags into a filter.
+    pub fn to_filter(&self) -> anyhow::Result<DateFilter> {
+        DateFilter::parse(
+            self.created_after.as_deref(),
+            self.created_before.as_deref(),
+            self.updated_since.as_deref(),
+        )
+    }
+}
+
+/// Pagination flags shared by `find` and `list`.
+#[derive(Args, Debug)]
+pub struct PageArgs {
+    /// Show at most N results
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Skip the first N results
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub offset: usize,
+}
+
+/// Flags saving `find` and `list` results for packing (see `invy check`).
+#[derive(Args, Debug)]
+pub struct SaveArgs {
+    /// Add the names of the results to this container's checklist,
+    /// creating the container when missing
+    #[arg(long, value_name = "CONTAINER", conflicts_with = "save_as_kit")]
+    pub save_as_checklist: Option<String>,
+
+    /// Create this container, move the results into it and put their names
+    /// on its checklist
+    #[arg(long, value_name = "CONTAINER")]
+    pub save_as_kit: Option<String>,
+}
+
+impl Commands {
+    /// Whether the command only reads the inventory, so it can run on an
+    /// export (`--from-export`).
+    pub fn reads_only(&self) -> bool {
+        match self {
+            Commands::Find { save, .. } | Commands::List { save, .. } => save.to_save().is_none(),
+            Commands::Show { .. }
+            | Commands::Where { .. }
+            | Commands::Count { .. }
+            | Commands::Dupes { .. }
+            | Commands::Heavy { .. }
+            | Commands::Optimize { .. }
+            | Commands::Expiring { .. }
+            | Commands::Shopping { .. }
+            | Commands::Near { .. }
+            | Commands::Consumed { .. }
+            | Commands::Stats { .. }
+            | Commands::Export { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+impl SaveArgs {
+    /// The checklist or kit to save to, if any.
+    pub fn to_save(&self) -> Option<SaveAs<'_>> {
+        match (&self.save_as_checklist, &self.save_as_kit) {
+            (Some(container), _) => Some(SaveAs::Checklist(container)),
+            (None, Some(kit)) => Some(SaveAs::Kit(kit)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ShareCommands {
+    /// Create a read-only link to a container's contents
+    Create {
+        /// Container to share
+        container: String,
+
+        /// When the link expires: a duration (7d, 2w, 1m), a date, or "never"
+        #[arg(long, default_value = "7d")]
+        expires: String,
+
+        /// Public address of `invy serve` used in the link
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+
+    /// List share links, including expired ones
+    List {
+        /// Public address of `invy serve` used in the links
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+
+    /// Revoke a share link
+    Revoke {
+        /// Token of the share to revoke
+        token: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum I18nCommands {
+    /// Set an item's name in a language, replacing any previous one
+    Set {
+        /// Item to name
+        item: String,
+
+        /// Language code (e.g. es, pt-BR)
+        lang: String,
+
+        /// The item's name in that language
+        name: String,
+    },
+
+    /// Remove an item's name in a language
+    Unset {
+        /// Item to update
+        item: String,
+
+        /// Language code of the name to remove
+        lang: String,
+    },
+
+    /// List an item's names in other languages
+    List {
+        /// Item to inspect
+        item: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AliasCommands {
+    /// Add another name for an item (e.g. DMM for a multimeter)
+    Add {
+        /// Item to name
+        item: String,
+
+        /// The other name
+        alias: String,
+    },
+
+    /// Remove one of an item's aliases
+    Rm {
+        /// Item to update
+        item: String,
+
+        /// Alias to remove
+        alias: String,
+    },
+
+    /// List an item's aliases
+    List {
+        /// Item to inspect
+        item: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuditCommands {
+    /// Start an audit, replacing any audit already running
+    Start {
+        /// Container to audit (default: the whole inventory)
+        container: Option<String>,
+    },
+
+    /// Mark items as found where they are recorded
+    Check {
+        /// Items that were found
+        #[arg(required = true)]
+        items: Vec<String>,
+    },
+
+    /// List the items not checked since the audit started
+    Report,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StorageCommands {
+    /// Record the conditions inside a container (e.g. an attic)
+    Set {
+        /// Container to describe
+        container: String,
+
+        #[command(flatten)]
+        conditions: ConditionFlags,
+    },
+
+    /// Record the conditions an item needs
+    Require {
+        /// Item to describe
+        item: String,
+
+        #[command(flatten)]
+        conditions: ConditionFlags,
+    },
+
+    /// Forget an item's recorded conditions and requirements
+    Clear {
+        /// Container or item to clear
+        item: String,
+    },
+
+    /// List recorded conditions and requirements
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TodoCommands {
+    /// Add a task about an item
+    Add {
+        /// Item the task is about
+        item: String,
+
+        /// What needs doing (e.g. "replace brake pads")
+        text: String,
+    },
+
+    /// Mark a task done
+    Done {
+        /// Task ID, as shown by `invy todo list`
+        id: i64,
+    },
+
+    /// List open tasks, oldest first
+    List {
+        /// Only tasks about this container or items inside it
+        #[arg(short = 'i', long = "in", value_name = "CONTAINER")]
+        within: Option<String>,
+
+        /// Include tasks already done
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+/// Values shared by `storage set` and `storage require`.
+#[derive(Args, Debug)]
+pub struct ConditionFlags {
+    /// Temperature range in °C (5..40, ..30, 10..)
+    #[arg(long, value_name = "RANGE", allow_hyphen_values = true)]
+    pub temp: Option<String>,
+
+    /// Relative humidity range in percent (20..70, ..60)
+    #[arg(long, value_name = "RANGE")]
+    pub humidity: Option<String>,
+
+    /// Free-form note, e.g. "hot in summer" (use "" to clear)
+    #[arg(long)]
+    pub note: Option<String>,
+}
+
+impl ConditionFlags {
+    /// Borrow the flags for the storage command.
+    pub fn to_args(&self) -> ConditionArgs<'_> {
+        ConditionArgs {
+            temp: self.temp.as_deref(),
+            humidity: self.humidity.as_deref(),
+            note: self.note.as_deref(),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ViewsCommands {
+    /// Create or refresh the views
+    Install,
+
+    /// Show whether each view is installed and up to date
+    Status,
+}
+
+#[cfg(feature = "ocr")]
+#[derive(Subcommand, Debug)]
+pub enum PhotoCommands {
+    /// Extract text from photos with OCR, optionally proposing items to add
+    Ocr {
+        /// Photos to read (e.g. a labeled bin or a receipt)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Ask whether to add each line of text as an item
+        #[arg(long)]
+        suggest_items: bool,
+
+        /// Container accepted items are added to (auto-creates if needed)
+        #[arg(short = 'i', long = "in", requires = "suggest_items")]
+        container: Option<String>,
+    },
+}
+
+#[cfg(feature = "notify")]
+#[derive(Subcommand, Debug)]
+pub enum NotifyCommands {
+    /// Send a message to every configured backend to check the setup
+    Test {
+        /// Text to send
+        message: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CronCommands {
+    /// Run every job that is due (meant for a single crontab line)
+    Run {
+        /// Run every scheduled job, even if it is not due
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print the config file, or write it to a file
+    Export {
+        /// File to write (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Install a config file exported on another machine
+    Import {
+        /// File written by `invy config export`
+        file: PathBuf,
+
+        /// Replace an existing config file (the old one is kept as .bak)
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DbCommands {
+    /// Print the database file path
+    Path,
+
+    /// Show schema version, item count, size and journal mode
+    Info,
+
+    /// Migrate the schema (default: to the latest version)
+    Migrate {
+        /// Target schema version
+        #[arg(long)]
+        to: Option<u32>,
     },
 }
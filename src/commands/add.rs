@@ -3,56 +3,263 @@
 //! See SPEC.md#invy-add-name
 
 use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::io::{self, IsTerminal, Write};
 use std::path::Path;
 
+use crate::classify;
+use crate::config::ClassifyConfig;
 use crate::db;
+use crate::editor;
+use crate::model::{ItemKind, ItemWithPath};
 use crate::output::{self, Format};
+use crate::quantity::Quantity;
+use crate::strict;
+use crate::timespec;
+
+/// What `invy add` creates besides the name.
+#[derive(Debug)]
+pub struct AddOptions<'a> {
+    /// Optional description
+    pub desc: Option<&'a str>,
+    /// Container to place the item in (auto-created if needed); `/` places it
+    /// at root
+    pub container: Option<&'a str>,
+    /// How much of the item there is (e.g. `3`, `3m`)
+    pub quantity: &'a str,
+    /// What the item is
+    pub kind: ItemKind,
+    /// Day the item expires, as a date or from now (`6m`)
+    pub expires: Option<&'a str>,
+    /// Write the description in `$EDITOR` instead
+    pub editor: bool,
+    /// Add this many identical containers, numbered `name-1`, `name-2`, ...
+    pub count: Option<u32>,
+    /// Group the numbered containers join (default: the name)
+    pub group: Option<&'a str>,
+    /// Whether a missing container is created, and strict mode
+    pub placement: db::Placement,
+}
+
+/// What `insert` gives a new item besides its name and container.
+#[derive(Debug)]
+pub struct Details<'a> {
+    /// Optional description
+    pub desc: Option<&'a str>,
+    /// How much of the item there is
+    pub quantity: &'a Quantity,
+    /// What the item is
+    pub kind: ItemKind,
+    /// Day the item expires (`YYYY-MM-DD`)
+    pub expires: Option<&'a str>,
+}
+
+impl Default for AddOptions<'_> {
+    fn default() -> Self {
+        Self {
+            desc: None,
+            container: None,
+            quantity: "1",
+            kind: ItemKind::Item,
+            expires: None,
+            editor: false,
+            count: None,
+            group: None,
+            placement: db::Placement::default(),
+        }
+    }
+}
 
 /// Add a new item to the inventory.
 ///
 /// # Arguments
 /// * `name` - Name of the item
-/// * `desc` - Optional description
-/// * `container` - Optional container to place item in (auto-creates if needed)
-/// * `json` - Output as JSON
-/// * `csv` - Output as CSV
+/// * `options` - Description, container, quantity, kind, expiry date, how
+///   many numbered copies to add and how the container is found
+/// * `format` - Output format
 /// * `db_path` - Optional custom database path
-pub fn run(
+pub fn run(name: &str, options: &AddOptions, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let AddOptions {
+        desc,
+        container,
+        quantity,
+        kind,
+        expires,
+        editor,
+        count,
+        group,
+        placement,
+    } = *options;
+    let quantity = Quantity::parse(quantity)?;
+    let expires = expires.map(timespec::parse_date).transpose()?;
+    let edited = if editor {
+        Some(editor::edit_text(desc.unwrap_or_default())?)
+    } else {
+        None
+    };
+    let desc = match edited.as_deref() {
+        Some("") => None,
+        Some(text) => Some(text),
+        None => desc,
+    };
+
+    let conn = db::open(db_path)?;
+    let details = Details {
+        desc,
+        quantity: &quantity,
+        kind,
+        expires: expires.as_deref(),
+    };
+
+    if let Some(count) = count {
+        let group = group.unwrap_or(name);
+        // Numbered copies are there to hold things
+        let details = Details {
+            kind: match kind {
+                ItemKind::Item => ItemKind::Container,
+                kind => kind,
+            },
+            ..details
+        };
+        let (items, plain_container) = db::with_tx(&conn, |tx| {
+            let mut items = Vec::new();
+            let mut plain_container = None;
+            for number in 1..=count {
+                let (mut item, plain) = insert(
+                    tx,
+                    &format!("{}-{}", name, number),
+                    &details,
+                    container,
+                    placement,
+                )?;
+                db::set_item_group(tx, item.id, group)?;
+                item.group = Some(group.to_string());
+                plain_container = plain;
+                items.push(item);
+            }
+            Ok((items, plain_container))
+        })?;
+
+        output::print_slash_warning(&mut io::stderr(), name)?;
+        if let Some(container) = plain_container {
+            output::print_plain_container_warning(&mut io::stderr(), &container)?;
+        }
+        return output::print_added_group(&mut io::stdout(), &items, group, format);
+    }
+
+    let (item_with_path, plain_container) =
+        db::with_tx(&conn, |tx| insert(tx, name, &details, container, placement))?;
+
+    output::print_slash_warning(&mut io::stderr(), name)?;
+    if let Some(container) = plain_container {
+        output::print_plain_container_warning(&mut io::stderr(), &container)?;
+    }
+    output::print_added(&mut io::stdout(), &item_with_path, format)
+}
+
+/// Pick a container for an item added without `--in` from the `[classify]`
+/// rules, telling the user on stderr.
+///
+/// With `ask`, the user confirms first; when stdin is not a terminal or in
+/// strict mode there is no one to ask, so the rule is only mentioned and
+/// `None` is returned.
+///
+/// # Arguments
+/// * `name` - Name of the new item
+/// * `desc` - Its description, searched along with the name
+/// * `config` - The `[classify]` table
+pub fn classify(name: &str, desc: Option<&str>, config: &ClassifyConfig) -> Result<Option<String>> {
+    let text = format!("{} {}", name, desc.unwrap_or_default());
+    let Some(suggestion) = classify::suggest(&config.rules, &text) else {
+        return Ok(None);
+    };
+    let container = suggestion.container.to_string();
+
+    if !config.ask {
+        eprintln!(
+            "Filing in {} (rule '{}'); use --in to choose another place",
+            container, suggestion.keyword
+        );
+        return Ok(Some(container));
+    }
+    if strict::enabled() || !io::stdin().is_terminal() {
+        eprintln!(
+            "hint: rule '{}' suggests --in {}",
+            suggestion.keyword, container
+        );
+        return Ok(None);
+    }
+    eprint!("File '{}' in {}? [Y/n] ", name, container);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(match answer.trim().to_lowercase().as_str() {
+        "" | "y" | "yes" => Some(container),
+        _ => None,
+    })
+}
+
+/// Insert `name` into `container` (auto-created if `placement` allows; `/`
+/// or `None` for root), returning the new item and, when the container is a
+/// plain item, its name so callers can warn about it.
+pub fn insert(
+    conn: &Connection,
     name: &str,
-    desc: Option<&str>,
+    details: &Details,
     container: Option<&str>,
-    json: bool,
-    csv: bool,
-    db_path: Option<&Path>,
-) -> Result<()> {
-    let conn = db::open(db_path)?;
-    let format = Format::from_flags(json, csv);
+    placement: db::Placement,
+) -> Result<(ItemWithPath, Option<String>)> {
+    let Details {
+        desc,
+        quantity,
+        kind,
+        expires,
+    } = *details;
+    if let Some(warning) = output::slash_warning(name) {
+        strict::check_when(placement.strict, || warning)?;
+    }
 
     // Resolve container if specified
+    let mut plain_container = None;
     let container_id = match container {
+        Some("/") | None => None,
         Some(container_ref) => {
-            let container_item = db::resolve_or_create_container(&conn, container_ref)?;
+            let container_item = db::resolve_or_create_container(conn, container_ref, placement)?;
+            if db::check_container_kind(&container_item, placement)?.is_some() {
+                plain_container = Some(container_item.name.clone());
+            }
             Some(container_item.id)
         }
-        None => None,
     };
 
     // Check for duplicate name in same container
-    if db::name_exists_in_container(&conn, name, container_id)? {
+    if db::name_exists_in_container(conn, name, container_id)? {
         let location = match container {
+            Some("/") | None => "(root)".to_string(),
             Some(c) => c.to_string(),
-            None => "(root)".to_string(),
         };
         return Err(anyhow!("item '{}' already exists in {}", name, location));
     }
 
     // Insert the item
-    let item = db::insert_item(&conn, name, desc, container_id)?;
+    let mut item = db::insert_item(conn, name, desc, container_id)?;
+    if *quantity != Quantity::count(1) {
+        db::update_item_quantity(conn, item.id, quantity)?;
+        item.quantity = quantity.amount;
+        item.unit = quantity.unit.clone();
+    }
+    if kind != ItemKind::Item {
+        db::set_item_kind(conn, item.id, kind)?;
+        item.kind = kind;
+    }
+    if expires.is_some() {
+        db::update_item_expiry(conn, item.id, expires)?;
+    }
 
     // Get full path for display
-    let path = db::get_item_path(&conn, item.id)?;
-    let child_count = db::count_children(&conn, item.id)?;
-    let item_with_path = item.with_path(path, Some(child_count));
-
-    output::print_added(&item_with_path, format)
+    let path = db::get_item_path(conn, item.id)?;
+    let child_count = db::count_children(conn, item.id)?;
+    let mut item = item.with_path(path, Some(child_count));
+    item.expires_at = expires.map(str::to_string);
+    Ok((item, plain_container))
 }
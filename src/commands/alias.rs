@@ -0,0 +1,99 @@
+//! Alias command implementation: other names items are known by.
+//!
+//! See SPEC.md#invy-alias
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::Alias;
+use crate::output::{self, Format};
+
+/// Give an item another name that `show`, `find` and other commands accept.
+///
+/// # Arguments
+/// * `item_ref` - Item to name
+/// * `alias` - The other name (e.g. `DMM` for a multimeter)
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn add(item_ref: &str, alias: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let alias = alias.trim();
+    if alias.is_empty() {
+        return Err(anyhow!("alias cannot be empty"));
+    }
+    if alias.contains('/') {
+        return Err(anyhow!("alias '{}' cannot contain '/'", alias));
+    }
+
+    let conn = db::open(db_path)?;
+
+    let alias = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+        if item.name.eq_ignore_ascii_case(alias) {
+            return Err(anyhow!("'{}' is already the item's name", alias));
+        }
+        if !db::add_alias(tx, item.id, alias)? {
+            return Err(anyhow!("'{}' already has the alias '{}'", item.name, alias));
+        }
+        Ok(Alias {
+            item_id: item.id,
+            path: db::get_item_path(tx, item.id)?,
+            alias: alias.to_string(),
+        })
+    })?;
+
+    output::print_alias(&mut io::stdout(), &alias, false, format)
+}
+
+/// Remove one of an item's aliases.
+///
+/// # Arguments
+/// * `item_ref` - Item to update
+/// * `alias` - Alias to remove
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn remove(item_ref: &str, alias: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let alias = alias.trim();
+    let conn = db::open(db_path)?;
+
+    let alias = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+        if !db::remove_alias(tx, item.id, alias)? {
+            return Err(anyhow!("'{}' has no alias '{}'", item.name, alias));
+        }
+        Ok(Alias {
+            item_id: item.id,
+            path: db::get_item_path(tx, item.id)?,
+            alias: alias.to_string(),
+        })
+    })?;
+
+    output::print_alias(&mut io::stdout(), &alias, true, format)
+}
+
+/// List an item's aliases.
+///
+/// # Arguments
+/// * `item_ref` - Item to inspect
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn list(item_ref: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let item = db::resolve_item(&conn, item_ref)?
+        .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+    let path = db::get_item_path(&conn, item.id)?;
+    let aliases: Vec<Alias> = db::list_aliases(&conn, item.id)?
+        .into_iter()
+        .map(|alias| Alias {
+            item_id: item.id,
+            path: path.clone(),
+            alias,
+        })
+        .collect();
+
+    output::print_aliases(&mut io::stdout(), &path, &aliases, format)
+}
@@ -0,0 +1,149 @@
+//! Audit command implementation: a physical stocktake of a container.
+//!
+//! See SPEC.md#invy-audit
+
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::{AuditReport, Item};
+use crate::output::{self, Format};
+
+/// Start an audit of a container (the whole inventory without one),
+/// replacing any audit already running.
+///
+/// # Arguments
+/// * `container_ref` - Container to audit
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn start(container_ref: Option<&str>, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let report = db::with_tx(&conn, |tx| {
+        let container = match container_ref {
+            Some("/") | None => None,
+            Some(container_ref) => Some(
+                db::resolve_item(tx, container_ref)?
+                    .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?,
+            ),
+        };
+        let started_at = db::start_audit(tx, container.as_ref().map(|c| c.id))?;
+        progress(tx, container.as_ref(), started_at)
+    })?;
+
+    output::print_audit(&mut io::stdout(), &report, true, format)
+}
+
+/// Mark items as found where they are recorded.
+///
+/// # Arguments
+/// * `item_refs` - Items that were found
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn check(item_refs: &[String], format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let (items, report) = db::with_tx(&conn, |tx| {
+        let (container, started_at) = current(tx)?;
+        let mut items = Vec::new();
+        for item_ref in item_refs {
+            let item = db::resolve_item(tx, item_ref)?
+                .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+            if let Some(container) = &container {
+                if !db::is_ancestor(tx, container.id, item.id)? || item.id == container.id {
+                    return Err(anyhow!(
+                        "'{}' is not inside '{}', the container being audited; move it there with `invy mv` first",
+                        db::join_path(&db::get_item_path(tx, item.id)?),
+                        container.name
+                    ));
+                }
+            }
+            let verified_at = db::verify_item(tx, item.id)?;
+            let path = db::get_item_path(tx, item.id)?;
+            let mut item = item.with_path(path, None);
+            item.verified_at = Some(verified_at);
+            items.push(item);
+        }
+        let report = progress(tx, container.as_ref(), started_at)?;
+        Ok((items, report))
+    })?;
+
+    output::print_audit_checked(&mut io::stdout(), &items, &report, format)
+}
+
+/// Report the items of the running audit that have not been checked yet.
+///
+/// # Arguments
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn report(format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let (container, started_at) = current(&conn)?;
+    let report = progress(&conn, container.as_ref(), started_at)?;
+    output::print_audit(&mut io::stdout(), &report, false, format)
+}
+
+/// The running audit's container (`None` for the whole inventory) and start
+/// time.
+fn current(conn: &Connection) -> Result<(Option<Item>, String)> {
+    let (container_id, started_at) = db::get_audit(conn)?.ok_or_else(|| {
+        anyhow!("no audit running. Start one with `invy audit start <container>`")
+    })?;
+    let container = match container_id {
+        Some(id) => Some(db::get_item_by_id(conn, id)?.ok_or_else(|| {
+            anyhow!("the container being audited was removed. Start a new audit with `invy audit start`")
+        })?),
+        None => None,
+    };
+    Ok((container, started_at))
+}
+
+/// Count what is checked inside `container` and list what is not.
+fn progress(
+    conn: &Connection,
+    container: Option<&Item>,
+    started_at: String,
+) -> Result<AuditReport> {
+    let mut children: HashMap<Option<i64>, Vec<Item>> = HashMap::new();
+    for item in db::list_all_items(conn)? {
+        children.entry(item.container_id).or_default().push(item);
+    }
+    let mut stack = vec![container.map(|c| c.id)];
+    let mut inside = Vec::new();
+    while let Some(parent) = stack.pop() {
+        for child in children.remove(&parent).unwrap_or_default() {
+            stack.push(Some(child.id));
+            inside.push(child);
+        }
+    }
+
+    let verifications = db::list_verifications(conn)?;
+    let total = inside.len();
+    let mut unchecked = Vec::new();
+    for item in inside {
+        let verified_at = verifications.get(&item.id).cloned();
+        if verified_at.as_deref() >= Some(started_at.as_str()) {
+            continue;
+        }
+        let path = db::get_item_path(conn, item.id)?;
+        let mut item = item.with_path(path, None);
+        item.verified_at = verified_at;
+        unchecked.push(item);
+    }
+    unchecked.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(AuditReport {
+        container_id: container.map(|c| c.id),
+        path: match container {
+            Some(container) => db::get_item_path(conn, container.id)?,
+            None => Vec::new(),
+        },
+        started_at,
+        checked: total - unchecked.len(),
+        total,
+        unchecked,
+    })
+}
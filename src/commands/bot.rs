@@ -0,0 +1,309 @@
+//! Bot command implementation: answer find, show and add from a Telegram
+//! chat.
+//!
+//! Only compiled with the `bot` cargo feature.
+//!
+//! See SPEC.md#invy-bot
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::commands::add;
+use crate::db;
+use crate::model::ItemKind;
+use crate::output::{self, Format};
+use crate::quantity::Quantity;
+
+/// Default Telegram Bot API server.
+const TELEGRAM_API: &str = "https://api.telegram.org";
+
+/// Setting holding the ID of the next update to fetch, so messages are
+/// answered once even across restarts.
+const OFFSET_KEY: &str = "bot.telegram.offset";
+
+/// How long one `getUpdates` call waits for new messages.
+const POLL_SECONDS: u64 = 50;
+
+/// Pause before polling again after a network error.
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Most search results listed in one reply.
+const MAX_RESULTS: usize = 20;
+
+const HELP: &str = "Ask me where things are:
+where is the passport?
+/find passport - search names and descriptions
+/show office/desk/passport - details of one item
+/add hammer in garage/toolbox - add an item";
+
+/// How `invy bot` connects to Telegram and whom it answers.
+#[derive(Debug)]
+pub struct BotOptions<'a> {
+    /// Bot token from @BotFather
+    pub token: &'a str,
+    /// Telegram user IDs allowed to use the bot
+    pub allow: &'a [i64],
+    /// Bot API server (default: `https://api.telegram.org`)
+    pub api_url: Option<&'a str>,
+    /// Answer waiting messages and exit instead of polling forever
+    pub once: bool,
+    /// Whether `/add` creates a missing container, and strict mode
+    pub placement: db::Placement,
+}
+
+/// Envelope around every Bot API result.
+#[derive(Debug, Deserialize)]
+struct Reply<T> {
+    ok: bool,
+    description: Option<String>,
+    result: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    from: Option<User>,
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+/// A bot on the Telegram Bot API.
+struct Telegram {
+    /// API server and bot token; kept out of error messages
+    base: String,
+}
+
+impl Telegram {
+    fn call<T: DeserializeOwned>(&self, method: &str, body: serde_json::Value) -> Result<T> {
+        let reply: Reply<T> = ureq::post(&format!("{}/{}", self.base, method))
+            .header("User-Agent", concat!("invy/", env!("CARGO_PKG_VERSION")))
+            .send_json(body)
+            .and_then(|mut response| response.body_mut().read_json())
+            .with_context(|| format!("Telegram {} failed", method))?;
+        match reply.result {
+            Some(result) if reply.ok => Ok(result),
+            _ => Err(anyhow!(
+                "Telegram {} failed: {}",
+                method,
+                reply.description.as_deref().unwrap_or("no result")
+            )),
+        }
+    }
+
+    fn updates(&self, offset: i64, timeout: u64) -> Result<Vec<Update>> {
+        self.call(
+            "getUpdates",
+            serde_json::json!({
+                "offset": offset,
+                "timeout": timeout,
+                "allowed_updates": ["message"],
+            }),
+        )
+    }
+
+    fn send(&self, chat_id: i64, text: &str) -> Result<()> {
+        self.call::<serde_json::Value>(
+            "sendMessage",
+            serde_json::json!({ "chat_id": chat_id, "text": text }),
+        )?;
+        Ok(())
+    }
+}
+
+/// Answer chat messages from allowed users until interrupted.
+///
+/// Each update is marked as handled before it is answered, so a crash never
+/// repeats an `/add`.
+///
+/// # Arguments
+/// * `options` - Token, allowed users, API server and polling mode
+/// * `db_path` - Optional custom database path
+pub fn run(options: &BotOptions, db_path: Option<&Path>) -> Result<()> {
+    if options.allow.is_empty() {
+        return Err(anyhow!(
+            "no users allowed; pass --allow <telegram user id> or set [bot] allow"
+        ));
+    }
+    let conn = db::open(db_path)?;
+    let api = options.api_url.unwrap_or(TELEGRAM_API);
+    let telegram = Telegram {
+        base: format!("{}/bot{}", api.trim_end_matches('/'), options.token),
+    };
+    if !options.once {
+        eprintln!(
+            "Answering Telegram messages from {} user(s); press Ctrl-C to stop",
+            options.allow.len()
+        );
+    }
+
+    loop {
+        let offset = db::get_setting(&conn, OFFSET_KEY)?
+            .and_then(|offset| offset.parse().ok())
+            .unwrap_or(0);
+        let timeout = if options.once { 0 } else { POLL_SECONDS };
+        let updates = match telegram.updates(offset, timeout) {
+            Ok(updates) => updates,
+            Err(err) if !options.once => {
+                eprintln!("Warning: {:#}; retrying", err);
+                std::thread::sleep(RETRY_DELAY);
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+
+        for update in updates {
+            db::set_setting(&conn, OFFSET_KEY, &(update.update_id + 1).to_string())?;
+            if let Some(message) = update.message {
+                handle(&conn, &telegram, options, message);
+            }
+        }
+        if options.once {
+            return Ok(());
+        }
+    }
+}
+
+/// Reply to one message. Failures are reported on stderr so one bad
+/// message cannot stop the bot.
+fn handle(conn: &Connection, telegram: &Telegram, options: &BotOptions, message: Message) {
+    let (Some(user), Some(text)) = (message.from, message.text) else {
+        return;
+    };
+    let reply = if options.allow.contains(&user.id) {
+        answer(conn, &text, options.placement).unwrap_or_else(|err| format!("Error: {:#}", err))
+    } else {
+        eprintln!("Ignored a message from user {}", user.id);
+        format!(
+            "Sorry, you are not on this bot's allow list. Your user ID is {}.",
+            user.id
+        )
+    };
+    if let Err(err) = telegram.send(message.chat.id, &reply) {
+        eprintln!("Warning: could not reply: {:#}", err);
+    }
+}
+
+/// The reply to a chat message: a `/command` or a plain question, which is
+/// searched for like `/find`.
+fn answer(conn: &Connection, text: &str, placement: db::Placement) -> Result<String> {
+    let text = text.trim();
+    let (command, args) = match text.strip_prefix('/') {
+        Some(rest) => {
+            let (command, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            // In groups commands arrive as `/find@house_bot`
+            let command = command.split('@').next().unwrap_or(command);
+            (command, args.trim())
+        }
+        None => ("find", question_subject(text)),
+    };
+
+    match command {
+        "start" | "help" => Ok(HELP.to_string()),
+        "find" => find(conn, args),
+        "show" => show(conn, args),
+        "add" => add(conn, args, placement),
+        _ => Ok(format!("Unknown command /{}\n\n{}", command, HELP)),
+    }
+}
+
+/// What a question like "where is the passport?" asks about.
+fn question_subject(text: &str) -> &str {
+    fn strip_word<'t>(text: &'t str, words: &[&str]) -> &'t str {
+        for word in words {
+            let prefix = text.get(..word.len());
+            if prefix.is_some_and(|prefix| prefix.eq_ignore_ascii_case(word)) {
+                return text[word.len()..].trim_start();
+            }
+        }
+        text
+    }
+
+    let text = strip_word(text, &["where is ", "where are ", "where's "]);
+    let text = strip_word(text, &["the ", "my ", "our "]);
+    text.trim_end_matches(['?', '!', '.', ' '])
+}
+
+fn find(conn: &Connection, query: &str) -> Result<String> {
+    if query.is_empty() {
+        return Ok("Usage: /find <text>".to_string());
+    }
+    let items = db::search_items(conn, query)?;
+    if items.is_empty() {
+        return Ok(format!("Nothing matches '{}'", query));
+    }
+
+    let mut lines = Vec::new();
+    for item in items.iter().take(MAX_RESULTS) {
+        lines.push(db::join_path(&db::get_item_path(conn, item.id)?));
+    }
+    if items.len() > MAX_RESULTS {
+        lines.push(format!("... and {} more", items.len() - MAX_RESULTS));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn show(conn: &Connection, item_ref: &str) -> Result<String> {
+    if item_ref.is_empty() {
+        return Ok("Usage: /show <item>".to_string());
+    }
+    let item = db::resolve_item(conn, item_ref)?
+        .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+    let path = db::get_item_path(conn, item.id)?;
+    let child_count = db::count_children(conn, item.id)?;
+    let mut item = item.with_path(path, Some(child_count));
+    let aliases = db::list_aliases(conn, item.id)?;
+    if !aliases.is_empty() {
+        item.aliases = Some(aliases);
+    }
+
+    let mut text = Vec::new();
+    output::print_item(&mut text, &item, Format::Human)?;
+    Ok(String::from_utf8_lossy(&text).trim_end().to_string())
+}
+
+/// `/add <name> [in <container>]`
+fn add(conn: &Connection, args: &str, placement: db::Placement) -> Result<String> {
+    let (name, container) = match args.rsplit_once(" in ") {
+        Some((name, container)) => (name.trim(), Some(container.trim())),
+        None => (args, None),
+    };
+    if name.is_empty() {
+        return Ok("Usage: /add <name> [in <container>]".to_string());
+    }
+
+    let (item, plain_container) = db::with_tx(conn, |tx| {
+        let details = add::Details {
+            desc: None,
+            quantity: &Quantity::count(1),
+            kind: ItemKind::Item,
+            expires: None,
+        };
+        add::insert(tx, name, &details, container, placement)
+    })?;
+    let mut text = Vec::new();
+    output::print_added(&mut text, &item, Format::Human)?;
+    output::print_slash_warning(&mut text, name)?;
+    if let Some(container) = plain_container {
+        output::print_plain_container_warning(&mut text, &container)?;
+    }
+    Ok(String::from_utf8_lossy(&text).trim_end().to_string())
+}
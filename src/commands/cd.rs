@@ -0,0 +1,55 @@
+//! Cd and pwd command implementations.
+//!
+//! See SPEC.md#invy-cd-container
+
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+
+/// Set or clear the current container context.
+///
+/// # Arguments
+/// * `container` - Container to focus on (`None`, "/" or "root" clears)
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(container: Option<&str>, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let context_id = match container {
+        None | Some("/") | Some("root") => None,
+        Some(container_ref) => {
+            let item = db::resolve_item(&conn, container_ref)?
+                .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?;
+            Some(item.id)
+        }
+    };
+    db::set_context(&conn, context_id)?;
+
+    print_current(&conn, format)
+}
+
+/// Show the current container context.
+///
+/// # Arguments
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn pwd(format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    print_current(&conn, format)
+}
+
+fn print_current(conn: &Connection, format: Format) -> Result<()> {
+    let context = match db::get_context(conn)? {
+        Some(item) => {
+            let path = db::get_item_path(conn, item.id)?;
+            Some(item.with_path(path, None))
+        }
+        None => None,
+    };
+
+    output::print_context(&mut io::stdout(), context.as_ref(), format)
+}
@@ -0,0 +1,175 @@
+//! Check command implementation.
+//!
+//! See SPEC.md#invy-check-container
+
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::{ChecklistEntry, ChecklistReport, Item};
+use crate::output::{self, Format};
+
+/// Where `find` and `list` save their results (`--save-as-checklist`,
+/// `--save-as-kit`).
+#[derive(Debug, Clone, Copy)]
+pub enum SaveAs<'a> {
+    /// Add the results' names to this container's checklist
+    Checklist(&'a str),
+    /// Create this container, move the results into it and put their names
+    /// on its checklist
+    Kit(&'a str),
+}
+
+/// Verify that a container holds everything on its checklist, after
+/// applying any checklist changes.
+///
+/// # Arguments
+/// * `container_ref` - Container to check
+/// * `require` - Names to add to the checklist
+/// * `unrequire` - Names to remove from the checklist
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    container_ref: &str,
+    require: &[String],
+    unrequire: &[String],
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let report = db::with_tx(&conn, |tx| {
+        let container = db::resolve_item(tx, container_ref)?
+            .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?;
+
+        for name in require {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(anyhow!("checklist entries cannot be empty"));
+            }
+            db::add_checklist_entry(tx, container.id, name)?;
+        }
+        for name in unrequire {
+            if !db::remove_checklist_entry(tx, container.id, name.trim())? {
+                return Err(anyhow!(
+                    "'{}' is not on the checklist of '{}'",
+                    name,
+                    container.name
+                ));
+            }
+        }
+
+        let mut entries = Vec::new();
+        for name in db::list_checklist(tx, container.id)? {
+            let path = match db::find_descendant_by_name(tx, container.id, &name)? {
+                Some(id) => Some(db::get_item_path(tx, id)?),
+                None => None,
+            };
+            entries.push(ChecklistEntry {
+                name,
+                present: path.is_some(),
+                path,
+            });
+        }
+
+        Ok(ChecklistReport {
+            container: db::get_item_path(tx, container.id)?,
+            missing: entries.iter().filter(|e| !e.present).count(),
+            entries,
+        })
+    })?;
+
+    output::print_checklist(&mut io::stdout(), &report, format)?;
+
+    if report.missing > 0 {
+        return Err(anyhow!(
+            "{} item(s) missing from '{}'",
+            report.missing,
+            db::join_path(&report.container)
+        ));
+    }
+    Ok(())
+}
+
+/// Save search results as a checklist or kit, in one transaction, and say
+/// what was saved on stderr (stdout has the results themselves).
+///
+/// # Arguments
+/// * `conn` - Database connection
+/// * `save` - Checklist or kit to save to
+/// * `items` - The results, with their own (not translated) names
+/// * `placement` - Whether a missing container is created, and strict mode
+pub fn save_results(
+    conn: &Connection,
+    save: SaveAs,
+    items: &[Item],
+    placement: db::Placement,
+) -> Result<()> {
+    if items.is_empty() {
+        return Err(anyhow!("no results to save"));
+    }
+
+    let message = db::with_tx(conn, |tx| match save {
+        SaveAs::Checklist(container_ref) => {
+            let container = db::resolve_or_create_container(tx, container_ref, placement)?;
+            let mut added = 0;
+            for item in items {
+                if db::add_checklist_entry(tx, container.id, &item.name)? {
+                    added += 1;
+                }
+            }
+            Ok(format!(
+                "Added {} names to the checklist of {}",
+                added,
+                db::join_path(&db::get_item_path(tx, container.id)?)
+            ))
+        }
+        SaveAs::Kit(kit_ref) => {
+            if db::resolve_item(tx, kit_ref)?.is_some() {
+                return Err(anyhow!(
+                    "'{}' already exists; use --save-as-checklist to add to its checklist",
+                    kit_ref
+                ));
+            }
+            let kit = db::resolve_or_create_container(tx, kit_ref, placement)?;
+            let ids: HashSet<i64> = items.iter().map(|item| item.id).collect();
+            let mut moved = 0;
+            for item in items {
+                db::add_checklist_entry(tx, kit.id, &item.name)?;
+                if db::is_ancestor(tx, item.id, kit.id)? {
+                    return Err(anyhow!("cannot move '{}' into a kit inside it", item.name));
+                }
+                // Results inside another result travel with it
+                let mut inside_result = false;
+                for &other in &ids {
+                    if other != item.id && db::is_ancestor(tx, other, item.id)? {
+                        inside_result = true;
+                        break;
+                    }
+                }
+                if inside_result {
+                    continue;
+                }
+                if db::name_exists_in_container(tx, &item.name, Some(kit.id))? {
+                    return Err(anyhow!(
+                        "more than one result is named '{}'; a kit holds one of each",
+                        item.name
+                    ));
+                }
+                db::move_item(tx, item.id, Some(kit.id))?;
+                moved += 1;
+            }
+            Ok(format!(
+                "Moved {} items into the new kit {}",
+                moved,
+                db::join_path(&db::get_item_path(tx, kit.id)?)
+            ))
+        }
+    })?;
+
+    eprintln!("{}", message);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Config command implementation: move settings between machines.
+//!
+//! See SPEC.md#invy-config
+
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::config;
+use crate::output::{self, Format};
+
+/// Write the config file to `output`, or print it.
+///
+/// # Arguments
+/// * `config_path` - Config file to export (default location if `None`)
+/// * `output` - File to write; stdout if `None`
+/// * `format` - Output format (`json` prints the settings as JSON)
+pub fn export(config_path: Option<&Path>, output: Option<&Path>, format: Format) -> Result<()> {
+    let source = config::resolve_path(config_path)?;
+    if !source.exists() {
+        return Err(anyhow!("no config file at {}", source.display()));
+    }
+    let contents = fs::read_to_string(&source)
+        .with_context(|| format!("Failed to read config file {:?}", source))?;
+    config::parse(&contents).with_context(|| format!("Invalid config file {:?}", source))?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, &contents)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            output::print_config_copied(&mut io::stdout(), "exported", &source, path, format)
+        }
+        None => output::print_config(&mut io::stdout(), &contents, format),
+    }
+}
+
+/// Install a config file exported on another machine.
+///
+/// # Arguments
+/// * `file` - File written by `invy config export`
+/// * `config_path` - Where to install it (default location if `None`)
+/// * `force` - Replace an existing config file (kept as `.bak`)
+/// * `format` - Output format
+pub fn import(file: &Path, config_path: Option<&Path>, force: bool, format: Format) -> Result<()> {
+    let contents =
+        fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    config::parse(&contents).with_context(|| format!("Invalid config file {:?}", file))?;
+
+    let target = config::resolve_path(config_path)?;
+    if target.exists() {
+        if fs::read_to_string(&target).ok().as_deref() == Some(contents.as_str()) {
+            return output::print_config_copied(
+                &mut io::stdout(),
+                "unchanged",
+                file,
+                &target,
+                format,
+            );
+        }
+        if !force {
+            return Err(anyhow!(
+                "config file {} already exists (use --force to replace it)",
+                target.display()
+            ));
+        }
+        let backup = target.with_extension("toml.bak");
+        fs::copy(&target, &backup)
+            .with_context(|| format!("Failed to back up {}", target.display()))?;
+    }
+
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(&target, &contents)
+        .with_context(|| format!("Failed to write {}", target.display()))?;
+
+    output::print_config_copied(&mut io::stdout(), "imported", file, &target, format)
+}
@@ -0,0 +1,92 @@
+//! Consume and consumed command implementations.
+//!
+//! See SPEC.md#invy-consume-item
+
+use anyhow::{anyhow, Context, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+use crate::quantity::Quantity;
+use crate::timespec;
+
+/// Use up some of an item, archiving it once none are left.
+///
+/// Unlike `rm`, every consumption is kept in the consumption log. Units
+/// convert within their family (`30cm` from `3 m` leaves `270 cm`); a bare
+/// number is taken in the item's own unit.
+///
+/// # Arguments
+/// * `item` - Item to consume
+/// * `quantity` - How much to consume (e.g. `2`, `30cm`)
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(item_ref: &str, quantity: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let mut take = Quantity::parse(quantity)?;
+
+    let conn = db::open(db_path)?;
+
+    let entry = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+
+        let have = item.measure();
+        if take.unit.is_none() {
+            take.unit = have.unit.clone();
+        }
+        let (taken, left) = have
+            .subtract(&take)
+            .with_context(|| format!("cannot consume {} of '{}'", take, item.name))?;
+
+        if left.amount < 0 {
+            return Err(anyhow!(
+                "cannot consume {} of '{}': only {} left",
+                take,
+                item.name,
+                have
+            ));
+        }
+
+        let remaining = left.amount;
+        if remaining == 0 {
+            let child_count = db::count_children(tx, item.id)?;
+            if child_count > 0 {
+                return Err(anyhow!(
+                    "cannot consume '{}': it still contains {} items",
+                    item.name,
+                    child_count
+                ));
+            }
+        }
+
+        let path = db::get_item_path(tx, item.id)?;
+        let entry = db::record_consumption(tx, &item, &path, &taken, remaining)?;
+
+        if remaining == 0 {
+            db::delete_item(tx, item.id)?;
+        } else {
+            // Back to the item's own unit when that is exact (300 cm -> 3 m)
+            let left = left.to_unit(have.unit.as_deref()).unwrap_or(left);
+            db::update_item_quantity(tx, item.id, &left)?;
+        }
+        Ok(entry)
+    })?;
+
+    output::print_consumed(&mut io::stdout(), &entry, format)
+}
+
+/// Report consumption log entries, newest first.
+///
+/// # Arguments
+/// * `since` - Optional age or date to start from (e.g. `30d`)
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn report(since: Option<&str>, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let cutoff = since.map(timespec::parse_since).transpose()?;
+
+    let conn = db::open(db_path)?;
+    let entries = db::list_consumptions(&conn, cutoff.as_deref())?;
+
+    output::print_consumptions(&mut io::stdout(), &entries, format)
+}
@@ -0,0 +1,65 @@
+//! Count command implementation.
+//!
+//! See SPEC.md#invy-count-container
+
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+
+/// Returned after printing a count of zero, so the process exits with code 1
+/// without an error message, like `grep -c`.
+#[derive(Debug)]
+pub struct NoneCounted;
+
+impl fmt::Display for NoneCounted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nothing counted")
+    }
+}
+
+impl std::error::Error for NoneCounted {}
+
+/// Print how many items are in a container.
+///
+/// # Arguments
+/// * `container` - Container to count in (default: root)
+/// * `recursive` - Count everything below it, not just direct children
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    container: Option<&str>,
+    recursive: bool,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let count = match container {
+        Some(container_ref) => {
+            let container = db::resolve_item(&conn, container_ref)?
+                .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?;
+            if recursive {
+                db::count_descendants(&conn, container.id)?
+            } else {
+                db::count_children(&conn, container.id)?
+            }
+        }
+        None if recursive => db::count_items(&conn)?,
+        None => db::count_root_items(&conn)?,
+    };
+
+    report(count, format)
+}
+
+/// Print `count`, failing with [`NoneCounted`] when it is zero.
+pub fn report(count: i64, format: Format) -> Result<()> {
+    output::print_count(&mut io::stdout(), count, format)?;
+    if count == 0 {
+        return Err(NoneCounted.into());
+    }
+    Ok(())
+}
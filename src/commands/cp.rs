@@ -0,0 +1,80 @@
+//! Copy command implementation.
+//!
+//! See SPEC.md#invy-cp-item-destination
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+
+/// Copy an item, optionally with its whole subtree, into a container.
+///
+/// # Arguments
+/// * `item` - Item to copy
+/// * `destination` - Target container (use "/" for root)
+/// * `recursive` - Copy the item's contents as well
+/// * `placement` - Whether a missing destination is created, and strict mode
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    item_ref: &str,
+    destination: &str,
+    recursive: bool,
+    placement: db::Placement,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let (item_with_path, copied_count) = db::with_tx(&conn, |tx| {
+        // Resolve the item to copy
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+
+        // Resolve destination
+        let new_container_id = if destination == "/" || destination == "root" {
+            None
+        } else {
+            let container = db::resolve_or_create_container(tx, destination, placement)?;
+
+            // A recursive copy into its own subtree would copy the copy
+            if recursive && (container.id == item.id || db::is_ancestor(tx, item.id, container.id)?)
+            {
+                return Err(anyhow!(
+                    "cannot copy '{}' into itself or its descendants",
+                    item.name
+                ));
+            }
+
+            Some(container.id)
+        };
+
+        // Check for name conflict in destination
+        if db::name_exists_in_container(tx, &item.name, new_container_id)? {
+            let dest_name = if new_container_id.is_none() {
+                "(root)".to_string()
+            } else {
+                destination.to_string()
+            };
+            return Err(anyhow!(
+                "item '{}' already exists in {}",
+                item.name,
+                dest_name
+            ));
+        }
+
+        // Perform the copy
+        let copy = db::copy_item(tx, item.id, new_container_id, recursive)?;
+        let copied_count = 1 + db::count_descendants(tx, copy.id)?;
+
+        // Get full path for display
+        let path = db::get_item_path(tx, copy.id)?;
+        let child_count = db::count_children(tx, copy.id)?;
+        let item_with_path = copy.with_path(path, Some(child_count));
+        Ok((item_with_path, copied_count))
+    })?;
+
+    output::print_copied(&mut io::stdout(), &item_with_path, copied_count, format)
+}
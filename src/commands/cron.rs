@@ -0,0 +1,188 @@
+//! Cron command implementation.
+//!
+//! See SPEC.md#invy-cron-run
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::Connection;
+use std::io;
+use std::path::Path;
+
+use crate::config::{Config, CronConfig};
+use crate::db;
+use crate::model::{CronRun, CronStatus};
+#[cfg(feature = "notify")]
+use crate::notify;
+use crate::output::{self, Format};
+use crate::timespec;
+
+/// A periodic job `invy cron run` knows how to run.
+#[derive(Debug, Clone, Copy)]
+enum Job {
+    Backup,
+    VerifyHashes,
+    PruneShares,
+}
+
+impl Job {
+    /// Jobs in the order they run.
+    const ALL: [Job; 3] = [Job::Backup, Job::VerifyHashes, Job::PruneShares];
+
+    /// Name used in output and as the `[cron]` config key.
+    fn name(self) -> &'static str {
+        match self {
+            Job::Backup => "backup",
+            Job::VerifyHashes => "verify_hashes",
+            Job::PruneShares => "prune_shares",
+        }
+    }
+
+    fn schedule(self, config: &CronConfig) -> Option<&str> {
+        match self {
+            Job::Backup => config.backup.as_deref(),
+            Job::VerifyHashes => config.verify_hashes.as_deref(),
+            Job::PruneShares => config.prune_shares.as_deref(),
+        }
+    }
+
+    /// Run the job, returning a one-line summary of what it did.
+    fn run(self, conn: &Connection, db_file: &Path, config: &CronConfig) -> Result<String> {
+        match self {
+            Job::Backup => backup(conn, db_file, config.backup_dir.as_deref()),
+            Job::VerifyHashes => verify_hashes(conn),
+            Job::PruneShares => {
+                let removed = db::with_tx(conn, |tx| db::delete_expired_shares(tx))?;
+                Ok(format!("removed {} expired share(s)", removed))
+            }
+        }
+    }
+}
+
+/// Run every scheduled job that is due.
+///
+/// A job is due when its interval has passed since its last successful run
+/// (stored in the database, so the schedule follows the data file). Failed
+/// jobs stay due and are retried on the next run, and are pushed to the
+/// `[notify]` backends when built with notifications.
+///
+/// # Arguments
+/// * `config` - Configuration with the `[cron]` schedules
+/// * `force` - Run every scheduled job, due or not
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(config: &Config, force: bool, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let cron = &config.cron;
+    let db_file = db::resolve_path(db_path)?;
+    let conn = db::open(Some(&db_file))?;
+
+    let mut runs = Vec::new();
+    for job in Job::ALL {
+        let Some(schedule) = job.schedule(cron) else {
+            continue;
+        };
+        let cutoff = timespec::parse_since(schedule)
+            .with_context(|| format!("invalid [cron] {} schedule", job.name()))?;
+
+        let key = format!("cron.{}.last_run", job.name());
+        let last_run = db::get_setting(&conn, &key)?;
+        if let Some(last) = last_run.as_deref().filter(|last| *last > cutoff.as_str()) {
+            if !force {
+                runs.push(CronRun {
+                    job: job.name().to_string(),
+                    status: CronStatus::Skipped,
+                    detail: format!("not due (every {}, last ran {})", schedule, last),
+                    last_run,
+                });
+                continue;
+            }
+        }
+
+        let started = timespec::now();
+        let run = match job.run(&conn, &db_file, cron) {
+            Ok(detail) => {
+                db::set_setting(&conn, &key, &started)?;
+                CronRun {
+                    job: job.name().to_string(),
+                    status: CronStatus::Ran,
+                    detail,
+                    last_run: Some(started),
+                }
+            }
+            Err(err) => CronRun {
+                job: job.name().to_string(),
+                status: CronStatus::Failed,
+                detail: format!("{:#}", err),
+                last_run,
+            },
+        };
+        runs.push(run);
+    }
+
+    output::print_cron_runs(&mut io::stdout(), &runs, format)?;
+
+    let failed = runs
+        .iter()
+        .filter(|r| r.status == CronStatus::Failed)
+        .count();
+    #[cfg(feature = "notify")]
+    if failed > 0 {
+        let message: Vec<String> = runs
+            .iter()
+            .filter(|r| r.status == CronStatus::Failed)
+            .map(|r| format!("{}: {}", r.job, r.detail))
+            .collect();
+        let title = format!("invy: {} job(s) failed", failed);
+        // A broken notifier must not hide the job failures themselves
+        if let Err(err) = notify::send_all(&config.notify, &title, &message.join("\n")) {
+            eprintln!("Warning: could not send notification: {:#}", err);
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow!("{} job(s) failed", failed));
+    }
+    Ok(())
+}
+
+/// Copy the database to a timestamped file in `dir` (default:
+/// `invy-backups` next to the database).
+fn backup(conn: &Connection, db_file: &Path, dir: Option<&Path>) -> Result<String> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => db_file
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("invy-backups"),
+    };
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory {:?}", dir))?;
+
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    let target = dir.join(format!("invy-{}.db", stamp));
+    db::backup_to(conn, &target)?;
+    Ok(format!("wrote {}", target.display()))
+}
+
+/// Compare every container with a recorded hash against its contents.
+fn verify_hashes(conn: &Connection) -> Result<String> {
+    let ids = db::list_hashed_items(conn)?;
+    let mut changed = Vec::new();
+    for &id in &ids {
+        let hash = db::hash_contents(conn, id)?;
+        let recorded = db::get_recorded_hash(conn, id)?;
+        if recorded.map(|(stored, _)| stored) != Some(hash) {
+            changed.push(db::join_path(&db::get_item_path(conn, id)?));
+        }
+    }
+
+    if changed.is_empty() {
+        Ok(format!(
+            "{} container(s) match their recorded hash",
+            ids.len()
+        ))
+    } else {
+        Err(anyhow!(
+            "{} container(s) changed: {}",
+            changed.len(),
+            changed.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,58 @@
+//! Database maintenance command implementations.
+//!
+//! See SPEC.md#invy-db
+
+use anyhow::Result;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::DbInfo;
+use crate::output::{self, Format};
+
+/// Print the database file path.
+///
+/// # Arguments
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn path(format: Format, db_path: Option<&Path>) -> Result<()> {
+    let path = db::resolve_path(db_path)?;
+    output::print_db_path(&mut io::stdout(), &path, format)
+}
+
+/// Show storage-level information without migrating the schema.
+///
+/// # Arguments
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn info(format: Format, db_path: Option<&Path>) -> Result<()> {
+    let path = db::resolve_path(db_path)?;
+    let conn = db::open_unmigrated(Some(&path))?;
+
+    let info = DbInfo {
+        path: path.display().to_string(),
+        schema_version: db::schema_version(&conn)?,
+        latest_schema_version: db::LATEST_SCHEMA_VERSION,
+        item_count: db::count_items(&conn)?,
+        size_bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+        journal_mode: db::journal_mode(&conn)?,
+    };
+
+    output::print_db_info(&mut io::stdout(), &info, format)
+}
+
+/// Migrate the schema to a given version (default: latest).
+///
+/// # Arguments
+/// * `to` - Target schema version
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn migrate(to: Option<u32>, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open_unmigrated(db_path)?;
+
+    let from = db::schema_version(&conn)?;
+    let target = to.unwrap_or(db::LATEST_SCHEMA_VERSION);
+    db::migrate_to(&conn, target)?;
+
+    output::print_migrated(&mut io::stdout(), from, target, format)
+}
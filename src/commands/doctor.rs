@@ -0,0 +1,163 @@
+//! Doctor command implementation: database integrity checks and repairs.
+//!
+//! See SPEC.md#invy-doctor
+
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::{DoctorIssue, Item, ViewStatus};
+use crate::output::{self, Format};
+
+/// Check the database file and the item hierarchy, optionally repairing
+/// what can be repaired.
+///
+/// Items in a container cycle or whose container no longer exists are moved
+/// to root, and duplicate names within a container are renamed `name (2)`.
+/// File corruption found by `PRAGMA integrity_check` cannot be repaired.
+///
+/// # Arguments
+/// * `fix` - Repair the problems found
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(fix: bool, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let issues = if fix {
+        db::with_tx(&conn, |tx| check(tx, true))?
+    } else {
+        check(&conn, false)?
+    };
+
+    output::print_doctor(&mut io::stdout(), &issues, format)?;
+
+    let remaining = issues.iter().filter(|issue| issue.fix.is_none()).count();
+    if remaining > 0 {
+        return Err(anyhow!(
+            "{} problem(s) found{}",
+            remaining,
+            if fix {
+                ""
+            } else {
+                " (run with --fix to repair)"
+            }
+        ));
+    }
+    Ok(())
+}
+
+/// Run every check in order. Dangling containers and cycles are repaired
+/// before duplicates are looked for, since moving items to root can only
+/// produce names that are then renamed to be unique.
+fn check(conn: &Connection, fix: bool) -> Result<Vec<DoctorIssue>> {
+    let mut issues: Vec<DoctorIssue> = db::integrity_check(conn)?
+        .into_iter()
+        .map(|message| DoctorIssue {
+            check: "integrity".to_string(),
+            item_id: None,
+            message,
+            fix: None,
+        })
+        .collect();
+
+    for item in db::find_dangling_containers(conn)? {
+        let message = format!(
+            "'{}' (#{}) is in container #{}, which does not exist",
+            item.name,
+            item.id,
+            item.container_id.unwrap_or_default()
+        );
+        let fix = fix.then(|| move_to_root(conn, &item)).transpose()?;
+        issues.push(issue("dangling", &item, message, fix));
+    }
+
+    for item in db::find_cycles(conn)? {
+        let message = format!("'{}' (#{}) is inside its own contents", item.name, item.id);
+        let fix = fix.then(|| move_to_root(conn, &item)).transpose()?;
+        issues.push(issue("cycle", &item, message, fix));
+    }
+
+    for item in db::find_duplicate_names(conn)? {
+        let message = format!(
+            "'{}' (#{}) has the same name as another item in its container",
+            item.name, item.id
+        );
+        let fix = if fix {
+            let name = db::free_name(conn, &item.name, item.container_id)?;
+            db::update_item_name(conn, item.id, &name)?;
+            Some(format!("renamed to '{}'", name))
+        } else {
+            None
+        };
+        issues.push(issue("duplicate", &item, message, fix));
+    }
+
+    // Stored paths cannot be trusted once the hierarchy was broken
+    if fix {
+        db::rebuild_item_paths(conn)?;
+    }
+
+    issues.extend(check_views(conn, fix)?);
+
+    Ok(issues)
+}
+
+/// Report convenience views left behind by an upgrade. Views are optional,
+/// so a missing one only counts once some other view has been installed.
+fn check_views(conn: &Connection, fix: bool) -> Result<Vec<DoctorIssue>> {
+    let views = db::view_status(conn)?;
+    if views.iter().all(|view| view.status == ViewStatus::Missing) {
+        return Ok(Vec::new());
+    }
+
+    let outdated: Vec<_> = views
+        .into_iter()
+        .filter_map(|view| {
+            let problem = match view.status {
+                ViewStatus::Ok => return None,
+                ViewStatus::Missing => "not installed",
+                ViewStatus::Stale => "out of date",
+                ViewStatus::Broken => "broken",
+            };
+            Some(format!("view {} is {}", view.name, problem))
+        })
+        .collect();
+    if fix && !outdated.is_empty() {
+        db::install_views(conn)?;
+    }
+
+    Ok(outdated
+        .into_iter()
+        .map(|message| DoctorIssue {
+            check: "view".to_string(),
+            item_id: None,
+            message,
+            fix: fix.then(|| "reinstalled".to_string()),
+        })
+        .collect())
+}
+
+/// Move an item to root, renaming it if the name is taken there.
+fn move_to_root(conn: &Connection, item: &Item) -> Result<String> {
+    let name = db::free_name(conn, &item.name, None)?;
+    if name != item.name {
+        db::update_item_name(conn, item.id, &name)?;
+    }
+    db::move_item(conn, item.id, None)?;
+    Ok(if name == item.name {
+        "moved to root".to_string()
+    } else {
+        format!("moved to root as '{}'", name)
+    })
+}
+
+fn issue(check: &str, item: &Item, message: String, fix: Option<String>) -> DoctorIssue {
+    DoctorIssue {
+        check: check.to_string(),
+        item_id: Some(item.id),
+        message,
+        fix,
+    }
+}
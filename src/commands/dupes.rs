@@ -0,0 +1,112 @@
+//! Dupes command implementation.
+//!
+//! See SPEC.md#invy-dupes
+
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::fuzzy;
+use crate::model::{DupeGroup, DupeKind};
+use crate::output::{self, Format};
+
+/// List items sharing a name, or with names a few typos apart, across
+/// containers.
+///
+/// # Arguments
+/// * `max_distance` - Edits allowed between similar names (default: scaled
+///   by name length)
+/// * `exact` - Only report identical names
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    max_distance: Option<usize>,
+    exact: bool,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    // Items by case-folded name; a BTreeMap keeps groups in name order
+    let mut by_name: BTreeMap<String, Vec<_>> = BTreeMap::new();
+    for item in db::list_all_items(&conn)? {
+        by_name
+            .entry(item.name.to_lowercase())
+            .or_default()
+            .push(item);
+    }
+    let names: Vec<&String> = by_name.keys().collect();
+
+    // Union names that are close enough
+    let mut parent: Vec<usize> = (0..names.len()).collect();
+    if !exact {
+        for i in 0..names.len() {
+            for j in i + 1..names.len() {
+                if similar(names[i], names[j], max_distance) {
+                    let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+    }
+
+    let mut clusters: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..names.len() {
+        let root = find(&mut parent, i);
+        clusters.entry(root).or_default().push(i);
+    }
+
+    let mut paths: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut groups = Vec::new();
+    for members in clusters.values() {
+        let items: Vec<_> = members.iter().flat_map(|&i| &by_name[names[i]]).collect();
+        if items.len() < 2 {
+            continue;
+        }
+        let kind = if members.len() == 1 {
+            DupeKind::Exact
+        } else {
+            DupeKind::Similar
+        };
+        let mut with_paths = Vec::new();
+        for item in items {
+            let path = match paths.get(&item.id) {
+                Some(path) => path.clone(),
+                None => db::get_item_path(&conn, item.id)?,
+            };
+            paths.insert(item.id, path.clone());
+            with_paths.push(item.clone().with_path(path, None));
+        }
+        with_paths.sort_by(|a, b| a.path.cmp(&b.path));
+        groups.push(DupeGroup {
+            kind,
+            items: with_paths,
+        });
+    }
+
+    output::print_dupes(&mut io::stdout(), &groups, format)
+}
+
+/// Whether two distinct (case-folded) names are near-duplicates.
+///
+/// By default short names must match exactly and longer ones may differ by
+/// one edit per five characters, up to two.
+fn similar(a: &str, b: &str, max_distance: Option<usize>) -> bool {
+    let shorter = a.chars().count().min(b.chars().count());
+    let allowed = max_distance.unwrap_or((shorter / 5).min(2));
+    if allowed == 0 || a.chars().count().abs_diff(b.chars().count()) > allowed {
+        return false;
+    }
+    fuzzy::edit_distance(a, b) <= allowed
+}
+
+/// Union-find root with path halving.
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
@@ -3,81 +3,350 @@
 //! See SPEC.md#invy-edit-item
 
 use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::io;
 use std::path::Path;
 
 use crate::db;
-use crate::output::{self, Format};
+use crate::editor;
+use crate::model::{ItemKind, ItemWithPath};
+use crate::output::{self, Format, UpdatedFields};
+use crate::quantity::{self, Quantity};
+use crate::strict;
+use crate::timespec;
 
-/// Edit an existing item's name or description.
+/// The changes `invy edit` makes; `None` leaves a field alone.
+#[derive(Debug, Default)]
+pub struct EditChanges<'a> {
+    /// New name
+    pub name: Option<&'a str>,
+    /// New description ("" clears it)
+    pub desc: Option<&'a str>,
+    /// New weight, e.g. `2kg` ("" clears it)
+    pub weight: Option<&'a str>,
+    /// Restock below this quantity, e.g. `2` or `500g` ("" clears it)
+    pub min_qty: Option<&'a str>,
+    /// Day the item expires, as a date or from now (`6m`) ("" clears it)
+    pub expires: Option<&'a str>,
+    /// Container to move the item into ("/" for root)
+    pub container: Option<&'a str>,
+    /// What the item is
+    pub kind: Option<ItemKind>,
+    /// Write the new description in `$EDITOR`, starting from the current one
+    pub editor: bool,
+    /// Text to add to the end of the current description
+    pub append_desc: Option<&'a str>,
+}
+
+/// Placed between the current description and appended text.
+const APPEND_SEPARATOR: &str = "; ";
+
+/// An edited item with the values it had before.
+struct Edited {
+    item: ItemWithPath,
+    old_name: String,
+    old_desc: Option<String>,
+    old_path: Vec<String>,
+    old_kind: ItemKind,
+}
+
+/// Edit existing items' name, description, weight, restocking threshold,
+/// expiry date, container or kind.
+///
+/// Several items get the same change in one transaction; if any of them
+/// fails, none is changed.
 ///
 /// # Arguments
-/// * `item` - Item to edit
-/// * `name` - Optional new name
-/// * `desc` - Optional new description (use "" to clear)
-/// * `json` - Output as JSON
-/// * `csv` - Output as CSV
+/// * `item_refs` - Items to edit
+/// * `changes` - Fields to change
+/// * `expected_updated_at` - Fail unless the item's `updated_at` still equals this
+/// * `placement` - Whether a missing `--in` container is created, and strict mode
+/// * `format` - Output format
 /// * `db_path` - Optional custom database path
 pub fn run(
-    item_ref: &str,
-    new_name: Option<&str>,
-    new_desc: Option<&str>,
-    json: bool,
-    csv: bool,
+    item_refs: &[String],
+    changes: &EditChanges,
+    expected_updated_at: Option<&str>,
+    placement: db::Placement,
+    format: Format,
     db_path: Option<&Path>,
 ) -> Result<()> {
+    let EditChanges {
+        name: new_name,
+        desc: new_desc,
+        weight: new_weight,
+        min_qty: new_min_qty,
+        expires: new_expires,
+        container: new_container,
+        kind: new_kind,
+        editor,
+        append_desc,
+    } = *changes;
     let conn = db::open(db_path)?;
-    let format = Format::from_flags(json, csv);
 
     // Check that at least one change is specified
-    if new_name.is_none() && new_desc.is_none() {
-        return Err(anyhow!("no changes specified. Use --name or --desc"));
+    if new_name.is_none()
+        && new_desc.is_none()
+        && new_weight.is_none()
+        && new_min_qty.is_none()
+        && new_expires.is_none()
+        && new_container.is_none()
+        && new_kind.is_none()
+        && !editor
+        && append_desc.is_none()
+    {
+        return Err(anyhow!(
+            "no changes specified. Use --name, --desc, --append-desc, --editor, --weight, --min-qty, --expires, --in or --kind"
+        ));
+    }
+    if item_refs.len() > 1 {
+        let single = [
+            ("--name", new_name.is_some()),
+            ("--editor", editor),
+            ("--expected-updated-at", expected_updated_at.is_some()),
+        ];
+        if let Some((flag, _)) = single.iter().find(|(_, given)| *given) {
+            return Err(anyhow!("{} edits one item at a time", flag));
+        }
+    }
+
+    // The editor runs before the write transaction so the database is not
+    // locked while the user types
+    let edited_desc;
+    let new_desc = match (editor, item_refs) {
+        (true, [item_ref]) => {
+            let item = db::resolve_item(&conn, item_ref)?
+                .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+            edited_desc = editor::edit_text(item.description.as_deref().unwrap_or_default())?;
+            Some(edited_desc.as_str())
+        }
+        _ => new_desc,
+    };
+    let parsed = Parsed::parse(changes)?;
+    let changes = EditChanges {
+        desc: new_desc,
+        ..*changes
+    };
+
+    let edited = db::with_tx(&conn, |tx| {
+        item_refs
+            .iter()
+            .map(|item_ref| {
+                edit_item(
+                    tx,
+                    item_ref,
+                    &changes,
+                    &parsed,
+                    expected_updated_at,
+                    placement,
+                )
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    if let Some(name) = new_name {
+        output::print_slash_warning(&mut io::stderr(), name)?;
     }
+    if let [edited] = edited.as_slice() {
+        let changed = UpdatedFields {
+            old_name: new_name.map(|_| edited.old_name.as_str()),
+            old_desc: if new_desc.is_some() || append_desc.is_some() {
+                Some(edited.old_desc.as_deref())
+            } else {
+                None
+            },
+            new_weight: parsed.weight,
+            new_min_qty: parsed.min_qty.as_ref().map(Option::as_ref),
+            new_expires: parsed.expires.as_ref().map(Option::as_deref),
+            old_path: new_container.map(|_| edited.old_path.as_slice()),
+            old_kind: new_kind.map(|_| edited.old_kind),
+        };
+        return output::print_updated(&mut io::stdout(), &edited.item, &changed, format);
+    }
+    let items: Vec<ItemWithPath> = edited.into_iter().map(|edited| edited.item).collect();
+    output::print_updated_items(&mut io::stdout(), &items, format)
+}
 
+/// Apply `changes` to one item in its own transaction and return the
+/// updated item, for callers other than the CLI (`invy --api`, `invy
+/// serve`). `changes.editor` is ignored.
+pub fn update(
+    conn: &Connection,
+    item_ref: &str,
+    changes: &EditChanges,
+    expected_updated_at: Option<&str>,
+    placement: db::Placement,
+) -> Result<ItemWithPath> {
+    let parsed = Parsed::parse(changes)?;
+    let edited = db::with_tx(conn, |tx| {
+        edit_item(
+            tx,
+            item_ref,
+            changes,
+            &parsed,
+            expected_updated_at,
+            placement,
+        )
+    })?;
+    Ok(edited.item)
+}
+
+/// Values of `EditChanges` parsed once for every item; `None` leaves a field
+/// alone and `Some(None)` clears it.
+struct Parsed {
+    /// Weight in grams
+    weight: Option<Option<i64>>,
+    min_qty: Option<Option<Quantity>>,
+    /// Expiry date (`YYYY-MM-DD`)
+    expires: Option<Option<String>>,
+}
+
+impl Parsed {
+    /// Parse the values given as text ("" clears them), refusing in strict
+    /// mode a new name that would need a warning.
+    fn parse(changes: &EditChanges) -> Result<Self> {
+        if let Some(warning) = changes.name.and_then(output::slash_warning) {
+            strict::check(|| warning)?;
+        }
+        let weight = match changes.weight.map(str::trim) {
+            Some("") => Some(None),
+            Some(weight) => Some(Some(quantity::parse_weight(weight)?)),
+            None => None,
+        };
+        let min_qty = match changes.min_qty.map(str::trim) {
+            Some("") => Some(None),
+            Some(min) => Some(Some(Quantity::parse(min)?)),
+            None => None,
+        };
+        let expires = match changes.expires.map(str::trim) {
+            Some("") => Some(None),
+            Some(expires) => Some(Some(timespec::parse_date(expires)?)),
+            None => None,
+        };
+        Ok(Parsed {
+            weight,
+            min_qty,
+            expires,
+        })
+    }
+}
+
+/// Apply `changes` to one item. `changes.weight`, `changes.min_qty` and
+/// `changes.expires` are ignored in favor of the already parsed values in
+/// `parsed`.
+fn edit_item(
+    tx: &Connection,
+    item_ref: &str,
+    changes: &EditChanges,
+    parsed: &Parsed,
+    expected_updated_at: Option<&str>,
+    placement: db::Placement,
+) -> Result<Edited> {
     // Resolve the item to edit
-    let item = db::resolve_item(&conn, item_ref)?
-        .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+    let item =
+        db::resolve_item(tx, item_ref)?.ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+    db::ensure_unchanged(&item, expected_updated_at)?;
 
     let old_name = item.name.clone();
     let old_desc = item.description.clone();
+    let old_path = db::get_item_path(tx, item.id)?;
+    let old_kind = item.kind;
+
+    // Resolve the new container with the same checks as `invy mv`
+    let container_id = match changes.container {
+        Some(destination) => db::resolve_destination(
+            tx,
+            destination,
+            &item,
+            &format!("'{}'", item.name),
+            placement,
+        )?,
+        None => item.container_id,
+    };
+
+    // Check for a name conflict where the item ends up
+    let name = changes.name.unwrap_or(&item.name);
+    if (name != item.name || container_id != item.container_id)
+        && db::name_exists_in_container(tx, name, container_id)?
+    {
+        let location = match changes.container {
+            Some(destination) => db::destination_label(destination),
+            None if container_id.is_some() => "container".to_string(),
+            None => "(root)".to_string(),
+        };
+        return Err(anyhow!("item '{}' already exists in {}", name, location));
+    }
+
+    if container_id != item.container_id {
+        db::move_item(tx, item.id, container_id)?;
+    }
 
     // Update name if specified
-    if let Some(name) = new_name {
-        // Check for name conflict
-        if name != item.name && db::name_exists_in_container(&conn, name, item.container_id)? {
-            let location = if item.container_id.is_some() {
-                "container"
-            } else {
-                "(root)"
-            };
-            return Err(anyhow!("item '{}' already exists in {}", name, location));
-        }
-        db::update_item_name(&conn, item.id, name)?;
+    if let Some(name) = changes.name {
+        db::update_item_name(tx, item.id, name)?;
     }
 
     // Update description if specified
-    if let Some(desc) = new_desc {
+    if let Some(desc) = changes.desc {
         let desc_value = if desc.is_empty() { None } else { Some(desc) };
-        db::update_item_description(&conn, item.id, desc_value)?;
+        db::update_item_description(tx, item.id, desc_value)?;
+    }
+    if let Some(text) = changes
+        .append_desc
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+    {
+        let desc = match item.description.as_deref().map(str::trim_end) {
+            Some(current) if !current.is_empty() => {
+                format!("{}{}{}", current, APPEND_SEPARATOR, text)
+            }
+            _ => text.to_string(),
+        };
+        db::update_item_description(tx, item.id, Some(&desc))?;
+    }
+
+    if let Some(grams) = parsed.weight {
+        db::update_item_weight(tx, item.id, grams)?;
+    }
+
+    if let Some(min) = &parsed.min_qty {
+        if let Some(min) = min {
+            item.measure().subtract(min).map_err(|_| {
+                anyhow!(
+                    "minimum {} cannot be compared with the quantity of '{}' ({})",
+                    min,
+                    item.name,
+                    item.measure()
+                )
+            })?;
+        }
+        db::update_item_min_quantity(tx, item.id, min.as_ref())?;
+    }
+
+    if let Some(expires) = &parsed.expires {
+        db::update_item_expiry(tx, item.id, expires.as_deref())?;
+    }
+
+    if let Some(kind) = changes.kind.filter(|&kind| kind != item.kind) {
+        db::set_item_kind(tx, item.id, kind)?;
     }
 
     // Get updated item for display
-    let updated_item = db::get_item_by_id(&conn, item.id)?
+    let updated_item = db::get_item_by_id(tx, item.id)?
         .ok_or_else(|| anyhow!("Failed to retrieve updated item"))?;
-    let path = db::get_item_path(&conn, updated_item.id)?;
-    let item_with_path = updated_item.with_path(path, None);
-
-    output::print_updated(
-        &item_with_path,
-        if new_name.is_some() {
-            Some(&old_name)
-        } else {
-            None
-        },
-        if new_desc.is_some() {
-            Some(old_desc.as_deref())
-        } else {
-            None
-        },
-        format,
-    )
+    let path = db::get_item_path(tx, updated_item.id)?;
+    let mut item_with_path = updated_item.with_path(path, None);
+    item_with_path.weight_g = db::subtree_weights(tx)?.get(&item.id).copied();
+    if let Some(min) = db::get_min_quantity(tx, item.id)? {
+        item_with_path.min_quantity = Some(min.amount);
+        item_with_path.min_unit = min.unit;
+    }
+    item_with_path.expires_at = db::get_expiry(tx, item.id)?;
+    Ok(Edited {
+        item: item_with_path,
+        old_name,
+        old_desc,
+        old_path,
+        old_kind,
+    })
 }
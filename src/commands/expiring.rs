@@ -0,0 +1,39 @@
+//! Expiring command implementation: items past or near their expiry date.
+//!
+//! See SPEC.md#invy-expiring
+
+use anyhow::Result;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+use crate::timespec;
+
+/// List items that expire within `within` of today, including those
+/// already expired, soonest first.
+///
+/// # Arguments
+/// * `within` - How far ahead to look: a duration (`30d`) or a date
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(within: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let until = timespec::parse_date(within)?;
+    let conn = db::open(db_path)?;
+
+    let mut items = Vec::new();
+    for (item, expires_at) in db::list_expiring(&conn, &until)? {
+        let path = db::get_item_path(&conn, item.id)?;
+        let mut item = item.with_path(path, None);
+        item.expires_at = Some(expires_at);
+        items.push(item);
+    }
+
+    output::print_expiring(
+        &mut io::stdout(),
+        &items,
+        &timespec::today(),
+        within,
+        format,
+    )
+}
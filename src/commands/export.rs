@@ -0,0 +1,467 @@
+//! Export command implementation.
+//!
+//! See SPEC.md#invy-export
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use crate::age;
+use crate::db;
+use crate::model::{ExportRow, Item};
+use crate::output::{self, Format};
+use crate::pathglob::PathFilter;
+use crate::timespec;
+
+/// Layout produced by `invy export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportTarget {
+    /// JSON array of flat item rows
+    Json,
+    /// Directory with `items.json` and a Datasette `metadata.json`
+    Datasette,
+    /// Directory of Markdown notes, one per container, for Obsidian
+    MarkdownVault,
+    /// iCalendar (.ics) feed of expiry dates
+    Ical,
+}
+
+/// Name of the vault note listing the top level.
+const VAULT_INDEX: &str = "_Inventory";
+
+/// Export the whole inventory, or the parts of it `filter` keeps.
+///
+/// # Arguments
+/// * `container` - Only export this container and what is inside it
+/// * `target` - Layout to produce
+/// * `output` - File (json, ical) or directory (datasette, markdown-vault)
+///   to write; json and ical go to stdout when omitted
+/// * `filter` - `--include` / `--exclude` path globs
+/// * `recipients` - age recipients to encrypt the json export to; empty
+///   for plain text
+/// * `format` - Output format for the summary message
+/// * `db_path` - Optional custom database path
+pub fn run(
+    container: Option<&str>,
+    target: ExportTarget,
+    output_path: Option<&Path>,
+    filter: &PathFilter,
+    recipients: &[String],
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let within = match container {
+        Some(container) => {
+            let item = db::resolve_item(&conn, container)?
+                .ok_or_else(|| anyhow!("container '{}' not found", container))?;
+            db::get_item_path(&conn, item.id)?
+        }
+        None => Vec::new(),
+    };
+    let items = db::list_all_items(&conn)?;
+    let rows = export_rows(items, &within, filter);
+
+    if !recipients.is_empty() {
+        if target != ExportTarget::Json {
+            return Err(anyhow!("--encrypt only works with the json target"));
+        }
+        let mut plaintext = serde_json::to_vec_pretty(&rows)?;
+        plaintext.push(b'\n');
+        let encrypted = age::encrypt(&plaintext, recipients)?;
+        let Some(path) = output_path else {
+            let mut stdout = io::stdout();
+            if stdout.is_terminal() {
+                return Err(anyhow!(
+                    "refusing to print an encrypted export to the terminal. Use --output <file>"
+                ));
+            }
+            stdout.write_all(&encrypted)?;
+            return Ok(());
+        };
+        fs::write(path, encrypted).with_context(|| format!("Failed to write {:?}", path))?;
+        return output::print_exported(&mut io::stdout(), rows.len(), path, format);
+    }
+
+    if target == ExportTarget::Ical {
+        let expiries: HashMap<i64, String> = db::list_expiring(&conn, "9999-12-31")?
+            .into_iter()
+            .map(|(item, expires_at)| (item.id, expires_at))
+            .collect();
+        let events: Vec<(&ExportRow, &str)> = rows
+            .iter()
+            .filter_map(|row| expiries.get(&row.id).map(|date| (row, date.as_str())))
+            .collect();
+        let feed = ical_feed(&events, &timespec::now())?;
+        let Some(path) = output_path else {
+            io::stdout().write_all(feed.as_bytes())?;
+            return Ok(());
+        };
+        fs::write(path, feed).with_context(|| format!("Failed to write {:?}", path))?;
+        return output::print_exported(&mut io::stdout(), events.len(), path, format);
+    }
+
+    match (target, output_path) {
+        (ExportTarget::Json, None) => {
+            let mut stdout = io::stdout();
+            serde_json::to_writer(&mut stdout, &rows)?;
+            writeln!(stdout)?;
+            return Ok(());
+        }
+        (ExportTarget::Json, Some(path)) => write_json(path, &rows)?,
+        (ExportTarget::Datasette, Some(dir)) => {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory {:?}", dir))?;
+            write_json(&dir.join("items.json"), &rows)?;
+            write_json(&dir.join("metadata.json"), &datasette_metadata())?;
+        }
+        (ExportTarget::MarkdownVault, Some(dir)) => write_vault(dir, &rows)?,
+        (ExportTarget::Datasette, None) => {
+            return Err(anyhow!(
+                "the datasette target writes a directory. Use --output <dir>"
+            ));
+        }
+        (ExportTarget::MarkdownVault, None) => {
+            return Err(anyhow!(
+                "the markdown-vault target writes a directory. Use --output <dir>"
+            ));
+        }
+        (ExportTarget::Ical, _) => unreachable!("ical export returned early"),
+    }
+
+    let path = output_path.expect("stdout export returned early");
+    output::print_exported(&mut io::stdout(), rows.len(), path, format)
+}
+
+/// Flatten the items under `within` (a container path; empty for all) that
+/// `filter` keeps into rows ordered by path, resolving paths in memory.
+fn export_rows(items: Vec<Item>, within: &[String], filter: &PathFilter) -> Vec<ExportRow> {
+    let by_id: HashMap<i64, &Item> = items.iter().map(|item| (item.id, item)).collect();
+    let mut child_counts: HashMap<i64, i64> = HashMap::new();
+    for item in &items {
+        if let Some(container_id) = item.container_id {
+            *child_counts.entry(container_id).or_default() += 1;
+        }
+    }
+
+    let container_path = |item: &Item| {
+        let mut path = Vec::new();
+        let mut current = item.container_id.and_then(|id| by_id.get(&id));
+        while let Some(parent) = current {
+            path.push(parent.name.as_str());
+            current = parent.container_id.and_then(|id| by_id.get(&id));
+        }
+        path.reverse();
+        path
+    };
+
+    let mut rows: Vec<ExportRow> = items
+        .iter()
+        .filter_map(|item| {
+            let container = container_path(item);
+            let mut path = container.clone();
+            path.push(&item.name);
+            let segments: Vec<String> = path.iter().map(|name| name.to_string()).collect();
+            if !segments.starts_with(within) || !filter.matches(&segments) {
+                return None;
+            }
+            Some(ExportRow {
+                id: item.id,
+                name: item.name.clone(),
+                description: item.description.clone(),
+                container_id: item.container_id,
+                container: container.join("/"),
+                path: path.join("/"),
+                depth: container.len() as i64,
+                quantity: item.quantity,
+                unit: item.unit.clone(),
+                child_count: child_counts.get(&item.id).copied().unwrap_or(0),
+                created_at: item.created_at.clone(),
+                updated_at: item.updated_at.clone(),
+                uuid: item.uuid.clone(),
+                kind: item.kind,
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| a.path.cmp(&b.path));
+    rows
+}
+
+/// Write a Markdown note for every container in `rows` (an item with
+/// contents, or a container or location by kind) into `dir`, in folders
+/// mirroring the containers, plus `_Inventory.md` for the top level.
+///
+/// Regenerating is idempotent: unchanged notes are not rewritten, and notes
+/// from an earlier export whose container is gone are removed. Other files
+/// in the vault are left alone.
+fn write_vault(dir: &Path, rows: &[ExportRow]) -> Result<()> {
+    let by_id: HashMap<i64, &ExportRow> = rows.iter().map(|row| (row.id, row)).collect();
+    let mut contents: HashMap<Option<i64>, Vec<&ExportRow>> = HashMap::new();
+    for row in rows {
+        // Rows whose container was not exported go at the top
+        let parent = row.container_id.filter(|id| by_id.contains_key(id));
+        contents.entry(parent).or_default().push(row);
+    }
+    let is_note = |row: &ExportRow| contents.contains_key(&Some(row.id)) || row.kind.holds_items();
+
+    // Note names relative to the vault, without `.md`; rows are sorted by
+    // path, so containers come before their contents
+    let mut names: HashMap<i64, String> = HashMap::new();
+    for row in rows {
+        let name = match row.container_id.and_then(|id| names.get(&id)) {
+            Some(parent) => format!("{}/{}", parent, note_file_name(&row.name)),
+            None => note_file_name(&row.name),
+        };
+        names.insert(row.id, name);
+    }
+    let link = |row: &ExportRow| format!("[[{}|{}]]", names[&row.id], row.name);
+    let list = |children: Option<&Vec<&ExportRow>>| {
+        let Some(children) = children else {
+            return "_Empty_\n".to_string();
+        };
+        let mut text = String::new();
+        for child in children {
+            text.push_str("- ");
+            if is_note(child) {
+                text.push_str(&link(child));
+            } else {
+                text.push_str(&child.name);
+            }
+            if let Some(description) = &child.description {
+                text.push_str(": ");
+                text.push_str(description);
+            }
+            if child.quantity != 1 || child.unit.is_some() {
+                let unit = child.unit.as_deref().unwrap_or_default();
+                text.push_str(&format!(" ({}{})", child.quantity, unit));
+            }
+            text.push('\n');
+        }
+        text
+    };
+
+    let mut notes: Vec<(String, String)> = vec![(
+        VAULT_INDEX.to_string(),
+        format!(
+            "---\ngenerator: invy\n---\n# Inventory\n\n{}",
+            list(contents.get(&None))
+        ),
+    )];
+    for row in rows.iter().filter(|row| is_note(row)) {
+        // JSON strings are valid YAML, so values need no other escaping
+        let quote = |text: &str| serde_json::to_string(text).expect("strings serialize");
+        let mut note = String::from("---\ngenerator: invy\n");
+        note.push_str(&format!("id: {}\n", row.id));
+        if let Some(uuid) = &row.uuid {
+            note.push_str(&format!("uuid: {}\n", quote(uuid)));
+        }
+        note.push_str(&format!("path: {}\n", quote(&row.path)));
+        note.push_str(&format!("kind: {}\n", row.kind.as_str()));
+        note.push_str(&format!("quantity: {}\n", row.quantity));
+        if let Some(unit) = &row.unit {
+            note.push_str(&format!("unit: {}\n", quote(unit)));
+        }
+        note.push_str(&format!("created: {}\n", quote(&row.created_at)));
+        note.push_str(&format!("updated: {}\n", quote(&row.updated_at)));
+        note.push_str("tags:\n  - invy\n---\n");
+        note.push_str(&format!("# {}\n\n", row.name));
+        if let Some(description) = &row.description {
+            note.push_str(&format!("{}\n\n", description));
+        }
+        match row.container_id.and_then(|id| by_id.get(&id)) {
+            Some(parent) => note.push_str(&format!("Inside {}\n\n", link(parent))),
+            None => note.push_str(&format!("Inside [[{}|Inventory]]\n\n", VAULT_INDEX)),
+        }
+        note.push_str("## Contents\n\n");
+        note.push_str(&list(contents.get(&Some(row.id))));
+        notes.push((names[&row.id].clone(), note));
+    }
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+    let mut written = HashSet::new();
+    for (name, note) in notes {
+        let path = dir.join(format!("{}.md", name));
+        if fs::read_to_string(&path).ok().as_deref() != Some(note.as_str()) {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory {:?}", parent))?;
+            }
+            fs::write(&path, note).with_context(|| format!("Failed to write {:?}", path))?;
+        }
+        written.insert(path);
+    }
+    remove_stale_notes(dir, &written)?;
+    Ok(())
+}
+
+/// A name usable as a note file name and in a wiki-link: characters that
+/// file systems or Obsidian links reject become `-`.
+fn note_file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    let name = name.trim();
+    match name.strip_prefix('.') {
+        Some(rest) => format!("-{}", rest),
+        None if name.is_empty() => "-".to_string(),
+        None => name.to_string(),
+    }
+}
+
+/// Remove notes an earlier export wrote that `written` no longer has, and
+/// folders that emptied. Returns whether `dir` was emptied this way.
+fn remove_stale_notes(dir: &Path, written: &HashSet<PathBuf>) -> Result<bool> {
+    let mut empty = true;
+    let mut removed = false;
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {:?}", dir))? {
+        let path = entry?.path();
+        let stale = if path.is_dir() {
+            remove_stale_notes(&path, written)?
+        } else {
+            path.extension().is_some_and(|ext| ext == "md")
+                && !written.contains(&path)
+                && fs::read_to_string(&path)
+                    .is_ok_and(|text| text.starts_with("---\ngenerator: invy\n"))
+        };
+        if !stale {
+            empty = false;
+            continue;
+        }
+        if path.is_dir() {
+            fs::remove_dir(&path)
+        } else {
+            fs::remove_file(&path)
+        }
+        .with_context(|| format!("Failed to remove {:?}", path))?;
+        removed = true;
+    }
+    Ok(empty && removed)
+}
+
+/// Datasette `metadata.json` describing the `items` table.
+fn datasette_metadata() -> serde_json::Value {
+    json!({
+        "title": "invy inventory",
+        "description": "Exported with `invy export --target datasette`. Load with: sqlite-utils insert invy.db items items.json --pk id",
+        "databases": {
+            "invy": {
+                "tables": {
+                    "items": {
+                        "description": "Every item in the inventory, one row per item",
+                        "sort": "path",
+                        "label_column": "name",
+                        "facets": ["container"],
+                        "columns": {
+                            "id": "Item ID",
+                            "name": "Item name (unique within its container)",
+                            "description": "Free-text description",
+                            "container_id": "ID of the containing item; null at root",
+                            "container": "Path of the containing item; empty at root",
+                            "path": "Full slash-separated path",
+                            "depth": "Nesting level; root items are 0",
+                            "quantity": "How much of the item there is, in unit",
+                            "unit": "Unit of quantity (e.g. m, g); null for a plain count",
+                            "child_count": "Number of items directly inside",
+                            "created_at": "Creation time (UTC)",
+                            "updated_at": "Last update time (UTC)",
+                            "uuid": "Stable identifier that survives renames and re-imports",
+                            "kind": "item, container or location"
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// An iCalendar feed with an all-day event on each item's expiry date.
+///
+/// Event UIDs derive from the item's UUID (or ID), so a calendar
+/// subscribed to a regularly regenerated file updates events in place.
+///
+/// # Arguments
+/// * `events` - Rows with their expiry date (`YYYY-MM-DD`)
+/// * `now` - SQLite timestamp for `DTSTAMP`
+fn ical_feed(events: &[(&ExportRow, &str)], now: &str) -> Result<String> {
+    let stamp = format!("{}Z", now.replace(['-', ':'], "").replace(' ', "T"));
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//invy//invy export//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:invy".to_string(),
+    ];
+    for (row, date) in events {
+        let start = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .with_context(|| format!("invalid expiry date '{}' on '{}'", date, row.path))?;
+        let end = start.succ_opt().unwrap_or(start);
+        let uid = match &row.uuid {
+            Some(uuid) => uuid.clone(),
+            None => row.id.to_string(),
+        };
+        let mut description = row.path.clone();
+        if let Some(desc) = &row.description {
+            description.push('\n');
+            description.push_str(desc);
+        }
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}-expires@invy", uid),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+            format!("SUMMARY:{}", ical_text(&format!("{} expires", row.name))),
+            format!("DESCRIPTION:{}", ical_text(&description)),
+        ]);
+        if !row.container.is_empty() {
+            lines.push(format!("LOCATION:{}", ical_text(&row.container)));
+        }
+        lines.extend(["TRANSP:TRANSPARENT".to_string(), "END:VEVENT".to_string()]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut feed = String::new();
+    for line in lines {
+        feed.push_str(&fold_ical_line(&line));
+        feed.push_str("\r\n");
+    }
+    Ok(feed)
+}
+
+/// Escape a TEXT value: backslashes, semicolons, commas and newlines.
+fn ical_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line longer than 75 octets onto continuation lines that
+/// start with a space, without splitting a character.
+fn fold_ical_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+fn write_json(path: &Path, value: &impl serde::Serialize) -> Result<()> {
+    let mut contents = serde_json::to_string_pretty(value)?;
+    contents.push('\n');
+    fs::write(path, contents).with_context(|| format!("Failed to write {:?}", path))
+}
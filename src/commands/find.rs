@@ -2,33 +2,170 @@
 //!
 //! See SPEC.md#invy-find-query
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::io;
 use std::path::Path;
 
-use crate::db;
+use crate::commands::check::{self, SaveAs};
+use crate::commands::count;
+use crate::db::{self, SortKey};
+use crate::model::Item;
 use crate::output::{self, Format};
+use crate::pathglob::PathFilter;
+use crate::template::Template;
+use crate::timespec::DateFilter;
 
-/// Search for items by name or description.
+/// What `invy find` looks for.
+#[derive(Debug, Clone, Copy)]
+pub enum FindQuery<'a> {
+    /// Items whose name, description or name in another language contains
+    /// the text
+    Text(&'a str),
+    /// Containers with no item of this name anywhere inside
+    MissingChild(&'a str),
+    /// Containers missing something on their checklist
+    Incomplete,
+}
+
+/// Where `invy find` looks and how results are shown.
+#[derive(Debug)]
+pub struct FindOptions<'a> {
+    /// Only search below this container
+    pub within: Option<&'a str>,
+    /// Only include items whose full path passes this filter
+    pub paths: &'a PathFilter,
+    /// Only include items whose timestamps match
+    pub dates: &'a DateFilter,
+    /// Number of matches to skip
+    pub offset: usize,
+    /// Maximum number of matches to show
+    pub limit: Option<usize>,
+    /// Show names in this language where set (see `invy i18n`)
+    pub lang: Option<&'a str>,
+    /// Render each match through this template instead
+    pub template: Option<&'a Template>,
+    /// Also save the matches as a checklist or kit
+    pub save: Option<SaveAs<'a>>,
+    /// Whether the container `save` names is created, and strict mode
+    pub placement: db::Placement,
+    /// Only print the number of matches
+    pub count: bool,
+}
+
+/// Search for items by name or description, or for containers lacking
+/// expected contents.
 ///
 /// # Arguments
-/// * `query` - Search term (substring match, case-insensitive)
-/// * `json` - Output as JSON
-/// * `csv` - Output as CSV
+/// * `query` - What to look for
+/// * `options` - Scope, filtering, paging and display language
+/// * `format` - Output format
 /// * `db_path` - Optional custom database path
-pub fn run(query: &str, json: bool, csv: bool, db_path: Option<&Path>) -> Result<()> {
+pub fn run(
+    query: FindQuery,
+    options: &FindOptions,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let FindOptions {
+        within,
+        paths,
+        dates,
+        offset,
+        limit,
+        lang,
+        template,
+        save,
+        placement,
+        count,
+    } = *options;
     let conn = db::open(db_path)?;
-    let format = Format::from_flags(json, csv);
 
-    let items = db::search_items(&conn, query)?;
+    let within = match within {
+        Some(container_ref) => Some(
+            db::resolve_item(&conn, container_ref)?
+                .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?,
+        ),
+        None => None,
+    };
+
+    let mut items = Vec::new();
+    for item in matches(&conn, query, within.as_ref())? {
+        if dates.matches(&item.created_at, &item.updated_at)
+            && paths.matches(&db::get_item_path(&conn, item.id)?)
+        {
+            items.push(item);
+        }
+    }
+    if count {
+        return count::report(items.len() as i64, format);
+    }
+    let (items, page) = output::paginate(items, offset, limit);
+    if let Some(save) = save {
+        check::save_results(&conn, save, &items, placement)?;
+    }
 
     // Convert to ItemWithPath for display
-    let items_with_path: Vec<_> = items
-        .into_iter()
-        .map(|item| {
-            let path = db::get_item_path(&conn, item.id).unwrap_or_default();
-            item.with_path(path, None)
-        })
-        .collect();
-
-    output::print_items(&items_with_path, format)
+    let mut items_with_path = Vec::new();
+    for mut item in items {
+        db::localize_item(&conn, &mut item, lang)?;
+        let path = db::get_item_path_in(&conn, item.id, lang)?;
+        items_with_path.push(item.with_path(path, None));
+    }
+
+    match template {
+        Some(template) => output::print_templated(&mut io::stdout(), &items_with_path, template),
+        None => output::print_items(&mut io::stdout(), &items_with_path, page, format),
+    }
+}
+
+/// Items matching `query`, sorted by name.
+///
+/// With `within`, text matches and incomplete containers must be below it,
+/// and `MissingChild` checks its direct children (e.g. each room of a house)
+/// rather than every container.
+fn matches(conn: &Connection, query: FindQuery, within: Option<&Item>) -> Result<Vec<Item>> {
+    let below = |item: &Item| match within {
+        Some(container) => {
+            item.id != container.id && db::is_ancestor(conn, container.id, item.id).unwrap_or(false)
+        }
+        None => true,
+    };
+
+    match query {
+        FindQuery::Text(text) => Ok(db::search_items(conn, text)?
+            .into_iter()
+            .filter(below)
+            .collect()),
+        FindQuery::MissingChild(name) => {
+            let candidates = match within {
+                Some(container) => {
+                    db::list_items_sorted(conn, Some(container.id), SortKey::Name, false)?
+                }
+                None => db::list_containers(conn)?,
+            };
+            let mut missing = Vec::new();
+            for item in candidates {
+                if db::find_descendant_by_name(conn, item.id, name)?.is_none() {
+                    missing.push(item);
+                }
+            }
+            Ok(missing)
+        }
+        FindQuery::Incomplete => {
+            let mut incomplete = Vec::new();
+            for item in db::list_checklist_containers(conn)?
+                .into_iter()
+                .filter(below)
+            {
+                for name in db::list_checklist(conn, item.id)? {
+                    if db::find_descendant_by_name(conn, item.id, &name)?.is_none() {
+                        incomplete.push(item);
+                        break;
+                    }
+                }
+            }
+            Ok(incomplete)
+        }
+    }
 }
@@ -0,0 +1,89 @@
+//! Hash command implementation.
+//!
+//! See SPEC.md#invy-hash-container
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::{ContainerHash, HashStatus};
+use crate::output::{self, Format};
+
+/// Record or verify content hashes for containers.
+///
+/// # Arguments
+/// * `container` - Container to hash (all recorded containers with `verify`)
+/// * `verify` - Compare against the stored hash instead of recording
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    container: Option<&str>,
+    verify: bool,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let results = db::with_tx(&conn, |tx| {
+        let ids = match container {
+            Some(container_ref) => {
+                let item = db::resolve_item(tx, container_ref)?
+                    .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?;
+                vec![item.id]
+            }
+            None if verify => db::list_hashed_items(tx)?,
+            None => return Err(anyhow!("no container specified. Use --verify to check all")),
+        };
+
+        let mut results = Vec::new();
+        for id in ids {
+            let hash = db::hash_contents(tx, id)?;
+            let path = db::get_item_path(tx, id)?;
+
+            let result = if verify {
+                let recorded = db::get_recorded_hash(tx, id)?;
+                let status = match &recorded {
+                    Some((stored, _)) if *stored == hash => HashStatus::Ok,
+                    Some(_) => HashStatus::Changed,
+                    None => HashStatus::Unrecorded,
+                };
+                let (recorded_hash, recorded_at) = recorded.unzip();
+                ContainerHash {
+                    id,
+                    path,
+                    hash,
+                    recorded_hash,
+                    recorded_at,
+                    status,
+                }
+            } else {
+                db::record_hash(tx, id, &hash)?;
+                ContainerHash {
+                    id,
+                    path,
+                    hash,
+                    recorded_hash: None,
+                    recorded_at: None,
+                    status: HashStatus::Recorded,
+                }
+            };
+            results.push(result);
+        }
+        Ok(results)
+    })?;
+
+    output::print_hashes(&mut io::stdout(), &results, format)?;
+
+    let drifted = results
+        .iter()
+        .filter(|r| matches!(r.status, HashStatus::Changed | HashStatus::Unrecorded))
+        .count();
+    if drifted > 0 {
+        return Err(anyhow!(
+            "{} container(s) do not match their recorded hash",
+            drifted
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! Heavy command implementation: containers too heavy to lift safely.
+//!
+//! See SPEC.md#invy-heavy
+
+use anyhow::Result;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+
+/// Threshold used when neither `--over` nor `heavy_over` in the config is
+/// given.
+pub const DEFAULT_OVER: &str = "20kg";
+
+/// List containers whose contents (and own weight) add up to more than
+/// `over` grams, heaviest first.
+///
+/// # Arguments
+/// * `over` - Threshold in grams
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(over: i64, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let weights = db::subtree_weights(&conn)?;
+    let mut heavy = Vec::new();
+    for (&id, &weight) in &weights {
+        if weight <= over {
+            continue;
+        }
+        let child_count = db::count_children(&conn, id)?;
+        if child_count == 0 {
+            continue;
+        }
+        if let Some(item) = db::get_item_by_id(&conn, id)? {
+            let path = db::get_item_path(&conn, id)?;
+            let mut item = item.with_path(path, Some(child_count));
+            item.weight_g = Some(weight);
+            heavy.push(item);
+        }
+    }
+    heavy.sort_by(|a, b| b.weight_g.cmp(&a.weight_g).then(a.path.cmp(&b.path)));
+
+    output::print_heavy(&mut io::stdout(), &heavy, over, format)
+}
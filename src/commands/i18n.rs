@@ -0,0 +1,119 @@
+//! I18n command implementation: item names in other languages.
+//!
+//! See SPEC.md#invy-i18n
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::Translation;
+use crate::output::{self, Format};
+
+/// Set an item's name in another language, replacing any previous one.
+///
+/// # Arguments
+/// * `item_ref` - Item to name
+/// * `lang` - Language code (e.g. `es`, `pt-BR`)
+/// * `name` - The item's name in that language
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn set(
+    item_ref: &str,
+    lang: &str,
+    name: &str,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let lang = parse_lang(lang)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("name cannot be empty"));
+    }
+
+    let conn = db::open(db_path)?;
+
+    let translation = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+        db::set_item_name(tx, item.id, &lang, name)?;
+        Ok(Translation {
+            item_id: item.id,
+            path: db::get_item_path(tx, item.id)?,
+            lang: lang.clone(),
+            name: name.to_string(),
+        })
+    })?;
+
+    output::print_translation(&mut io::stdout(), &translation, false, format)
+}
+
+/// Remove an item's name in another language.
+///
+/// # Arguments
+/// * `item_ref` - Item to update
+/// * `lang` - Language code of the name to remove
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn unset(item_ref: &str, lang: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let lang = parse_lang(lang)?;
+    let conn = db::open(db_path)?;
+
+    let translation = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+        let name = db::remove_item_name(tx, item.id, &lang)?
+            .ok_or_else(|| anyhow!("'{}' has no '{}' name", item.name, lang))?;
+        Ok(Translation {
+            item_id: item.id,
+            path: db::get_item_path(tx, item.id)?,
+            lang: lang.clone(),
+            name,
+        })
+    })?;
+
+    output::print_translation(&mut io::stdout(), &translation, true, format)
+}
+
+/// List an item's names in other languages.
+///
+/// # Arguments
+/// * `item_ref` - Item to inspect
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn list(item_ref: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let item = db::resolve_item(&conn, item_ref)?
+        .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+    let path = db::get_item_path(&conn, item.id)?;
+
+    let translations: Vec<Translation> = db::list_item_names(&conn, item.id)?
+        .into_iter()
+        .map(|(lang, name)| Translation {
+            item_id: item.id,
+            path: path.clone(),
+            lang,
+            name,
+        })
+        .collect();
+
+    output::print_translations(&mut io::stdout(), &path, &translations, format)
+}
+
+/// Normalize a language code such as `es` or `pt-BR` to lowercase,
+/// rejecting anything that is not letters, digits and hyphens.
+pub fn parse_lang(lang: &str) -> Result<String> {
+    let lang = lang.trim().to_lowercase();
+    let valid = !lang.is_empty()
+        && lang.split('-').all(|part| {
+            (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+        && lang.starts_with(|c: char| c.is_ascii_alphabetic());
+    if !valid {
+        return Err(anyhow!(
+            "invalid language code '{}' (expected e.g. es or pt-BR)",
+            lang
+        ));
+    }
+    Ok(lang)
+}
@@ -0,0 +1,821 @@
+//! Import command implementation.
+//!
+//! See SPEC.md#invy-import-file
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::Connection;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::age;
+use crate::db;
+use crate::interrupt;
+use crate::model::{ExportRow, ImportIssue, ImportReport, ItemKind, NewItem, Severity};
+use crate::output::{self, Format};
+use crate::progress;
+use crate::quantity::Quantity;
+use crate::strict;
+
+/// Rows written per `db::insert_items` call, so the progress bar moves
+/// steadily through a flat file.
+const BATCH_SIZE: usize = 500;
+
+/// Columns understood in an import file.
+const COLUMNS: [&str; 5] = ["path", "description", "quantity", "uuid", "kind"];
+
+/// Where an import file comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportSource {
+    /// invy's CSV, or the JSON of `invy export`
+    Invy,
+    /// A HomeBox CSV export, or its items and locations as JSON
+    Homebox,
+    /// Grocy's locations, products, quantity units and stock as JSON
+    Grocy,
+}
+
+/// A validated row, ready to be written.
+struct ImportRow {
+    path: Vec<String>,
+    description: Option<String>,
+    quantity: Quantity,
+    /// Identifier to keep from an earlier export
+    uuid: Option<String>,
+    /// What the item is; decided by whether it has contents when not given
+    kind: Option<ItemKind>,
+    /// Already in the inventory; left untouched
+    skip: bool,
+}
+
+/// Import items from a CSV file with `path`, `description`, `quantity`,
+/// `uuid` and `kind` columns, or from the rows of an `invy export` JSON file.
+///
+/// The whole file is validated before anything is written; any error aborts
+/// the import and is reported with its line, column and a suggested fix.
+///
+/// # Arguments
+/// * `file` - CSV or export file to import
+/// * `source` - The program that wrote the file
+/// * `report_only` - Only validate and report, never write
+/// * `decrypt` - The file is age-encrypted
+/// * `identity` - age identity file to decrypt with
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    file: &Path,
+    source: ImportSource,
+    report_only: bool,
+    decrypt: bool,
+    identity: Option<&Path>,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let mut contents = Vec::new();
+    if decrypt {
+        contents = age::decrypt(file, identity)?;
+    } else {
+        std::fs::File::open(file)
+            .and_then(|mut f| f.read_to_end(&mut contents))
+            .with_context(|| format!("Failed to read {:?}", file))?;
+    }
+    contents = match source {
+        ImportSource::Invy if contents.trim_ascii_start().starts_with(b"[") => {
+            export_to_csv(&contents)?
+        }
+        ImportSource::Invy => contents,
+        ImportSource::Homebox => homebox_to_csv(&contents)?,
+        ImportSource::Grocy => grocy_to_csv(&contents)?,
+    };
+
+    let conn = db::open(db_path)?;
+
+    // Validate under the write lock so existence checks still hold when
+    // the rows are written. Ctrl-C rolls the whole import back.
+    let _guard = interrupt::protect();
+    let report = db::with_tx(&conn, |tx| {
+        let (mut rows, mut report) = validate(tx, &contents)?;
+        let refused = strict::enabled() && report.warnings > 0;
+        if report.errors == 0 && !refused && !report_only {
+            report.imported = write_rows(tx, &mut rows, format)?;
+        }
+        Ok(report)
+    })?;
+
+    output::print_import_report(&mut io::stdout(), &report, format)?;
+
+    if report.errors > 0 {
+        return Err(anyhow!(
+            "import aborted: {} error(s) found, nothing was written",
+            report.errors
+        ));
+    }
+    if report.warnings > 0 && !report_only {
+        strict::check(|| {
+            format!(
+                "import aborted: {} warning(s) found, nothing was written",
+                report.warnings
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Turn the rows of an `invy export` JSON file into import CSV.
+///
+/// Export paths are joined without escaping, so each path is rebuilt from
+/// the row names (and the `container` of rows whose parent was not
+/// exported) to keep a `/` inside a name intact.
+fn export_to_csv(contents: &[u8]) -> Result<Vec<u8>> {
+    let rows: Vec<ExportRow> =
+        serde_json::from_slice(contents).context("Failed to read the export file")?;
+    let mut paths: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(COLUMNS)?;
+    // Rows are sorted by path, so containers come before their contents
+    for row in rows {
+        let mut path = match row.container_id.and_then(|id| paths.get(&id)) {
+            Some(parent) => parent.clone(),
+            None => db::split_path(&row.container),
+        };
+        path.push(row.name);
+        let quantity = format!("{}{}", row.quantity, row.unit.unwrap_or_default());
+        writer.write_record([
+            db::join_path(&path).as_str(),
+            row.description.as_deref().unwrap_or(""),
+            quantity.as_str(),
+            row.uuid.as_deref().unwrap_or(""),
+            row.kind.as_str(),
+        ])?;
+        paths.insert(row.id, path);
+    }
+    Ok(writer.into_inner()?)
+}
+
+/// Import CSV being built from another program's data. Paths already used
+/// get a ` (2)`, ` (3)`, ... suffix, since those programs allow two items
+/// with one name in one place.
+struct ForeignRows {
+    writer: csv::Writer<Vec<u8>>,
+    paths: HashSet<String>,
+}
+
+impl ForeignRows {
+    fn new() -> Result<Self> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(COLUMNS)?;
+        Ok(ForeignRows {
+            writer,
+            paths: HashSet::new(),
+        })
+    }
+
+    fn push(
+        &mut self,
+        mut path: Vec<String>,
+        description: &str,
+        quantity: &Quantity,
+        uuid: Option<&str>,
+    ) -> Result<()> {
+        let name = path.pop().unwrap_or_default();
+        let mut n = 1;
+        let joined = loop {
+            let candidate = match n {
+                1 => name.clone(),
+                _ => format!("{} ({})", name, n),
+            };
+            path.push(candidate);
+            let joined = db::join_path(&path);
+            if self.paths.insert(joined.to_lowercase()) {
+                break joined;
+            }
+            path.pop();
+            n += 1;
+        };
+        self.writer.write_record([
+            joined.as_str(),
+            description,
+            quantity.to_string().as_str(),
+            uuid.filter(|uuid| is_uuid(uuid)).unwrap_or(""),
+            "",
+        ])?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Vec<u8>> {
+        Ok(self.writer.into_inner()?)
+    }
+}
+
+/// A description with labels or notes appended: `cordless; labels: tools`.
+fn describe(parts: &[(&str, String)]) -> String {
+    parts
+        .iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(label, text)| match label {
+            &"" => text.trim().to_string(),
+            label => format!("{}: {}", label, text.trim()),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Text of Grocy's HTML descriptions (`<p>Oat milk</p>`), tags removed.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A JSON string, or a number written as text (Grocy sends IDs and amounts
+/// either way).
+fn json_text(value: &Value, key: &str) -> String {
+    match value.get(key) {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Number(number)) => number.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// A whole quantity of at least 1 from a JSON number or numeric string.
+fn json_amount(value: &Value, key: &str) -> i64 {
+    let amount = match value.get(key) {
+        Some(Value::Number(number)) => number.as_f64().unwrap_or(1.0),
+        Some(Value::String(text)) => text.trim().parse().unwrap_or(1.0),
+        _ => 1.0,
+    };
+    (amount.round() as i64).max(1)
+}
+
+/// Turn a HomeBox export into import CSV. HomeBox's own CSV export has
+/// `HB.`-prefixed columns; its JSON is the `/v1/items` response (or just its
+/// `items`), optionally with the `/v1/locations/tree` response under
+/// `locations` to place items in nested locations.
+fn homebox_to_csv(contents: &[u8]) -> Result<Vec<u8>> {
+    let mut rows = ForeignRows::new()?;
+
+    if !contents.trim_ascii_start().starts_with(b"[")
+        && !contents.trim_ascii_start().starts_with(b"{")
+    {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(contents);
+        let headers: Vec<String> = reader
+            .headers()
+            .context("Failed to read the HomeBox CSV header row")?
+            .iter()
+            .map(|h| h.to_lowercase())
+            .collect();
+        let column = |name: &str| headers.iter().position(|h| h == name);
+        let Some(name_col) = column("hb.name") else {
+            return Err(anyhow!(
+                "not a HomeBox CSV export: it has no HB.name column"
+            ));
+        };
+        let field = |record: &csv::StringRecord, name: &str| {
+            column(name)
+                .and_then(|c| record.get(c))
+                .unwrap_or("")
+                .to_string()
+        };
+        for record in reader.records() {
+            let record = record.context("Failed to read the HomeBox CSV")?;
+            if field(&record, "hb.archived").eq_ignore_ascii_case("true") {
+                continue;
+            }
+            let mut path: Vec<String> = field(&record, "hb.location")
+                .split('/')
+                .map(|part| part.trim().to_string())
+                .filter(|part| !part.is_empty())
+                .collect();
+            path.push(record.get(name_col).unwrap_or("").to_string());
+            let quantity = field(&record, "hb.quantity")
+                .parse::<f64>()
+                .map_or(1, |amount| (amount.round() as i64).max(1));
+            let labels = field(&record, "hb.labels")
+                .split(';')
+                .map(str::trim)
+                .filter(|label| !label.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let description = describe(&[
+                ("", field(&record, "hb.description")),
+                ("labels", labels),
+                ("notes", field(&record, "hb.notes")),
+            ]);
+            rows.push(path, &description, &Quantity::count(quantity), None)?;
+        }
+        return rows.finish();
+    }
+
+    let data: Value =
+        serde_json::from_slice(contents).context("Failed to read the HomeBox JSON")?;
+    let items = match &data {
+        Value::Array(items) => items.clone(),
+        _ => match data.get("items") {
+            Some(Value::Array(items)) => items.clone(),
+            _ => return Err(anyhow!("not a HomeBox export: it has no \"items\" array")),
+        },
+    };
+
+    // Full paths of nested locations, by ID
+    fn walk(locations: &[Value], parent: &[String], paths: &mut HashMap<String, Vec<String>>) {
+        for location in locations {
+            let mut path = parent.to_vec();
+            path.push(json_text(location, "name"));
+            if let Some(Value::Array(children)) = location.get("children") {
+                walk(children, &path, paths);
+            }
+            paths.insert(json_text(location, "id"), path);
+        }
+    }
+    let mut locations = HashMap::new();
+    if let Some(Value::Array(tree)) = data.get("locations") {
+        walk(tree, &[], &mut locations);
+    }
+
+    for item in items {
+        if item.get("archived").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        let mut path = match item.get("location") {
+            Some(location) if location.is_object() => locations
+                .get(&json_text(location, "id"))
+                .cloned()
+                .unwrap_or_else(|| vec![json_text(location, "name")]),
+            _ => Vec::new(),
+        };
+        path.retain(|part| !part.trim().is_empty());
+        path.push(json_text(&item, "name"));
+        let labels = match item.get("labels") {
+            Some(Value::Array(labels)) => labels
+                .iter()
+                .map(|label| json_text(label, "name"))
+                .filter(|name| !name.is_empty())
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => String::new(),
+        };
+        let description = describe(&[
+            ("", json_text(&item, "description")),
+            ("labels", labels),
+            ("notes", json_text(&item, "notes")),
+        ]);
+        let quantity = Quantity::count(json_amount(&item, "quantity"));
+        let id = json_text(&item, "id").to_ascii_lowercase();
+        rows.push(path, &description, &quantity, Some(&id))?;
+    }
+    rows.finish()
+}
+
+/// Turn Grocy data into import CSV: an object with the `/api/objects/...`
+/// responses for `locations`, `products`, `quantity_units` and (optionally)
+/// `stock`. Each product in stock becomes an item in each location holding
+/// it, with the amount there; without `stock`, every product is one item in
+/// its default location.
+fn grocy_to_csv(contents: &[u8]) -> Result<Vec<u8>> {
+    let data: Value = serde_json::from_slice(contents).context("Failed to read the Grocy JSON")?;
+    let list = |key: &str| match data.get(key) {
+        Some(Value::Array(values)) => values.clone(),
+        _ => Vec::new(),
+    };
+    let products = list("products");
+    if products.is_empty() {
+        return Err(anyhow!("not a Grocy export: it has no \"products\" array"));
+    }
+    let by_id = |values: Vec<Value>| -> HashMap<String, Value> {
+        values
+            .into_iter()
+            .map(|value| (json_text(&value, "id"), value))
+            .collect()
+    };
+    let locations = by_id(list("locations"));
+    let units = by_id(list("quantity_units"));
+
+    // Amounts in stock by product and location, in first-seen order
+    let mut stock: Vec<((String, String), f64)> = Vec::new();
+    let entries = list("stock");
+    for entry in &entries {
+        let key = (
+            json_text(entry, "product_id"),
+            json_text(entry, "location_id"),
+        );
+        let amount = match entry.get("amount") {
+            Some(Value::Number(number)) => number.as_f64().unwrap_or(0.0),
+            Some(Value::String(text)) => text.trim().parse().unwrap_or(0.0),
+            _ => 0.0,
+        };
+        match stock.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, total)) => *total += amount,
+            None => stock.push((key, amount)),
+        }
+    }
+    let placements: Vec<(String, String, i64)> = if entries.is_empty() {
+        products
+            .iter()
+            .map(|p| (json_text(p, "id"), json_text(p, "location_id"), 1))
+            .collect()
+    } else {
+        stock
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0.0)
+            .map(|((product, location), amount)| {
+                (product, location, (amount.round() as i64).max(1))
+            })
+            .collect()
+    };
+
+    let products = by_id(products);
+    let mut rows = ForeignRows::new()?;
+    for (product_id, location_id, amount) in placements {
+        let Some(product) = products.get(&product_id) else {
+            continue;
+        };
+        let mut path = Vec::new();
+        // Stock entries may leave the location to the product's default
+        let location_id = Some(location_id)
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| json_text(product, "location_id"));
+        if let Some(location) = locations.get(&location_id) {
+            path.push(json_text(location, "name"));
+        }
+        path.push(json_text(product, "name"));
+
+        // Units like "Pack" are kept; pieces are a plain count
+        let unit = units
+            .get(&json_text(product, "qu_id_stock"))
+            .map(|unit| json_text(unit, "name"))
+            .unwrap_or_default();
+        let quantity = match unit.to_lowercase().as_str() {
+            "" | "piece" | "pieces" | "pc" | "pcs" => Quantity::count(amount),
+            _ => Quantity::parse(&format!("{}{}", amount, unit))
+                .unwrap_or_else(|_| Quantity::count(amount)),
+        };
+        let description = describe(&[("", strip_tags(&json_text(product, "description")))]);
+        rows.push(path, &description, &quantity, None)?;
+    }
+    rows.finish()
+}
+
+/// Check every row of the file without writing anything.
+fn validate(conn: &Connection, contents: &[u8]) -> Result<(Vec<ImportRow>, ImportReport)> {
+    let mut report = ImportReport::default();
+    let mut rows = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::Headers)
+        .from_reader(contents);
+
+    let headers: Vec<String> = reader
+        .headers()
+        .context("Failed to read the header row")?
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let column = |name: &str| headers.iter().position(|h| h == name);
+
+    for header in &headers {
+        if !COLUMNS.contains(&header.as_str()) {
+            push(
+                &mut report,
+                Severity::Warning,
+                1,
+                Some(header),
+                None,
+                format!("unknown column '{}' is ignored", header),
+                Some(format!("rename it to one of: {}", COLUMNS.join(", "))),
+            );
+        }
+    }
+
+    let Some(path_col) = column("path") else {
+        push(
+            &mut report,
+            Severity::Error,
+            1,
+            None,
+            None,
+            "missing required column 'path'".to_string(),
+            Some("start the file with a header row such as: path,description,quantity".to_string()),
+        );
+        return Ok((rows, report));
+    };
+    let desc_col = column("description");
+    let qty_col = column("quantity");
+    let uuid_col = column("uuid");
+    let kind_col = column("kind");
+
+    let mut seen: HashMap<String, u64> = HashMap::new();
+    let mut seen_uuids: HashMap<String, u64> = HashMap::new();
+
+    for result in reader.records() {
+        interrupt::check()?;
+        report.rows += 1;
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| p.line()).unwrap_or(0);
+                push(
+                    &mut report,
+                    Severity::Error,
+                    line,
+                    None,
+                    None,
+                    format!("malformed row: {}", e),
+                    Some(format!(
+                        "give every row {} comma-separated fields; quote values that contain commas",
+                        headers.len()
+                    )),
+                );
+                continue;
+            }
+        };
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        let field = |col: Option<usize>| col.and_then(|c| record.get(c)).unwrap_or("");
+        let errors_before = report.errors;
+
+        let raw_path = field(Some(path_col));
+        let path: Vec<String> = db::split_path(raw_path)
+            .iter()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect();
+        let normalized = db::join_path(&path);
+        if path.is_empty() {
+            push(
+                &mut report,
+                Severity::Error,
+                line,
+                Some("path"),
+                Some(raw_path),
+                "path is empty".to_string(),
+                Some("give the item's full path, e.g. garage/toolbox/hammer".to_string()),
+            );
+        } else if normalized != raw_path {
+            push(
+                &mut report,
+                Severity::Warning,
+                line,
+                Some("path"),
+                Some(raw_path),
+                format!("path will be imported as '{}'", normalized),
+                Some("remove empty segments and spaces around '/'".to_string()),
+            );
+        }
+
+        let raw_qty = field(qty_col).trim();
+        let quantity = if raw_qty.is_empty() {
+            Quantity::count(1)
+        } else {
+            match Quantity::parse(raw_qty) {
+                Ok(qty) => qty,
+                Err(_) if raw_qty.parse::<i64>().is_ok() => {
+                    push(
+                        &mut report,
+                        Severity::Error,
+                        line,
+                        Some("quantity"),
+                        Some(raw_qty),
+                        "quantity must be at least 1".to_string(),
+                        Some("remove the row or set a quantity of 1 or more".to_string()),
+                    );
+                    Quantity::count(1)
+                }
+                Err(_) => {
+                    push(
+                        &mut report,
+                        Severity::Error,
+                        line,
+                        Some("quantity"),
+                        Some(raw_qty),
+                        "quantity is not a whole number".to_string(),
+                        Some(
+                            "use digits with an optional unit, e.g. 3 or 250g, or leave it empty for 1"
+                                .to_string(),
+                        ),
+                    );
+                    Quantity::count(1)
+                }
+            }
+        };
+
+        let description = Some(field(desc_col).trim())
+            .filter(|d| !d.is_empty())
+            .map(String::from);
+
+        let raw_uuid = field(uuid_col).trim();
+        let uuid = Some(raw_uuid.to_ascii_lowercase()).filter(|u| !u.is_empty());
+        if let Some(uuid) = &uuid {
+            if !is_uuid(uuid) {
+                push(
+                    &mut report,
+                    Severity::Error,
+                    line,
+                    Some("uuid"),
+                    Some(raw_uuid),
+                    "uuid is not a valid UUID".to_string(),
+                    Some(
+                        "use the value from `invy export`, or leave it empty to assign a new one"
+                            .to_string(),
+                    ),
+                );
+            } else if let Some(first) = seen_uuids.get(uuid) {
+                push(
+                    &mut report,
+                    Severity::Error,
+                    line,
+                    Some("uuid"),
+                    Some(raw_uuid),
+                    format!("duplicate uuid, first seen on line {}", first),
+                    Some("leave it empty on all but one row".to_string()),
+                );
+            } else {
+                seen_uuids.insert(uuid.clone(), line);
+            }
+        }
+
+        let raw_kind = field(kind_col).trim();
+        let kind = if raw_kind.is_empty() {
+            None
+        } else {
+            match raw_kind.parse::<ItemKind>() {
+                Ok(kind) => Some(kind),
+                Err(_) => {
+                    push(
+                        &mut report,
+                        Severity::Error,
+                        line,
+                        Some("kind"),
+                        Some(raw_kind),
+                        "kind is not item, container or location".to_string(),
+                        Some(
+                            "use item, container or location, or leave it empty to decide by contents"
+                                .to_string(),
+                        ),
+                    );
+                    None
+                }
+            }
+        };
+
+        if report.errors > errors_before || path.is_empty() {
+            continue;
+        }
+
+        if let Some(first) = seen.get(&normalized) {
+            push(
+                &mut report,
+                Severity::Error,
+                line,
+                Some("path"),
+                Some(raw_path),
+                format!("duplicate path, first seen on line {}", first),
+                Some(format!(
+                    "merge line {} into line {} or remove it",
+                    line, first
+                )),
+            );
+            continue;
+        }
+        seen.insert(normalized.clone(), line);
+
+        // An item keeps its UUID across renames and moves, so a known UUID
+        // means the row is already imported even if its path changed
+        let known_uuid = match &uuid {
+            Some(uuid) => db::get_item_by_uuid_prefix(conn, uuid)?.is_some(),
+            None => false,
+        };
+        let skip = known_uuid || db::get_item_by_path(conn, &normalized)?.is_some();
+        if skip {
+            push(
+                &mut report,
+                Severity::Warning,
+                line,
+                Some("path"),
+                Some(raw_path),
+                "already in the inventory; the row will be skipped".to_string(),
+                Some("remove the row, or use `invy edit` to change the existing item".to_string()),
+            );
+        }
+
+        rows.push(ImportRow {
+            path,
+            description,
+            quantity,
+            uuid,
+            kind,
+            skip,
+        });
+    }
+
+    Ok((rows, report))
+}
+
+/// Insert the validated rows, creating missing containers along each path.
+/// Returns the number of rows written.
+fn write_rows(conn: &Connection, rows: &mut [ImportRow], format: Format) -> Result<usize> {
+    // Containers listed in the file must exist before their contents
+    // implicitly create them without a description.
+    rows.sort_by_key(|row| row.path.len());
+    // Rows with contents in the file are containers unless they say otherwise
+    let holders: HashSet<Vec<String>> = rows
+        .iter()
+        .flat_map(|row| (1..row.path.len()).map(|depth| row.path[..depth].to_vec()))
+        .collect();
+    for row in rows.iter_mut().filter(|row| row.kind.is_none()) {
+        if holders.contains(&row.path) {
+            row.kind = Some(ItemKind::Container);
+        }
+    }
+    let bar = progress::bar(
+        rows.iter().filter(|row| !row.skip).count() as u64,
+        "Importing",
+        format,
+    );
+
+    // Rows of one depth go in together; the next depth may be inside them
+    let mut imported = 0;
+    for level in rows.chunk_by(|a, b| a.path.len() == b.path.len()) {
+        let mut batch = Vec::new();
+        for row in level.iter().filter(|row| !row.skip) {
+            interrupt::check()?;
+            bar.inc(1);
+            let (name, parents) = row.path.split_last().expect("validated path is not empty");
+
+            let mut container_id = None;
+            for (depth, part) in parents.iter().enumerate() {
+                let prefix = db::join_path(&row.path[..=depth]);
+                let container = match db::get_item_by_path(conn, &prefix)? {
+                    Some(item) => item,
+                    None => db::insert_container(conn, part, container_id)?,
+                };
+                container_id = Some(container.id);
+            }
+
+            if db::name_exists_in_container(conn, name, container_id)? {
+                continue;
+            }
+            batch.push(NewItem {
+                description: row.description.clone(),
+                quantity: row.quantity.clone(),
+                uuid: row.uuid.clone(),
+                kind: row.kind.unwrap_or_default(),
+                ..NewItem::new(name.as_str(), container_id)
+            });
+            if batch.len() == BATCH_SIZE {
+                imported += db::insert_items(conn, &batch)?.len();
+                batch.clear();
+            }
+        }
+        imported += db::insert_items(conn, &batch)?.len();
+    }
+    bar.finish_and_clear();
+
+    Ok(imported)
+}
+
+/// Whether `s` is a lowercase hyphenated UUID such as
+/// `3f2a9c1e-7b4d-4e8a-9f00-12ab34cd56ef`.
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit() && !c.is_ascii_uppercase(),
+        })
+}
+
+fn push(
+    report: &mut ImportReport,
+    severity: Severity,
+    line: u64,
+    column: Option<&str>,
+    value: Option<&str>,
+    message: String,
+    suggestion: Option<String>,
+) {
+    match severity {
+        Severity::Error => report.errors += 1,
+        Severity::Warning => report.warnings += 1,
+    }
+    report.issues.push(ImportIssue {
+        severity,
+        line,
+        column: column.map(String::from),
+        value: value.map(String::from),
+        message,
+        suggestion,
+    });
+}
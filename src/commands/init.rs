@@ -0,0 +1,23 @@
+//! Init command implementation.
+//!
+//! See SPEC.md#invy-init
+
+use anyhow::Result;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+
+/// Create a new, empty inventory database.
+///
+/// # Arguments
+/// * `encrypted` - Encrypt the database with a passphrase
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(encrypted: bool, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let path = db::resolve_path(db_path)?;
+    db::create(&path, encrypted)?;
+
+    output::print_initialized(&mut io::stdout(), &path, encrypted, format)
+}
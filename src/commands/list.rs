@@ -4,62 +4,223 @@
 
 use anyhow::{anyhow, Result};
 use rusqlite::Connection;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io;
 use std::path::Path;
 
-use crate::db;
+use crate::commands::check::{self, SaveAs};
+use crate::db::{self, SortKey};
 use crate::model::{Item, TreeItem};
-use crate::output::{self, Format};
+use crate::output::{self, AgeColors, Format};
+use crate::template::Template;
+use crate::timespec::DateFilter;
+
+/// How `invy list` selects and orders items.
+#[derive(Debug)]
+pub struct ListOptions<'a> {
+    /// List all descendants
+    pub recursive: bool,
+    /// Only include items whose timestamps match
+    pub dates: &'a DateFilter,
+    /// Ordering of items (within each container when recursive)
+    pub sort: SortKey,
+    pub reverse: bool,
+    /// Number of items to skip (not recursive)
+    pub offset: usize,
+    /// Maximum number of items to show (not recursive)
+    pub limit: Option<usize>,
+    /// Include IDs, paths and timestamps (not recursive)
+    pub long: bool,
+    /// Color human rows by age
+    pub colors: Option<&'a AgeColors>,
+    /// Show names in this language where set (see `invy i18n`)
+    pub lang: Option<&'a str>,
+    /// Flag containers heavier than this many grams (not recursive)
+    pub heavy_over: i64,
+    /// Only list containers and locations (see `ItemKind`)
+    pub containers_only: bool,
+    /// Only list containers and locations that hold nothing, at any depth
+    pub empty: bool,
+    /// Only list the members of this group (see `invy add --count`)
+    pub group: Option<&'a str>,
+    /// Render each item through this template instead
+    pub template: Option<&'a Template>,
+    /// Also save the listed items as a checklist or kit
+    pub save: Option<SaveAs<'a>>,
+    /// Whether the container `save` names is created, and strict mode
+    pub placement: db::Placement,
+}
 
 /// List items, optionally within a specific container.
 ///
 /// # Arguments
 /// * `container` - Optional container to list (default: root)
-/// * `recursive` - List all descendants
-/// * `json` - Output as JSON
-/// * `csv` - Output as CSV
+/// * `options` - Filtering, ordering and paging
+/// * `format` - Output format
 /// * `db_path` - Optional custom database path
 pub fn run(
     container: Option<&str>,
-    recursive: bool,
-    json: bool,
-    csv: bool,
+    options: &ListOptions,
+    format: Format,
     db_path: Option<&Path>,
 ) -> Result<()> {
+    let ListOptions {
+        recursive,
+        dates,
+        sort,
+        reverse,
+        offset,
+        limit,
+        long,
+        colors,
+        lang,
+        heavy_over,
+        containers_only,
+        empty,
+        group,
+        template,
+        save,
+        placement,
+    } = *options;
     let conn = db::open(db_path)?;
-    let format = Format::from_flags(json, csv);
+    let members = match group {
+        Some(group) => {
+            let members = db::list_group_members(&conn, group)?;
+            if members.is_empty() {
+                return Err(anyhow!("group '{}' not found", group));
+            }
+            Some(members)
+        }
+        None => None,
+    };
+    let child_counts = db::child_counts(&conn)?;
+    let wanted = |item: &Item| {
+        dates.matches(&item.created_at, &item.updated_at)
+            && (!containers_only || item.kind.holds_items())
+            && (!empty || (item.kind.holds_items() && !child_counts.contains_key(&item.id)))
+            && members.as_ref().is_none_or(|m| m.contains(&item.id))
+    };
 
     if recursive {
         // Build tree structure for recursive listing
-        let items = db::list_all_items(&conn)?;
-        let tree = build_item_tree(&items, &conn);
-        output::print_tree_items(&tree, format)
+        let items = keep_matches_with_ancestors(
+            db::list_all_items_sorted(&conn, sort, reverse)?,
+            !dates.is_empty() || containers_only || empty || group.is_some(),
+            wanted,
+        );
+        if let Some(save) = save {
+            // The matches themselves, not the ancestors shown around them
+            let matches: Vec<Item> = items.iter().filter(|item| wanted(item)).cloned().collect();
+            check::save_results(&conn, save, &matches, placement)?;
+        }
+        let tree = build_item_tree(&items, lang, &conn)?;
+        match template {
+            Some(template) => output::print_tree_templated(&mut io::stdout(), &tree, template),
+            None => output::print_tree_items(&mut io::stdout(), &tree, colors, format),
+        }
     } else {
-        let items = if let Some(container_ref) = container {
+        let container_id = if let Some(container_ref) = container {
             // List items in specific container
             let container_item = db::resolve_item(&conn, container_ref)?
                 .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?;
-            db::list_items_in_container(&conn, container_item.id)?
+            Some(container_item.id)
         } else {
             // List root items
-            db::list_root_items(&conn)?
+            None
+        };
+        let items: Vec<_> = if empty {
+            // Empty containers are looked for at every depth, not just the top
+            let mut items = Vec::new();
+            for item in db::list_all_items_sorted(&conn, sort, reverse)? {
+                if !wanted(&item) {
+                    continue;
+                }
+                let below = match container_id {
+                    Some(id) => item.id != id && db::is_ancestor(&conn, id, item.id)?,
+                    None => true,
+                };
+                if below {
+                    items.push(item);
+                }
+            }
+            items
+        } else {
+            db::list_items_sorted(&conn, container_id, sort, reverse)?
+                .into_iter()
+                .filter(|item| wanted(item))
+                .collect()
         };
+        let (items, page) = output::paginate(items, offset, limit);
+        if let Some(save) = save {
+            check::save_results(&conn, save, &items, placement)?;
+        }
 
         // Convert to ListItem with child counts
-        let list_items: Vec<_> = items
-            .into_iter()
-            .map(|item| {
-                let child_count = db::count_children(&conn, item.id).unwrap_or(0);
+        let weights = db::subtree_weights(&conn)?;
+        let mut list_items = Vec::new();
+        for mut item in items {
+            db::localize_item(&conn, &mut item, lang)?;
+            let child_count = child_counts.get(&item.id).copied().unwrap_or(0);
+            let open_todos = db::count_open_todos(&conn, item.id)?;
+            // Templates may use the path, and empty containers come from
+            // anywhere below, so both get the long form
+            let mut list_item = if long || empty || template.is_some() {
+                let path = db::get_item_path_in(&conn, item.id, lang).unwrap_or_default();
+                item.into_long_list_item(child_count, path)
+            } else {
                 item.into_list_item(child_count)
-            })
-            .collect();
+            };
+            list_item.open_todos = Some(open_todos).filter(|&count| count > 0);
+            list_item.weight_g = weights.get(&list_item.id).copied();
+            list_item.heavy = child_count > 0 && list_item.weight_g.is_some_and(|w| w > heavy_over);
+            list_items.push(list_item);
+        }
 
-        output::print_list_items(&list_items, format)
+        match template {
+            Some(template) => output::print_templated(&mut io::stdout(), &list_items, template),
+            None => output::print_list_items(&mut io::stdout(), &list_items, page, colors, format),
+        }
     }
 }
 
+/// Keep items matching the filter plus their ancestors, so a filtered tree
+/// still shows where each match lives. Everything is kept unless `filtering`.
+fn keep_matches_with_ancestors(
+    items: Vec<Item>,
+    filtering: bool,
+    matches: impl Fn(&Item) -> bool,
+) -> Vec<Item> {
+    if !filtering {
+        return items;
+    }
+
+    let parents: HashMap<i64, Option<i64>> = items
+        .iter()
+        .map(|item| (item.id, item.container_id))
+        .collect();
+    let mut keep = HashSet::new();
+    for item in &items {
+        if matches(item) {
+            let mut current = Some(item.id);
+            while let Some(id) = current {
+                if !keep.insert(id) {
+                    break;
+                }
+                current = parents.get(&id).copied().flatten();
+            }
+        }
+    }
+
+    items
+        .into_iter()
+        .filter(|item| keep.contains(&item.id))
+        .collect()
+}
+
 /// Build a tree structure from flat items using container_id relationships.
-fn build_item_tree(items: &[Item], conn: &Connection) -> Vec<TreeItem> {
+///
+/// Siblings keep the order of `items`. Names are shown in `lang` where set.
+fn build_item_tree(items: &[Item], lang: Option<&str>, conn: &Connection) -> Result<Vec<TreeItem>> {
     // Build parent -> children mapping
     let mut children_map: HashMap<Option<i64>, Vec<&Item>> = HashMap::new();
     for item in items {
@@ -69,15 +230,16 @@ fn build_item_tree(items: &[Item], conn: &Connection) -> Vec<TreeItem> {
             .push(item);
     }
 
-    // Sort children alphabetically (case-insensitive)
-    for children in children_map.values_mut() {
-        children.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    }
+    // Counted over the whole inventory, so a filtered tree still shows
+    // how much each container holds
+    let child_counts = db::child_counts(conn)?;
 
     // Recursive tree builder
     fn build_subtree(
         parent_id: Option<i64>,
         children_map: &HashMap<Option<i64>, Vec<&Item>>,
+        child_counts: &HashMap<i64, i64>,
+        lang: Option<&str>,
         conn: &Connection,
     ) -> Vec<TreeItem> {
         children_map
@@ -86,13 +248,24 @@ fn build_item_tree(items: &[Item], conn: &Connection) -> Vec<TreeItem> {
                 children
                     .iter()
                     .map(|item| {
-                        let child_count = db::count_children(conn, item.id).unwrap_or(0);
+                        let child_count = child_counts.get(&item.id).copied().unwrap_or(0);
+                        let name = lang
+                            .and_then(|lang| db::get_item_name(conn, item.id, lang).ok().flatten())
+                            .unwrap_or_else(|| item.name.clone());
                         TreeItem {
                             id: item.id,
-                            name: item.name.clone(),
+                            name,
                             description: item.description.clone(),
                             child_count,
-                            children: build_subtree(Some(item.id), children_map, conn),
+                            uuid: item.uuid.clone(),
+                            updated_at: item.updated_at.clone(),
+                            children: build_subtree(
+                                Some(item.id),
+                                children_map,
+                                child_counts,
+                                lang,
+                                conn,
+                            ),
                         }
                     })
                     .collect()
@@ -100,5 +273,11 @@ fn build_item_tree(items: &[Item], conn: &Connection) -> Vec<TreeItem> {
             .unwrap_or_default()
     }
 
-    build_subtree(None, &children_map, conn)
+    Ok(build_subtree(
+        None,
+        &children_map,
+        &child_counts,
+        lang,
+        conn,
+    ))
 }
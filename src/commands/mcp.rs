@@ -0,0 +1,215 @@
+//! MCP command implementation: inventory tools for AI assistants over the
+//! Model Context Protocol, as newline-delimited JSON-RPC on stdin and stdout.
+//!
+//! Tools run the same operations as `invy --api` (see `crate::api`).
+//!
+//! See SPEC.md#invy-mcp
+
+use anyhow::Result;
+use rusqlite::Connection;
+use serde_json::{json, Map, Value};
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::api;
+use crate::db;
+
+/// Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Answer an MCP client on stdin and stdout until it closes stdin.
+///
+/// # Arguments
+/// * `default_container` - Container the `add` tool uses when given none
+/// * `placement` - Whether missing containers are created, and strict mode
+/// * `db_path` - Optional custom database path
+pub fn run(
+    default_container: Option<&str>,
+    placement: db::Placement,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = respond(&conn, &line, default_container, placement) {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The response to one message; notifications (no `id`) get none.
+fn respond(
+    conn: &Connection,
+    line: &str,
+    default_container: Option<&str>,
+    placement: db::Placement,
+) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(err) => {
+            return Some(api::error_response(
+                Value::Null,
+                api::PARSE_ERROR,
+                &err.to_string(),
+            ))
+        }
+    };
+    let id = message.get("id").cloned()?;
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(api::error_response(
+            id,
+            api::INVALID_REQUEST,
+            "request needs a \"method\" string",
+        ));
+    };
+    let params = match message.get("params") {
+        Some(Value::Object(params)) => params.clone(),
+        _ => Map::new(),
+    };
+
+    let result = match method {
+        "initialize" => initialize(&params),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tools() }),
+        "tools/call" => match call_tool(conn, &params, default_container, placement) {
+            Ok(result) => result,
+            Err(message) => {
+                return Some(api::error_response(id, api::INVALID_PARAMS, &message));
+            }
+        },
+        _ => {
+            let message = format!("unknown method '{}'", method);
+            return Some(api::error_response(id, api::METHOD_NOT_FOUND, &message));
+        }
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+/// Agree on a protocol version: the client's, if this server speaks it.
+fn initialize(params: &Map<String, Value>) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|version| Some(**version) == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "invy", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Tools for a home inventory of items inside containers \
+            (boxes, drawers) inside locations (rooms, shelves). To answer where \
+            something is, use find; each result's path runs from the outermost \
+            location to the item.",
+    })
+}
+
+/// Tool definitions for `tools/list`.
+fn tools() -> Value {
+    let text = |description: &str| json!({ "type": "string", "description": description });
+    let number =
+        |description: &str| json!({ "type": "integer", "minimum": 0, "description": description });
+    json!([
+        {
+            "name": "find",
+            "description": "Search items whose name, description or alias contains the query \
+                (case-insensitive). Each result has its full path, i.e. where it is.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": text("Text to search for, e.g. passport"),
+                    "limit": number("Return at most this many items"),
+                    "offset": number("Skip this many items first"),
+                },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "list",
+            "description": "List what is directly inside a container or location, or the \
+                top-level items when none is given.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "in": text("Container name, path (garage/shelf) or #ID; omit for the top level"),
+                    "limit": number("Return at most this many items"),
+                    "offset": number("Skip this many items first"),
+                },
+            },
+        },
+        {
+            "name": "add",
+            "description": "Add an item to the inventory. Containers in the path are created \
+                when missing.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": text("Item name, unique within its container"),
+                    "description": text("Free-text description"),
+                    "container": text("Where it goes: a container name, path (office/desk) or #ID"),
+                    "quantity": text("How many, optionally with a unit: 3, 2m, 500g"),
+                    "kind": {
+                        "type": "string",
+                        "enum": ["item", "container", "location"],
+                        "description": "item (default), container (a box, a drawer) or location (a room, a shelf)",
+                    },
+                    "expires_at": text("Day it expires: a date (2025-06-30) or a time from now (6m)"),
+                },
+                "required": ["name"],
+            },
+        },
+        {
+            "name": "move",
+            "description": "Move an item, with everything inside it, into another container. \
+                The destination is created when missing.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "item": text("Item name, path or #ID"),
+                    "to": text("Destination container name, path or #ID; / for the top level"),
+                },
+                "required": ["item", "to"],
+            },
+        },
+    ])
+}
+
+/// Run a tool. Failures of the tool itself are results with `isError` set,
+/// so the assistant sees them; `Err` is for an unknown tool.
+fn call_tool(
+    conn: &Connection,
+    params: &Map<String, Value>,
+    default_container: Option<&str>,
+    placement: db::Placement,
+) -> std::result::Result<Value, String> {
+    let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+    let method = match name {
+        "find" | "list" | "add" => name,
+        "move" => "mv",
+        _ => return Err(format!("unknown tool '{}'", name)),
+    };
+    let arguments = match params.get("arguments") {
+        Some(Value::Object(arguments)) => arguments.clone(),
+        _ => Map::new(),
+    };
+    let result = api::call(conn, method, &arguments, default_container, placement)
+        .expect("every tool maps to an api method");
+    Ok(match result {
+        Ok(value) => json!({
+            "content": [{
+                "type": "text",
+                "text": serde_json::to_string_pretty(&value).expect("JSON values serialize"),
+            }],
+            "isError": false,
+        }),
+        Err(err) => json!({
+            "content": [{ "type": "text", "text": format!("{:#}", err) }],
+            "isError": true,
+        }),
+    })
+}
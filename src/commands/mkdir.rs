@@ -0,0 +1,43 @@
+//! Mkdir command implementation.
+//!
+//! See SPEC.md#invy-mkdir-path
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+
+/// Create a container path, e.g. `garage/shelf2/bin-a`, making every level
+/// that does not exist yet.
+///
+/// Unlike `add --in`, this only builds structure; it ignores `--no-create`
+/// and fails when the whole path already exists.
+///
+/// # Arguments
+/// * `path` - Container path, anchored at the root
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(path: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    if db::split_path(path).is_empty() {
+        return Err(anyhow!("container path cannot be empty"));
+    }
+    let conn = db::open(db_path)?;
+
+    let created = db::with_tx(&conn, |tx| {
+        let (_, created) = db::create_container_path(tx, path)?;
+        if created.is_empty() {
+            return Err(anyhow!("'{}' already exists", path));
+        }
+        created
+            .into_iter()
+            .map(|item| {
+                let path = db::get_item_path(tx, item.id)?;
+                Ok(item.with_path(path, Some(0)))
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    output::print_created(&mut io::stdout(), &created, format)
+}
@@ -4,9 +4,53 @@
 //! See SPEC.md for behavioral specifications.
 
 pub mod add;
+pub mod alias;
+pub mod audit;
+#[cfg(feature = "bot")]
+pub mod bot;
+pub mod cd;
+pub mod check;
+pub mod config;
+pub mod consume;
+pub mod count;
+pub mod cp;
+pub mod cron;
+pub mod db;
+pub mod doctor;
+pub mod dupes;
 pub mod edit;
+pub mod expiring;
+pub mod export;
 pub mod find;
+pub mod hash;
+pub mod heavy;
+pub mod i18n;
+pub mod import;
+pub mod init;
 pub mod list;
+pub mod mcp;
+pub mod mkdir;
 pub mod mv;
+pub mod near;
+#[cfg(feature = "notify")]
+pub mod notify;
+pub mod optimize;
+#[cfg(feature = "ocr")]
+pub mod photo;
+pub mod rename;
 pub mod rm;
+#[cfg(feature = "self-update")]
+pub mod self_update;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod share;
+pub mod shopping;
 pub mod show;
+pub mod stats;
+pub mod storage;
+pub mod sync;
+pub mod todo;
+pub mod triage;
+pub mod unpacked;
+pub mod views;
+pub mod r#where;
@@ -3,84 +3,162 @@
 //! See SPEC.md#invy-mv-item-destination
 
 use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::io;
 use std::path::Path;
 
-use crate::db;
+use crate::db::{self, SortKey};
+use crate::model::ItemWithPath;
 use crate::output::{self, Format};
 
 /// Move an item to a different container.
 ///
 /// # Arguments
-/// * `item` - Item to move
+/// * `item` - Item to move; `container/*` moves the container's contents
 /// * `destination` - Target container (use "/" for root)
-/// * `json` - Output as JSON
-/// * `csv` - Output as CSV
+/// * `contents` - Move the item's children instead of the item itself
+/// * `expected_updated_at` - Fail unless the item's `updated_at` still equals this
+/// * `placement` - Whether a missing destination is created, and strict mode
+/// * `format` - Output format
 /// * `db_path` - Optional custom database path
 pub fn run(
     item_ref: &str,
     destination: &str,
-    json: bool,
-    csv: bool,
+    contents: bool,
+    expected_updated_at: Option<&str>,
+    placement: db::Placement,
+    format: Format,
     db_path: Option<&Path>,
 ) -> Result<()> {
+    if let Some(container_ref) = contents_ref(item_ref) {
+        return run_contents(
+            container_ref,
+            destination,
+            expected_updated_at,
+            placement,
+            format,
+            db_path,
+        );
+    }
+    if contents {
+        return run_contents(
+            item_ref,
+            destination,
+            expected_updated_at,
+            placement,
+            format,
+            db_path,
+        );
+    }
+
     let conn = db::open(db_path)?;
-    let format = Format::from_flags(json, csv);
+    let (item_with_path, old_path) = db::with_tx(&conn, |tx| {
+        move_item(tx, item_ref, destination, expected_updated_at, placement)
+    })?;
+
+    output::print_moved(&mut io::stdout(), &item_with_path, &old_path, format)
+}
 
+/// Move one item into `destination` (auto-created if `placement` allows; `/`
+/// for root), returning it with its new path, and its old path.
+pub fn move_item(
+    conn: &Connection,
+    item_ref: &str,
+    destination: &str,
+    expected_updated_at: Option<&str>,
+    placement: db::Placement,
+) -> Result<(ItemWithPath, Vec<String>)> {
     // Resolve the item to move
-    let item = db::resolve_item(&conn, item_ref)?
+    let item = db::resolve_item(conn, item_ref)?
         .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+    db::ensure_unchanged(&item, expected_updated_at)?;
 
     // Get old path for display
-    let old_path = db::get_item_path(&conn, item.id)?;
-
-    // Resolve destination
-    let new_container_id = if destination == "/" || destination == "root" {
-        None
-    } else {
-        let container = db::resolve_or_create_container(&conn, destination)?;
-
-        // Check for circular reference
-        if container.id == item.id {
-            return Err(anyhow!(
-                "cannot move '{}' into itself or its descendants",
-                item.name
-            ));
-        }
-        if db::is_ancestor(&conn, item.id, container.id)? {
-            return Err(anyhow!(
-                "cannot move '{}' into itself or its descendants",
-                item.name
-            ));
-        }
+    let old_path = db::get_item_path(conn, item.id)?;
 
-        Some(container.id)
-    };
+    let new_container_id = db::resolve_destination(
+        conn,
+        destination,
+        &item,
+        &format!("'{}'", item.name),
+        placement,
+    )?;
 
     // Check for name conflict in destination
-    if db::name_exists_in_container(&conn, &item.name, new_container_id)? {
+    if db::name_exists_in_container(conn, &item.name, new_container_id)? {
         // Check if it's the same item (moving to same place)
         if item.container_id != new_container_id {
-            let dest_name = if destination == "/" || destination == "root" {
-                "(root)".to_string()
-            } else {
-                destination.to_string()
-            };
             return Err(anyhow!(
                 "item '{}' already exists in {}",
                 item.name,
-                dest_name
+                db::destination_label(destination)
             ));
         }
     }
 
     // Perform the move
-    db::move_item(&conn, item.id, new_container_id)?;
+    db::move_item(conn, item.id, new_container_id)?;
 
     // Get updated item for display
-    let updated_item = db::get_item_by_id(&conn, item.id)?
+    let updated_item = db::get_item_by_id(conn, item.id)?
         .ok_or_else(|| anyhow!("Failed to retrieve moved item"))?;
-    let new_path = db::get_item_path(&conn, updated_item.id)?;
-    let item_with_path = updated_item.with_path(new_path, None);
+    let new_path = db::get_item_path(conn, updated_item.id)?;
+    Ok((updated_item.with_path(new_path, None), old_path))
+}
+
+/// Move every item directly inside a container, leaving the container where
+/// it is. `expected_updated_at` is checked against the container.
+fn run_contents(
+    container_ref: &str,
+    destination: &str,
+    expected_updated_at: Option<&str>,
+    placement: db::Placement,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let (moved, from) = db::with_tx(&conn, |tx| {
+        let container = db::resolve_item(tx, container_ref)?
+            .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?;
+        db::ensure_unchanged(&container, expected_updated_at)?;
+        let children = db::list_items_sorted(tx, Some(container.id), SortKey::Name, false)?;
+        if children.is_empty() {
+            return Err(anyhow!("'{}' has no contents to move", container.name));
+        }
+
+        let label = format!("the contents of '{}'", container.name);
+        let new_container_id =
+            db::resolve_destination(tx, destination, &container, &label, placement)?;
+
+        // Check every name before moving anything
+        for child in &children {
+            if db::name_exists_in_container(tx, &child.name, new_container_id)? {
+                return Err(anyhow!(
+                    "item '{}' already exists in {}",
+                    child.name,
+                    db::destination_label(destination)
+                ));
+            }
+        }
+
+        let mut moved = Vec::new();
+        for child in children {
+            db::move_item(tx, child.id, new_container_id)?;
+            let updated = db::get_item_by_id(tx, child.id)?
+                .ok_or_else(|| anyhow!("Failed to retrieve moved item"))?;
+            let path = db::get_item_path(tx, updated.id)?;
+            moved.push(updated.with_path(path, None));
+        }
+        Ok((moved, db::get_item_path(tx, container.id)?))
+    })?;
+
+    output::print_moved_contents(&mut io::stdout(), &moved, &from, format)
+}
 
-    output::print_moved(&item_with_path, &old_path, format)
+/// The container named by a `container/*` reference, if `item_ref` is one.
+fn contents_ref(item_ref: &str) -> Option<&str> {
+    item_ref
+        .strip_suffix("/*")
+        .filter(|container| !container.is_empty() && !container.ends_with('\\'))
 }
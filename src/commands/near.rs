@@ -0,0 +1,86 @@
+//! Near command implementation: items physically close to another one.
+//!
+//! See SPEC.md#invy-near-item
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::Item;
+use crate::output::{self, Format};
+
+/// List items within `radius` containers of `item_ref`, nearest first.
+///
+/// An item's distance is how many containers up from `item_ref` it shares
+/// one with: 1 for everything in the same container (including inside the
+/// boxes next to it), 2 for the rest of the container around that, and so
+/// on. The item's own contents are at distance 0.
+///
+/// # Arguments
+/// * `item_ref` - Item to search around
+/// * `radius` - Largest distance to include
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(item_ref: &str, radius: usize, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let origin = db::resolve_item(&conn, item_ref)?
+        .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+
+    let mut children: HashMap<Option<i64>, Vec<Item>> = HashMap::new();
+    let mut parents = HashMap::new();
+    for item in db::list_all_items(&conn)? {
+        parents.insert(item.id, item.container_id);
+        children.entry(item.container_id).or_default().push(item);
+    }
+
+    // Each ring is the subtree of the next container up, minus the subtree
+    // already covered; `None` is the root, which contains everything.
+    let mut found = Vec::new();
+    let mut inner = origin.id;
+    collect(&children, Some(inner), None, 0, &mut found);
+    for distance in 1..=radius {
+        let outer = parents.get(&inner).copied().flatten();
+        collect(&children, outer, Some(inner), distance, &mut found);
+        match outer {
+            Some(id) => inner = id,
+            None => break,
+        }
+    }
+
+    let mut near = Vec::new();
+    for (item, distance) in found {
+        let path = db::get_item_path(&conn, item.id)?;
+        let child_count = db::count_children(&conn, item.id)?;
+        let mut item = item.clone().with_path(path, Some(child_count));
+        item.distance = Some(distance);
+        near.push(item);
+    }
+    near.sort_by(|a, b| {
+        a.distance
+            .cmp(&b.distance)
+            .then(a.path.len().cmp(&b.path.len()))
+            .then(a.path.cmp(&b.path))
+    });
+
+    output::print_near(&mut io::stdout(), &near, &origin.name, radius, format)
+}
+
+/// Add everything below `container` except the subtree of `skip`, tagged
+/// with `distance`.
+fn collect<'a>(
+    children: &'a HashMap<Option<i64>, Vec<Item>>,
+    container: Option<i64>,
+    skip: Option<i64>,
+    distance: usize,
+    found: &mut Vec<(&'a Item, usize)>,
+) {
+    for child in children.get(&container).into_iter().flatten() {
+        if Some(child.id) == skip {
+            continue;
+        }
+        found.push((child, distance));
+        collect(children, Some(child.id), skip, distance, found);
+    }
+}
@@ -0,0 +1,28 @@
+//! Notify command implementation.
+//!
+//! Only compiled with the `notify` cargo feature.
+//!
+//! See SPEC.md#invy-notify-test
+
+use anyhow::{anyhow, Result};
+use std::io::{self, Write};
+
+use crate::config::NotifyConfig;
+use crate::notify;
+
+/// Send a message to every configured backend to check the setup.
+///
+/// # Arguments
+/// * `config` - The `[notify]` backends
+/// * `message` - Text to send (default: a short test message)
+pub fn test(config: &NotifyConfig, message: Option<&str>) -> Result<()> {
+    if notify::notifiers(config).is_empty() {
+        return Err(anyhow!(
+            "no notification backends configured. Add [notify.ntfy] or [notify.telegram] to the config file"
+        ));
+    }
+    let message = message.unwrap_or("Notifications from invy are working");
+    let sent = notify::send_all(config, "invy", message)?;
+    writeln!(io::stdout(), "Sent to {}", sent.join(", "))?;
+    Ok(())
+}
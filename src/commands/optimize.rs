@@ -0,0 +1,147 @@
+//! Optimize command implementation: suggestions for where things would be
+//! easier to find.
+//!
+//! See SPEC.md#invy-optimize
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::classify;
+use crate::db;
+use crate::model::{Item, OptimizeSuggestion};
+use crate::output::{self, Format};
+use crate::timespec;
+
+/// What `invy optimize` looks at.
+#[derive(Debug)]
+pub struct OptimizeOptions<'a> {
+    /// Only count uses since this time spec (e.g. 90d)
+    pub since: &'a str,
+    /// Uses that make an item frequently used
+    pub min_uses: i64,
+    /// Deepest level a frequently used item should sit at
+    pub max_depth: usize,
+    /// `[classify.rules]`: keyword to container
+    pub rules: &'a HashMap<String, String>,
+    /// Print `invy mv` commands instead of a report
+    pub script: bool,
+}
+
+/// Suggest moving items that a keyword rule files elsewhere, and frequently
+/// used items that sit deep down, most used first.
+///
+/// # Arguments
+/// * `options` - Period, thresholds and rules
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(options: &OptimizeOptions, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let uses = db::usage_counts(&conn, &timespec::parse_since(options.since)?)?;
+    let items = db::list_all_items(&conn)?;
+    let by_id: HashMap<i64, &Item> = items.iter().map(|item| (item.id, item)).collect();
+
+    let ancestors = |item: &Item| {
+        let mut ids = Vec::new();
+        let mut current = item.container_id;
+        while let Some(id) = current {
+            ids.push(id);
+            current = by_id.get(&id).and_then(|parent| parent.container_id);
+        }
+        ids.reverse();
+        ids
+    };
+    let path = |item: &Item| {
+        let mut path: Vec<String> = ancestors(item)
+            .iter()
+            .filter_map(|id| by_id.get(id).map(|parent| parent.name.clone()))
+            .collect();
+        path.push(item.name.clone());
+        path
+    };
+
+    // Containers whose contents are used most, shallow enough to move
+    // frequently used things into
+    let mut busy: HashMap<i64, i64> = HashMap::new();
+    for item in &items {
+        if let (Some(container_id), Some(count)) = (item.container_id, uses.get(&item.id)) {
+            *busy.entry(container_id).or_default() += count;
+        }
+    }
+    let mut busy: Vec<(i64, i64, Vec<String>)> = busy
+        .into_iter()
+        .filter_map(|(id, count)| by_id.get(&id).map(|container| (count, *container)))
+        .filter(|(_, container)| ancestors(container).len() < options.max_depth)
+        .map(|(count, container)| (count, container.id, path(container)))
+        .collect();
+    busy.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.2.cmp(&b.2)));
+
+    let mut suggestions = Vec::new();
+    for item in &items {
+        let item_uses = uses.get(&item.id).copied().unwrap_or(0);
+        let above = ancestors(item);
+        let depth = above.len();
+        // Moving an item into itself or its own contents is impossible
+        let inside_item = |id: i64| {
+            id == item.id
+                || by_id
+                    .get(&id)
+                    .is_some_and(|other| ancestors(other).contains(&item.id))
+        };
+
+        let text = format!(
+            "{} {}",
+            item.name,
+            item.description.as_deref().unwrap_or("")
+        );
+        if let Some(rule) = classify::suggest(options.rules, &text) {
+            let target = db::resolve_item(&conn, rule.container).ok().flatten();
+            let misfiled = match &target {
+                Some(target) => item.container_id != Some(target.id) && !inside_item(target.id),
+                None => true,
+            };
+            if misfiled {
+                let destination = match &target {
+                    Some(target) => path(target),
+                    None => db::split_path(rule.container),
+                };
+                suggestions.push(OptimizeSuggestion {
+                    item_id: item.id,
+                    path: path(item),
+                    uses: item_uses,
+                    depth,
+                    reason: format!("rule '{}' files it elsewhere", rule.keyword),
+                    destination: Some(destination),
+                });
+                continue;
+            }
+        }
+
+        if item_uses < options.min_uses || depth <= options.max_depth {
+            continue;
+        }
+        let top = by_id
+            .get(&above[0])
+            .map(|top| top.name.clone())
+            .unwrap_or_default();
+        let destination = busy
+            .iter()
+            .find(|(_, id, _)| Some(*id) != item.container_id && !inside_item(*id))
+            .map(|(_, _, path)| path.clone());
+        suggestions.push(OptimizeSuggestion {
+            item_id: item.id,
+            path: path(item),
+            uses: item_uses,
+            depth,
+            reason: format!("used {} times, {} levels deep in {}", item_uses, depth, top),
+            destination,
+        });
+    }
+    suggestions.sort_by(|a, b| b.uses.cmp(&a.uses).then(a.path.cmp(&b.path)));
+
+    if options.script {
+        return output::print_optimize_script(&mut io::stdout(), &suggestions);
+    }
+    output::print_optimize(&mut io::stdout(), &suggestions, format)
+}
@@ -0,0 +1,156 @@
+//! Photo command implementation: item names from photos of labels and
+//! receipts.
+//!
+//! Only compiled with the `ocr` cargo feature. Text is extracted by a local
+//! `tesseract` install, so photos never leave the machine.
+//!
+//! See SPEC.md#invy-photo-ocr
+
+use anyhow::{anyhow, Context, Result};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::commands::add::{self, AddOptions};
+use crate::output::Format;
+use crate::strict;
+
+/// Program run to extract text, unless `INVY_TESSERACT` names another.
+const TESSERACT: &str = "tesseract";
+
+/// Extract text from photos, optionally proposing each line as an item to add.
+///
+/// # Arguments
+/// * `files` - Photos to read (e.g. a labeled bin or a receipt)
+/// * `suggest_items` - Ask whether to add each suggested name
+/// * `container` - Container accepted items are added to
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn ocr(
+    files: &[PathBuf],
+    suggest_items: bool,
+    container: Option<&str>,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    if suggest_items {
+        strict::check(|| "--suggest-items asks about each name; strict mode never prompts".into())?;
+    }
+    let mut texts = Vec::new();
+    for file in files {
+        texts.push(extract_text(file)?);
+    }
+
+    if !suggest_items {
+        let mut stdout = io::stdout();
+        for text in &texts {
+            write!(stdout, "{}", text)?;
+        }
+        return Ok(());
+    }
+
+    let names = suggest_names(&texts.concat());
+    if names.is_empty() {
+        eprintln!("No item names found");
+        return Ok(());
+    }
+
+    let mut lines = io::stdin().lock().lines();
+    let mut failed = 0;
+    for name in names {
+        eprint!("Add '{}'? [y/N/e(dit)/q] ", name);
+        io::stderr().flush()?;
+        let answer = lines.next().transpose()?.unwrap_or_default();
+        let name = match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => name,
+            "e" | "edit" => {
+                eprint!("Name: ");
+                io::stderr().flush()?;
+                let edited = lines.next().transpose()?.unwrap_or_default();
+                match edited.trim() {
+                    "" => continue,
+                    edited => edited.to_string(),
+                }
+            }
+            "q" | "quit" => break,
+            _ => continue,
+        };
+        // Keep going past duplicates so one clash doesn't end the session
+        let options = AddOptions {
+            container,
+            ..AddOptions::default()
+        };
+        if let Err(err) = add::run(&name, &options, format, db_path) {
+            eprintln!("Error: {}", err);
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{} item(s) could not be added", failed));
+    }
+    Ok(())
+}
+
+/// Run the OCR backend on one photo.
+fn extract_text(file: &Path) -> Result<String> {
+    if !file.is_file() {
+        return Err(anyhow!("photo '{}' not found", file.display()));
+    }
+    let program = std::env::var_os("INVY_TESSERACT").unwrap_or_else(|| TESSERACT.into());
+    let output = Command::new(&program)
+        .arg(file)
+        .arg("stdout")
+        .output()
+        .with_context(|| {
+            format!(
+                "Failed to run {} (install tesseract or set INVY_TESSERACT)",
+                program.to_string_lossy()
+            )
+        })?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "OCR failed for '{}': {}",
+            file.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Turn OCR text into candidate item names, one per line.
+///
+/// Trailing prices and counts (`4.99`, `$12`, `x2`) are dropped, lines with
+/// fewer than three letters are skipped as noise, and repeats are removed.
+fn suggest_names(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let mut words: Vec<&str> = line
+            .split_whitespace()
+            .map(|w| w.trim_matches(|c: char| "-*•·|:;,".contains(c)))
+            .filter(|w| !w.is_empty())
+            .collect();
+        while words.last().is_some_and(|w| is_amount(w)) {
+            words.pop();
+        }
+        let name = words.join(" ");
+        if name.chars().filter(|c| c.is_alphabetic()).count() < 3 {
+            continue;
+        }
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Whether a word is a price or count rather than part of a name.
+fn is_amount(word: &str) -> bool {
+    let digits = word
+        .trim_start_matches(['x', 'X'])
+        .trim_matches(|c: char| "$€£".contains(c));
+    !digits.is_empty()
+        && digits
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == ',')
+}
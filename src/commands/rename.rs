@@ -0,0 +1,216 @@
+//! Rename command implementation.
+//!
+//! See SPEC.md#invy-rename
+
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+use crate::db::{self, SortKey};
+use crate::model::RenamedItem;
+use crate::output::{self, Format};
+use crate::pathglob;
+use crate::strict;
+
+/// A piece of a rename template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    /// `{n}` or `{n:03}`: the counter, zero-padded to a width
+    Counter(usize),
+    /// `{1}`, `{2}`, ...: what a wildcard in the pattern matched
+    Capture(usize),
+    /// `{name}`: the current name
+    Name,
+}
+
+/// Rename the items in a container whose names match a glob, building each
+/// new name from a template.
+///
+/// # Arguments
+/// * `matching` - Name glob; each `*` and `?` is captured for the template
+/// * `template` - New name with `{n}`, `{n:02}`, `{1}`... and `{name}`
+/// * `container` - Container whose direct contents are renamed (default: root)
+/// * `start` - First counter value
+/// * `dry_run` - Preview the new names without writing them
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    matching: &str,
+    template: &str,
+    container: Option<&str>,
+    start: i64,
+    dry_run: bool,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let parts = parse_template(template)?;
+    let wildcards = matching.chars().filter(|c| matches!(c, '*' | '?')).count();
+    if let Some(index) = parts.iter().find_map(|part| match part {
+        Part::Capture(index) if *index > wildcards => Some(*index),
+        _ => None,
+    }) {
+        return Err(anyhow!(
+            "template uses {{{}}} but '{}' has {} wildcard(s)",
+            index,
+            matching,
+            wildcards
+        ));
+    }
+    let conn = db::open(db_path)?;
+
+    let renames = db::with_tx(&conn, |tx| {
+        let container_id = match container {
+            Some("/") | None => None,
+            Some(container_ref) => Some(
+                db::resolve_item(tx, container_ref)?
+                    .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?
+                    .id,
+            ),
+        };
+        let items = db::list_items_sorted(tx, container_id, SortKey::Name, false)?;
+
+        let mut renames = Vec::new();
+        let mut counter = start;
+        for item in &items {
+            let Some(captures) = pathglob::name_captures(matching, &item.name) else {
+                continue;
+            };
+            let new_name = expand(&parts, counter, &captures, &item.name);
+            counter += 1;
+            if new_name.trim().is_empty() {
+                return Err(anyhow!("template gives '{}' an empty name", item.name));
+            }
+            renames.push(RenamedItem {
+                id: item.id,
+                old_name: item.name.clone(),
+                new_name,
+            });
+        }
+        if renames.is_empty() {
+            return Err(anyhow!(
+                "no items named like '{}' in {}",
+                matching,
+                db::destination_label(container.unwrap_or("/"))
+            ));
+        }
+
+        for rename in &renames {
+            if let Some(warning) = output::slash_warning(&rename.new_name) {
+                strict::check(|| warning)?;
+            }
+        }
+
+        // Every name in the container afterwards must be unique
+        let renamed: HashSet<i64> = renames.iter().map(|r| r.id).collect();
+        let mut taken: HashSet<&str> = items
+            .iter()
+            .filter(|item| !renamed.contains(&item.id))
+            .map(|item| item.name.as_str())
+            .collect();
+        for rename in &renames {
+            if !taken.insert(&rename.new_name) {
+                return Err(anyhow!(
+                    "'{}' would be renamed to '{}', which is already taken",
+                    rename.old_name,
+                    rename.new_name
+                ));
+            }
+        }
+
+        if !dry_run {
+            // Park the names first so swaps (a -> b, b -> a) don't collide
+            for rename in &renames {
+                db::update_item_name(tx, rename.id, &format!("\u{0}rename-{}", rename.id))?;
+            }
+            for rename in &renames {
+                db::update_item_name(tx, rename.id, &rename.new_name)?;
+            }
+        }
+        Ok(renames)
+    })?;
+
+    for rename in &renames {
+        output::print_slash_warning(&mut io::stderr(), &rename.new_name)?;
+    }
+    output::print_renames(&mut io::stdout(), &renames, dry_run, format)
+}
+
+fn parse_template(template: &str) -> Result<Vec<Part>> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(anyhow!("unclosed '{{' in template '{}'", template)),
+                    }
+                }
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(parse_placeholder(&placeholder)?);
+            }
+            '}' => {
+                return Err(anyhow!(
+                    "unmatched '}}' in template '{}' (use '}}}}')",
+                    template
+                ))
+            }
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    Ok(parts)
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<Part> {
+    let unknown = || {
+        anyhow!(
+            "unknown placeholder '{{{}}}'. Use {{n}}, {{n:02}}, {{1}} or {{name}}",
+            placeholder
+        )
+    };
+    match placeholder.split_once(':') {
+        Some(("n", width)) => width.parse().map(Part::Counter).map_err(|_| unknown()),
+        Some(_) => Err(unknown()),
+        None if placeholder == "n" => Ok(Part::Counter(0)),
+        None if placeholder == "name" => Ok(Part::Name),
+        None => match placeholder.parse::<usize>() {
+            Ok(index) if index > 0 => Ok(Part::Capture(index)),
+            _ => Err(unknown()),
+        },
+    }
+}
+
+/// Build a new name; capture indexes were checked against the pattern.
+fn expand(parts: &[Part], counter: i64, captures: &[String], name: &str) -> String {
+    let mut expanded = String::new();
+    for part in parts {
+        match part {
+            Part::Text(text) => expanded.push_str(text),
+            Part::Counter(width) => {
+                expanded.push_str(&format!("{:0width$}", counter, width = width))
+            }
+            Part::Capture(index) => expanded.push_str(&captures[index - 1]),
+            Part::Name => expanded.push_str(name),
+        }
+    }
+    expanded
+}
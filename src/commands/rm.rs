@@ -3,9 +3,10 @@
 //! See SPEC.md#invy-rm-item
 
 use anyhow::{anyhow, Result};
+use std::io;
 use std::path::Path;
 
-use crate::db;
+use crate::backend::{self, Storage};
 use crate::output::{self, Format};
 
 /// Remove an item from the inventory.
@@ -14,26 +15,35 @@ use crate::output::{self, Format};
 ///
 /// # Arguments
 /// * `item` - Item to remove
-/// * `json` - Output as JSON
-/// * `csv` - Output as CSV
+/// * `format` - Output format
 /// * `db_path` - Optional custom database path
-pub fn run(item_ref: &str, json: bool, csv: bool, db_path: Option<&Path>) -> Result<()> {
-    let conn = db::open(db_path)?;
-    let format = Format::from_flags(json, csv);
-
-    // Resolve the item to remove
-    let item = db::resolve_item(&conn, item_ref)?
-        .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
-
-    let item_name = item.name.clone();
-
-    // Get children that will be orphaned
-    let children = db::list_items_in_container(&conn, item.id)?;
-    let orphaned_names: Vec<String> = children.iter().map(|c| c.name.clone()).collect();
-
-    // The ON DELETE SET NULL will automatically orphan children to root
-    // when we delete the container
-    db::delete_item(&conn, item.id)?;
+pub fn run(item_ref: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let store = backend::open(db_path)?;
+    let (item_id, item_name, orphaned_names) = remove(store.as_ref(), item_ref)?;
+
+    output::print_removed(
+        &mut io::stdout(),
+        item_id,
+        &item_name,
+        &orphaned_names,
+        format,
+    )
+}
 
-    output::print_removed(&item_name, &orphaned_names, format)
+/// Remove an item in its own transaction, returning its ID, its name and the
+/// names of the children orphaned to root.
+pub fn remove(store: &dyn Storage, item_ref: &str) -> Result<(i64, String, Vec<String>)> {
+    backend::with_tx(store, |tx| {
+        // Resolve the item to remove
+        let item = tx
+            .resolve_item(item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+
+        // Get children that will be orphaned to root
+        let children = tx.children(Some(item.id))?;
+        tx.delete_item(item.id)?;
+
+        let orphaned_names: Vec<String> = children.into_iter().map(|c| c.name).collect();
+        Ok((item.id, item.name, orphaned_names))
+    })
 }
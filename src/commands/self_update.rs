@@ -0,0 +1,201 @@
+//! Self-update command implementation.
+//!
+//! Only compiled with the `self-update` cargo feature.
+//!
+//! See SPEC.md#invy-self-update
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// GitHub release feed for the latest published version.
+const RELEASE_FEED: &str = "https://api.github.com/repos/brettsvoid/invy/releases/latest";
+
+/// minisign public key that release binaries are signed with.
+///
+/// Supplied at build time so the key lives with the release tooling rather
+/// than in the source tree. Without it, `--check` still works but installing
+/// is refused because signatures cannot be verified.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("INVY_RELEASE_PUBKEY");
+
+/// Largest binary we are willing to download.
+const MAX_DOWNLOAD_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Check for a newer release and optionally install it.
+///
+/// # Arguments
+/// * `check` - Only report whether an update is available
+pub fn run(check: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let exe = env::current_exe().context("Failed to locate the running binary")?;
+
+    let release: Release = ureq::get(RELEASE_FEED)
+        .header("User-Agent", concat!("invy/", env!("CARGO_PKG_VERSION")))
+        .header("Accept", "application/vnd.github+json")
+        .call()
+        .context("Failed to query release feed")?
+        .body_mut()
+        .read_json()
+        .context("Failed to parse release feed")?;
+
+    let latest = release.tag_name.trim_start_matches('v');
+    let mut out = io::stdout();
+
+    if !is_newer(latest, current) {
+        writeln!(out, "invy {} is up to date", current)?;
+        return Ok(());
+    }
+
+    writeln!(out, "Update available: {} -> {}", current, latest)?;
+    if check {
+        return Ok(());
+    }
+
+    if let Some(manager) = package_manager(&exe) {
+        return Err(anyhow!(
+            "invy is managed by {}. Update with your package manager instead",
+            manager
+        ));
+    }
+
+    let public_key = RELEASE_PUBLIC_KEY
+        .ok_or_else(|| anyhow!("this build has no release signing key; cannot verify updates"))?;
+
+    let asset_name = asset_name();
+    let binary = find_asset(&release, &asset_name)?;
+    let signature = find_asset(&release, &format!("{}.minisig", asset_name))?;
+
+    let bytes = download(&binary.browser_download_url)?;
+    let sig = String::from_utf8(download(&signature.browser_download_url)?)
+        .context("Release signature is not valid UTF-8")?;
+    verify(public_key, &bytes, &sig)?;
+
+    replace_binary(&exe, &bytes)?;
+    writeln!(out, "Updated invy to {}", latest)?;
+    Ok(())
+}
+
+/// Name of the release asset for this platform (e.g. `invy-linux-x86_64`).
+fn asset_name() -> String {
+    let suffix = if cfg!(windows) { ".exe" } else { "" };
+    format!("invy-{}-{}{}", env::consts::OS, env::consts::ARCH, suffix)
+}
+
+fn find_asset<'a>(release: &'a Release, name: &str) -> Result<&'a Asset> {
+    release
+        .assets
+        .iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| anyhow!("release {} has no asset '{}'", release.tag_name, name))
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    ureq::get(url)
+        .header("User-Agent", concat!("invy/", env!("CARGO_PKG_VERSION")))
+        .call()
+        .with_context(|| format!("Failed to download {}", url))?
+        .body_mut()
+        .with_config()
+        .limit(MAX_DOWNLOAD_BYTES)
+        .read_to_vec()
+        .with_context(|| format!("Failed to download {}", url))
+}
+
+fn verify(public_key: &str, bytes: &[u8], signature: &str) -> Result<()> {
+    let key = minisign_verify::PublicKey::from_base64(public_key)
+        .map_err(|e| anyhow!("invalid release signing key: {}", e))?;
+    let sig = minisign_verify::Signature::decode(signature)
+        .map_err(|e| anyhow!("invalid release signature: {}", e))?;
+    key.verify(bytes, &sig, false)
+        .map_err(|e| anyhow!("signature verification failed: {}", e))
+}
+
+/// Detect binaries installed by Homebrew or Scoop, which must not be
+/// overwritten behind the package manager's back.
+fn package_manager(exe: &Path) -> Option<&'static str> {
+    let path = exe.to_string_lossy().to_lowercase().replace('\\', "/");
+    if path.contains("/cellar/") || path.contains("/homebrew/") {
+        Some("Homebrew")
+    } else if path.contains("/scoop/") {
+        Some("Scoop")
+    } else {
+        None
+    }
+}
+
+/// Atomically swap the running binary for `bytes`.
+///
+/// Windows cannot overwrite a running executable, so the old one is renamed
+/// aside first and left for the next update to clean up.
+fn replace_binary(exe: &Path, bytes: &[u8]) -> Result<()> {
+    let staged = sibling(exe, "new");
+    fs::write(&staged, bytes).with_context(|| format!("Failed to write {:?}", staged))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+
+    if cfg!(windows) {
+        let old = sibling(exe, "old");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old).with_context(|| format!("Failed to move {:?} aside", exe))?;
+    }
+
+    fs::rename(&staged, exe).with_context(|| format!("Failed to replace {:?}", exe))
+}
+
+fn sibling(exe: &Path, extension: &str) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    exe.with_file_name(name)
+}
+
+/// Compare dotted numeric versions (`0.10.0` is newer than `0.9.3`).
+fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parts(v: &str) -> Vec<u64> {
+        v.split(['.', '-']).map_while(|p| p.parse().ok()).collect()
+    }
+    parts(candidate) > parts(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_comparison_is_numeric() {
+        assert!(is_newer("0.10.0", "0.9.3"));
+        assert!(is_newer("1.0.0", "0.2.0"));
+        assert!(!is_newer("0.2.0", "0.2.0"));
+        assert!(!is_newer("0.1.9", "0.2.0"));
+    }
+
+    #[test]
+    fn package_managed_installs_are_detected() {
+        let brew = Path::new("/opt/homebrew/Cellar/invy/0.2.0/bin/invy");
+        let scoop = Path::new(r"C:\Users\me\scoop\apps\invy\current\invy.exe");
+        let cargo = Path::new("/home/me/.cargo/bin/invy");
+
+        assert_eq!(package_manager(brew), Some("Homebrew"));
+        assert_eq!(package_manager(scoop), Some("Scoop"));
+        assert_eq!(package_manager(cargo), None);
+    }
+}
@@ -0,0 +1,463 @@
+//! Serve command implementation: a JSON API over HTTP, for phone-friendly
+//! frontends and other tools, and a small web UI built on it.
+//!
+//! Only compiled with the `serve` cargo feature. Each connection is read on
+//! its own thread, so a slow client does not hold up the others; requests
+//! then take turns on a single connection to the database and are answered
+//! by the operations of `invy --api` (see `api`). Responses are the
+//! commands' `--json` output.
+//!
+//! See SPEC.md#invy-serve
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::Connection;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+
+use crate::api::{self, Code};
+use crate::db;
+use crate::model::Item;
+use crate::output::{self, Format};
+
+/// The web UI, a single page that uses the API.
+const INDEX_HTML: &str = include_str!("serve/index.html");
+
+/// Largest request line plus headers accepted.
+const MAX_HEAD: usize = 64 * 1024;
+
+/// Largest request body accepted.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where `invy serve` listens and whom it answers.
+#[derive(Debug)]
+pub struct ServeOptions<'a> {
+    /// Address to listen on (`127.0.0.1` keeps it on this machine)
+    pub bind: &'a str,
+    /// Port to listen on (0 picks a free one)
+    pub port: u16,
+    /// Require `Authorization: Bearer <token>` on every API request
+    pub token: Option<&'a str>,
+    /// Container new items go into when the request names none
+    pub default_container: Option<&'a str>,
+    /// Whether missing containers are created, and strict mode
+    pub placement: db::Placement,
+}
+
+/// A parsed HTTP request.
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+/// An HTTP response, JSON unless it is the web UI.
+#[derive(Debug)]
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+    headers: Vec<(&'static str, String)>,
+}
+
+impl Response {
+    fn json(status: u16, body: &Value) -> Self {
+        Response {
+            status,
+            content_type: "application/json",
+            body: format!("{}\n", body).into_bytes(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn page() -> Self {
+        Response {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: INDEX_HTML.as_bytes().to_vec(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn error(error: &ApiError) -> Self {
+        let body = serde_json::json!({ "error": error.message }).to_string();
+        let mut headers = Vec::new();
+        if error.status == 401 {
+            headers.push(("WWW-Authenticate", "Bearer".to_string()));
+        }
+        Response {
+            status: error.status,
+            content_type: "application/json",
+            body: format!("{}\n", body).into_bytes(),
+            headers,
+        }
+    }
+
+    fn write_to(&self, stream: &mut impl Write) -> Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len()
+        )?;
+        for (name, value) in &self.headers {
+            write!(stream, "{}: {}\r\n", name, value)?;
+        }
+        write!(stream, "\r\n")?;
+        stream.write_all(&self.body)?;
+        stream.flush()?;
+        Ok(())
+    }
+}
+
+/// A failure with the HTTP status it is reported with.
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(what: &str) -> Self {
+        ApiError::new(404, format!("{} not found", what))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Answer API requests until interrupted.
+///
+/// # Arguments
+/// * `options` - Address, port, access token and default container
+/// * `db_path` - Optional custom database path
+pub fn run(options: &ServeOptions, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let listener = TcpListener::bind((options.bind, options.port))
+        .with_context(|| format!("Failed to listen on {}:{}", options.bind, options.port))?;
+    eprintln!(
+        "Serving the inventory on http://{}; press Ctrl-C to stop",
+        listener.local_addr()?
+    );
+
+    let conn = Mutex::new(conn);
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(err) => {
+                    eprintln!("Warning: could not accept a connection: {}", err);
+                    continue;
+                }
+            };
+            let conn = &conn;
+            scope.spawn(move || {
+                let response = match read_request(&mut stream) {
+                    Ok(request) => {
+                        // A panic in another request leaves the connection
+                        // usable: each request runs in its own transaction
+                        let conn = conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                        respond(&conn, options, &request)
+                    }
+                    Err(err) => Response::error(&ApiError::new(400, format!("{:#}", err))),
+                };
+                if let Err(err) = response.write_to(&mut stream) {
+                    eprintln!("Warning: could not respond: {:#}", err);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Read one request. Only `Content-Length` bodies are supported.
+fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            return Err(anyhow!("connection closed before the request was complete"));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+
+        let mut headers = [httparse::EMPTY_HEADER; 32];
+        let mut parsed = httparse::Request::new(&mut headers);
+        let httparse::Status::Complete(head_len) = parsed.parse(&buffer)? else {
+            if buffer.len() > MAX_HEAD {
+                return Err(anyhow!("request headers are too large"));
+            }
+            continue;
+        };
+
+        let header = |name: &str| {
+            parsed
+                .headers
+                .iter()
+                .find(|header| header.name.eq_ignore_ascii_case(name))
+                .map(|header| String::from_utf8_lossy(header.value).into_owned())
+        };
+        let length: usize = match header("Content-Length") {
+            Some(length) => length.trim().parse().context("invalid Content-Length")?,
+            None => 0,
+        };
+        if length > MAX_BODY {
+            return Err(anyhow!("request body is too large"));
+        }
+        let target = parsed.path.unwrap_or("/");
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let mut request = Request {
+            method: parsed.method.unwrap_or_default().to_string(),
+            path: path.to_string(),
+            query: form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect(),
+            authorization: header("Authorization"),
+            body: buffer[head_len..].to_vec(),
+        };
+
+        while request.body.len() < length {
+            let read = stream.read(&mut chunk)?;
+            if read == 0 {
+                return Err(anyhow!("connection closed before the body was complete"));
+            }
+            request.body.extend_from_slice(&chunk[..read]);
+        }
+        request.body.truncate(length);
+        return Ok(request);
+    }
+}
+
+/// The response to a request, with failures turned into error responses.
+fn respond(conn: &Connection, options: &ServeOptions, request: &Request) -> Response {
+    let response = route(conn, options, request).and_then(|response| response);
+    response.unwrap_or_else(|err| {
+        if let Some(err) = err.downcast_ref::<ApiError>() {
+            return Response::error(err);
+        }
+        let status = match err.downcast_ref::<api::Error>().map(|err| err.code) {
+            Some(Code::NotFound) => 404,
+            Some(Code::InvalidParams | Code::Failed) => 400,
+            // The database or the file system failed, not the request
+            None => 500,
+        };
+        Response::error(&ApiError::new(status, format!("{:#}", err)))
+    })
+}
+
+/// Check access and dispatch on the method and path.
+fn route(conn: &Connection, options: &ServeOptions, request: &Request) -> Result<Result<Response>> {
+    let segments: Vec<&str> = request
+        .path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    // The page itself holds no data; it asks for the token when the API
+    // wants one
+    if segments.is_empty() {
+        return Ok(match request.method.as_str() {
+            "GET" => Ok(Response::page()),
+            _ => Err(method_not_allowed()),
+        });
+    }
+    // Share links carry their own credential, the token in the URL
+    if let ["share", token] = segments[..] {
+        return Ok(match request.method.as_str() {
+            "GET" => shared(conn, token),
+            _ => Err(method_not_allowed()),
+        });
+    }
+    if let Some(token) = options.token {
+        let expected = format!("Bearer {}", token);
+        let given = request.authorization.as_deref().unwrap_or_default();
+        // In constant time, so response timing does not give the token away
+        if !bool::from(given.as_bytes().ct_eq(expected.as_bytes())) {
+            return Err(ApiError::new(401, "missing or wrong access token").into());
+        }
+    }
+
+    Ok(match (request.method.as_str(), &segments[..]) {
+        ("GET", ["items"]) => list(conn, request),
+        ("POST", ["items"]) => create(conn, options, request),
+        ("GET", ["items", id]) => item(conn, id),
+        ("PATCH", ["items", id]) => update(conn, options, id, request),
+        ("DELETE", ["items", id]) => delete(conn, id),
+        ("GET", ["search"]) => search(conn, request),
+        (_, ["items"] | ["items", _] | ["search"]) => Err(method_not_allowed()),
+        _ => Err(ApiError::not_found("page").into()),
+    })
+}
+
+fn method_not_allowed() -> anyhow::Error {
+    ApiError::new(405, "method not allowed").into()
+}
+
+/// `GET /items?in=<container>`: the contents of a container, or the root.
+fn list(conn: &Connection, request: &Request) -> Result<Response> {
+    let params = query_params(
+        request,
+        &[("in", "in"), ("limit", "limit"), ("offset", "offset")],
+    );
+    Ok(Response::json(200, &api::list(conn, &params)?))
+}
+
+/// `GET /search?q=<text>`: items whose name, description or alias contains
+/// the text, as `invy find` finds them.
+fn search(conn: &Connection, request: &Request) -> Result<Response> {
+    if request.query.get("q").is_none_or(|query| query.is_empty()) {
+        return Err(ApiError::new(400, "missing search text; use /search?q=<text>").into());
+    }
+    let params = query_params(
+        request,
+        &[("q", "query"), ("limit", "limit"), ("offset", "offset")],
+    );
+    Ok(Response::json(200, &api::find(conn, &params)?))
+}
+
+/// `GET /items/<id>`: what `invy show --json` prints.
+fn item(conn: &Connection, id: &str) -> Result<Response> {
+    let item = find(conn, id)?;
+    Ok(Response::json(
+        200,
+        &api::show(conn, &format!("#{}", item.id))?,
+    ))
+}
+
+/// `POST /items`: add an item, as `invy add` does.
+fn create(conn: &Connection, options: &ServeOptions, request: &Request) -> Result<Response> {
+    let added = api::add(
+        conn,
+        &body(request)?,
+        options.default_container,
+        options.placement,
+    )?;
+    let mut response = Response::json(201, &added);
+    response
+        .headers
+        .push(("Location", format!("/items/{}", added["id"])));
+    Ok(response)
+}
+
+/// `PATCH /items/<id>`: change an item, as `invy edit` does. `null` clears
+/// a field.
+fn update(
+    conn: &Connection,
+    options: &ServeOptions,
+    id: &str,
+    request: &Request,
+) -> Result<Response> {
+    let item = find(conn, id)?;
+    let updated = api::edit(
+        conn,
+        &format!("#{}", item.id),
+        &body(request)?,
+        options.placement,
+    )?;
+    Ok(Response::json(200, &updated))
+}
+
+/// `DELETE /items/<id>`: remove an item, as `invy rm` does.
+fn delete(conn: &Connection, id: &str) -> Result<Response> {
+    let item = find(conn, id)?;
+    Ok(Response::json(
+        200,
+        &api::remove(conn, &format!("#{}", item.id))?,
+    ))
+}
+
+/// `GET /share/<token>`: the shared container and everything inside it,
+/// while the link is valid.
+fn shared(conn: &Connection, token: &str) -> Result<Response> {
+    // In constant time, like the access token
+    let share = db::list_shares(conn)?
+        .into_iter()
+        .find(|share| !share.expired && bool::from(share.token.as_bytes().ct_eq(token.as_bytes())))
+        .ok_or_else(|| ApiError::not_found("share"))?;
+
+    let mut children: HashMap<Option<i64>, Vec<Item>> = HashMap::new();
+    for item in db::list_all_items(conn)? {
+        children.entry(item.container_id).or_default().push(item);
+    }
+    let mut items = Vec::new();
+    let mut stack = vec![share.item_id];
+    while let Some(id) = stack.pop() {
+        for child in children.remove(&Some(id)).unwrap_or_default() {
+            stack.push(child.id);
+            items.push(child);
+        }
+    }
+    items.extend(db::get_item_by_id(conn, share.item_id)?);
+
+    let mut items = api::with_paths(conn, items)?;
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+    let items = api::to_json(|w| output::print_items(w, &items, None, Format::Json))?;
+    Ok(Response::json(200, &items))
+}
+
+/// The item with ID `id`, as given in the URL.
+fn find(conn: &Connection, id: &str) -> Result<Item> {
+    let item = match id.parse() {
+        Ok(id) => db::get_item_by_id(conn, id)?,
+        Err(_) => None,
+    };
+    Ok(item.ok_or_else(|| ApiError::not_found(&format!("item {}", id)))?)
+}
+
+/// The query parameters named in `names`, renamed to the operation's
+/// parameters.
+fn query_params(request: &Request, names: &[(&str, &str)]) -> Map<String, Value> {
+    names
+        .iter()
+        .filter_map(|&(query, param)| {
+            let value = request.query.get(query)?;
+            Some((param.to_string(), Value::String(value.clone())))
+        })
+        .collect()
+}
+
+/// The request body, which must be a JSON object.
+fn body(request: &Request) -> Result<Map<String, Value>> {
+    Ok(serde_json::from_slice(&request.body)
+        .map_err(|err| ApiError::new(400, format!("body must be a JSON object: {}", err)))?)
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,86 @@
+//! Share command implementation.
+//!
+//! See SPEC.md#invy-share
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::Share;
+use crate::output::{self, Format};
+use crate::timespec;
+
+/// Address used for share links when no base URL is configured.
+pub const DEFAULT_BASE_URL: &str = "http://localhost:8080";
+
+/// Create a read-only share link for a container's subtree.
+///
+/// # Arguments
+/// * `container` - Container to share
+/// * `expires` - Duration or date the link expires (`never` for no expiry)
+/// * `base_url` - Public address of `invy serve`
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn create(
+    container_ref: &str,
+    expires: &str,
+    base_url: Option<&str>,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let expires_at = match expires {
+        "never" => None,
+        spec => Some(timespec::parse_until(spec)?),
+    };
+
+    let conn = db::open(db_path)?;
+
+    let share = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, container_ref)?
+            .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?;
+        db::create_share(tx, item.id, expires_at.as_deref())
+    })?;
+
+    output::print_share(&mut io::stdout(), &with_url(share, base_url), format)
+}
+
+/// List share links, including expired ones.
+///
+/// # Arguments
+/// * `base_url` - Public address of `invy serve`
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn list(base_url: Option<&str>, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let shares: Vec<Share> = db::list_shares(&conn)?
+        .into_iter()
+        .map(|share| with_url(share, base_url))
+        .collect();
+
+    output::print_shares(&mut io::stdout(), &shares, format)
+}
+
+/// Revoke a share link so its URL stops working.
+///
+/// # Arguments
+/// * `token` - Token of the share to revoke
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn revoke(token: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    if !db::delete_share(&conn, token)? {
+        return Err(anyhow!("share '{}' not found", token));
+    }
+
+    output::print_revoked(&mut io::stdout(), token, format)
+}
+
+fn with_url(share: Share, base_url: Option<&str>) -> Share {
+    let base = base_url.unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/');
+    let url = format!("{}/share/{}", base, share.token);
+    Share {
+        url: Some(url),
+        ..share
+    }
+}
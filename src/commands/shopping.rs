@@ -0,0 +1,67 @@
+//! Shopping command implementation: items below their restocking threshold.
+//!
+//! See SPEC.md#invy-shopping
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::ShoppingItem;
+use crate::output::{self, Format};
+use crate::strict;
+
+/// List items whose quantity is below the minimum set with
+/// `invy edit --min-qty`, with how much to buy.
+///
+/// # Arguments
+/// * `within` - Only include items below this container
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(within: Option<&str>, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let within = match within {
+        Some(container_ref) => Some(
+            db::resolve_item(&conn, container_ref)?
+                .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?,
+        ),
+        None => None,
+    };
+
+    let mut low = Vec::new();
+    for (item, min) in db::list_min_quantities(&conn)? {
+        if let Some(container) = &within {
+            if !db::is_ancestor(&conn, container.id, item.id)? || item.id == container.id {
+                continue;
+            }
+        }
+        // `invy edit` only accepts comparable units, so this fails only for
+        // data written by other tools
+        let Ok((min, remaining)) = item.measure().subtract(&min) else {
+            let warning = format!(
+                "skipping '{}': its minimum {} cannot be compared with {}",
+                item.name,
+                min,
+                item.measure()
+            );
+            strict::check(|| warning.clone())?;
+            eprintln!("Warning: {}", warning);
+            continue;
+        };
+        if remaining.amount >= 0 {
+            continue;
+        }
+        low.push(ShoppingItem {
+            id: item.id,
+            path: db::get_item_path(&conn, item.id)?,
+            name: item.name,
+            quantity: remaining.amount + min.amount,
+            min_quantity: min.amount,
+            need: -remaining.amount,
+            unit: remaining.unit,
+        });
+    }
+    low.sort_by(|a, b| a.path.cmp(&b.path));
+
+    output::print_shopping(&mut io::stdout(), &low, format)
+}
@@ -3,40 +3,99 @@
 //! See SPEC.md#invy-show-item
 
 use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::io;
 use std::path::Path;
 
 use crate::db;
+use crate::model::{Item, ItemWithPath};
 use crate::output::{self, Format};
+use crate::strict;
+use crate::template::Template;
 
 /// Show detailed information about a specific item.
 ///
 /// # Arguments
 /// * `item` - Item name or path
-/// * `json` - Output as JSON
-/// * `csv` - Output as CSV
+/// * `by_desc` - Find the item by text in its description instead
+/// * `locations_history` - Include every container the item has lived in
+/// * `lang` - Show names in this language where set
+/// * `template` - Render the item through this template instead
+/// * `format` - Output format
 /// * `db_path` - Optional custom database path
-pub fn run(item_ref: &str, json: bool, csv: bool, db_path: Option<&Path>) -> Result<()> {
+pub fn run(
+    item_ref: &str,
+    by_desc: Option<&str>,
+    locations_history: bool,
+    lang: Option<&str>,
+    template: Option<&Template>,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
     let conn = db::open(db_path)?;
-    let format = Format::from_flags(json, csv);
 
-    let item = match db::resolve_item(&conn, item_ref)? {
+    let resolved = match by_desc {
+        Some(text) => Some(
+            db::get_item_by_description(&conn, text)?
+                .ok_or_else(|| anyhow!("no item's description contains '{}'", text))?,
+        ),
+        None => db::resolve_item(&conn, item_ref)?,
+    };
+    let mut item = match resolved {
         Some(item) => item,
         None => {
-            let suggestions = db::search_items(&conn, item_ref).unwrap_or_default();
+            let suggestions = if strict::enabled() {
+                Vec::new()
+            } else {
+                db::search_items(&conn, item_ref).unwrap_or_default()
+            };
             if !suggestions.is_empty() {
                 eprintln!("Did you mean:");
                 for suggestion in suggestions.iter().take(10) {
                     let path = db::get_item_path(&conn, suggestion.id).unwrap_or_default();
-                    eprintln!("  {}", path.join("/"));
+                    eprintln!("  {}", db::join_path(&path));
                 }
             }
             return Err(anyhow!("item '{}' not found", item_ref));
         }
     };
 
-    let path = db::get_item_path(&conn, item.id)?;
-    let child_count = db::count_children(&conn, item.id)?;
-    let item_with_path = item.with_path(path, Some(child_count));
+    // Lookups tell `invy optimize` what is used often
+    db::record_lookup(&conn, item.id)?;
+
+    db::localize_item(&conn, &mut item, lang)?;
+    let mut item_with_path = details(&conn, item, lang)?;
+    if matches!(format, Format::Json) {
+        item_with_path.stats = db::container_stats(&conn, item_with_path.id)?;
+    }
+    if locations_history {
+        item_with_path.locations = Some(db::list_locations(&conn, item_with_path.id)?);
+    }
+
+    match template {
+        Some(template) => output::print_templated(&mut io::stdout(), &[item_with_path], template),
+        None => output::print_item(&mut io::stdout(), &item_with_path, format),
+    }
+}
 
-    output::print_item(&item_with_path, format)
+/// Everything `show` prints about an item except container totals and the
+/// location history, with names in `lang` where set.
+pub fn details(conn: &Connection, item: Item, lang: Option<&str>) -> Result<ItemWithPath> {
+    let path = db::get_item_path_in(conn, item.id, lang)?;
+    let child_count = db::count_children(conn, item.id)?;
+    let mut details = item.with_path(path, Some(child_count));
+    details.open_todos = Some(db::count_open_todos(conn, details.id)?);
+    details.weight_g = db::subtree_weights(conn)?.get(&details.id).copied();
+    details.verified_at = db::get_verified_at(conn, details.id)?;
+    if let Some(min) = db::get_min_quantity(conn, details.id)? {
+        details.min_quantity = Some(min.amount);
+        details.min_unit = min.unit;
+    }
+    details.expires_at = db::get_expiry(conn, details.id)?;
+    details.group = db::get_item_group(conn, details.id)?;
+    let aliases = db::list_aliases(conn, details.id)?;
+    if !aliases.is_empty() {
+        details.aliases = Some(aliases);
+    }
+    Ok(details)
 }
@@ -0,0 +1,258 @@
+//! Stats command implementation.
+//!
+//! See SPEC.md#invy-stats
+
+use anyhow::Result;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::{ChurnPoint, ContainerHealth, HealthReport, HealthScore, Item, SeriesPoint};
+use crate::output::{self, Format};
+use crate::timespec::{self, Period};
+
+/// Setting holding the score and time of the last `stats --health` run.
+const HEALTH_SETTING: &str = "health_last";
+
+/// Show inventory statistics, optionally as a time series.
+///
+/// The series are computed from item creation times, the location history
+/// and the consumption log, so activity from before location history was
+/// tracked only shows up for items that still exist.
+///
+/// # Arguments
+/// * `series` - Show activity per period instead of the summary
+/// * `churn` - Show moves in and out of each container per period
+/// * `health` - Show the data-quality report, with items unchanged for
+///   `stale_after` counting as stale
+/// * `period` - Bucket size for `series` and `churn`
+/// * `since` - Optional age or date to start from (e.g. `1y`)
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    series: bool,
+    churn: bool,
+    health: Option<&str>,
+    period: Period,
+    since: Option<&str>,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let cutoff = since.map(timespec::parse_since).transpose()?;
+    let conn = db::open(db_path)?;
+
+    if let Some(stale_after) = health {
+        let report = health_report(&conn, stale_after)?;
+        output::print_health(&mut io::stdout(), &report, format)
+    } else if churn {
+        let points = churn_points(&conn, period, cutoff.as_deref())?;
+        output::print_churn(&mut io::stdout(), &points, format)
+    } else if series {
+        let points = series_points(&conn, period, cutoff.as_deref())?;
+        output::print_series(&mut io::stdout(), &points, format)
+    } else {
+        let stats = db::stats(&conn)?;
+        output::print_stats(&mut io::stdout(), &stats, format)
+    }
+}
+
+fn in_range(timestamp: &str, cutoff: Option<&str>) -> bool {
+    cutoff.is_none_or(|c| timestamp >= c)
+}
+
+fn series_points(
+    conn: &Connection,
+    period: Period,
+    cutoff: Option<&str>,
+) -> Result<Vec<SeriesPoint>> {
+    fn entry<'a>(
+        points: &'a mut BTreeMap<String, SeriesPoint>,
+        period: Period,
+        timestamp: &str,
+    ) -> &'a mut SeriesPoint {
+        let label = period.bucket(timestamp);
+        points.entry(label.clone()).or_insert_with(|| SeriesPoint {
+            period: label,
+            ..Default::default()
+        })
+    }
+
+    let mut points = BTreeMap::new();
+
+    let items = db::list_all_items(conn)?;
+    let existing: HashSet<i64> = items.iter().map(|item| item.id).collect();
+    for item in &items {
+        if in_range(&item.created_at, cutoff) {
+            entry(&mut points, period, &item.created_at).added += 1;
+        }
+    }
+
+    // An item's first history entry is its creation, which is the only
+    // record left of items that have since been removed or archived. Every
+    // later entry is a move.
+    let history = db::list_all_locations(conn)?;
+    for (i, (item_id, location)) in history.iter().enumerate() {
+        if !in_range(&location.moved_at, cutoff) {
+            continue;
+        }
+        let first = i == 0 || history[i - 1].0 != *item_id;
+        if !first {
+            entry(&mut points, period, &location.moved_at).moved += 1;
+        } else if !existing.contains(item_id) {
+            entry(&mut points, period, &location.moved_at).added += 1;
+        }
+    }
+
+    for consumption in db::list_consumptions(conn, cutoff)? {
+        let point = entry(&mut points, period, &consumption.consumed_at);
+        // Measured amounts (3 m of cable) count as one consumption
+        point.consumed += match consumption.unit {
+            Some(_) => 1,
+            None => consumption.quantity,
+        };
+        if consumption.remaining == Some(0) {
+            point.archived += 1;
+        }
+    }
+
+    Ok(points
+        .into_values()
+        .map(|p| SeriesPoint {
+            net: p.added - p.archived,
+            ..p
+        })
+        .collect())
+}
+
+fn churn_points(
+    conn: &Connection,
+    period: Period,
+    cutoff: Option<&str>,
+) -> Result<Vec<ChurnPoint>> {
+    fn entry<'a>(
+        points: &'a mut BTreeMap<(String, Vec<String>), ChurnPoint>,
+        label: &str,
+        container: &[String],
+    ) -> &'a mut ChurnPoint {
+        points
+            .entry((label.to_string(), container.to_vec()))
+            .or_insert_with(|| ChurnPoint {
+                period: label.to_string(),
+                container: container.to_vec(),
+                moved_in: 0,
+                moved_out: 0,
+            })
+    }
+
+    let mut points = BTreeMap::new();
+
+    let history = db::list_all_locations(conn)?;
+    for pair in history.windows(2) {
+        let ((prev_id, prev), (item_id, next)) = (&pair[0], &pair[1]);
+        if prev_id != item_id || !in_range(&next.moved_at, cutoff) {
+            continue;
+        }
+        let label = period.bucket(&next.moved_at);
+        entry(&mut points, &label, &prev.path).moved_out += 1;
+        entry(&mut points, &label, &next.path).moved_in += 1;
+    }
+
+    Ok(points.into_values().collect())
+}
+
+/// Score the inventory's data quality, overall and per top-level container,
+/// and remember the score to compare the next run against.
+fn health_report(conn: &Connection, stale_after: &str) -> Result<HealthReport> {
+    let cutoff = timespec::parse_since(stale_after)?;
+    let items = db::list_all_items(conn)?;
+    let verifications = db::list_verifications(conn)?;
+
+    let mut name_counts: HashMap<String, i64> = HashMap::new();
+    for item in &items {
+        *name_counts.entry(item.name.to_lowercase()).or_default() += 1;
+    }
+    let by_id: HashMap<i64, &Item> = items.iter().map(|item| (item.id, item)).collect();
+    let top_level = |item: &Item| {
+        let mut top = item;
+        while let Some(parent) = top.container_id.and_then(|id| by_id.get(&id)) {
+            top = parent;
+        }
+        top.id
+    };
+
+    let mut overall = HealthScore::default();
+    let mut containers: BTreeMap<i64, HealthScore> = BTreeMap::new();
+    for item in &items {
+        let described = item
+            .description
+            .as_deref()
+            .is_some_and(|d| !d.trim().is_empty());
+        let last_seen = match verifications.get(&item.id) {
+            Some(verified) if *verified > item.updated_at => verified,
+            _ => &item.updated_at,
+        };
+        let stale = *last_seen < cutoff;
+        let ambiguous = name_counts[&item.name.to_lowercase()] > 1;
+
+        // Loose items at root only count towards the overall score
+        let top = top_level(item);
+        let container = (top != item.id).then(|| containers.entry(top).or_default());
+        for score in std::iter::once(&mut overall).chain(container) {
+            score.items += 1;
+            score.described += described as i64;
+            score.stale += stale as i64;
+            score.ambiguous += ambiguous as i64;
+        }
+    }
+
+    let mut containers: Vec<ContainerHealth> = containers
+        .into_iter()
+        .map(|(id, health)| ContainerHealth {
+            path: vec![by_id[&id].name.clone()],
+            health: scored(health),
+        })
+        .collect();
+    containers.sort_by(|a, b| {
+        a.health
+            .score
+            .cmp(&b.health.score)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    let health = scored(overall);
+
+    let previous = db::get_setting(conn, HEALTH_SETTING)?;
+    let (previous_score, previous_at) = match previous.as_deref().and_then(|p| p.split_once(' ')) {
+        Some((score, at)) => (score.parse().ok(), Some(at.to_string())),
+        None => (None, None),
+    };
+    let now = timespec::now();
+    db::with_tx(conn, |tx| {
+        db::set_setting(tx, HEALTH_SETTING, &format!("{} {}", health.score, now))
+    })?;
+
+    Ok(HealthReport {
+        health,
+        stale_after: stale_after.to_string(),
+        previous_score,
+        previous_at,
+        containers,
+    })
+}
+
+/// Fill in the score: the average share of described, fresh and unambiguous
+/// items, as a percentage. Nothing to score counts as perfect.
+fn scored(health: HealthScore) -> HealthScore {
+    if health.items == 0 {
+        return HealthScore {
+            score: 100,
+            ..health
+        };
+    }
+    let good = health.described + (health.items - health.stale) + (health.items - health.ambiguous);
+    HealthScore {
+        score: (good * 100 + health.items * 3 / 2) / (health.items * 3),
+        ..health
+    }
+}
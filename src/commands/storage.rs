@@ -0,0 +1,246 @@
+//! Storage command implementation: temperature and humidity of containers,
+//! and what items need.
+//!
+//! See SPEC.md#invy-storage
+
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::{ConditionKind, StorageConditions, StorageIssue};
+use crate::output::{self, Format};
+
+/// Changes to an item's storage conditions; `None` leaves a value as is.
+#[derive(Debug, Default)]
+pub struct ConditionArgs<'a> {
+    /// Temperature range in °C, e.g. `5..40` or `..30`
+    pub temp: Option<&'a str>,
+    /// Relative humidity range in percent, e.g. `..60`
+    pub humidity: Option<&'a str>,
+    /// Free-form note (use "" to clear)
+    pub note: Option<&'a str>,
+}
+
+/// Record the conditions a container provides, or an item requires.
+///
+/// # Arguments
+/// * `item_ref` - Container or item to describe
+/// * `kind` - Whether these are a container's conditions or an item's needs
+/// * `args` - Values to change
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn set(
+    item_ref: &str,
+    kind: ConditionKind,
+    args: &ConditionArgs,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    if args.temp.is_none() && args.humidity.is_none() && args.note.is_none() {
+        return Err(anyhow!("nothing to set (use --temp, --humidity or --note)"));
+    }
+    let temp = args.temp.map(parse_range).transpose()?;
+    let humidity = args.humidity.map(parse_range).transpose()?;
+
+    let conn = db::open(db_path)?;
+
+    let conditions = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+
+        let mut conditions = match db::get_storage_conditions(tx, item.id, kind)? {
+            Some(existing) => existing,
+            None => StorageConditions {
+                item_id: item.id,
+                path: db::get_item_path(tx, item.id)?,
+                kind,
+                temp_min: None,
+                temp_max: None,
+                humidity_min: None,
+                humidity_max: None,
+                note: None,
+            },
+        };
+        if let Some((min, max)) = temp {
+            conditions.temp_min = min;
+            conditions.temp_max = max;
+        }
+        if let Some((min, max)) = humidity {
+            conditions.humidity_min = min;
+            conditions.humidity_max = max;
+        }
+        if let Some(note) = args.note {
+            conditions.note = Some(note.trim().to_string()).filter(|n| !n.is_empty());
+        }
+
+        db::set_storage_conditions(tx, &conditions)?;
+        Ok(conditions)
+    })?;
+
+    output::print_storage_conditions(&mut io::stdout(), &[conditions], format)
+}
+
+/// Forget an item's storage conditions and requirements.
+///
+/// # Arguments
+/// * `item_ref` - Container or item to clear
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn clear(item_ref: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let item = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+        if db::remove_storage_conditions(tx, item.id)? == 0 {
+            return Err(anyhow!("'{}' has no storage conditions", item.name));
+        }
+        let path = db::get_item_path(tx, item.id)?;
+        Ok(item.with_path(path, None))
+    })?;
+
+    output::print_storage_cleared(&mut io::stdout(), &item, format)
+}
+
+/// List every container's conditions and every item's requirements.
+///
+/// # Arguments
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn list(format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let all = db::list_storage_conditions(&conn)?;
+    output::print_storage_conditions(&mut io::stdout(), &all, format)
+}
+
+/// Report items whose nearest container with recorded conditions is too
+/// hot, cold, damp or dry for them.
+///
+/// # Arguments
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn check(format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let all = db::list_storage_conditions(&conn)?;
+
+    let provided: HashMap<i64, &StorageConditions> = all
+        .iter()
+        .filter(|c| c.kind == ConditionKind::Provides)
+        .map(|c| (c.item_id, c))
+        .collect();
+
+    let mut issues = Vec::new();
+    for needs in all.iter().filter(|c| c.kind == ConditionKind::Requires) {
+        let Some(has) = nearest_conditions(&conn, needs.item_id, &provided)? else {
+            continue;
+        };
+        let problems = problems(needs, has);
+        if !problems.is_empty() {
+            issues.push(StorageIssue {
+                item_id: needs.item_id,
+                path: needs.path.clone(),
+                container: has.path.clone(),
+                container_note: has.note.clone(),
+                problems,
+            });
+        }
+    }
+
+    output::print_storage_issues(&mut io::stdout(), &issues, format)?;
+
+    if !issues.is_empty() {
+        return Err(anyhow!(
+            "{} item(s) stored in unsuitable conditions",
+            issues.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Conditions of the closest container above an item that has some.
+fn nearest_conditions<'a>(
+    conn: &Connection,
+    item_id: i64,
+    provided: &HashMap<i64, &'a StorageConditions>,
+) -> Result<Option<&'a StorageConditions>> {
+    Ok(db::get_ancestor_ids(conn, item_id)?
+        .into_iter()
+        .find_map(|id| provided.get(&id).copied()))
+}
+
+/// Ways a container's conditions fall outside an item's needs. Bounds
+/// missing on either side are not compared.
+fn problems(needs: &StorageConditions, has: &StorageConditions) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (unit, (has_min, has_max), (needs_min, needs_max)) in [
+        (
+            "°C",
+            (has.temp_min, has.temp_max),
+            (needs.temp_min, needs.temp_max),
+        ),
+        (
+            "% humidity",
+            (has.humidity_min, has.humidity_max),
+            (needs.humidity_min, needs.humidity_max),
+        ),
+    ] {
+        if let (Some(has), Some(needs)) = (has_max, needs_max) {
+            if has > needs {
+                problems.push(format!(
+                    "reaches {}{}, needs at most {}{}",
+                    has, unit, needs, unit
+                ));
+            }
+        }
+        if let (Some(has), Some(needs)) = (has_min, needs_min) {
+            if has < needs {
+                problems.push(format!(
+                    "drops to {}{}, needs at least {}{}",
+                    has, unit, needs, unit
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Parse a range such as `5..40`, `..30`, `10..` or a single value `20`.
+/// A trailing `C`, `°C` or `%` is allowed on each bound.
+fn parse_range(spec: &str) -> Result<(Option<i64>, Option<i64>)> {
+    let invalid = || {
+        anyhow!(
+            "invalid range '{}' (expected e.g. 5..40, ..30 or 10..)",
+            spec
+        )
+    };
+    let bound = |s: &str| -> Result<Option<i64>> {
+        let s = s
+            .trim()
+            .trim_end_matches(['C', 'c', '%'])
+            .trim_end_matches('°');
+        if s.is_empty() {
+            return Ok(None);
+        }
+        s.parse().map(Some).map_err(|_| invalid())
+    };
+
+    let (min, max) = match spec.split_once("..") {
+        Some((min, max)) => (bound(min)?, bound(max)?),
+        None => {
+            let value = bound(spec)?.ok_or_else(invalid)?;
+            (Some(value), Some(value))
+        }
+    };
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max {
+            return Err(invalid());
+        }
+    }
+    if min.is_none() && max.is_none() {
+        return Err(invalid());
+    }
+    Ok((min, max))
+}
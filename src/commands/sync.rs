@@ -0,0 +1,292 @@
+//! Sync command implementation: one inventory on several machines, through
+//! a shared folder.
+//!
+//! Each machine appends the items it changed to its own file in the folder
+//! (`<replica>.jsonl`, one `SyncEntry` per line) and applies the lines other
+//! machines added since its last sync. The newest change to an item wins.
+//!
+//! See SPEC.md#invy-sync
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::Connection;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::db;
+use crate::interrupt;
+use crate::model::{SyncEntry, SyncReport};
+use crate::output::{self, Format};
+use crate::progress;
+use crate::timespec;
+
+/// Setting holding this machine's name in sync folders.
+const REPLICA_KEY: &str = "sync_replica";
+
+/// Send local changes to the sync folder and apply other machines' changes.
+///
+/// # Arguments
+/// * `dir` - Shared folder (e.g. in Dropbox or Syncthing)
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(dir: &Path, format: Format, db_path: Option<&Path>) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(anyhow!("sync folder '{}' not found", dir.display()));
+    }
+    let conn = db::open(db_path)?;
+
+    // Ctrl-C while receiving rolls everything back; the entries are applied
+    // again next time
+    let _guard = interrupt::protect();
+    let report = db::with_tx(&conn, |tx| {
+        let replica = match db::get_setting(tx, REPLICA_KEY)? {
+            Some(replica) => replica,
+            None => {
+                // First sync: everything is news to the other machines
+                let replica = new_replica_name(tx)?;
+                db::set_setting(tx, REPLICA_KEY, &replica)?;
+                db::log_all_items_for_sync(tx)?;
+                replica
+            }
+        };
+        let mut report = SyncReport {
+            replica,
+            ..SyncReport::default()
+        };
+        receive(tx, dir, &mut report, format)?;
+        send(tx, dir, &mut report)?;
+        Ok(report)
+    })?;
+
+    output::print_sync_report(&mut io::stdout(), &report, format)
+}
+
+/// A name for this machine: its host name and a random suffix, so two
+/// machines with the same host name never share a file.
+fn new_replica_name(conn: &Connection) -> Result<String> {
+    let host = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .or_else(|_| fs::read_to_string("/etc/hostname"))
+        .unwrap_or_default();
+    let host: String = host
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let host = host.trim_matches('-');
+    let suffix: String =
+        conn.query_row("SELECT lower(hex(randomblob(2)))", [], |row| row.get(0))?;
+    Ok(format!(
+        "{}-{}",
+        if host.is_empty() { "invy" } else { host },
+        suffix
+    ))
+}
+
+/// Apply the lines other machines added to their files since the last sync.
+fn receive(conn: &Connection, dir: &Path, report: &mut SyncReport, format: Format) -> Result<()> {
+    let mut peers: Vec<(String, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {:?}", dir))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "jsonl") {
+            if let Some(peer) = path.file_stem().and_then(|stem| stem.to_str()) {
+                if peer != report.replica {
+                    peers.push((peer.to_string(), path.clone()));
+                }
+            }
+        }
+    }
+    peers.sort();
+
+    let mut files = Vec::new();
+    for (peer, path) in peers {
+        let contents =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        let done = db::sync_peer_lines(conn, &peer)?;
+        files.push((peer, path, contents, done));
+    }
+    let pending = files
+        .iter()
+        .map(|(_, _, contents, done)| complete_lines(contents).count().saturating_sub(*done))
+        .sum::<usize>();
+    let bar = progress::bar(pending as u64, "Receiving", format);
+
+    // Applying changes fires the change log triggers; those echoes must not
+    // be sent back
+    let before = db::last_sync_change(conn)?;
+    let mut renamed = Vec::new();
+    for (peer, path, contents, done) in files {
+        let mut lines = 0;
+        for line in complete_lines(&contents) {
+            lines += 1;
+            if lines <= done {
+                continue;
+            }
+            interrupt::check()?;
+            bar.inc(1);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: SyncEntry = serde_json::from_str(line).with_context(|| {
+                format!("{}: line {} is not a sync entry", path.display(), lines)
+            })?;
+            report.received += 1;
+            renamed.extend(apply(conn, &entry, &peer, report)?);
+        }
+        db::set_sync_peer_lines(conn, &peer, lines)?;
+    }
+    bar.finish_and_clear();
+    db::clear_sync_changes(conn, before, None)?;
+    // Renamed copies differ from what their sender has, so they go back out
+    for uuid in renamed {
+        db::log_sync_change(conn, &uuid)?;
+    }
+    Ok(())
+}
+
+/// The lines of a sync file; a last line without a newline is still being
+/// written or copied.
+fn complete_lines(contents: &str) -> impl Iterator<Item = &str> {
+    contents.split_inclusive('\n').filter(|l| l.ends_with('\n'))
+}
+
+/// Apply one received change unless the local copy, or its deletion, is
+/// newer (ties go to the machine whose name sorts last). Returns the UUID of
+/// an item renamed to make room, which the other machines still know by its
+/// old name.
+fn apply(
+    conn: &Connection,
+    entry: &SyncEntry,
+    peer: &str,
+    report: &mut SyncReport,
+) -> Result<Option<String>> {
+    let local = db::get_item_by_uuid(conn, &entry.uuid)?;
+    let local_at = match &local {
+        Some(local) => Some(local.updated_at.clone()),
+        None => db::sync_tombstone(conn, &entry.uuid)?,
+    };
+    if let Some(local_at) = local_at {
+        let newer = entry.at > local_at || (entry.at == local_at && peer > report.replica.as_str());
+        if !newer {
+            return Ok(None);
+        }
+    }
+
+    let Some(item) = &entry.item else {
+        if let Some(local) = local {
+            db::delete_item(conn, local.id)?;
+            // Deleted when the sender deleted it, not now
+            db::set_sync_tombstone(conn, &entry.uuid, &entry.at)?;
+            report.applied += 1;
+        }
+        return Ok(None);
+    };
+
+    let mut container_id = None;
+    let mut notes = Vec::new();
+    if let Some(container) = &item.container {
+        match db::get_item_by_uuid(conn, container)? {
+            Some(parent) => container_id = Some(parent.id),
+            None => notes.push("its container was deleted here, so it is at root".to_string()),
+        }
+    }
+    if let (Some(local), Some(target)) = (&local, container_id) {
+        if local.id == target || db::is_ancestor(conn, local.id, target)? {
+            notes.push("the move would put it inside itself, so it stays".to_string());
+            container_id = local.container_id;
+        }
+    }
+
+    // Two different items with one name in one place: the one with the
+    // greater UUID gets a new name, so every machine renames the same one
+    let mut item = item.clone();
+    let mut at = entry.at.clone();
+    let mut renamed = None;
+    let clash = db::get_item_in_container(conn, &item.name, container_id)?
+        .filter(|other| local.as_ref().is_none_or(|local| local.id != other.id));
+    if let Some(other) = &clash {
+        let name = db::free_name(conn, &item.name, container_id)?;
+        if other.uuid.as_deref() < Some(entry.uuid.as_str()) {
+            item.name = name;
+            at = timespec::now();
+            renamed = Some(entry.uuid.clone());
+        } else {
+            db::update_item_name(conn, other.id, &name)?;
+            renamed = other.uuid.clone();
+        }
+    }
+
+    let id = db::write_sync_item(
+        conn,
+        local.map(|local| local.id),
+        &entry.uuid,
+        &item,
+        container_id,
+        &at,
+    )?;
+    report.applied += 1;
+    if let Some(other) = clash {
+        let loser = if renamed.as_deref() == Some(entry.uuid.as_str()) {
+            id
+        } else {
+            other.id
+        };
+        let path = db::join_path(&db::get_item_path(conn, loser)?);
+        report.conflicts.push(format!(
+            "{}: another '{}' is already there, so this one was renamed",
+            path, other.name
+        ));
+    }
+    if !notes.is_empty() {
+        let path = db::join_path(&db::get_item_path(conn, id)?);
+        for note in notes {
+            report.conflicts.push(format!("{}: {}", path, note));
+        }
+    }
+    Ok(renamed)
+}
+
+/// Append local changes to this machine's file, containers before their
+/// contents, and clear them from the log.
+fn send(conn: &Connection, dir: &Path, report: &mut SyncReport) -> Result<()> {
+    let through = db::last_sync_change(conn)?;
+    let mut upserts = Vec::new();
+    let mut deletes = Vec::new();
+    for (uuid, deleted, changed_at) in db::pending_sync_changes(conn)? {
+        match db::sync_item(conn, &uuid)? {
+            Some((item, depth, updated_at)) => upserts.push((
+                depth,
+                SyncEntry {
+                    uuid,
+                    at: updated_at,
+                    item: Some(item),
+                },
+            )),
+            None if deleted => deletes.push(SyncEntry {
+                uuid,
+                at: changed_at,
+                item: None,
+            }),
+            None => {}
+        }
+    }
+    upserts.sort_by_key(|(depth, _)| *depth);
+
+    let mut lines = String::new();
+    for entry in upserts.into_iter().map(|(_, entry)| entry).chain(deletes) {
+        lines.push_str(&serde_json::to_string(&entry)?);
+        lines.push('\n');
+        report.sent += 1;
+    }
+    if !lines.is_empty() {
+        let path = dir.join(format!("{}.jsonl", report.replica));
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .with_context(|| format!("Failed to write {:?}", path))?;
+    }
+    db::clear_sync_changes(conn, 0, Some(through))
+}
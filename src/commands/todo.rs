@@ -0,0 +1,90 @@
+//! Todo command implementation: maintenance tasks kept next to the items
+//! they are about.
+//!
+//! See SPEC.md#invy-todo
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+
+/// Add an open task about an item.
+///
+/// # Arguments
+/// * `item_ref` - Item the task is about
+/// * `text` - What needs doing (e.g. "replace brake pads")
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn add(item_ref: &str, text: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(anyhow!("todo text cannot be empty"));
+    }
+
+    let conn = db::open(db_path)?;
+
+    let todo = db::with_tx(&conn, |tx| {
+        let item = db::resolve_item(tx, item_ref)?
+            .ok_or_else(|| anyhow!("item '{}' not found", item_ref))?;
+        db::insert_todo(tx, item.id, text)
+    })?;
+
+    output::print_todo(&mut io::stdout(), &todo, format)
+}
+
+/// Mark a task done.
+///
+/// # Arguments
+/// * `id` - ID of the task, as shown by `invy todo list`
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn done(id: i64, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let todo = db::with_tx(&conn, |tx| {
+        let todo = db::get_todo(tx, id)?.ok_or_else(|| anyhow!("todo #{} not found", id))?;
+        if todo.done_at.is_some() {
+            return Err(anyhow!("todo #{} is already done", id));
+        }
+        db::complete_todo(tx, id)?;
+        db::get_todo(tx, id)?.ok_or_else(|| anyhow!("Failed to retrieve updated todo"))
+    })?;
+
+    output::print_todo(&mut io::stdout(), &todo, format)
+}
+
+/// List open tasks, optionally only those about items in a container.
+///
+/// # Arguments
+/// * `within` - Only tasks about this container or items inside it
+/// * `all` - Include tasks already done
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn list(within: Option<&str>, all: bool, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let within = match within {
+        Some(container_ref) => Some(
+            db::resolve_item(&conn, container_ref)?
+                .ok_or_else(|| anyhow!("container '{}' not found", container_ref))?,
+        ),
+        None => None,
+    };
+
+    let mut todos = Vec::new();
+    for todo in db::list_todos(&conn, all)? {
+        let inside = match &within {
+            Some(container) => {
+                todo.item_id == container.id || db::is_ancestor(&conn, container.id, todo.item_id)?
+            }
+            None => true,
+        };
+        if inside {
+            todos.push(todo);
+        }
+    }
+
+    output::print_todos(&mut io::stdout(), &todos, format)
+}
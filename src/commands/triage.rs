@@ -0,0 +1,48 @@
+//! Triage command implementation.
+//!
+//! See SPEC.md#invy-triage
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::db::{self, SortKey};
+use crate::output::{self, AgeColors, Format};
+
+/// List items waiting in the default container, oldest first.
+///
+/// # Arguments
+/// * `default_container` - The `default_container` config value
+/// * `colors` - Color human rows by age
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(
+    default_container: Option<&str>,
+    colors: Option<&AgeColors>,
+    format: Format,
+    db_path: Option<&Path>,
+) -> Result<()> {
+    let container_ref = default_container.ok_or_else(|| {
+        anyhow!("no default_container configured. Set default_container = \"inbox\" in config.toml")
+    })?;
+
+    let conn = db::open(db_path)?;
+
+    // An inbox that was never used simply has nothing to triage
+    let items = match db::resolve_item(&conn, container_ref)? {
+        Some(container) => {
+            db::list_items_sorted(&conn, Some(container.id), SortKey::Created, false)?
+        }
+        None => Vec::new(),
+    };
+
+    let list_items: Vec<_> = items
+        .into_iter()
+        .map(|item| {
+            let child_count = db::count_children(&conn, item.id).unwrap_or(0);
+            item.into_list_item(child_count)
+        })
+        .collect();
+
+    output::print_list_items(&mut io::stdout(), &list_items, None, colors, format)
+}
@@ -0,0 +1,126 @@
+//! Unpacked command implementation: tracking which boxes are unpacked after
+//! a move, and how much is left.
+//!
+//! See SPEC.md#invy-unpacked
+
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::model::{Item, ItemKind, PackedBox, UnpackingStatus};
+use crate::output::{self, Format};
+
+/// Mark boxes as unpacked, or as packed again with `undo`.
+///
+/// # Arguments
+/// * `box_refs` - Boxes (containers) to mark
+/// * `undo` - Mark them as still packed instead
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn mark(box_refs: &[String], undo: bool, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+
+    let (boxes, status) = db::with_tx(&conn, |tx| {
+        let mut boxes = Vec::new();
+        for box_ref in box_refs {
+            let item = db::resolve_item(tx, box_ref)?
+                .ok_or_else(|| anyhow!("box '{}' not found", box_ref))?;
+            if item.kind != ItemKind::Container {
+                return Err(anyhow!(
+                    "'{}' is a {}, not a box; only containers are packed (change it with `invy edit {} --kind container`)",
+                    item.name,
+                    item.kind.as_str(),
+                    box_ref
+                ));
+            }
+            if undo {
+                db::unmark_unpacked(tx, item.id)?;
+            } else {
+                db::mark_unpacked(tx, item.id)?;
+            }
+            let path = db::get_item_path(tx, item.id)?;
+            boxes.push(item.with_path(path, None));
+        }
+        Ok((boxes, progress(tx, None)?))
+    })?;
+
+    output::print_unpacked(&mut io::stdout(), &boxes, undo, &status, format)
+}
+
+/// Report how many boxes and items are still packed.
+///
+/// # Arguments
+/// * `within` - Only count what is inside this container or location
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn status(within: Option<&str>, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let within = match within {
+        Some("/") | None => None,
+        Some(within) => Some(
+            db::resolve_item(&conn, within)?
+                .ok_or_else(|| anyhow!("container '{}' not found", within))?,
+        ),
+    };
+    let status = progress(&conn, within.as_ref())?;
+    output::print_unpacking_status(&mut io::stdout(), &status, format)
+}
+
+/// Count boxes and items inside `within` (everything without it), and how
+/// many of them are still packed. An item is packed while any box around it
+/// is.
+fn progress(conn: &Connection, within: Option<&Item>) -> Result<UnpackingStatus> {
+    let unpacked = db::list_unpacked(conn)?;
+    let mut children: HashMap<Option<i64>, Vec<Item>> = HashMap::new();
+    for item in db::list_all_items(conn)? {
+        children.entry(item.container_id).or_default().push(item);
+    }
+
+    let mut status = UnpackingStatus {
+        boxes: 0,
+        unpacked_boxes: 0,
+        items: 0,
+        unpacked_items: 0,
+        packed: Vec::new(),
+    };
+    // Each entry carries the indexes (into `status.packed`) of the packed
+    // boxes around it
+    let mut stack = vec![(within.map(|c| c.id), Vec::new())];
+    while let Some((parent, packed_around)) = stack.pop() {
+        for child in children.remove(&parent).unwrap_or_default() {
+            let mut around: Vec<usize> = packed_around.clone();
+            match child.kind {
+                ItemKind::Container if unpacked.contains_key(&child.id) => {
+                    status.boxes += 1;
+                    status.unpacked_boxes += 1;
+                }
+                ItemKind::Container => {
+                    status.boxes += 1;
+                    around.push(status.packed.len());
+                    status.packed.push(PackedBox {
+                        id: child.id,
+                        name: child.name.clone(),
+                        path: db::get_item_path(conn, child.id)?,
+                        items: 0,
+                    });
+                }
+                ItemKind::Location => {}
+                ItemKind::Item => {
+                    status.items += 1;
+                    if around.is_empty() {
+                        status.unpacked_items += 1;
+                    }
+                    for &index in &around {
+                        status.packed[index].items += 1;
+                    }
+                }
+            }
+            stack.push((Some(child.id), around));
+        }
+    }
+    status.packed.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(status)
+}
@@ -0,0 +1,35 @@
+//! Views command implementations.
+//!
+//! See SPEC.md#invy-views
+
+use anyhow::Result;
+use std::io;
+use std::path::Path;
+
+use crate::db;
+use crate::output::{self, Format};
+
+/// Create or refresh the convenience SQL views.
+///
+/// # Arguments
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn install(format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let views = db::with_tx(&conn, |tx| {
+        db::install_views(tx)?;
+        db::view_status(tx)
+    })?;
+    output::print_views(&mut io::stdout(), &views, format)
+}
+
+/// Report whether each view is installed and current.
+///
+/// # Arguments
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn status(format: Format, db_path: Option<&Path>) -> Result<()> {
+    let conn = db::open(db_path)?;
+    let views = db::view_status(&conn)?;
+    output::print_views(&mut io::stdout(), &views, format)
+}
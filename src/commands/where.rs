@@ -0,0 +1,54 @@
+//! Where command implementation.
+//!
+//! See SPEC.md#invy-where-item
+
+use anyhow::{anyhow, Result};
+use std::io;
+use std::path::Path;
+
+use crate::backend;
+use crate::db;
+use crate::output::{self, Format};
+
+/// Print the full path of every item matching a name, or of the item at a
+/// path.
+///
+/// Unlike `invy show`, a name shared by several items is not an error: each
+/// match is printed.
+///
+/// # Arguments
+/// * `item_ref` - Item name or path
+/// * `format` - Output format
+/// * `db_path` - Optional custom database path
+pub fn run(item_ref: &str, format: Format, db_path: Option<&Path>) -> Result<()> {
+    let store = backend::open(db_path)?;
+
+    let items = if db::is_path(item_ref) {
+        store.item_at_path(item_ref)?.into_iter().collect()
+    } else {
+        store.items_named(&db::unescape_name(item_ref))?
+    };
+    if items.is_empty() {
+        return Err(anyhow!("item '{}' not found", item_ref));
+    }
+
+    // The match looked up most often, then most recently, is likely the one
+    // meant, so it comes first
+    let mut matches = Vec::new();
+    for item in items {
+        let lookups = store.lookups(item.id)?;
+        let path = store.item_path(item.id)?;
+        matches.push((lookups, item.with_path(path, None)));
+    }
+    matches.sort_by(|(a_lookups, a), (b_lookups, b)| {
+        b_lookups.cmp(a_lookups).then_with(|| a.path.cmp(&b.path))
+    });
+    let matches: Vec<_> = matches.into_iter().map(|(_, item)| item).collect();
+
+    // Lookups tell `invy optimize` what is used often
+    for item in &matches {
+        store.record_lookup(item.id)?;
+    }
+
+    output::print_paths(&mut io::stdout(), &matches, format)
+}
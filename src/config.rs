@@ -0,0 +1,229 @@
+//! Configuration file support for invy.
+//!
+//! Settings are read from `~/.config/invy/config.toml` (or the file given
+//! with `--config`). Command-line flags always take precedence.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::db::SortKey;
+use crate::output::{ColorChoice, Format};
+
+/// User configuration loaded from `config.toml`.
+///
+/// Every setting is optional; unknown keys are ignored so newer config files
+/// keep working with older binaries.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Database file to use when `--db` is not given
+    pub db: Option<PathBuf>,
+
+    /// Default output format when neither `--json` nor `--csv` is given
+    pub format: Option<Format>,
+
+    /// Order of `invy list` when `--sort` is not given
+    pub sort: Option<SortKey>,
+
+    /// Whether to color list rows by age when `--color` is not given
+    pub color: Option<ColorChoice>,
+
+    /// Public address of `invy serve`, used to build share links
+    pub base_url: Option<String>,
+
+    /// Container `invy add` uses when `--in` is not given (e.g. `inbox`)
+    pub default_container: Option<String>,
+
+    /// Language item names are shown in when `--lang` is not given (e.g. `es`)
+    pub lang: Option<String>,
+
+    /// Containers heavier than this are flagged by `invy list` and reported
+    /// by `invy heavy` (default `20kg`)
+    pub heavy_over: Option<String>,
+
+    /// Rows updated within this age are colored green (default `30d`)
+    pub color_fresh: Option<String>,
+
+    /// Rows not updated within this age are colored red (default `1y`)
+    pub color_stale: Option<String>,
+
+    /// Run every command in strict mode, as with `--strict`
+    pub strict: bool,
+
+    /// Create missing destination containers (default `true`); `false` acts
+    /// like `--no-create` on every command
+    pub auto_create: Option<bool>,
+
+    /// Named output templates for `--template` (see `crate::template`)
+    pub templates: HashMap<String, String>,
+
+    /// Schedules for `invy cron run`
+    pub cron: CronConfig,
+
+    /// Keyword rules that file new items when `invy add` has no `--in`
+    pub classify: ClassifyConfig,
+
+    /// Where notifications are pushed
+    #[cfg(feature = "notify")]
+    pub notify: NotifyConfig,
+
+    /// Telegram bot answering `invy bot` questions
+    #[cfg(feature = "bot")]
+    pub bot: BotConfig,
+}
+
+/// The `[cron]` table: how often each job runs (e.g. `1d`, `12h`, `2w`).
+///
+/// A job without a schedule never runs.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CronConfig {
+    /// Copy the database into `backup_dir`
+    pub backup: Option<String>,
+
+    /// Where backups go (default: `invy-backups` next to the database)
+    pub backup_dir: Option<PathBuf>,
+
+    /// Check every container with a recorded hash (see `invy hash`)
+    pub verify_hashes: Option<String>,
+
+    /// Delete expired share tokens
+    pub prune_shares: Option<String>,
+}
+
+/// The `[classify]` table: where `invy add` files items given no `--in`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ClassifyConfig {
+    /// Ask before filing instead of filing with a notice
+    pub ask: bool,
+
+    /// `[classify.rules]`: keyword in the name or description → container
+    /// (e.g. `battery = "electronics-bin"`)
+    pub rules: HashMap<String, String>,
+}
+
+/// The `[notify]` table: backends that get notifications (see `invy notify`).
+#[cfg(feature = "notify")]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    pub ntfy: Option<NtfyConfig>,
+    pub telegram: Option<TelegramConfig>,
+}
+
+/// `[notify.ntfy]`: an ntfy topic.
+#[cfg(feature = "notify")]
+#[derive(Debug, Deserialize)]
+pub struct NtfyConfig {
+    /// Topic URL, e.g. `https://ntfy.sh/invy-home`
+    pub url: String,
+
+    /// Access token for protected topics
+    pub token: Option<String>,
+}
+
+/// `[notify.telegram]`: a bot and the chat it writes to.
+#[cfg(feature = "notify")]
+#[derive(Debug, Deserialize)]
+pub struct TelegramConfig {
+    /// Bot token from @BotFather
+    pub token: String,
+
+    /// Chat (or user) ID the bot sends to, or `@channel`
+    pub chat_id: ChatId,
+
+    /// Bot API server (default: `https://api.telegram.org`)
+    pub api_url: Option<String>,
+}
+
+/// A Telegram chat: a numeric ID or a `@channel` name.
+#[cfg(feature = "notify")]
+#[derive(Debug, Deserialize, serde::Serialize)]
+#[serde(untagged)]
+pub enum ChatId {
+    Id(i64),
+    Name(String),
+}
+
+/// The `[bot]` table: the Telegram bot run by `invy bot`.
+#[cfg(feature = "bot")]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BotConfig {
+    /// Bot token from @BotFather
+    pub telegram_token: Option<String>,
+
+    /// Telegram user IDs allowed to use the bot
+    pub allow: Vec<i64>,
+
+    /// Bot API server (default: `https://api.telegram.org`)
+    pub api_url: Option<String>,
+}
+
+/// Get the default config file path.
+///
+/// `~/.config/invy/config.toml` on Unix and `%APPDATA%\invy\config.toml` on
+/// Windows. `XDG_CONFIG_HOME` overrides the base directory on all platforms.
+pub fn default_config_path() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Ok(PathBuf::from(dir).join("invy").join("config.toml"));
+    }
+    let dirs = directories::BaseDirs::new()
+        .ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    let base = if cfg!(windows) {
+        dirs.config_dir().to_path_buf()
+    } else {
+        dirs.home_dir().join(".config")
+    };
+    Ok(base.join("invy").join("config.toml"))
+}
+
+/// Load configuration.
+///
+/// An explicit `path` must exist; the default location is optional and a
+/// missing file yields the default configuration.
+pub fn load(path: Option<&Path>) -> Result<Config> {
+    let config_path = resolve_path(path)?;
+    if path.is_none() && !config_path.exists() {
+        return Ok(Config::default());
+    }
+
+    let contents = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read config file {:?}", config_path))?;
+    let mut config =
+        parse(&contents).with_context(|| format!("Invalid config file {:?}", config_path))?;
+
+    config.db = config.db.map(|p| expand_home(&p));
+    config.cron.backup_dir = config.cron.backup_dir.map(|p| expand_home(&p));
+    Ok(config)
+}
+
+/// The config file in use: `path` if given, otherwise the default location.
+pub fn resolve_path(path: Option<&Path>) -> Result<PathBuf> {
+    match path {
+        Some(p) => Ok(p.to_path_buf()),
+        None => default_config_path(),
+    }
+}
+
+/// Parse the contents of a config file, without expanding paths.
+pub fn parse(contents: &str) -> Result<Config> {
+    Ok(toml::from_str(contents)?)
+}
+
+/// Expand a leading `~` to the user's home directory.
+///
+/// `Path::strip_prefix` compares components, so `~\x` also works on Windows.
+fn expand_home(path: &Path) -> PathBuf {
+    let Ok(rest) = path.strip_prefix("~") else {
+        return path.to_path_buf();
+    };
+    match directories::BaseDirs::new() {
+        Some(home) => home.home_dir().join(rest),
+        None => path.to_path_buf(),
+    }
+}
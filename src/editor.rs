@@ -0,0 +1,50 @@
+//! Editing text in the user's editor, for `--editor` on `add` and `edit`.
+
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::process::Command;
+
+use crate::interrupt;
+
+/// Editor used when neither `VISUAL` nor `EDITOR` is set.
+const DEFAULT_EDITOR: &str = if cfg!(windows) { "notepad" } else { "vi" };
+
+/// Open `$VISUAL` (or `$EDITOR`) on a temporary file holding `initial` and
+/// return what was saved, with trailing whitespace removed.
+///
+/// The variable may include arguments (`code --wait`). An editor that exits
+/// with an error, or Ctrl-C while it is open, aborts the command.
+pub fn edit_text(initial: &str) -> Result<String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+
+    let mut bytes = [0u8; 6];
+    getrandom::fill(&mut bytes).map_err(|e| anyhow!("Failed to name temporary file: {}", e))?;
+    let suffix: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let path = std::env::temp_dir().join(format!("invy-description-{}.txt", suffix));
+    fs::write(&path, initial).with_context(|| format!("Failed to write {:?}", path))?;
+
+    // Ctrl-C reaches the editor too; wait for it so the file is removed
+    let guard = interrupt::protect();
+    let status = Command::new(program).args(words).arg(&path).status();
+    let text = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+    drop(guard);
+    interrupt::check()?;
+
+    let status = status
+        .with_context(|| format!("Failed to run editor '{}' (set VISUAL or EDITOR)", program))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "editor exited with {}; nothing was changed",
+            status
+        ));
+    }
+    let text = text.with_context(|| format!("Failed to read {:?}", path))?;
+    Ok(text.trim_end().to_string())
+}
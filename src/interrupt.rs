@@ -0,0 +1,72 @@
+//! Ctrl-C handling.
+//!
+//! Outside a protected section Ctrl-C ends invy at once, as it always did:
+//! nothing is being written, and an answer not yet given to a prompt is
+//! simply dropped. Inside one (a long write, or while `$EDITOR` is open)
+//! the interrupt is only noted; the section checks for it between steps and
+//! fails with `Interrupted`, so its transaction is rolled back and any
+//! temporary files are cleaned up before invy exits.
+//!
+//! See SPEC.md#error-messages
+
+use std::fmt;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Exit code after Ctrl-C, as shells report a process killed by SIGINT.
+pub const EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static PROTECTED: AtomicUsize = AtomicUsize::new(0);
+
+/// The error a protected section returns once Ctrl-C was pressed.
+#[derive(Debug)]
+pub struct Interrupted;
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "interrupted; nothing was changed")
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+/// Install the Ctrl-C handler. Call once at startup.
+pub fn install() {
+    let result = ctrlc::set_handler(|| {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        if PROTECTED.load(Ordering::SeqCst) == 0 {
+            eprintln!();
+            process::exit(EXIT_CODE);
+        }
+    });
+    // Without a handler Ctrl-C still ends invy, just less gracefully
+    if let Err(err) = result {
+        eprintln!("Warning: cannot handle Ctrl-C: {}", err);
+    }
+}
+
+/// Keeps Ctrl-C from ending invy until dropped; see [`protect`].
+#[must_use]
+pub struct Guard(());
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        PROTECTED.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Note Ctrl-C instead of exiting until the guard is dropped. The protected
+/// code calls [`check`] between steps.
+pub fn protect() -> Guard {
+    PROTECTED.fetch_add(1, Ordering::SeqCst);
+    Guard(())
+}
+
+/// Fail with [`Interrupted`] if Ctrl-C was pressed.
+pub fn check() -> anyhow::Result<()> {
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        return Err(Interrupted.into());
+    }
+    Ok(())
+}
@@ -2,56 +2,675 @@
 //!
 //! See SPEC.md for full behavioral specification.
 
+mod age;
+mod api;
 mod cli;
 mod commands;
-mod db;
-mod model;
+mod config;
+mod editor;
+mod interrupt;
+#[cfg(feature = "notify")]
+mod notify;
 mod output;
+mod progress;
+mod sandbox;
+mod suggest;
+mod table;
+mod template;
 
-use anyhow::Result;
-use clap::Parser;
-use cli::{Cli, Commands};
+// Storage, queries and the data model live in invy-core; commands reach
+// them under the same paths as the CLI's own modules
+use invy_core::{backend, classify, db, fuzzy, model, pathglob, quantity, strict, timespec};
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+use anyhow::{anyhow, Result};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use cli::{
+    AliasCommands, AuditCommands, Cli, Commands, ConfigCommands, CronCommands, DbCommands,
+    I18nCommands, ShareCommands, StorageCommands, TodoCommands, ViewsCommands,
+};
+use commands::add::AddOptions;
+use commands::edit::EditChanges;
+use commands::find::{FindOptions, FindQuery};
+use commands::list::ListOptions;
+use model::ConditionKind;
+use output::{AgeColors, Format};
+use pathglob::PathFilter;
+use template::Template;
 
-    let db_path = cli.db.as_deref();
+fn main() {
+    if let Err(err) = run() {
+        // A closed pipe (e.g. `invy list -r | head`) is not a failure
+        if output::is_broken_pipe(&err) {
+            std::process::exit(0);
+        }
+        // A zero count has already been printed; only the exit code reports it
+        if err.downcast_ref::<commands::count::NoneCounted>().is_some() {
+            std::process::exit(1);
+        }
+        eprintln!("Error: {:?}", err);
+        if err.downcast_ref::<strict::Violation>().is_some() {
+            std::process::exit(strict::EXIT_CODE);
+        }
+        if err.downcast_ref::<interrupt::Interrupted>().is_some() {
+            std::process::exit(interrupt::EXIT_CODE);
+        }
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let cli = Cli::try_parse().unwrap_or_else(|err| suggest::exit(err));
+    interrupt::install();
+    invy_core::notice::set_handler(|message| eprintln!("{}", message));
+    // `config` commands must work even when the current file is missing or
+    // broken, since they are how it gets replaced
+    let config = match cli.command {
+        Some(Commands::Config { .. }) => config::Config::default(),
+        _ => config::load(cli.config.as_deref())?,
+    };
+
+    // CLI flags (and their environment variables) win over config values
+    let db_path = cli.db.as_deref().or(config.db.as_deref());
+    // Dropped (and deleted) when the command returns
+    let sandbox = if let Some(export) = &cli.from_export {
+        if !cli.command.as_ref().is_some_and(Commands::reads_only) {
+            return Err(anyhow!(
+                "--from-export is read-only; use find, list, show, where, stats or another report"
+            ));
+        }
+        Some(sandbox::Sandbox::from_export(export)?)
+    } else if cli.sandbox {
+        Some(sandbox::Sandbox::create(db_path)?)
+    } else {
+        None
+    };
+    let db_path = sandbox.as_ref().map(|s| s.db_path()).or(db_path);
+    // After the sandbox, whose tutorial inventory creates its own containers
+    let strict = cli.strict || config.strict;
+    if strict {
+        strict::enable();
+    }
+    let placement = db::Placement {
+        auto_create: !cli.no_create && config.auto_create != Some(false),
+        strict,
+    };
+    let requested = if cli.markdown {
+        Some(Format::Markdown)
+    } else {
+        cli.format
+    };
+    let format = if cli.quiet {
+        Format::Quiet
+    } else {
+        Format::from_flags_or(cli.json, cli.csv, requested.or(config.format))
+    };
+    // Parsed only by the commands that use them, so a bad value in the
+    // config file cannot stop `invy doctor` or `invy config` from running
+    let lang = || -> Result<Option<String>> {
+        cli.lang
+            .as_deref()
+            .or(config.lang.as_deref())
+            .map(commands::i18n::parse_lang)
+            .transpose()
+    };
+    let heavy_over = || {
+        quantity::parse_weight(
+            config
+                .heavy_over
+                .as_deref()
+                .unwrap_or(commands::heavy::DEFAULT_OVER),
+        )
+    };
+    let colors = || {
+        AgeColors::resolve(
+            cli.color.or(config.color).unwrap_or_default(),
+            format,
+            config.color_fresh.as_deref(),
+            config.color_stale.as_deref(),
+        )
+    };
+
+    let command = match (cli.command, cli.api) {
+        (Some(command), false) => command,
+        (None, true) => return api::run(config.default_container.as_deref(), placement, db_path),
+        (Some(_), true) => suggest::exit(Cli::command().error(
+            ErrorKind::ArgumentConflict,
+            "--api reads its commands from stdin; it cannot be combined with a subcommand",
+        )),
+        (None, false) => suggest::exit(Cli::command().error(
+            ErrorKind::MissingSubcommand,
+            "a subcommand is required (or --api)",
+        )),
+    };
+
+    match command {
+        Commands::Init { encrypted } => commands::init::run(encrypted, format, db_path),
 
-    match cli.command {
         Commands::Add {
             name,
             desc,
+            editor,
             container,
-        } => commands::add::run(
-            &name,
-            desc.as_deref(),
-            container.as_deref(),
-            cli.json,
-            cli.csv,
+            qty,
+            kind,
+            expires,
+            count,
+            group,
+        } => {
+            let classified = match container {
+                Some(_) => None,
+                None => commands::add::classify(&name, desc.as_deref(), &config.classify)?,
+            };
+            commands::add::run(
+                &name,
+                &AddOptions {
+                    desc: desc.as_deref(),
+                    container: container
+                        .as_deref()
+                        .or(classified.as_deref())
+                        .or(config.default_container.as_deref()),
+                    quantity: &qty,
+                    kind,
+                    expires: expires.as_deref(),
+                    editor,
+                    count,
+                    group: group.as_deref(),
+                    placement,
+                },
+                format,
+                db_path,
+            )
+        }
+
+        Commands::Mkdir { path } => commands::mkdir::run(&path, format, db_path),
+
+        Commands::Find {
+            query,
+            missing_child,
+            incomplete,
+            within,
+            path,
+            not_path,
+            dates,
+            page,
+            save,
+            template,
+            count,
+        } => commands::find::run(
+            match (&query, &missing_child) {
+                (_, Some(name)) => FindQuery::MissingChild(name),
+                _ if incomplete => FindQuery::Incomplete,
+                (query, _) => FindQuery::Text(query.as_deref().unwrap_or_default()),
+            },
+            &FindOptions {
+                within: within.as_deref(),
+                paths: &PathFilter::new(&path, &not_path)?,
+                dates: &dates.to_filter()?,
+                offset: page.offset,
+                limit: page.limit,
+                lang: lang()?.as_deref(),
+                template: Template::from_arg(template.as_deref(), &config.templates, format)?
+                    .as_ref(),
+                save: save.to_save(),
+                placement,
+                count,
+            },
+            format,
             db_path,
         ),
 
-        Commands::Find { query } => commands::find::run(&query, cli.json, cli.csv, db_path),
+        Commands::Count {
+            container,
+            recursive,
+        } => commands::count::run(container.as_deref(), recursive, format, db_path),
 
         Commands::List {
             container,
             recursive,
-        } => commands::list::run(container.as_deref(), recursive, cli.json, cli.csv, db_path),
+            sort,
+            reverse,
+            long,
+            containers_only,
+            empty,
+            group,
+            dates,
+            page,
+            save,
+            template,
+        } => commands::list::run(
+            container.as_deref(),
+            &ListOptions {
+                recursive,
+                dates: &dates.to_filter()?,
+                sort: sort.or(config.sort).unwrap_or_default(),
+                reverse,
+                offset: page.offset,
+                limit: page.limit,
+                long,
+                colors: colors()?.as_ref(),
+                lang: lang()?.as_deref(),
+                heavy_over: heavy_over()?,
+                containers_only,
+                empty,
+                group: group.as_deref(),
+                template: Template::from_arg(template.as_deref(), &config.templates, format)?
+                    .as_ref(),
+                save: save.to_save(),
+                placement,
+            },
+            format,
+            db_path,
+        ),
+
+        Commands::Show {
+            item,
+            by_desc,
+            locations_history,
+            template,
+        } => commands::show::run(
+            &item.to_ref(),
+            by_desc.as_deref(),
+            locations_history,
+            lang()?.as_deref(),
+            Template::from_arg(template.as_deref(), &config.templates, format)?.as_ref(),
+            format,
+            db_path,
+        ),
 
-        Commands::Show { item } => commands::show::run(&item, cli.json, cli.csv, db_path),
+        Commands::Where { item } => commands::r#where::run(&item, format, db_path),
 
-        Commands::Mv { item, destination } => {
-            commands::mv::run(&item, &destination, cli.json, cli.csv, db_path)
+        Commands::Mv {
+            item,
+            destination,
+            id,
+            contents,
+            expected_updated_at,
+        } => {
+            // With --id the only positional argument is the destination
+            let (item, destination) = match (id, destination) {
+                (Some(id), None) => (format!("#{}", id), item),
+                (None, Some(destination)) => (item, destination),
+                (Some(_), Some(_)) => suggest::exit(Cli::command().error(
+                    ErrorKind::ArgumentConflict,
+                    "--id replaces the item argument; give only the destination",
+                )),
+                (None, None) => suggest::exit(Cli::command().error(
+                    ErrorKind::MissingRequiredArgument,
+                    "a destination is required: invy mv <ITEM> <DESTINATION>",
+                )),
+            };
+            commands::mv::run(
+                &item,
+                &destination,
+                contents,
+                expected_updated_at.as_deref(),
+                placement,
+                format,
+                db_path,
+            )
         }
 
-        Commands::Rm { item } => commands::rm::run(&item, cli.json, cli.csv, db_path),
+        Commands::Rename {
+            matching,
+            template,
+            container,
+            start,
+            dry_run,
+        } => commands::rename::run(
+            &matching,
+            &template,
+            container.as_deref(),
+            start,
+            dry_run,
+            format,
+            db_path,
+        ),
+
+        Commands::Triage => commands::triage::run(
+            config.default_container.as_deref(),
+            colors()?.as_ref(),
+            format,
+            db_path,
+        ),
+
+        Commands::Dupes {
+            max_distance,
+            exact,
+        } => commands::dupes::run(max_distance, exact, format, db_path),
+
+        Commands::Heavy { over } => {
+            let over = match over {
+                Some(over) => quantity::parse_weight(&over)?,
+                None => heavy_over()?,
+            };
+            commands::heavy::run(over, format, db_path)
+        }
+
+        Commands::Optimize {
+            since,
+            min_uses,
+            max_depth,
+            script,
+        } => commands::optimize::run(
+            &commands::optimize::OptimizeOptions {
+                since: &since,
+                min_uses,
+                max_depth,
+                rules: &config.classify.rules,
+                script,
+            },
+            format,
+            db_path,
+        ),
+
+        Commands::Expiring { within } => commands::expiring::run(&within, format, db_path),
+
+        Commands::Shopping { within } => {
+            commands::shopping::run(within.as_deref(), format, db_path)
+        }
+
+        Commands::Near { item, radius } => {
+            commands::near::run(&item, radius as usize, format, db_path)
+        }
+
+        Commands::Cd { container } => commands::cd::run(container.as_deref(), format, db_path),
+
+        Commands::Pwd => commands::cd::pwd(format, db_path),
+
+        Commands::Cp {
+            item,
+            destination,
+            recursive,
+        } => commands::cp::run(&item, &destination, recursive, placement, format, db_path),
+
+        Commands::I18n { command } => match command {
+            I18nCommands::Set { item, lang, name } => {
+                commands::i18n::set(&item, &lang, &name, format, db_path)
+            }
+            I18nCommands::Unset { item, lang } => {
+                commands::i18n::unset(&item, &lang, format, db_path)
+            }
+            I18nCommands::List { item } => commands::i18n::list(&item, format, db_path),
+        },
 
-        Commands::Edit { item, name, desc } => commands::edit::run(
-            &item,
-            name.as_deref(),
-            desc.as_deref(),
-            cli.json,
-            cli.csv,
+        Commands::Alias { command } => match command {
+            AliasCommands::Add { item, alias } => {
+                commands::alias::add(&item, &alias, format, db_path)
+            }
+            AliasCommands::Rm { item, alias } => {
+                commands::alias::remove(&item, &alias, format, db_path)
+            }
+            AliasCommands::List { item } => commands::alias::list(&item, format, db_path),
+        },
+
+        Commands::Audit { command } => match command {
+            AuditCommands::Start { container } => {
+                commands::audit::start(container.as_deref(), format, db_path)
+            }
+            AuditCommands::Check { items } => commands::audit::check(&items, format, db_path),
+            AuditCommands::Report => commands::audit::report(format, db_path),
+        },
+
+        Commands::Unpacked { boxes, undo } => {
+            commands::unpacked::mark(&boxes, undo, format, db_path)
+        }
+
+        Commands::UnpackingStatus { within } => {
+            commands::unpacked::status(within.as_deref(), format, db_path)
+        }
+
+        Commands::Storage { command } => match command {
+            StorageCommands::Set {
+                container,
+                conditions,
+            } => commands::storage::set(
+                &container,
+                ConditionKind::Provides,
+                &conditions.to_args(),
+                format,
+                db_path,
+            ),
+            StorageCommands::Require { item, conditions } => commands::storage::set(
+                &item,
+                ConditionKind::Requires,
+                &conditions.to_args(),
+                format,
+                db_path,
+            ),
+            StorageCommands::Clear { item } => commands::storage::clear(&item, format, db_path),
+            StorageCommands::List => commands::storage::list(format, db_path),
+        },
+
+        Commands::StorageCheck => commands::storage::check(format, db_path),
+
+        Commands::Todo { command } => match command {
+            TodoCommands::Add { item, text } => commands::todo::add(&item, &text, format, db_path),
+            TodoCommands::Done { id } => commands::todo::done(id, format, db_path),
+            TodoCommands::List { within, all } => {
+                commands::todo::list(within.as_deref(), all, format, db_path)
+            }
+        },
+
+        Commands::Hash { container, verify } => {
+            commands::hash::run(container.as_deref(), verify, format, db_path)
+        }
+
+        Commands::Check {
+            container,
+            require,
+            unrequire,
+        } => commands::check::run(&container, &require, &unrequire, format, db_path),
+
+        Commands::Rm { item } => commands::rm::run(&item.to_ref(), format, db_path),
+
+        Commands::Consume { item, qty } => commands::consume::run(&item, &qty, format, db_path),
+
+        Commands::Consumed { since } => {
+            commands::consume::report(since.as_deref(), format, db_path)
+        }
+
+        Commands::Stats {
+            series,
+            churn,
+            health,
+            stale_after,
+            period,
+            since,
+        } => commands::stats::run(
+            series,
+            churn,
+            health.then_some(stale_after.as_str()),
+            period,
+            since.as_deref(),
+            format,
+            db_path,
+        ),
+
+        Commands::Import {
+            file,
+            from,
+            report_only,
+            decrypt,
+            identity,
+        } => commands::import::run(
+            &file,
+            from,
+            report_only,
+            decrypt,
+            identity.as_deref(),
+            format,
+            db_path,
+        ),
+
+        Commands::Export {
+            container,
+            target,
+            output,
+            include,
+            exclude,
+            encrypt: _,
+            recipient,
+        } => {
+            let filter = PathFilter::new(&include, &exclude)?;
+            commands::export::run(
+                container.as_deref(),
+                target,
+                output.as_deref(),
+                &filter,
+                &recipient,
+                format,
+                db_path,
+            )
+        }
+
+        Commands::Share { command } => match command {
+            ShareCommands::Create {
+                container,
+                expires,
+                base_url,
+            } => commands::share::create(
+                &container,
+                &expires,
+                base_url.as_deref().or(config.base_url.as_deref()),
+                format,
+                db_path,
+            ),
+            ShareCommands::List { base_url } => commands::share::list(
+                base_url.as_deref().or(config.base_url.as_deref()),
+                format,
+                db_path,
+            ),
+            ShareCommands::Revoke { token } => commands::share::revoke(&token, format, db_path),
+        },
+
+        #[cfg(feature = "self-update")]
+        Commands::SelfUpdate { check } => commands::self_update::run(check),
+
+        #[cfg(feature = "bot")]
+        Commands::Bot {
+            telegram_token,
+            mut allow,
+            once,
+        } => {
+            let token = telegram_token
+                .or(config.bot.telegram_token)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "no Telegram bot token; pass --telegram-token or set [bot] telegram_token"
+                    )
+                })?;
+            allow.extend(config.bot.allow);
+            commands::bot::run(
+                &commands::bot::BotOptions {
+                    token: &token,
+                    allow: &allow,
+                    api_url: config.bot.api_url.as_deref(),
+                    once,
+                    placement,
+                },
+                db_path,
+            )
+        }
+
+        Commands::Mcp => {
+            commands::mcp::run(config.default_container.as_deref(), placement, db_path)
+        }
+
+        #[cfg(feature = "serve")]
+        Commands::Serve { port, bind, token } => commands::serve::run(
+            &commands::serve::ServeOptions {
+                bind: &bind,
+                port,
+                token: token.as_deref(),
+                default_container: config.default_container.as_deref(),
+                placement,
+            },
+            db_path,
+        ),
+
+        Commands::Sync { dir } => commands::sync::run(&dir, format, db_path),
+
+        #[cfg(feature = "notify")]
+        Commands::Notify { command } => match command {
+            cli::NotifyCommands::Test { message } => {
+                commands::notify::test(&config.notify, message.as_deref())
+            }
+        },
+
+        #[cfg(feature = "ocr")]
+        Commands::Photo { command } => match command {
+            cli::PhotoCommands::Ocr {
+                files,
+                suggest_items,
+                container,
+            } => commands::photo::ocr(
+                &files,
+                suggest_items,
+                container.as_deref().or(config.default_container.as_deref()),
+                format,
+                db_path,
+            ),
+        },
+
+        Commands::Views { command } => match command {
+            ViewsCommands::Install => commands::views::install(format, db_path),
+            ViewsCommands::Status => commands::views::status(format, db_path),
+        },
+
+        Commands::Cron { command } => match command {
+            CronCommands::Run { force } => commands::cron::run(&config, force, format, db_path),
+        },
+
+        Commands::Config { command } => match command {
+            ConfigCommands::Export { output } => {
+                commands::config::export(cli.config.as_deref(), output.as_deref(), format)
+            }
+            ConfigCommands::Import { file, force } => {
+                commands::config::import(&file, cli.config.as_deref(), force, format)
+            }
+        },
+
+        Commands::Db { command } => match command {
+            DbCommands::Path => commands::db::path(format, db_path),
+            DbCommands::Info => commands::db::info(format, db_path),
+            DbCommands::Migrate { to } => commands::db::migrate(to, format, db_path),
+        },
+
+        Commands::Doctor { fix } => commands::doctor::run(fix, format, db_path),
+
+        Commands::Edit {
+            items,
+            id,
+            name,
+            desc,
+            editor,
+            append_desc,
+            weight,
+            min_qty,
+            expires,
+            container,
+            kind,
+            expected_updated_at,
+        } => commands::edit::run(
+            &items
+                .into_iter()
+                .chain(id.iter().map(|id| format!("#{}", id)))
+                .collect::<Vec<_>>(),
+            &EditChanges {
+                name: name.as_deref(),
+                desc: desc.as_deref(),
+                weight: weight.as_deref(),
+                min_qty: min_qty.as_deref(),
+                expires: expires.as_deref(),
+                container: container.as_deref(),
+                kind,
+                editor,
+                append_desc: append_desc.as_deref(),
+            },
+            expected_updated_at.as_deref(),
+            placement,
+            format,
             db_path,
         ),
     }
@@ -0,0 +1,105 @@
+//! Push notifications to a phone through ntfy or a Telegram bot.
+//!
+//! Backends are configured under `[notify]` in the config file; every one
+//! configured gets each message.
+//!
+//! See SPEC.md#notifications
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::config::{NotifyConfig, NtfyConfig, TelegramConfig};
+
+/// Default Telegram Bot API server.
+const TELEGRAM_API: &str = "https://api.telegram.org";
+
+/// Somewhere messages can be pushed to.
+pub trait Notifier {
+    /// Name used in output and errors (e.g. `ntfy`).
+    fn name(&self) -> &'static str;
+
+    /// Deliver one message.
+    fn send(&self, title: &str, message: &str) -> Result<()>;
+}
+
+/// Posts to an ntfy topic URL (e.g. `https://ntfy.sh/invy-home`).
+pub struct Ntfy<'a> {
+    config: &'a NtfyConfig,
+}
+
+impl Notifier for Ntfy<'_> {
+    fn name(&self) -> &'static str {
+        "ntfy"
+    }
+
+    fn send(&self, title: &str, message: &str) -> Result<()> {
+        let mut request = ureq::post(&self.config.url)
+            .header("User-Agent", concat!("invy/", env!("CARGO_PKG_VERSION")))
+            .header("Title", title);
+        if let Some(token) = &self.config.token {
+            request = request.header("Authorization", &format!("Bearer {}", token));
+        }
+        request
+            .send(message)
+            .with_context(|| format!("Failed to post to {}", self.config.url))?;
+        Ok(())
+    }
+}
+
+/// Sends messages from a Telegram bot to one chat.
+pub struct Telegram<'a> {
+    config: &'a TelegramConfig,
+}
+
+impl Notifier for Telegram<'_> {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn send(&self, title: &str, message: &str) -> Result<()> {
+        let api = self.config.api_url.as_deref().unwrap_or(TELEGRAM_API);
+        let url = format!(
+            "{}/bot{}/sendMessage",
+            api.trim_end_matches('/'),
+            self.config.token
+        );
+        ureq::post(&url)
+            .header("User-Agent", concat!("invy/", env!("CARGO_PKG_VERSION")))
+            .send_json(serde_json::json!({
+                "chat_id": self.config.chat_id,
+                "text": format!("{}\n{}", title, message),
+            }))
+            // The URL holds the bot token, so it stays out of the error
+            .with_context(|| format!("Failed to send Telegram message via {}", api))?;
+        Ok(())
+    }
+}
+
+/// Every backend configured in `[notify]`, in a fixed order.
+pub fn notifiers(config: &NotifyConfig) -> Vec<Box<dyn Notifier + '_>> {
+    let mut notifiers: Vec<Box<dyn Notifier + '_>> = Vec::new();
+    if let Some(ntfy) = &config.ntfy {
+        notifiers.push(Box::new(Ntfy { config: ntfy }));
+    }
+    if let Some(telegram) = &config.telegram {
+        notifiers.push(Box::new(Telegram { config: telegram }));
+    }
+    notifiers
+}
+
+/// Send a message to every configured backend, returning the names of
+/// those that got it. Fails if any backend fails, after trying them all.
+pub fn send_all(config: &NotifyConfig, title: &str, message: &str) -> Result<Vec<&'static str>> {
+    let mut sent = Vec::new();
+    let mut errors = Vec::new();
+    for notifier in notifiers(config) {
+        match notifier.send(title, message) {
+            Ok(()) => sent.push(notifier.name()),
+            Err(err) => errors.push(format!("{}: {:#}", notifier.name(), err)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(sent)
+    } else {
+        Err(anyhow!("{}", errors.join("; ")))
+    }
+}
@@ -1,19 +1,41 @@
 //! Output formatting for invy.
 //!
 //! Supports human-readable, JSON, and CSV output formats.
+//!
+//! Every printer writes to a caller-supplied `Write` so output can be
+//! captured; the CLI passes `io::stdout()`.
 
-use anyhow::Result;
-use serde::Serialize;
-use std::io;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
 
-use crate::model::{ItemWithPath, ListItem, TreeItem};
+use crate::db;
+use crate::model::{
+    Alias, AuditReport, ChecklistReport, ChurnPoint, ConditionKind, Consumption, ContainerHash,
+    CronRun, CronStatus, DbInfo, DoctorIssue, DupeGroup, DupeKind, HashStatus, HealthReport,
+    ImportReport, ItemKind, ItemWithPath, ListItem, OptimizeSuggestion, RenamedItem, SeriesPoint,
+    Severity, Share, ShoppingItem, Stats, StorageConditions, StorageIssue, SyncReport, Todo,
+    Translation, TreeItem, UnpackingStatus, ViewInfo, ViewStatus,
+};
+use crate::quantity::{self, Quantity};
+use crate::table::{self, Table};
+use crate::template::Template;
+use crate::timespec;
 
 /// Output format selection.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
 pub enum Format {
     Human,
     Json,
     Csv,
+    /// GitHub-flavored Markdown tables (trees as nested bullets); commands
+    /// without a table print their human output
+    Markdown,
+    /// Only the IDs of affected items, one per line (`--quiet`)
+    #[value(skip)]
+    #[serde(skip)]
+    Quiet,
 }
 
 impl Format {
@@ -27,296 +49,3012 @@ impl Format {
             Format::Human
         }
     }
+
+    /// Create format from CLI flags, falling back to a configured default.
+    pub fn from_flags_or(json: bool, csv: bool, default: Option<Format>) -> Self {
+        match default {
+            Some(format) if !json && !csv => format,
+            _ => Self::from_flags(json, csv),
+        }
+    }
+}
+
+/// When to color human output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Only when stdout is a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Default age below which rows are fresh.
+pub const DEFAULT_COLOR_FRESH: &str = "30d";
+
+/// Default age beyond which rows are stale.
+pub const DEFAULT_COLOR_STALE: &str = "1y";
+
+/// Row colors by `updated_at` age, for reviewing outdated records: green
+/// when fresh, red when stale, grey in between.
+#[derive(Debug, Clone)]
+pub struct AgeColors {
+    fresh_since: String,
+    stale_before: String,
+}
+
+impl AgeColors {
+    /// Build from age thresholds such as `30d` and `1y`.
+    pub fn new(fresh: &str, stale: &str) -> Result<Self> {
+        Ok(AgeColors {
+            fresh_since: crate::timespec::parse_since(fresh)?,
+            stale_before: crate::timespec::parse_since(stale)?,
+        })
+    }
+
+    /// Colors to use for this invocation, if any. Only human output is
+    /// colored.
+    pub fn resolve(
+        choice: ColorChoice,
+        format: Format,
+        fresh: Option<&str>,
+        stale: Option<&str>,
+    ) -> Result<Option<Self>> {
+        let enabled = match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && io::IsTerminal::is_terminal(&io::stdout())
+            }
+        };
+        if !enabled || !matches!(format, Format::Human) {
+            return Ok(None);
+        }
+        Self::new(
+            fresh.unwrap_or(DEFAULT_COLOR_FRESH),
+            stale.unwrap_or(DEFAULT_COLOR_STALE),
+        )
+        .context("Invalid color_fresh or color_stale setting")
+        .map(Some)
+    }
+
+    fn paint(&self, updated_at: &str, line: &str) -> String {
+        let code = if updated_at >= self.fresh_since.as_str() {
+            "32"
+        } else if updated_at < self.stale_before.as_str() {
+            "31"
+        } else {
+            "90"
+        };
+        format!("\x1b[{}m{}\x1b[0m", code, line)
+    }
+}
+
+/// Color a row when age colors are enabled.
+fn paint(colors: Option<&AgeColors>, updated_at: &str, line: String) -> String {
+    match colors {
+        Some(colors) => colors.paint(updated_at, &line),
+        None => line,
+    }
+}
+
+/// Window of a longer result shown with `--limit`/`--offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub offset: usize,
+    pub limit: Option<usize>,
+    /// Number of results before paging
+    pub total: usize,
+}
+
+/// Cut `items` down to the requested window. Returns no page when neither
+/// bound is set, so unpaged output keeps its plain shape.
+pub fn paginate<T>(items: Vec<T>, offset: usize, limit: Option<usize>) -> (Vec<T>, Option<Page>) {
+    if offset == 0 && limit.is_none() {
+        return (items, None);
+    }
+    let total = items.len();
+    let items = items
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    (
+        items,
+        Some(Page {
+            offset,
+            limit,
+            total,
+        }),
+    )
+}
+
+/// Check whether an error was caused by writing to a closed pipe.
+pub fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        let io_err = cause.downcast_ref::<io::Error>().or_else(|| {
+            match cause.downcast_ref::<csv::Error>().map(|e| e.kind()) {
+                Some(csv::ErrorKind::Io(e)) => Some(e),
+                _ => None,
+            }
+        });
+        io_err.is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe)
+    })
+}
+
+/// Print IDs one per line (for `--quiet`).
+fn print_ids(w: &mut impl Write, ids: impl IntoIterator<Item = i64>) -> Result<()> {
+    for id in ids {
+        writeln!(w, "{}", id)?;
+    }
+    Ok(())
+}
+
+/// Print the IDs of a tree, parents before their children.
+fn print_tree_ids(w: &mut impl Write, items: &[TreeItem]) -> Result<()> {
+    for item in items {
+        writeln!(w, "{}", item.id)?;
+        print_tree_ids(w, &item.children)?;
+    }
+    Ok(())
+}
+
+/// Output a single item (for add, show commands).
+pub fn print_item(w: &mut impl Write, item: &ItemWithPath, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => print_item_human(w, item),
+        Format::Json => print_json(w, item),
+        Format::Csv => print_item_csv(w, item),
+        Format::Quiet => print_ids(w, [item.id]),
+    }
+}
+
+/// Output a list of items (for find command).
+///
+/// With a page, human output ends in a "Showing" footer and JSON wraps the
+/// items in an object carrying the total.
+pub fn print_items(
+    w: &mut impl Write,
+    items: &[ItemWithPath],
+    page: Option<Page>,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human => {
+            print_items_human(w, items)?;
+            print_page_footer(w, items.len(), page)
+        }
+        Format::Json => print_json_page(w, items, page),
+        Format::Csv => print_items_csv(w, items),
+        Format::Markdown => {
+            let rows: Vec<_> = items
+                .iter()
+                .map(|i| {
+                    vec![
+                        db::join_path(&i.path),
+                        i.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            print_markdown_table(w, &["Path", "Description"], &rows)?;
+            print_markdown_footer(w, items.len(), page)
+        }
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// The warning for a new name containing `/`, which references must
+/// escape; `None` for other names.
+pub fn slash_warning(name: &str) -> Option<String> {
+    name.contains('/').then(|| {
+        format!(
+            "'{}' contains '/'; refer to it as '{}'",
+            name,
+            db::escape_name(name)
+        )
+    })
+}
+
+/// Warn that a new name contains `/`, which references must escape.
+/// Nothing is printed for other names.
+pub fn print_slash_warning(w: &mut impl Write, name: &str) -> Result<()> {
+    if let Some(warning) = slash_warning(name) {
+        writeln!(w, "Warning: {}", warning)?;
+    }
+    Ok(())
+}
+
+/// Warn that an item was put inside a plain item.
+pub fn print_plain_container_warning(w: &mut impl Write, container: &str) -> Result<()> {
+    writeln!(w, "Warning: {}", db::plain_container_warning(container))?;
+    Ok(())
+}
+
+/// Print containers over the lifting threshold (for heavy command).
+pub fn print_heavy(
+    w: &mut impl Write,
+    items: &[ItemWithPath],
+    over: i64,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if items.is_empty() {
+                writeln!(w, "No containers over {}", quantity::format_weight(over))?;
+                return Ok(());
+            }
+            let mut table = Table::new(&["WEIGHT", "ITEMS", "PATH"])
+                .align_right(0)
+                .align_right(1)
+                .shrink(2);
+            for item in items {
+                table.push(vec![
+                    quantity::format_weight(item.weight_g.unwrap_or(0)),
+                    child_count_cell(item.child_count.unwrap_or(0)),
+                    db::join_path(&item.path),
+                ]);
+            }
+            for line in table.render(table::terminal_width()) {
+                writeln!(w, "{}", line)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "path", "weight_g"])?;
+            for item in items {
+                wtr.write_record([
+                    item.id.to_string(),
+                    db::join_path(&item.path),
+                    item.weight_g.unwrap_or(0).to_string(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print items near another one with their distance (for near command).
+pub fn print_near(
+    w: &mut impl Write,
+    items: &[ItemWithPath],
+    origin: &str,
+    radius: usize,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if items.is_empty() {
+                writeln!(w, "Nothing within {} of '{}'", radius, origin)?;
+                return Ok(());
+            }
+            let mut table = Table::new(&["DIST", "PATH"]).align_right(0).shrink(1);
+            for item in items {
+                table.push(vec![
+                    item.distance.unwrap_or(0).to_string(),
+                    db::join_path(&item.path),
+                ]);
+            }
+            for line in table.render(table::terminal_width()) {
+                writeln!(w, "{}", line)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "path", "distance"])?;
+            for item in items {
+                wtr.write_record([
+                    item.id.to_string(),
+                    db::join_path(&item.path),
+                    item.distance.unwrap_or(0).to_string(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print only the paths of items, one per line (for where command).
+pub fn print_paths(w: &mut impl Write, items: &[ItemWithPath], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            for item in items {
+                writeln!(w, "{}", db::join_path(&item.path))?;
+            }
+            Ok(())
+        }
+        Format::Json => {
+            let paths: Vec<String> = items.iter().map(|i| db::join_path(&i.path)).collect();
+            print_json(w, &paths)
+        }
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "path"])?;
+            for item in items {
+                wtr.write_record([item.id.to_string(), db::join_path(&item.path)])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Output list items with child counts (for list command).
+///
+/// Human rows are colored by age when `colors` is set.
+pub fn print_list_items(
+    w: &mut impl Write,
+    items: &[ListItem],
+    page: Option<Page>,
+    colors: Option<&AgeColors>,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human => {
+            print_list_items_human(w, items, colors)?;
+            print_page_footer(w, items.len(), page)
+        }
+        Format::Json => print_json_page(w, items, page),
+        Format::Csv => print_list_items_csv(w, items),
+        Format::Markdown => {
+            print_list_items_markdown(w, items)?;
+            print_markdown_footer(w, items.len(), page)
+        }
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print added item message.
+pub fn print_added(w: &mut impl Write, item: &ItemWithPath, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Added: {}", item.name)?;
+            if item.path.len() > 1 {
+                let container_path = &item.path[..item.path.len() - 1];
+                writeln!(w, "  -> {}", container_path.join(" -> "))?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, item),
+        Format::Csv => {
+            writeln!(w, "id,name,description,container")?;
+            writeln!(
+                w,
+                "{},{},{},{}",
+                item.id,
+                item.name,
+                item.description.as_deref().unwrap_or(""),
+                if item.path.len() > 1 {
+                    item.path[item.path.len() - 2].clone()
+                } else {
+                    String::new()
+                }
+            )?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, [item.id]),
+    }
+}
+
+/// Print initialized database message.
+pub fn print_initialized(
+    w: &mut impl Write,
+    path: &std::path::Path,
+    encrypted: bool,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            let kind = if encrypted { "encrypted " } else { "" };
+            writeln!(w, "Initialized {}database: {}", kind, path.display())?;
+            Ok(())
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct InitOutput<'a> {
+                path: &'a std::path::Path,
+                encrypted: bool,
+            }
+            print_json(w, &InitOutput { path, encrypted })
+        }
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["path", "encrypted"])?;
+            wtr.write_record([&path.display().to_string(), &encrypted.to_string()])?;
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print the current container context (for cd command).
+pub fn print_context(
+    w: &mut impl Write,
+    context: Option<&ItemWithPath>,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            match context {
+                Some(item) => writeln!(w, "Context: {}", db::join_path(&item.path))?,
+                None => writeln!(w, "Context: (root)")?,
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, &context),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "path"])?;
+            if let Some(item) = context {
+                wtr.write_record([&item.id.to_string(), &db::join_path(&item.path)])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, context.map(|item| item.id)),
+    }
+}
+
+/// Print the numbered containers added together by `invy add --count`.
+pub fn print_added_group(
+    w: &mut impl Write,
+    items: &[ItemWithPath],
+    group: &str,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Added {} to group '{}':", items.len(), group)?;
+            for item in items {
+                writeln!(w, "  - {}", item.name)?;
+            }
+            if let Some(item) = items.first().filter(|item| item.path.len() > 1) {
+                let container_path = &item.path[..item.path.len() - 1];
+                writeln!(w, "  -> {}", container_path.join(" -> "))?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => print_items_csv(w, items),
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print the containers `invy mkdir` created, outermost first.
+pub fn print_created(w: &mut impl Write, items: &[ItemWithPath], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            for item in items {
+                writeln!(w, "Created: {}", db::join_path(&item.path))?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => print_items_csv(w, items),
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print copied item message.
+pub fn print_copied(
+    w: &mut impl Write,
+    item: &ItemWithPath,
+    copied_count: i64,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Copied: {}", item.name)?;
+            if item.path.len() > 1 {
+                let container_path = &item.path[..item.path.len() - 1];
+                writeln!(w, "  -> {}", container_path.join(" -> "))?;
+            } else {
+                writeln!(w, "  -> (root)")?;
+            }
+            if copied_count > 1 {
+                writeln!(w, "  {} items copied", copied_count)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, item),
+        Format::Csv => print_item_csv(w, item),
+        Format::Quiet => print_ids(w, [item.id]),
+    }
+}
+
+/// Print moved item message.
+pub fn print_moved(
+    w: &mut impl Write,
+    item: &ItemWithPath,
+    old_path: &[String],
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Moved: {}", item.name)?;
+            if old_path.len() > 1 {
+                writeln!(
+                    w,
+                    "  {} -> {}",
+                    old_path[..old_path.len() - 1].join(" -> "),
+                    if item.path.len() > 1 {
+                        item.path[..item.path.len() - 1].join(" -> ")
+                    } else {
+                        "(root)".to_string()
+                    }
+                )?;
+            } else {
+                writeln!(
+                    w,
+                    "  (root) -> {}",
+                    if item.path.len() > 1 {
+                        item.path[..item.path.len() - 1].join(" -> ")
+                    } else {
+                        "(root)".to_string()
+                    }
+                )?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, item),
+        Format::Csv => print_item_csv(w, item),
+        Format::Quiet => print_ids(w, [item.id]),
+    }
+}
+
+/// Print the items changed together by `invy edit` on several items.
+pub fn print_updated_items(
+    w: &mut impl Write,
+    items: &[ItemWithPath],
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Updated {} items:", items.len())?;
+            for item in items {
+                writeln!(w, "  {}", db::join_path(&item.path))?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => print_items_csv(w, items),
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print the items moved out of a container by `invy mv --contents`.
+pub fn print_moved_contents(
+    w: &mut impl Write,
+    items: &[ItemWithPath],
+    from: &[String],
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            let to = match items.first() {
+                Some(item) if item.path.len() > 1 => {
+                    db::join_path(&item.path[..item.path.len() - 1])
+                }
+                _ => "(root)".to_string(),
+            };
+            writeln!(
+                w,
+                "Moved {} items: {} -> {}",
+                items.len(),
+                db::join_path(from),
+                to
+            )?;
+            for item in items {
+                writeln!(w, "  - {}", item.name)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => print_items_csv(w, items),
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print removed item message.
+pub fn print_removed(
+    w: &mut impl Write,
+    id: i64,
+    name: &str,
+    orphaned: &[String],
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Removed: {}", name)?;
+            if !orphaned.is_empty() {
+                writeln!(w, "Orphaned {} items to root:", orphaned.len())?;
+                for item_name in orphaned {
+                    writeln!(w, "  - {}", item_name)?;
+                }
+            }
+            Ok(())
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct RemovedOutput {
+                removed: String,
+                orphaned: Vec<String>,
+            }
+            print_json(
+                w,
+                &RemovedOutput {
+                    removed: name.to_string(),
+                    orphaned: orphaned.to_vec(),
+                },
+            )
+        }
+        Format::Csv => {
+            writeln!(w, "removed,orphaned")?;
+            writeln!(w, "{},{}", name, orphaned.join(";"))?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, [id]),
+    }
+}
+
+fn measure(amount: i64, unit: &Option<String>) -> Quantity {
+    Quantity {
+        amount,
+        unit: unit.clone(),
+    }
+}
+
+/// Print items that have expired or expire soon (for expiring command).
+/// `today` is `YYYY-MM-DD`; `within` is the look-ahead as given.
+pub fn print_expiring(
+    w: &mut impl Write,
+    items: &[ItemWithPath],
+    today: &str,
+    within: &str,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human => {
+            if items.is_empty() {
+                writeln!(w, "Nothing expires within {}", within)?;
+                return Ok(());
+            }
+            let mut table = Table::new(&["EXPIRES", "LEFT", "PATH"])
+                .align_right(1)
+                .shrink(2);
+            for item in items {
+                let expires_at = item.expires_at.clone().unwrap_or_default();
+                let left = match timespec::days_between(today, &expires_at) {
+                    Some(days) if days < 0 => "expired".to_string(),
+                    Some(0) => "today".to_string(),
+                    Some(days) => format!("{}d", days),
+                    None => "?".to_string(),
+                };
+                table.push(vec![expires_at, left, db::join_path(&item.path)]);
+            }
+            for line in table.render(table::terminal_width()) {
+                writeln!(w, "{}", line)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "name", "path", "expires_at"])?;
+            for item in items {
+                wtr.write_record([
+                    item.id.to_string(),
+                    item.name.clone(),
+                    db::join_path(&item.path),
+                    item.expires_at.clone().unwrap_or_default(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Markdown => {
+            let rows: Vec<_> = items
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.name.clone(),
+                        item.expires_at.clone().unwrap_or_default(),
+                        location_label(&item.path[..item.path.len() - 1]),
+                    ]
+                })
+                .collect();
+            print_markdown_table(w, &["Item", "Expires", "Where"], &rows)
+        }
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print items running low with how much to buy (for shopping command).
+pub fn print_shopping(w: &mut impl Write, items: &[ShoppingItem], format: Format) -> Result<()> {
+    let amount = |amount: i64, item: &ShoppingItem| measure(amount, &item.unit).to_string();
+    match format {
+        Format::Human => {
+            if items.is_empty() {
+                writeln!(w, "Nothing is running low")?;
+                return Ok(());
+            }
+            let mut table = Table::new(&["NEED", "HAVE", "MIN", "PATH"])
+                .align_right(0)
+                .align_right(1)
+                .align_right(2)
+                .shrink(3);
+            for item in items {
+                table.push(vec![
+                    amount(item.need, item),
+                    amount(item.quantity, item),
+                    amount(item.min_quantity, item),
+                    db::join_path(&item.path),
+                ]);
+            }
+            for line in table.render(table::terminal_width()) {
+                writeln!(w, "{}", line)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record([
+                "id",
+                "name",
+                "path",
+                "quantity",
+                "min_quantity",
+                "need",
+                "unit",
+            ])?;
+            for item in items {
+                wtr.write_record([
+                    item.id.to_string(),
+                    item.name.clone(),
+                    db::join_path(&item.path),
+                    item.quantity.to_string(),
+                    item.min_quantity.to_string(),
+                    item.need.to_string(),
+                    item.unit.clone().unwrap_or_default(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Markdown => {
+            let rows: Vec<_> = items
+                .iter()
+                .map(|item| {
+                    vec![
+                        item.name.clone(),
+                        amount(item.need, item),
+                        amount(item.quantity, item),
+                        location_label(&item.path[..item.path.len() - 1]),
+                    ]
+                })
+                .collect();
+            print_markdown_table(w, &["Item", "Need", "Have", "Where"], &rows)
+        }
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+/// Print consumed item message.
+pub fn print_consumed(w: &mut impl Write, entry: &Consumption, format: Format) -> Result<()> {
+    let remaining = entry.remaining.unwrap_or(0);
+    match format {
+        Format::Human | Format::Markdown => {
+            let taken = measure(entry.quantity, &entry.unit);
+            match entry.unit {
+                Some(_) => write!(w, "Consumed: {} of {}", taken, entry.name)?,
+                None => write!(w, "Consumed: {} x {}", taken, entry.name)?,
+            }
+            if remaining > 0 {
+                writeln!(w, " ({} left)", measure(remaining, &entry.unit))?;
+            } else {
+                writeln!(w, " (none left, archived)")?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, entry),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record([
+                "id",
+                "name",
+                "path",
+                "quantity",
+                "remaining",
+                "consumed_at",
+                "unit",
+            ])?;
+            wtr.write_record([
+                &entry.id.to_string(),
+                &entry.name,
+                &db::join_path(&entry.path),
+                &entry.quantity.to_string(),
+                &remaining.to_string(),
+                &entry.consumed_at,
+                entry.unit.as_deref().unwrap_or(""),
+            ])?;
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, entry.item_id.filter(|_| remaining > 0)),
+    }
+}
+
+/// Print consumption log entries (for consumed command).
+pub fn print_consumptions(
+    w: &mut impl Write,
+    entries: &[Consumption],
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if entries.is_empty() {
+                return Ok(());
+            }
+            let quantities: Vec<String> = entries
+                .iter()
+                .map(|e| measure(e.quantity, &e.unit).to_string())
+                .collect();
+            let max_qty = quantities.iter().map(|q| q.len()).max().unwrap_or(3).max(3);
+            writeln!(
+                w,
+                "{:<19} {:>width$} PATH",
+                "CONSUMED",
+                "QTY",
+                width = max_qty
+            )?;
+            for (entry, quantity) in entries.iter().zip(&quantities) {
+                writeln!(
+                    w,
+                    "{:<19} {:>width$} {}",
+                    entry.consumed_at,
+                    quantity,
+                    db::join_path(&entry.path),
+                    width = max_qty
+                )?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, entries),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record([
+                "id",
+                "item_id",
+                "name",
+                "path",
+                "quantity",
+                "consumed_at",
+                "remaining",
+                "unit",
+            ])?;
+            for entry in entries {
+                wtr.write_record([
+                    &entry.id.to_string(),
+                    &entry.item_id.map(|id| id.to_string()).unwrap_or_default(),
+                    &entry.name,
+                    &db::join_path(&entry.path),
+                    &entry.quantity.to_string(),
+                    &entry.consumed_at,
+                    &entry.remaining.map(|r| r.to_string()).unwrap_or_default(),
+                    entry.unit.as_deref().unwrap_or(""),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, entries.iter().map(|e| e.id)),
+    }
+}
+
+/// What an edit changed, for `print_updated`: the old value of each changed
+/// field, `None` for fields left alone.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UpdatedFields<'a> {
+    pub old_name: Option<&'a str>,
+    pub old_desc: Option<Option<&'a str>>,
+    /// The new weight in grams (`Some(None)` when cleared)
+    pub new_weight: Option<Option<i64>>,
+    /// The new restocking threshold (`Some(None)` when cleared)
+    pub new_min_qty: Option<Option<&'a Quantity>>,
+    /// The new expiry date (`Some(None)` when cleared)
+    pub new_expires: Option<Option<&'a str>>,
+    pub old_path: Option<&'a [String]>,
+    pub old_kind: Option<ItemKind>,
+}
+
+/// Print updated item message.
+pub fn print_updated(
+    w: &mut impl Write,
+    item: &ItemWithPath,
+    changed: &UpdatedFields,
+    format: Format,
+) -> Result<()> {
+    let UpdatedFields {
+        old_name,
+        old_desc,
+        new_weight,
+        new_min_qty,
+        new_expires,
+        old_path,
+        old_kind,
+    } = *changed;
+    match format {
+        Format::Human | Format::Markdown => {
+            write!(w, "Updated: {}", item.name)?;
+            if let Some(old) = old_name {
+                if old != item.name {
+                    write!(w, " (was: {})", old)?;
+                }
+            }
+            writeln!(w)?;
+
+            if let Some(old_d) = old_desc {
+                let new_d = item.description.as_deref();
+                if old_d != new_d {
+                    writeln!(
+                        w,
+                        "  description: {:?} -> {:?}",
+                        old_d.unwrap_or("(none)"),
+                        new_d.unwrap_or("(none)")
+                    )?;
+                }
+            }
+
+            if let Some(weight) = new_weight {
+                let weight = weight.map(quantity::format_weight);
+                writeln!(w, "  weight: {}", weight.as_deref().unwrap_or("(none)"))?;
+            }
+
+            if let Some(min) = new_min_qty {
+                let min = min.map(Quantity::to_string);
+                writeln!(w, "  min qty: {}", min.as_deref().unwrap_or("(none)"))?;
+            }
+
+            if let Some(expires) = new_expires {
+                writeln!(w, "  expires: {}", expires.unwrap_or("(none)"))?;
+            }
+
+            if let Some(old_path) = old_path {
+                let container = |path: &[String]| match path.split_last() {
+                    Some((_, parents)) if !parents.is_empty() => db::join_path(parents),
+                    _ => "(root)".to_string(),
+                };
+                let (old, new) = (container(old_path), container(&item.path));
+                if old != new {
+                    writeln!(w, "  location: {} -> {}", old, new)?;
+                }
+            }
+
+            if let Some(old_kind) = old_kind.filter(|&kind| kind != item.kind) {
+                writeln!(w, "  kind: {} -> {}", old_kind.as_str(), item.kind.as_str())?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, item),
+        Format::Csv => print_item_csv(w, item),
+        Format::Quiet => print_ids(w, [item.id]),
+    }
+}
+
+/// Print the problems found by doctor, and what was fixed.
+pub fn print_doctor(w: &mut impl Write, issues: &[DoctorIssue], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if issues.is_empty() {
+                writeln!(w, "No problems found")?;
+                return Ok(());
+            }
+            for issue in issues {
+                writeln!(w, "{}: {}", issue.check, issue.message)?;
+                if let Some(fix) = &issue.fix {
+                    writeln!(w, "  fixed: {}", fix)?;
+                }
+            }
+            let fixed = issues.iter().filter(|issue| issue.fix.is_some()).count();
+            writeln!(w, "Found {} problem(s), fixed {}", issues.len(), fixed)?;
+            Ok(())
+        }
+        Format::Json => print_json(w, issues),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["check", "id", "message", "fix"])?;
+            for issue in issues {
+                wtr.write_record([
+                    issue.check.as_str(),
+                    &issue.item_id.map(|id| id.to_string()).unwrap_or_default(),
+                    &issue.message,
+                    issue.fix.as_deref().unwrap_or(""),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, issues.iter().filter_map(|issue| issue.item_id)),
+    }
+}
+
+/// Print an import validation report (for import command).
+pub fn print_import_report(
+    w: &mut impl Write,
+    report: &ImportReport,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            for issue in &report.issues {
+                let severity = match issue.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                write!(w, "line {}", issue.line)?;
+                if let Some(column) = &issue.column {
+                    write!(w, ", {}", column)?;
+                }
+                write!(w, ": {}: {}", severity, issue.message)?;
+                if let Some(value) = &issue.value {
+                    write!(w, " ('{}')", value)?;
+                }
+                writeln!(w)?;
+                if let Some(suggestion) = &issue.suggestion {
+                    writeln!(w, "  fix: {}", suggestion)?;
+                }
+            }
+            writeln!(
+                w,
+                "Checked {} rows: {} error(s), {} warning(s)",
+                report.rows, report.errors, report.warnings
+            )?;
+            if report.imported > 0 {
+                writeln!(w, "Imported {} items", report.imported)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, report),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record([
+                "severity",
+                "line",
+                "column",
+                "value",
+                "message",
+                "suggestion",
+            ])?;
+            for issue in &report.issues {
+                let severity = match issue.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                wtr.write_record([
+                    severity,
+                    &issue.line.to_string(),
+                    issue.column.as_deref().unwrap_or(""),
+                    issue.value.as_deref().unwrap_or(""),
+                    &issue.message,
+                    issue.suggestion.as_deref().unwrap_or(""),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print convenience view states (for views commands).
+pub fn print_views(w: &mut impl Write, views: &[ViewInfo], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            for view in views {
+                let status = match view.status {
+                    ViewStatus::Ok => "OK",
+                    ViewStatus::Missing => "NOT INSTALLED",
+                    ViewStatus::Stale => "STALE (run invy views install)",
+                    ViewStatus::Broken => "BROKEN (run invy views install)",
+                };
+                writeln!(w, "{}  {}", view.name, status)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, views),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["name", "status"])?;
+            for view in views {
+                let status = serde_json::to_value(view.status)?;
+                wtr.write_record([view.name.as_str(), status.as_str().unwrap_or("")])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print scheduled job results (for cron run command).
+pub fn print_cron_runs(w: &mut impl Write, runs: &[CronRun], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if runs.is_empty() {
+                writeln!(
+                    w,
+                    "No jobs scheduled. Add a [cron] section to the config file"
+                )?;
+            }
+            let width = runs.iter().map(|r| r.job.len()).max().unwrap_or(0);
+            for run in runs {
+                let status = match run.status {
+                    CronStatus::Ran => "ran",
+                    CronStatus::Skipped => "skipped",
+                    CronStatus::Failed => "FAILED",
+                };
+                writeln!(
+                    w,
+                    "{:<width$}  {:<7}  {}",
+                    run.job,
+                    status,
+                    run.detail,
+                    width = width
+                )?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, runs),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["job", "status", "detail", "last_run"])?;
+            for run in runs {
+                let status = serde_json::to_value(run.status)?;
+                wtr.write_record([
+                    run.job.as_str(),
+                    status.as_str().unwrap_or(""),
+                    &run.detail,
+                    run.last_run.as_deref().unwrap_or(""),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print export summary (for export command).
+pub fn print_exported(
+    w: &mut impl Write,
+    count: usize,
+    path: &std::path::Path,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Exported {} items to {}", count, path.display())?;
+            Ok(())
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct ExportedOutput<'a> {
+                exported: usize,
+                path: &'a std::path::Path,
+            }
+            print_json(
+                w,
+                &ExportedOutput {
+                    exported: count,
+                    path,
+                },
+            )
+        }
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["exported", "path"])?;
+            wtr.write_record([count.to_string(), path.display().to_string()])?;
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print what `invy sync` sent, received and could not apply as it was.
+pub fn print_sync_report(w: &mut impl Write, report: &SyncReport, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(
+                w,
+                "Synced as {}: sent {}, received {} ({} applied)",
+                report.replica, report.sent, report.received, report.applied
+            )?;
+            for conflict in &report.conflicts {
+                writeln!(w, "conflict: {}", conflict)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, report),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["replica", "sent", "received", "applied", "conflicts"])?;
+            wtr.write_record([
+                report.replica.clone(),
+                report.sent.to_string(),
+                report.received.to_string(),
+                report.applied.to_string(),
+                report.conflicts.len().to_string(),
+            ])?;
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print a config file (for config export without --output).
+///
+/// JSON output converts the settings; every other format prints the TOML
+/// as written.
+pub fn print_config(w: &mut impl Write, contents: &str, format: Format) -> Result<()> {
+    match format {
+        Format::Json => {
+            let value: toml::Value = toml::from_str(contents)?;
+            print_json(w, &value)
+        }
+        _ => {
+            write!(w, "{}", contents)?;
+            Ok(())
+        }
+    }
+}
+
+/// Print where a config file was copied (for config export and import).
+///
+/// `action` is `exported`, `imported` or `unchanged`.
+pub fn print_config_copied(
+    w: &mut impl Write,
+    action: &str,
+    from: &std::path::Path,
+    to: &std::path::Path,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            match action {
+                "unchanged" => writeln!(w, "Config already up to date: {}", to.display())?,
+                "imported" => {
+                    writeln!(w, "Imported config {} to {}", from.display(), to.display())?
+                }
+                _ => writeln!(w, "Exported config {} to {}", from.display(), to.display())?,
+            }
+            Ok(())
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct CopiedOutput<'a> {
+                action: &'a str,
+                from: &'a std::path::Path,
+                to: &'a std::path::Path,
+            }
+            print_json(w, &CopiedOutput { action, from, to })
+        }
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["action", "from", "to"])?;
+            wtr.write_record([
+                action.to_string(),
+                from.display().to_string(),
+                to.display().to_string(),
+            ])?;
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print the database file path (for db path command).
+pub fn print_db_path(w: &mut impl Write, path: &std::path::Path, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "{}", path.display())?;
+            Ok(())
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct PathOutput<'a> {
+                path: &'a std::path::Path,
+            }
+            print_json(w, &PathOutput { path })
+        }
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["path"])?;
+            wtr.write_record([path.display().to_string()])?;
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => {
+            writeln!(w, "{}", path.display())?;
+            Ok(())
+        }
+    }
+}
+
+/// Print a bare count (for count command and `find --count`).
+pub fn print_count(w: &mut impl Write, count: i64, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown | Format::Quiet => {
+            writeln!(w, "{}", count)?;
+            Ok(())
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct CountOutput {
+                count: i64,
+            }
+            print_json(w, &CountOutput { count })
+        }
+        Format::Csv => {
+            writeln!(w, "count")?;
+            writeln!(w, "{}", count)?;
+            Ok(())
+        }
+    }
+}
+
+/// Print storage-level database information (for db info command).
+pub fn print_db_info(w: &mut impl Write, info: &DbInfo, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Path:           {}", info.path)?;
+            writeln!(
+                w,
+                "Schema version: {} (latest {})",
+                info.schema_version, info.latest_schema_version
+            )?;
+            writeln!(w, "Items:          {}", info.item_count)?;
+            writeln!(w, "Size:           {} bytes", info.size_bytes)?;
+            writeln!(w, "Journal mode:   {}", info.journal_mode)?;
+            Ok(())
+        }
+        Format::Json => print_json(w, info),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.serialize(info)?;
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print schema migration result (for db migrate command).
+pub fn print_migrated(w: &mut impl Write, from: u32, to: u32, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if from == to {
+                writeln!(w, "Schema already at version {}", to)?;
+            } else {
+                writeln!(w, "Migrated schema: {} -> {}", from, to)?;
+            }
+            Ok(())
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct MigrateOutput {
+                from: u32,
+                to: u32,
+            }
+            print_json(w, &MigrateOutput { from, to })
+        }
+        Format::Csv => {
+            writeln!(w, "from,to")?;
+            writeln!(w, "{},{}", from, to)?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print inventory-wide counts (for stats command).
+pub fn print_stats(w: &mut impl Write, stats: &Stats, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Items:       {}", stats.item_count)?;
+            writeln!(w, "Containers:  {}", stats.container_count)?;
+            writeln!(w, "At root:     {}", stats.root_count)?;
+            writeln!(w, "Consumed:    {}", stats.consumed_count)?;
+            Ok(())
+        }
+        Format::Json => print_json(w, stats),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record([
+                "item_count",
+                "container_count",
+                "root_count",
+                "consumed_count",
+            ])?;
+            wtr.write_record([
+                stats.item_count.to_string(),
+                stats.container_count.to_string(),
+                stats.root_count.to_string(),
+                stats.consumed_count.to_string(),
+            ])?;
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print the data-quality report (for stats --health).
+pub fn print_health(w: &mut impl Write, report: &HealthReport, format: Format) -> Result<()> {
+    let percent = |count: i64, items: i64| match items {
+        0 => "-".to_string(),
+        _ => format!("{}%", (count * 100 + items / 2) / items),
+    };
+    match format {
+        Format::Human | Format::Markdown => {
+            let health = &report.health;
+            write!(w, "Data quality: {}/100", health.score)?;
+            match (report.previous_score, &report.previous_at) {
+                (Some(previous), Some(at)) => {
+                    let change = match health.score - previous {
+                        0 => "unchanged".to_string(),
+                        d if d > 0 => format!("up {}", d),
+                        d => format!("down {}", -d),
+                    };
+                    writeln!(w, " ({} since {})", change, at)?;
+                }
+                _ => writeln!(w)?,
+            }
+            writeln!(
+                w,
+                "Described:       {} of {} items ({})",
+                health.described,
+                health.items,
+                percent(health.described, health.items)
+            )?;
+            writeln!(
+                w,
+                "Stale:           {} of {} items ({}, unchanged and unaudited for {})",
+                health.stale,
+                health.items,
+                percent(health.stale, health.items),
+                report.stale_after
+            )?;
+            writeln!(
+                w,
+                "Ambiguous names: {} of {} items ({}, name used more than once)",
+                health.ambiguous,
+                health.items,
+                percent(health.ambiguous, health.items)
+            )?;
+            if report.containers.is_empty() {
+                return Ok(());
+            }
+            writeln!(w)?;
+            let mut table = Table::new(&[
+                "SCORE",
+                "ITEMS",
+                "DESCRIBED",
+                "STALE",
+                "AMBIGUOUS",
+                "CONTAINER",
+            ])
+            .align_right(0)
+            .align_right(1)
+            .align_right(2)
+            .align_right(3)
+            .align_right(4)
+            .shrink(5);
+            for container in &report.containers {
+                let health = &container.health;
+                table.push(vec![
+                    health.score.to_string(),
+                    health.items.to_string(),
+                    percent(health.described, health.items),
+                    percent(health.stale, health.items),
+                    percent(health.ambiguous, health.items),
+                    db::join_path(&container.path),
+                ]);
+            }
+            for line in table.render(table::terminal_width()) {
+                writeln!(w, "{}", line)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, report),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record([
+                "container",
+                "items",
+                "described",
+                "stale",
+                "ambiguous",
+                "score",
+            ])?;
+            let overall = std::iter::once((String::new(), &report.health));
+            let containers = report
+                .containers
+                .iter()
+                .map(|c| (db::join_path(&c.path), &c.health));
+            for (path, health) in overall.chain(containers) {
+                wtr.write_record([
+                    path,
+                    health.items.to_string(),
+                    health.described.to_string(),
+                    health.stale.to_string(),
+                    health.ambiguous.to_string(),
+                    health.score.to_string(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print activity per period (for stats --series).
+pub fn print_series(w: &mut impl Write, points: &[SeriesPoint], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if points.is_empty() {
+                return Ok(());
+            }
+            let width = points
+                .iter()
+                .map(|p| p.period.len())
+                .max()
+                .unwrap_or(6)
+                .max(6);
+            writeln!(
+                w,
+                "{:<width$} {:>6} {:>8} {:>6} {:>6} {:>8}",
+                "PERIOD", "ADDED", "ARCHIVED", "NET", "MOVED", "CONSUMED"
+            )?;
+            for p in points {
+                writeln!(
+                    w,
+                    "{:<width$} {:>6} {:>8} {:>6} {:>6} {:>8}",
+                    p.period, p.added, p.archived, p.net, p.moved, p.consumed
+                )?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, points),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["period", "added", "archived", "net", "moved", "consumed"])?;
+            for p in points {
+                wtr.write_record([
+                    p.period.clone(),
+                    p.added.to_string(),
+                    p.archived.to_string(),
+                    p.net.to_string(),
+                    p.moved.to_string(),
+                    p.consumed.to_string(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print moves in and out of containers per period (for stats --churn).
+pub fn print_churn(w: &mut impl Write, points: &[ChurnPoint], format: Format) -> Result<()> {
+    let container = |p: &ChurnPoint| {
+        if p.container.is_empty() {
+            "(root)".to_string()
+        } else {
+            db::join_path(&p.container)
+        }
+    };
+    match format {
+        Format::Human | Format::Markdown => {
+            if points.is_empty() {
+                return Ok(());
+            }
+            let width = points
+                .iter()
+                .map(|p| p.period.len())
+                .max()
+                .unwrap_or(6)
+                .max(6);
+            writeln!(w, "{:<width$} {:>4} {:>4} CONTAINER", "PERIOD", "IN", "OUT")?;
+            for p in points {
+                writeln!(
+                    w,
+                    "{:<width$} {:>4} {:>4} {}",
+                    p.period,
+                    p.moved_in,
+                    p.moved_out,
+                    container(p)
+                )?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, points),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["period", "container", "moved_in", "moved_out"])?;
+            for p in points {
+                wtr.write_record([
+                    p.period.clone(),
+                    container(p),
+                    p.moved_in.to_string(),
+                    p.moved_out.to_string(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+/// Print a newly created share link (for share create).
+pub fn print_share(w: &mut impl Write, share: &Share, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Shared: {}", db::join_path(&share.path))?;
+            writeln!(
+                w,
+                "  URL:     {}",
+                share.url.as_deref().unwrap_or(&share.token)
+            )?;
+            writeln!(
+                w,
+                "  Expires: {}",
+                share.expires_at.as_deref().unwrap_or("never")
+            )?;
+            Ok(())
+        }
+        Format::Json => print_json(w, share),
+        Format::Csv => print_shares_csv(w, std::slice::from_ref(share)),
+        Format::Quiet => {
+            writeln!(w, "{}", share.token)?;
+            Ok(())
+        }
+    }
+}
+
+/// Print share links (for share list).
+pub fn print_shares(w: &mut impl Write, shares: &[Share], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            for share in shares {
+                let status = if share.expired { " (expired)" } else { "" };
+                writeln!(w, "{}{}", db::join_path(&share.path), status)?;
+                writeln!(
+                    w,
+                    "  URL:     {}",
+                    share.url.as_deref().unwrap_or(&share.token)
+                )?;
+                writeln!(
+                    w,
+                    "  Expires: {}",
+                    share.expires_at.as_deref().unwrap_or("never")
+                )?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, shares),
+        Format::Csv => print_shares_csv(w, shares),
+        Format::Quiet => {
+            for share in shares {
+                writeln!(w, "{}", share.token)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Print revoked share message.
+pub fn print_revoked(w: &mut impl Write, token: &str, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(w, "Revoked: {}", token)?;
+            Ok(())
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct RevokedOutput<'a> {
+                revoked: &'a str,
+            }
+            print_json(w, &RevokedOutput { revoked: token })
+        }
+        Format::Csv => {
+            writeln!(w, "revoked")?;
+            writeln!(w, "{}", token)?;
+            Ok(())
+        }
+        Format::Quiet => Ok(()),
+    }
+}
+
+fn print_shares_csv(w: &mut impl Write, shares: &[Share]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(&mut *w);
+    wtr.write_record([
+        "token",
+        "path",
+        "url",
+        "created_at",
+        "expires_at",
+        "expired",
+    ])?;
+    for share in shares {
+        wtr.write_record([
+            share.token.as_str(),
+            &db::join_path(&share.path),
+            share.url.as_deref().unwrap_or(""),
+            &share.created_at,
+            share.expires_at.as_deref().unwrap_or(""),
+            if share.expired { "true" } else { "false" },
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Print the renames made (or previewed, with `dry_run`) by `invy rename`.
+pub fn print_renames(
+    w: &mut impl Write,
+    renames: &[RenamedItem],
+    dry_run: bool,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            for rename in renames {
+                writeln!(w, "{} -> {}", rename.old_name, rename.new_name)?;
+            }
+            if dry_run {
+                writeln!(w, "Would rename {} items (dry run)", renames.len())?;
+            } else {
+                writeln!(w, "Renamed {} items", renames.len())?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, renames),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "old_name", "new_name"])?;
+            for rename in renames {
+                wtr.write_record([&rename.id.to_string(), &rename.old_name, &rename.new_name])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, renames.iter().map(|rename| rename.id)),
+    }
+}
+
+/// Print duplicate groups (for dupes command).
+pub fn print_dupes(w: &mut impl Write, groups: &[DupeGroup], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            for group in groups {
+                let mut names: Vec<&str> = group.items.iter().map(|i| i.name.as_str()).collect();
+                names.sort_unstable();
+                names.dedup();
+                let kind = match group.kind {
+                    DupeKind::Exact => "Same name",
+                    DupeKind::Similar => "Similar",
+                };
+                writeln!(w, "{}: {}", kind, names.join(", "))?;
+                for item in &group.items {
+                    writeln!(w, "  {}", db::join_path(&item.path))?;
+                }
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, groups),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["group", "kind", "id", "name", "path"])?;
+            for (n, group) in groups.iter().enumerate() {
+                let kind = serde_json::to_value(group.kind)?;
+                for item in &group.items {
+                    wtr.write_record([
+                        &(n + 1).to_string(),
+                        kind.as_str().unwrap_or(""),
+                        &item.id.to_string(),
+                        &item.name,
+                        &db::join_path(&item.path),
+                    ])?;
+                }
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, groups.iter().flat_map(|g| g.items.iter().map(|i| i.id))),
+    }
+}
+
+/// Print storage conditions (for storage set, require and list).
+pub fn print_storage_conditions(
+    w: &mut impl Write,
+    conditions: &[StorageConditions],
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if conditions.is_empty() {
+                writeln!(w, "No storage conditions recorded")?;
+                return Ok(());
+            }
+            let mut table = Table::new(&["PATH", "", "TEMP", "HUMIDITY", "NOTE"]).shrink(0);
+            for c in conditions {
+                table.push(vec![
+                    db::join_path(&c.path),
+                    match c.kind {
+                        ConditionKind::Provides => "has",
+                        ConditionKind::Requires => "needs",
+                    }
+                    .to_string(),
+                    range_label(c.temp_min, c.temp_max, "°C"),
+                    range_label(c.humidity_min, c.humidity_max, "%"),
+                    c.note.clone().unwrap_or_default(),
+                ]);
+            }
+            for line in table.render(table::terminal_width()).iter().skip(1) {
+                writeln!(w, "{}", line.trim_end())?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, conditions),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record([
+                "id",
+                "path",
+                "kind",
+                "temp_min",
+                "temp_max",
+                "humidity_min",
+                "humidity_max",
+                "note",
+            ])?;
+            let cell = |v: Option<i64>| v.map(|v| v.to_string()).unwrap_or_default();
+            for c in conditions {
+                wtr.write_record([
+                    c.item_id.to_string(),
+                    db::join_path(&c.path),
+                    c.kind.as_str().to_string(),
+                    cell(c.temp_min),
+                    cell(c.temp_max),
+                    cell(c.humidity_min),
+                    cell(c.humidity_max),
+                    c.note.clone().unwrap_or_default(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, conditions.iter().map(|c| c.item_id)),
+    }
+}
+
+/// A bound pair as typed on the command line: `5..40°C`, `..30°C`, `20%`.
+fn range_label(min: Option<i64>, max: Option<i64>, unit: &str) -> String {
+    let bound = |v: Option<i64>| v.map(|v| v.to_string()).unwrap_or_default();
+    match (min, max) {
+        (None, None) => String::new(),
+        (Some(min), Some(max)) if min == max => format!("{}{}", min, unit),
+        _ => format!("{}..{}{}", bound(min), bound(max), unit),
+    }
+}
+
+/// Print cleared storage conditions message.
+pub fn print_storage_cleared(
+    w: &mut impl Write,
+    item: &ItemWithPath,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            writeln!(
+                w,
+                "Cleared storage conditions: {}",
+                db::join_path(&item.path)
+            )?;
+            Ok(())
+        }
+        Format::Json => print_json(w, item),
+        Format::Csv => print_item_csv(w, item),
+        Format::Quiet => print_ids(w, [item.id]),
+    }
+}
+
+/// Print items stored in unsuitable conditions (for storage-check).
+pub fn print_storage_issues(
+    w: &mut impl Write,
+    issues: &[StorageIssue],
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if issues.is_empty() {
+                writeln!(w, "All items are stored in suitable conditions")?;
+                return Ok(());
+            }
+            for issue in issues {
+                write!(
+                    w,
+                    "{} in {}",
+                    db::join_path(&issue.path),
+                    db::join_path(&issue.container)
+                )?;
+                match &issue.container_note {
+                    Some(note) => writeln!(w, " ({})", note)?,
+                    None => writeln!(w)?,
+                }
+                for problem in &issue.problems {
+                    writeln!(w, "  {}", problem)?;
+                }
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, issues),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "path", "container", "problem"])?;
+            for issue in issues {
+                for problem in &issue.problems {
+                    wtr.write_record([
+                        &issue.item_id.to_string(),
+                        &db::join_path(&issue.path),
+                        &db::join_path(&issue.container),
+                        problem,
+                    ])?;
+                }
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, issues.iter().map(|i| i.item_id)),
+    }
+}
+
+/// Print reorganization suggestions (for `invy optimize`).
+pub fn print_optimize(
+    w: &mut impl Write,
+    suggestions: &[OptimizeSuggestion],
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if suggestions.is_empty() {
+                writeln!(w, "Nothing to reorganize")?;
+                return Ok(());
+            }
+            for suggestion in suggestions {
+                write!(
+                    w,
+                    "{}: {}",
+                    db::join_path(&suggestion.path),
+                    suggestion.reason
+                )?;
+                match &suggestion.destination {
+                    Some(destination) => writeln!(w, "; consider {}", db::join_path(destination))?,
+                    None => writeln!(w)?,
+                }
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, suggestions),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "path", "uses", "depth", "reason", "destination"])?;
+            for suggestion in suggestions {
+                wtr.write_record([
+                    &suggestion.item_id.to_string(),
+                    &db::join_path(&suggestion.path),
+                    &suggestion.uses.to_string(),
+                    &suggestion.depth.to_string(),
+                    &suggestion.reason,
+                    &suggestion
+                        .destination
+                        .as_ref()
+                        .map(|d| db::join_path(d))
+                        .unwrap_or_default(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, suggestions.iter().map(|s| s.item_id)),
+    }
+}
+
+/// Print suggestions as a shell script of `invy mv` commands; suggestions
+/// without a destination become comments.
+pub fn print_optimize_script(w: &mut impl Write, suggestions: &[OptimizeSuggestion]) -> Result<()> {
+    writeln!(w, "#!/bin/sh")?;
+    writeln!(w, "# Suggested by invy optimize; review before running")?;
+    writeln!(w, "set -e")?;
+    for suggestion in suggestions {
+        let path = db::join_path(&suggestion.path);
+        writeln!(w, "# {}", suggestion.reason)?;
+        match &suggestion.destination {
+            Some(destination) => writeln!(
+                w,
+                "invy mv {} {}",
+                shell_quote(&path),
+                shell_quote(&db::join_path(destination))
+            )?,
+            None => writeln!(w, "# invy mv {} <somewhere shallower>", shell_quote(&path))?,
+        }
+    }
+    Ok(())
+}
+
+/// Quote an argument for a POSIX shell unless it is plainly safe.
+fn shell_quote(arg: &str) -> String {
+    if !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:#@%+=,".contains(c))
+    {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Print a name set or removed by `invy i18n set`/`unset`.
+pub fn print_translation(
+    w: &mut impl Write,
+    translation: &Translation,
+    removed: bool,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            let verb = if removed { "Removed" } else { "Named" };
+            writeln!(
+                w,
+                "{}: {} ({}: {})",
+                verb,
+                db::join_path(&translation.path),
+                translation.lang,
+                translation.name
+            )?;
+            Ok(())
+        }
+        Format::Json => print_json(w, translation),
+        Format::Csv => print_translations_csv(w, std::slice::from_ref(translation)),
+        Format::Quiet => print_ids(w, [translation.item_id]),
+    }
+}
+
+/// Print an alias that was just added or removed.
+pub fn print_alias(w: &mut impl Write, alias: &Alias, removed: bool, format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            let verb = if removed { "Removed alias" } else { "Alias" };
+            writeln!(
+                w,
+                "{}: {} -> {}",
+                verb,
+                alias.alias,
+                db::join_path(&alias.path)
+            )?;
+            Ok(())
+        }
+        Format::Json => print_json(w, alias),
+        Format::Csv => print_aliases_csv(w, std::slice::from_ref(alias)),
+        Format::Quiet => print_ids(w, [alias.item_id]),
+    }
+}
+
+/// What an audit covers, for messages.
+fn audit_label(report: &AuditReport) -> String {
+    if report.path.is_empty() {
+        "the whole inventory".to_string()
+    } else {
+        db::join_path(&report.path)
+    }
+}
+
+/// Print an audit's progress (for `invy audit start` and `invy audit
+/// report`). `started` prints only how much there is to check.
+pub fn print_audit(
+    w: &mut impl Write,
+    report: &AuditReport,
+    started: bool,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if started {
+                writeln!(
+                    w,
+                    "Started audit of {}: {} item(s) to check",
+                    audit_label(report),
+                    report.total
+                )?;
+                return Ok(());
+            }
+            writeln!(
+                w,
+                "Audit of {} started {}: {} of {} checked",
+                audit_label(report),
+                report.started_at,
+                report.checked,
+                report.total
+            )?;
+            if report.unchecked.is_empty() {
+                writeln!(w, "Everything has been checked")?;
+                return Ok(());
+            }
+            let mut table = Table::new(&["PATH", "LAST VERIFIED"]).shrink(0);
+            for item in &report.unchecked {
+                table.push(vec![
+                    db::join_path(&item.path),
+                    item.verified_at
+                        .clone()
+                        .unwrap_or_else(|| "never".to_string()),
+                ]);
+            }
+            for line in table.render(table::terminal_width()) {
+                writeln!(w, "{}", line)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, report),
+        Format::Csv => print_verified_csv(w, &report.unchecked),
+        Format::Quiet if started => print_ids(w, report.container_id),
+        Format::Quiet => print_ids(w, report.unchecked.iter().map(|i| i.id)),
+    }
+}
+
+/// Print items just checked off in an audit (for `invy audit check`).
+pub fn print_audit_checked(
+    w: &mut impl Write,
+    items: &[ItemWithPath],
+    report: &AuditReport,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            for item in items {
+                writeln!(w, "Checked: {}", db::join_path(&item.path))?;
+            }
+            writeln!(w, "{} of {} checked", report.checked, report.total)?;
+            Ok(())
+        }
+        Format::Json => print_json(w, items),
+        Format::Csv => print_verified_csv(w, items),
+        Format::Quiet => print_ids(w, items.iter().map(|i| i.id)),
+    }
+}
+
+fn print_verified_csv(w: &mut impl Write, items: &[ItemWithPath]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(&mut *w);
+    wtr.write_record(["id", "path", "verified_at"])?;
+    for item in items {
+        wtr.write_record([
+            item.id.to_string(),
+            db::join_path(&item.path),
+            item.verified_at.clone().unwrap_or_default(),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Print boxes just marked unpacked, or packed again with `undo` (for
+/// `invy unpacked`).
+pub fn print_unpacked(
+    w: &mut impl Write,
+    boxes: &[ItemWithPath],
+    undo: bool,
+    status: &UnpackingStatus,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            let label = if undo { "Packed" } else { "Unpacked" };
+            for item in boxes {
+                writeln!(w, "{}: {}", label, db::join_path(&item.path))?;
+            }
+            writeln!(
+                w,
+                "{} of {} boxes unpacked",
+                status.unpacked_boxes, status.boxes
+            )?;
+            Ok(())
+        }
+        Format::Json => print_json(w, boxes),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "path"])?;
+            for item in boxes {
+                wtr.write_record([item.id.to_string(), db::join_path(&item.path)])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, boxes.iter().map(|i| i.id)),
+    }
+}
+
+/// Print how far unpacking has come (for `invy unpacking-status`).
+pub fn print_unpacking_status(
+    w: &mut impl Write,
+    status: &UnpackingStatus,
+    format: Format,
+) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if status.boxes == 0 {
+                writeln!(w, "No boxes to unpack")?;
+                return Ok(());
+            }
+            writeln!(
+                w,
+                "Boxes unpacked: {} of {} ({}%)",
+                status.unpacked_boxes,
+                status.boxes,
+                percent(status.unpacked_boxes, status.boxes)
+            )?;
+            writeln!(
+                w,
+                "Items unpacked: {} of {} ({}%)",
+                status.unpacked_items,
+                status.items,
+                percent(status.unpacked_items, status.items)
+            )?;
+            if status.packed.is_empty() {
+                writeln!(w, "Everything is unpacked")?;
+                return Ok(());
+            }
+            writeln!(w)?;
+            let mut table = Table::new(&["ITEMS", "STILL PACKED"])
+                .align_right(0)
+                .shrink(1);
+            for packed in &status.packed {
+                table.push(vec![packed.items.to_string(), db::join_path(&packed.path)]);
+            }
+            for line in table.render(table::terminal_width()) {
+                writeln!(w, "{}", line)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, status),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["id", "path", "items"])?;
+            for packed in &status.packed {
+                wtr.write_record([
+                    packed.id.to_string(),
+                    db::join_path(&packed.path),
+                    packed.items.to_string(),
+                ])?;
+            }
+            wtr.flush()?;
+            Ok(())
+        }
+        Format::Quiet => print_ids(w, status.packed.iter().map(|p| p.id)),
+    }
 }
 
-/// Output a single item (for add, show commands).
-pub fn print_item(item: &ItemWithPath, format: Format) -> Result<()> {
-    match format {
-        Format::Human => print_item_human(item),
-        Format::Json => print_json(item),
-        Format::Csv => print_item_csv(item),
+/// `part` as a whole percentage of `total`, rounded down (100 when there is
+/// nothing to count).
+fn percent(part: usize, total: usize) -> usize {
+    if total == 0 {
+        return 100;
     }
+    part * 100 / total
 }
 
-/// Output a list of items (for find, list commands).
-pub fn print_items(items: &[ItemWithPath], format: Format) -> Result<()> {
+/// Print an item's aliases (for `invy alias list`).
+pub fn print_aliases(
+    w: &mut impl Write,
+    path: &[String],
+    aliases: &[Alias],
+    format: Format,
+) -> Result<()> {
     match format {
-        Format::Human => print_items_human(items),
-        Format::Json => print_json(items),
-        Format::Csv => print_items_csv(items),
+        Format::Human | Format::Markdown => {
+            let path = db::join_path(path);
+            if aliases.is_empty() {
+                writeln!(w, "{} has no aliases. Add one with `invy alias add`", path)?;
+                return Ok(());
+            }
+            writeln!(w, "{}", path)?;
+            for alias in aliases {
+                writeln!(w, "  {}", alias.alias)?;
+            }
+            Ok(())
+        }
+        Format::Json => print_json(w, aliases),
+        Format::Csv => print_aliases_csv(w, aliases),
+        Format::Quiet => Ok(()),
     }
 }
 
-/// Output list items with child counts (for list command).
-pub fn print_list_items(items: &[ListItem], format: Format) -> Result<()> {
-    match format {
-        Format::Human => print_list_items_human(items),
-        Format::Json => print_json(items),
-        Format::Csv => print_list_items_csv(items),
+fn print_aliases_csv(w: &mut impl Write, aliases: &[Alias]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(&mut *w);
+    wtr.write_record(["item_id", "path", "alias"])?;
+    for alias in aliases {
+        wtr.write_record([
+            &alias.item_id.to_string(),
+            &db::join_path(&alias.path),
+            &alias.alias,
+        ])?;
     }
+    wtr.flush()?;
+    Ok(())
 }
 
-/// Print added item message.
-pub fn print_added(item: &ItemWithPath, format: Format) -> Result<()> {
+/// Print a task that was just added or marked done (for todo add/done).
+pub fn print_todo(w: &mut impl Write, todo: &Todo, format: Format) -> Result<()> {
     match format {
-        Format::Human => {
-            println!("Added: {}", item.name);
-            if item.path.len() > 1 {
-                let container_path = &item.path[..item.path.len() - 1];
-                println!("  -> {}", container_path.join(" -> "));
-            }
+        Format::Human | Format::Markdown => {
+            let verb = if todo.done_at.is_some() {
+                "Done"
+            } else {
+                "Added"
+            };
+            writeln!(
+                w,
+                "{}: #{} {} ({})",
+                verb,
+                todo.id,
+                todo.text,
+                db::join_path(&todo.path)
+            )?;
             Ok(())
         }
-        Format::Json => print_json(item),
-        Format::Csv => {
-            println!("id,name,description,container");
-            println!(
-                "{},{},{},{}",
-                item.id,
-                item.name,
-                item.description.as_deref().unwrap_or(""),
-                if item.path.len() > 1 {
-                    item.path[item.path.len() - 2].clone()
-                } else {
-                    String::new()
+        Format::Json => print_json(w, todo),
+        Format::Csv => print_todos_csv(w, std::slice::from_ref(todo)),
+        Format::Quiet => print_ids(w, [todo.id]),
+    }
+}
+
+/// Print tasks, oldest first (for todo list).
+pub fn print_todos(w: &mut impl Write, todos: &[Todo], format: Format) -> Result<()> {
+    match format {
+        Format::Human | Format::Markdown => {
+            if todos.is_empty() {
+                writeln!(w, "No open todos")?;
+                return Ok(());
+            }
+            let done = todos.iter().any(|todo| todo.done_at.is_some());
+            let header: &[&'static str] = if done {
+                &["ID", "ITEM", "TODO", "DONE"]
+            } else {
+                &["ID", "ITEM", "TODO"]
+            };
+            let mut table = Table::new(header).align_right(0).shrink(2);
+            for todo in todos {
+                let mut row = vec![
+                    todo.id.to_string(),
+                    db::join_path(&todo.path),
+                    todo.text.clone(),
+                ];
+                if done {
+                    row.push(todo.done_at.clone().unwrap_or_else(|| "-".to_string()));
                 }
-            );
+                table.push(row);
+            }
+            for line in table.render(table::terminal_width()) {
+                writeln!(w, "{}", line)?;
+            }
             Ok(())
         }
+        Format::Json => print_json(w, todos),
+        Format::Csv => print_todos_csv(w, todos),
+        Format::Quiet => print_ids(w, todos.iter().map(|todo| todo.id)),
     }
 }
 
-/// Print moved item message.
-pub fn print_moved(item: &ItemWithPath, old_path: &[String], format: Format) -> Result<()> {
+fn print_todos_csv(w: &mut impl Write, todos: &[Todo]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(&mut *w);
+    wtr.write_record(["id", "item_id", "path", "text", "created_at", "done_at"])?;
+    for todo in todos {
+        wtr.write_record([
+            &todo.id.to_string(),
+            &todo.item_id.to_string(),
+            &db::join_path(&todo.path),
+            &todo.text,
+            &todo.created_at,
+            todo.done_at.as_deref().unwrap_or(""),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Print an item's names in other languages (for i18n list).
+pub fn print_translations(
+    w: &mut impl Write,
+    path: &[String],
+    translations: &[Translation],
+    format: Format,
+) -> Result<()> {
     match format {
-        Format::Human => {
-            println!("Moved: {}", item.name);
-            if old_path.len() > 1 {
-                println!(
-                    "  {} -> {}",
-                    old_path[..old_path.len() - 1].join(" -> "),
-                    if item.path.len() > 1 {
-                        item.path[..item.path.len() - 1].join(" -> ")
-                    } else {
-                        "(root)".to_string()
-                    }
-                );
-            } else {
-                println!(
-                    "  (root) -> {}",
-                    if item.path.len() > 1 {
-                        item.path[..item.path.len() - 1].join(" -> ")
-                    } else {
-                        "(root)".to_string()
-                    }
-                );
+        Format::Human | Format::Markdown => {
+            let path = db::join_path(path);
+            if translations.is_empty() {
+                writeln!(
+                    w,
+                    "{} has no other names. Add one with `invy i18n set`",
+                    path
+                )?;
+                return Ok(());
+            }
+            writeln!(w, "{}", path)?;
+            let mut table = Table::new(&["LANG", "NAME"]);
+            for translation in translations {
+                table.push(vec![translation.lang.clone(), translation.name.clone()]);
+            }
+            for line in table.render(None).iter().skip(1) {
+                writeln!(w, "  {}", line)?;
             }
             Ok(())
         }
-        Format::Json => print_json(item),
-        Format::Csv => print_item_csv(item),
+        Format::Json => print_json(w, translations),
+        Format::Csv => print_translations_csv(w, translations),
+        Format::Quiet => Ok(()),
     }
 }
 
-/// Print removed item message.
-pub fn print_removed(name: &str, orphaned: &[String], format: Format) -> Result<()> {
+fn print_translations_csv(w: &mut impl Write, translations: &[Translation]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(&mut *w);
+    wtr.write_record(["id", "path", "lang", "name"])?;
+    for translation in translations {
+        wtr.write_record([
+            &translation.item_id.to_string(),
+            &db::join_path(&translation.path),
+            &translation.lang,
+            &translation.name,
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Print a container's checklist and what is missing (for check command).
+pub fn print_checklist(w: &mut impl Write, report: &ChecklistReport, format: Format) -> Result<()> {
+    let container = db::join_path(&report.container);
     match format {
-        Format::Human => {
-            println!("Removed: {}", name);
-            if !orphaned.is_empty() {
-                println!("Orphaned {} items to root:", orphaned.len());
-                for item_name in orphaned {
-                    println!("  - {}", item_name);
+        Format::Human | Format::Markdown => {
+            if report.entries.is_empty() {
+                writeln!(
+                    w,
+                    "{} has no checklist. Add entries with --require <name>",
+                    container
+                )?;
+                return Ok(());
+            }
+            writeln!(
+                w,
+                "{}: {} of {} present",
+                container,
+                report.entries.len() - report.missing,
+                report.entries.len()
+            )?;
+            let width = report
+                .entries
+                .iter()
+                .map(|e| e.name.chars().count())
+                .max()
+                .unwrap_or(0);
+            for entry in &report.entries {
+                match &entry.path {
+                    Some(path) => writeln!(
+                        w,
+                        "[x] {:<width$}  {}",
+                        entry.name,
+                        db::join_path(path),
+                        width = width
+                    )?,
+                    None => writeln!(w, "[ ] {}", entry.name)?,
                 }
             }
             Ok(())
         }
-        Format::Json => {
-            #[derive(Serialize)]
-            struct RemovedOutput {
-                removed: String,
-                orphaned: Vec<String>,
-            }
-            print_json(&RemovedOutput {
-                removed: name.to_string(),
-                orphaned: orphaned.to_vec(),
-            })
-        }
+        Format::Json => print_json(w, report),
         Format::Csv => {
-            println!("removed,orphaned");
-            println!("{},{}", name, orphaned.join(";"));
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record(["container", "name", "present", "path"])?;
+            for entry in &report.entries {
+                wtr.write_record([
+                    container.as_str(),
+                    &entry.name,
+                    &entry.present.to_string(),
+                    &entry
+                        .path
+                        .as_ref()
+                        .map(|p| db::join_path(p))
+                        .unwrap_or_default(),
+                ])?;
+            }
+            wtr.flush()?;
             Ok(())
         }
+        Format::Quiet => Ok(()),
     }
 }
 
-/// Print updated item message.
-pub fn print_updated(
-    item: &ItemWithPath,
-    old_name: Option<&str>,
-    old_desc: Option<Option<&str>>,
-    format: Format,
-) -> Result<()> {
+/// Print container content hashes (for hash command).
+pub fn print_hashes(w: &mut impl Write, hashes: &[ContainerHash], format: Format) -> Result<()> {
     match format {
-        Format::Human => {
-            print!("Updated: {}", item.name);
-            if let Some(old) = old_name {
-                if old != item.name {
-                    print!(" (was: {})", old);
+        Format::Human | Format::Markdown => {
+            for h in hashes {
+                let path = db::join_path(&h.path);
+                match h.status {
+                    HashStatus::Recorded => writeln!(w, "{}  {}", path, h.hash)?,
+                    HashStatus::Ok => writeln!(w, "{}  OK", path)?,
+                    HashStatus::Changed => writeln!(
+                        w,
+                        "{}  CHANGED (recorded {})",
+                        path,
+                        h.recorded_at.as_deref().unwrap_or("-")
+                    )?,
+                    HashStatus::Unrecorded => writeln!(w, "{}  NO RECORDED HASH", path)?,
                 }
             }
-            println!();
-
-            if let Some(old_d) = old_desc {
-                let new_d = item.description.as_deref();
-                if old_d != new_d {
-                    println!(
-                        "  description: {:?} -> {:?}",
-                        old_d.unwrap_or("(none)"),
-                        new_d.unwrap_or("(none)")
-                    );
-                }
+            Ok(())
+        }
+        Format::Json => print_json(w, hashes),
+        Format::Csv => {
+            let mut wtr = csv::Writer::from_writer(&mut *w);
+            wtr.write_record([
+                "id",
+                "path",
+                "hash",
+                "recorded_hash",
+                "recorded_at",
+                "status",
+            ])?;
+            for h in hashes {
+                let status = serde_json::to_value(h.status)?;
+                wtr.write_record([
+                    &h.id.to_string(),
+                    &db::join_path(&h.path),
+                    &h.hash,
+                    h.recorded_hash.as_deref().unwrap_or(""),
+                    h.recorded_at.as_deref().unwrap_or(""),
+                    status.as_str().unwrap_or(""),
+                ])?;
             }
+            wtr.flush()?;
             Ok(())
         }
-        Format::Json => print_json(item),
-        Format::Csv => print_item_csv(item),
+        Format::Quiet => print_ids(w, hashes.iter().map(|h| h.id)),
     }
 }
 
 // Human-readable formatters
 
-fn print_item_human(item: &ItemWithPath) -> Result<()> {
-    println!("Name:        {}", item.name);
-    println!(
+fn print_item_human(w: &mut impl Write, item: &ItemWithPath) -> Result<()> {
+    writeln!(w, "Name:        {}", item.name)?;
+    if let Some(aliases) = item.aliases.as_ref().filter(|aliases| !aliases.is_empty()) {
+        writeln!(w, "Aliases:     {}", aliases.join(", "))?;
+    }
+    writeln!(
+        w,
         "Description: {}",
         item.description.as_deref().unwrap_or("-")
-    );
+    )?;
 
-    if item.path.len() > 1 {
-        let location = &item.path[..item.path.len() - 1];
-        let mut reversed = location.to_vec();
-        reversed.reverse();
-        println!("Location:    {}", reversed.join(" -> "));
-    } else {
-        println!("Location:    (root)");
+    let container_path = &item.path[..item.path.len().saturating_sub(1)];
+    writeln!(w, "Location:    {}", location_label(container_path))?;
+
+    if item.kind != ItemKind::Item {
+        writeln!(w, "Kind:        {}", item.kind.as_str())?;
+    }
+    if let Some(group) = &item.group {
+        writeln!(w, "Group:       {}", group)?;
+    }
+
+    if item.quantity != 1 || item.unit.is_some() {
+        writeln!(w, "Quantity:    {}", measure(item.quantity, &item.unit))?;
+    }
+    if let Some(min) = item.min_quantity {
+        writeln!(w, "Minimum:     {}", measure(min, &item.min_unit))?;
+    }
+    if let Some(expires_at) = &item.expires_at {
+        if expires_at.as_str() < timespec::today().as_str() {
+            writeln!(w, "Expires:     {} (expired)", expires_at)?;
+        } else {
+            writeln!(w, "Expires:     {}", expires_at)?;
+        }
     }
 
     if let Some(count) = item.child_count {
         if count > 0 {
-            println!("Contains:    {} items", count);
+            writeln!(w, "Contains:    {} items", count)?;
         }
     }
 
-    println!("Created:     {}", item.created_at);
-    println!("Updated:     {}", item.updated_at);
+    if let Some(weight) = item.weight_g {
+        writeln!(w, "Weight:      {}", quantity::format_weight(weight))?;
+    }
+
+    if let Some(count) = item.open_todos.filter(|&count| count > 0) {
+        writeln!(w, "Todos:       {} open", count)?;
+    }
+
+    writeln!(w, "Created:     {}", item.created_at)?;
+    writeln!(w, "Updated:     {}", item.updated_at)?;
+    if let Some(verified_at) = &item.verified_at {
+        writeln!(w, "Verified:    {}", verified_at)?;
+    }
+
+    if let Some(locations) = &item.locations {
+        writeln!(w, "Locations:")?;
+        for location in locations {
+            writeln!(
+                w,
+                "  {}  {}",
+                location.moved_at,
+                location_label(&location.path)
+            )?;
+        }
+    }
 
     Ok(())
 }
 
-fn print_items_human(items: &[ItemWithPath]) -> Result<()> {
+/// Render a container path innermost-first, as in the `Location:` line.
+fn location_label(path: &[String]) -> String {
+    if path.is_empty() {
+        "(root)".to_string()
+    } else {
+        let mut reversed = path.to_vec();
+        reversed.reverse();
+        reversed.join(" -> ")
+    }
+}
+
+fn print_items_human(w: &mut impl Write, items: &[ItemWithPath]) -> Result<()> {
     for item in items {
-        println!("{}", item.path.join("/"));
+        writeln!(w, "{}", db::join_path(&item.path))?;
         if let Some(ref desc) = item.description {
-            println!("  {}", desc);
+            writeln!(w, "  {}", desc)?;
         }
-        println!();
+        writeln!(w)?;
     }
     Ok(())
 }
 
-fn print_list_items_human(items: &[ListItem]) -> Result<()> {
+fn print_list_items_human(
+    w: &mut impl Write,
+    items: &[ListItem],
+    colors: Option<&AgeColors>,
+) -> Result<()> {
     if items.is_empty() {
         return Ok(());
     }
+    if items.iter().any(|item| item.details.is_some()) {
+        return print_list_items_long(w, items, colors);
+    }
 
-    // Calculate column widths
-    let max_name = items.iter().map(|i| i.name.len()).max().unwrap_or(4).max(4);
-    let max_desc = items
-        .iter()
-        .map(|i| i.description.as_ref().map(|d| d.len()).unwrap_or(1))
-        .max()
-        .unwrap_or(11)
-        .max(11);
-
-    // Header
-    println!(
-        "{:<width_name$} {:<width_desc$} ITEMS",
-        "NAME",
-        "DESCRIPTION",
-        width_name = max_name,
-        width_desc = max_desc
-    );
-
-    // Rows
+    // The TODOS and WEIGHT columns only appear when something has open
+    // tasks or a known weight
+    let todos = items.iter().any(|item| item.open_todos.is_some());
+    let weights = items.iter().any(|item| item.weight_g.is_some());
+    let mut header = vec!["NAME", "DESCRIPTION", "ITEMS"];
+    if todos {
+        header.push("TODOS");
+    }
+    if weights {
+        header.push("WEIGHT");
+    }
+    let mut table = Table::new(&header).shrink(0).shrink(1);
+    for item in items {
+        let mut row = vec![
+            item.name.clone(),
+            item.description.clone().unwrap_or_else(|| "-".to_string()),
+            child_count_cell(item.child_count),
+        ];
+        if todos {
+            row.push(child_count_cell(item.open_todos.unwrap_or(0)));
+        }
+        if weights {
+            row.push(match item.weight_g {
+                Some(weight) if item.heavy => {
+                    format!("{} (heavy)", quantity::format_weight(weight))
+                }
+                Some(weight) => quantity::format_weight(weight),
+                None => "-".to_string(),
+            });
+        }
+        table.push(row);
+    }
+    print_table(w, &table, items, colors)
+}
+
+/// Write a rendered table, coloring each row by its item's age.
+fn print_table(
+    w: &mut impl Write,
+    table: &Table,
+    items: &[ListItem],
+    colors: Option<&AgeColors>,
+) -> Result<()> {
+    let lines = table.render(table::terminal_width());
+    let (header, rows) = lines.split_first().expect("table has a header");
+    writeln!(w, "{}", header)?;
+    for (item, line) in items.iter().zip(rows) {
+        writeln!(w, "{}", paint(colors, &item.updated_at, line.clone()))?;
+    }
+    Ok(())
+}
+
+fn child_count_cell(count: i64) -> String {
+    if count > 0 {
+        count.to_string()
+    } else {
+        "-".to_string()
+    }
+}
+
+/// Extended table for `list --long`, one line per item like `ls -l`.
+fn print_list_items_long(
+    w: &mut impl Write,
+    items: &[ListItem],
+    colors: Option<&AgeColors>,
+) -> Result<()> {
+    let mut table = Table::new(&["ID", "ITEMS", "CREATED", "UPDATED", "PATH", "DESCRIPTION"])
+        .align_right(0)
+        .align_right(1)
+        .shrink(4)
+        .shrink(5);
     for item in items {
-        let desc = item.description.as_deref().unwrap_or("-");
-        let items_str = if item.child_count > 0 {
+        let (path, created, updated) = match &item.details {
+            Some(d) => (
+                db::join_path(&d.path),
+                d.created_at.clone(),
+                d.updated_at.clone(),
+            ),
+            None => (item.name.clone(), "-".to_string(), "-".to_string()),
+        };
+        table.push(vec![
+            item.id.to_string(),
+            child_count_cell(item.child_count),
+            created,
+            updated,
+            path,
+            item.description.clone().unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    print_table(w, &table, items, colors)
+}
+
+// Markdown formatters
+
+/// Escape a value for a Markdown table cell.
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+fn print_markdown_table(w: &mut impl Write, header: &[&str], rows: &[Vec<String>]) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    writeln!(w, "| {} |", header.join(" | "))?;
+    writeln!(w, "|{}", "---|".repeat(header.len()))?;
+    for row in rows {
+        let cells: Vec<_> = row.iter().map(|c| markdown_cell(c)).collect();
+        writeln!(w, "| {} |", cells.join(" | "))?;
+    }
+    Ok(())
+}
+
+fn print_list_items_markdown(w: &mut impl Write, items: &[ListItem]) -> Result<()> {
+    let items_cell = |item: &ListItem| {
+        if item.child_count > 0 {
             item.child_count.to_string()
         } else {
             "-".to_string()
-        };
-        println!(
-            "{:<width_name$} {:<width_desc$} {}",
-            item.name,
-            desc,
-            items_str,
-            width_name = max_name,
-            width_desc = max_desc
+        }
+    };
+
+    if items.iter().any(|item| item.details.is_some()) {
+        let rows: Vec<_> = items
+            .iter()
+            .map(|item| {
+                let details = item.details.as_ref();
+                vec![
+                    item.id.to_string(),
+                    items_cell(item),
+                    details.map(|d| d.created_at.clone()).unwrap_or_default(),
+                    details.map(|d| d.updated_at.clone()).unwrap_or_default(),
+                    details
+                        .map(|d| db::join_path(&d.path))
+                        .unwrap_or_else(|| item.name.clone()),
+                    item.description.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        return print_markdown_table(
+            w,
+            &["ID", "Items", "Created", "Updated", "Path", "Description"],
+            &rows,
         );
     }
 
+    let rows: Vec<_> = items
+        .iter()
+        .map(|item| {
+            vec![
+                item.name.clone(),
+                item.description.clone().unwrap_or_default(),
+                items_cell(item),
+            ]
+        })
+        .collect();
+    print_markdown_table(w, &["Name", "Description", "Items"], &rows)
+}
+
+/// Page footer as its own paragraph, so it is not read as a table row.
+fn print_markdown_footer(w: &mut impl Write, shown: usize, page: Option<Page>) -> Result<()> {
+    if page.is_some() {
+        writeln!(w)?;
+    }
+    print_page_footer(w, shown, page)
+}
+
+/// Nested bullet list, two spaces of indent per level.
+fn print_tree_items_markdown(w: &mut impl Write, items: &[TreeItem], depth: usize) -> Result<()> {
+    for item in items {
+        write!(w, "{}- {}", "  ".repeat(depth), item.name)?;
+        if let Some(ref desc) = item.description {
+            write!(w, " ({})", desc)?;
+        }
+        if item.child_count > 0 {
+            write!(w, " [{}]", item.child_count)?;
+        }
+        writeln!(w)?;
+        print_tree_items_markdown(w, &item.children, depth + 1)?;
+    }
     Ok(())
 }
 
 // JSON formatter
 
-fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+fn print_json<T: Serialize + ?Sized>(w: &mut impl Write, value: &T) -> Result<()> {
     let json = serde_json::to_string(value)?;
-    println!("{}", json);
+    writeln!(w, "{}", json)?;
+    Ok(())
+}
+
+fn print_json_page<T: Serialize>(
+    w: &mut impl Write,
+    items: &[T],
+    page: Option<Page>,
+) -> Result<()> {
+    let Some(page) = page else {
+        return print_json(w, items);
+    };
+
+    #[derive(Serialize)]
+    struct PagedOutput<'a, T> {
+        total: usize,
+        offset: usize,
+        limit: Option<usize>,
+        items: &'a [T],
+    }
+    print_json(
+        w,
+        &PagedOutput {
+            total: page.total,
+            offset: page.offset,
+            limit: page.limit,
+            items,
+        },
+    )
+}
+
+fn print_page_footer(w: &mut impl Write, shown: usize, page: Option<Page>) -> Result<()> {
+    let Some(page) = page else {
+        return Ok(());
+    };
+    if shown == 0 {
+        writeln!(w, "Showing 0 of {}", page.total)?;
+    } else {
+        writeln!(
+            w,
+            "Showing {}-{} of {}",
+            page.offset + 1,
+            page.offset + shown,
+            page.total
+        )?;
+    }
     Ok(())
 }
 
 // CSV formatters
 
-fn print_item_csv(item: &ItemWithPath) -> Result<()> {
-    let mut wtr = csv::Writer::from_writer(io::stdout());
-    wtr.write_record(["id", "name", "description", "path"])?;
-    wtr.write_record([
-        &item.id.to_string(),
-        &item.name,
-        item.description.as_deref().unwrap_or(""),
-        &item.path.join("/"),
-    ])?;
+fn print_item_csv(w: &mut impl Write, item: &ItemWithPath) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(&mut *w);
+    let mut header = vec!["id", "name", "description", "path"];
+    let mut record = vec![
+        item.id.to_string(),
+        item.name.clone(),
+        item.description.clone().unwrap_or_default(),
+        db::join_path(&item.path),
+    ];
+    if let Some(locations) = &item.locations {
+        header.push("locations");
+        record.push(
+            locations
+                .iter()
+                .map(|l| format!("{}@{}", db::join_path(&l.path), l.moved_at))
+                .collect::<Vec<_>>()
+                .join(";"),
+        );
+    }
+    wtr.write_record(&header)?;
+    wtr.write_record(&record)?;
     wtr.flush()?;
     Ok(())
 }
 
-fn print_items_csv(items: &[ItemWithPath]) -> Result<()> {
-    let mut wtr = csv::Writer::from_writer(io::stdout());
+fn print_items_csv(w: &mut impl Write, items: &[ItemWithPath]) -> Result<()> {
+    let mut wtr = csv::Writer::from_writer(&mut *w);
     wtr.write_record(["id", "name", "description", "path"])?;
     for item in items {
         wtr.write_record([
             &item.id.to_string(),
             &item.name,
             item.description.as_deref().unwrap_or(""),
-            &item.path.join("/"),
+            &db::join_path(&item.path),
         ])?;
     }
     wtr.flush()?;
     Ok(())
 }
 
-fn print_list_items_csv(items: &[ListItem]) -> Result<()> {
-    let mut wtr = csv::Writer::from_writer(io::stdout());
-    wtr.write_record(["id", "name", "description", "child_count"])?;
+fn print_list_items_csv(w: &mut impl Write, items: &[ListItem]) -> Result<()> {
+    let long = items.iter().any(|item| item.details.is_some());
+    let mut wtr = csv::Writer::from_writer(&mut *w);
+    let mut header = vec!["id", "name", "description", "child_count"];
+    if long {
+        header.extend(["path", "created_at", "updated_at"]);
+    }
+    wtr.write_record(&header)?;
     for item in items {
-        wtr.write_record([
-            &item.id.to_string(),
-            &item.name,
-            item.description.as_deref().unwrap_or(""),
-            &item.child_count.to_string(),
-        ])?;
+        let mut record = vec![
+            item.id.to_string(),
+            item.name.clone(),
+            item.description.clone().unwrap_or_default(),
+            item.child_count.to_string(),
+        ];
+        if let Some(details) = &item.details {
+            record.extend([
+                db::join_path(&details.path),
+                details.created_at.clone(),
+                details.updated_at.clone(),
+            ]);
+        }
+        wtr.write_record(&record)?;
     }
     wtr.flush()?;
     Ok(())
@@ -325,12 +3063,64 @@ fn print_list_items_csv(items: &[ListItem]) -> Result<()> {
 // Tree output (for recursive list)
 
 /// Output tree items with hierarchy (for recursive list command).
-pub fn print_tree_items(items: &[TreeItem], format: Format) -> Result<()> {
+///
+/// Human rows are colored by age when `colors` is set.
+pub fn print_tree_items(
+    w: &mut impl Write,
+    items: &[TreeItem],
+    colors: Option<&AgeColors>,
+    format: Format,
+) -> Result<()> {
     match format {
-        Format::Human => print_tree_items_human(items),
-        Format::Json => print_json(items),
-        Format::Csv => print_tree_items_csv(items),
+        Format::Human => print_tree_items_human(w, items, colors),
+        Format::Json => print_json(w, items),
+        Format::Csv => print_tree_items_csv(w, items),
+        Format::Markdown => print_tree_items_markdown(w, items, 0),
+        Format::Quiet => print_tree_ids(w, items),
+    }
+}
+
+/// Print each item through a template, one rendering per line (for
+/// `--template`).
+pub fn print_templated<T: Serialize>(
+    w: &mut impl Write,
+    items: &[T],
+    template: &Template,
+) -> Result<()> {
+    for item in items {
+        writeln!(w, "{}", template.render(item)?)?;
+    }
+    Ok(())
+}
+
+/// Print a tree through a template, parents before their children. Each
+/// item also gets its `path` and its `depth` (0 at the top).
+pub fn print_tree_templated(
+    w: &mut impl Write,
+    items: &[TreeItem],
+    template: &Template,
+) -> Result<()> {
+    fn walk(
+        w: &mut impl Write,
+        items: &[TreeItem],
+        template: &Template,
+        path: &mut Vec<String>,
+    ) -> Result<()> {
+        for item in items {
+            path.push(item.name.clone());
+            let mut context = serde_json::to_value(item)?;
+            if let Some(fields) = context.as_object_mut() {
+                fields.remove("children");
+                fields.insert("path".into(), path.clone().into());
+                fields.insert("depth".into(), (path.len() - 1).into());
+            }
+            writeln!(w, "{}", template.render(&context)?)?;
+            walk(w, &item.children, template, path)?;
+            path.pop();
+        }
+        Ok(())
     }
+    walk(w, items, template, &mut Vec::new())
 }
 
 /// Tree rendering characters
@@ -339,23 +3129,38 @@ const TREE_LAST: &str = "└── ";
 const TREE_VERTICAL: &str = "│   ";
 const TREE_SPACE: &str = "    ";
 
-fn print_tree_items_human(items: &[TreeItem]) -> Result<()> {
-    fn print_item_line(item: &TreeItem) {
-        print!("{}", item.name);
+fn print_tree_items_human(
+    w: &mut impl Write,
+    items: &[TreeItem],
+    colors: Option<&AgeColors>,
+) -> Result<()> {
+    fn print_item_line(
+        w: &mut impl Write,
+        item: &TreeItem,
+        colors: Option<&AgeColors>,
+    ) -> Result<()> {
+        let mut line = item.name.clone();
         if let Some(ref desc) = item.description {
-            print!(" ({})", desc);
+            line.push_str(&format!(" ({})", desc));
         }
         if item.child_count > 0 {
-            print!(" [{}]", item.child_count);
+            line.push_str(&format!(" [{}]", item.child_count));
         }
-        println!();
+        writeln!(w, "{}", paint(colors, &item.updated_at, line))?;
+        Ok(())
     }
 
-    fn print_subtree(item: &TreeItem, prefix: &str, is_last: bool) {
+    fn print_subtree(
+        w: &mut impl Write,
+        item: &TreeItem,
+        prefix: &str,
+        is_last: bool,
+        colors: Option<&AgeColors>,
+    ) -> Result<()> {
         let connector = if is_last { TREE_LAST } else { TREE_BRANCH };
 
-        print!("{}{}", prefix, connector);
-        print_item_line(item);
+        write!(w, "{}{}", prefix, connector)?;
+        print_item_line(w, item, colors)?;
 
         let child_prefix = format!(
             "{}{}",
@@ -365,25 +3170,26 @@ fn print_tree_items_human(items: &[TreeItem]) -> Result<()> {
 
         let child_count = item.children.len();
         for (i, child) in item.children.iter().enumerate() {
-            print_subtree(child, &child_prefix, i == child_count - 1);
+            print_subtree(w, child, &child_prefix, i == child_count - 1, colors)?;
         }
+        Ok(())
     }
 
     for item in items {
         // Root items: print without prefix
-        print_item_line(item);
+        print_item_line(w, item, colors)?;
 
         // Print children with tree structure
         let child_count = item.children.len();
         for (i, child) in item.children.iter().enumerate() {
-            print_subtree(child, "", i == child_count - 1);
+            print_subtree(w, child, "", i == child_count - 1, colors)?;
         }
     }
 
     Ok(())
 }
 
-fn print_tree_items_csv(items: &[TreeItem]) -> Result<()> {
+fn print_tree_items_csv(w: &mut impl Write, items: &[TreeItem]) -> Result<()> {
     // Flatten tree for CSV output
     fn collect_flat(items: &[TreeItem], result: &mut Vec<ListItem>) {
         for item in items {
@@ -392,6 +3198,12 @@ fn print_tree_items_csv(items: &[TreeItem]) -> Result<()> {
                 name: item.name.clone(),
                 description: item.description.clone(),
                 child_count: item.child_count,
+                uuid: item.uuid.clone(),
+                open_todos: None,
+                weight_g: None,
+                heavy: false,
+                updated_at: item.updated_at.clone(),
+                details: None,
             });
             collect_flat(&item.children, result);
         }
@@ -399,5 +3211,168 @@ fn print_tree_items_csv(items: &[TreeItem]) -> Result<()> {
 
     let mut flat_items = Vec::new();
     collect_flat(items, &mut flat_items);
-    print_list_items_csv(&flat_items)
+    print_list_items_csv(w, &flat_items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_item() -> ItemWithPath {
+        ItemWithPath {
+            id: 3,
+            name: "hammer".to_string(),
+            description: Some("claw hammer".to_string()),
+            path: vec!["garage".to_string(), "hammer".to_string()],
+            kind: ItemKind::Item,
+            child_count: None,
+            created_at: "2024-01-15 10:30:00".to_string(),
+            updated_at: "2024-01-15 10:30:00".to_string(),
+            quantity: 1,
+            unit: None,
+            uuid: None,
+            open_todos: None,
+            weight_g: None,
+            distance: None,
+            verified_at: None,
+            min_quantity: None,
+            min_unit: None,
+            expires_at: None,
+            locations: None,
+            aliases: None,
+            group: None,
+            stats: None,
+        }
+    }
+
+    fn capture(f: impl FnOnce(&mut Vec<u8>) -> Result<()>) -> String {
+        let mut buf = Vec::new();
+        f(&mut buf).expect("write to buffer");
+        String::from_utf8(buf).expect("utf-8 output")
+    }
+
+    #[test]
+    fn markdown_table_escapes_pipes() {
+        let mut item = sample_item();
+        item.description = Some("claw | framing".to_string());
+        let out = capture(|w| print_items(w, &[item], None, Format::Markdown));
+        assert_eq!(
+            out,
+            "| Path | Description |\n|---|---|\n| garage/hammer | claw \\| framing |\n"
+        );
+    }
+
+    #[test]
+    fn broken_pipe_is_detected() {
+        let err = anyhow::Error::new(io::Error::from(io::ErrorKind::BrokenPipe));
+        assert!(is_broken_pipe(&err));
+
+        let err = err.context("writing list");
+        assert!(is_broken_pipe(&err));
+
+        let err = anyhow::Error::new(io::Error::from(io::ErrorKind::NotFound));
+        assert!(!is_broken_pipe(&err));
+    }
+
+    #[test]
+    fn added_human_shows_container() {
+        let out = capture(|w| print_added(w, &sample_item(), Format::Human));
+        assert_eq!(out, "Added: hammer\n  -> garage\n");
+    }
+
+    #[test]
+    fn items_json_is_single_line_array() {
+        let out = capture(|w| print_items(w, &[sample_item()], None, Format::Json));
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value[0]["path"][1], "hammer");
+    }
+
+    #[test]
+    fn items_csv_joins_path() {
+        let out = capture(|w| print_items(w, &[sample_item()], None, Format::Csv));
+        assert_eq!(
+            out,
+            "id,name,description,path\n3,hammer,claw hammer,garage/hammer\n"
+        );
+    }
+
+    #[test]
+    fn tree_human_draws_connectors() {
+        let tree = vec![TreeItem {
+            id: 1,
+            name: "garage".to_string(),
+            description: None,
+            child_count: 2,
+            uuid: None,
+            updated_at: String::new(),
+            children: vec![
+                TreeItem {
+                    id: 2,
+                    name: "hammer".to_string(),
+                    description: None,
+                    child_count: 0,
+                    uuid: None,
+                    updated_at: String::new(),
+                    children: Vec::new(),
+                },
+                TreeItem {
+                    id: 3,
+                    name: "saw".to_string(),
+                    description: None,
+                    child_count: 0,
+                    uuid: None,
+                    updated_at: String::new(),
+                    children: Vec::new(),
+                },
+            ],
+        }];
+        let out = capture(|w| print_tree_items(w, &tree, None, Format::Human));
+        assert_eq!(out, "garage [2]\n├── hammer\n└── saw\n");
+    }
+
+    #[test]
+    fn expiring_counts_days_left() {
+        let dated = |id, expires_at: &str| ItemWithPath {
+            id,
+            expires_at: Some(expires_at.to_string()),
+            ..sample_item()
+        };
+        let items = [
+            dated(1, "2024-05-30"),
+            dated(2, "2024-06-01"),
+            dated(3, "2024-06-11"),
+        ];
+        let out = capture(|w| print_expiring(w, &items, "2024-06-01", "30d", Format::Human));
+        assert_eq!(
+            out,
+            "EXPIRES       LEFT PATH\n\
+             2024-05-30 expired garage/hammer\n\
+             2024-06-01   today garage/hammer\n\
+             2024-06-11     10d garage/hammer\n"
+        );
+        let out = capture(|w| print_expiring(w, &[], "2024-06-01", "2w", Format::Human));
+        assert_eq!(out, "Nothing expires within 2w\n");
+    }
+
+    #[test]
+    fn age_colors_by_updated_at() {
+        let colors = AgeColors::new("2024-06-01", "2024-01-01").unwrap();
+        assert_eq!(
+            colors.paint("2024-06-15 00:00:00", "new"),
+            "\x1b[32mnew\x1b[0m"
+        );
+        assert_eq!(
+            colors.paint("2024-03-01 00:00:00", "aging"),
+            "\x1b[90maging\x1b[0m"
+        );
+        assert_eq!(
+            colors.paint("2023-12-31 23:59:59", "old"),
+            "\x1b[31mold\x1b[0m"
+        );
+        assert!(
+            AgeColors::resolve(ColorChoice::Always, Format::Json, None, None)
+                .unwrap()
+                .is_none()
+        );
+    }
 }
@@ -0,0 +1,22 @@
+//! Progress bars for commands that can run for a while (`import`, `sync`).
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::{self, IsTerminal};
+
+use crate::output::Format;
+
+/// A bar on stderr counting up to `len`, labelled `message`.
+///
+/// Hidden when stdout is not a terminal or with `--quiet`, so piped output
+/// and scripts never see it.
+pub fn bar(len: u64, message: &'static str, format: Format) -> ProgressBar {
+    if matches!(format, Format::Quiet) || !io::stdout().is_terminal() {
+        return ProgressBar::hidden();
+    }
+    let style = ProgressStyle::with_template("{msg} [{bar:30}] {pos}/{len}")
+        .expect("valid progress template")
+        .progress_chars("=> ");
+    ProgressBar::new(len)
+        .with_style(style)
+        .with_message(message)
+}
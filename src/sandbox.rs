@@ -0,0 +1,181 @@
+//! Throwaway database copies for `--sandbox`.
+//!
+//! The command runs against a copy of the real database in a temporary
+//! directory that is deleted afterwards, so risky operations can be tried
+//! and their output inspected without changing anything. Without a real
+//! database to copy, the sandbox holds a small tutorial inventory instead.
+//!
+//! `--from-export` uses the same kind of temporary database, loaded from an
+//! `invy export` JSON file, to inspect a snapshot without importing it.
+//!
+//! See SPEC.md#sandbox
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::db;
+use crate::model::ExportRow;
+use crate::quantity::Quantity;
+
+/// Items in the tutorial inventory: container path, name, description,
+/// quantity. Containers are created along the way.
+const TUTORIAL: &[(&str, &str, Option<&str>, &str)] = &[
+    ("/", "garage", Some("Detached, unheated"), "1"),
+    ("garage", "toolbox", Some("Red metal box on the shelf"), "1"),
+    ("garage/toolbox", "hammer", Some("Claw hammer, 16oz"), "1"),
+    ("garage/toolbox", "screwdrivers", None, "6"),
+    ("garage/toolbox", "tape measure", Some("5m"), "1"),
+    ("garage", "extension cable", None, "10m"),
+    ("/", "kitchen", None, "1"),
+    ("kitchen", "drawer", Some("Left of the sink"), "1"),
+    ("kitchen/drawer", "AA batteries", None, "8"),
+    ("kitchen/drawer", "scissors", None, "1"),
+    (
+        "kitchen/drawer",
+        "first-aid kit",
+        Some("Plasters and bandages"),
+        "1",
+    ),
+    ("/", "office", None, "1"),
+    ("office", "desk", None, "1"),
+    ("office/desk", "stapler", None, "1"),
+    (
+        "office/desk",
+        "A\\/V cables",
+        Some("HDMI and audio leads"),
+        "4",
+    ),
+];
+
+/// A temporary database, removed with its directory when dropped.
+#[derive(Debug)]
+pub struct Sandbox {
+    dir: PathBuf,
+    db_path: PathBuf,
+}
+
+impl Sandbox {
+    /// Copy the database at `real` (default location when `None`) into a new
+    /// temporary directory, or seed the tutorial inventory if there is none.
+    pub fn create(real: Option<&Path>) -> Result<Self> {
+        let real = match real {
+            Some(path) => path.to_path_buf(),
+            None => db::default_db_path()?,
+        };
+
+        let sandbox = Self::empty()?;
+        let mut stderr = io::stderr();
+        if real.exists() {
+            fs::copy(&real, &sandbox.db_path)
+                .with_context(|| format!("Failed to copy {:?}", real))?;
+            // Committed changes not yet checkpointed live in the WAL file
+            let wal = wal_path(&real);
+            if wal.exists() {
+                fs::copy(&wal, wal_path(&sandbox.db_path))
+                    .with_context(|| format!("Failed to copy {:?}", wal))?;
+            }
+            writeln!(
+                stderr,
+                "Sandbox: using a copy of {}; changes will be discarded",
+                real.display()
+            )?;
+        } else {
+            seed_tutorial(&sandbox.db_path)?;
+            writeln!(
+                stderr,
+                "Sandbox: no database at {}; using the tutorial inventory",
+                real.display()
+            )?;
+        }
+        Ok(sandbox)
+    }
+
+    /// Load the rows of an `invy export` JSON file into a new temporary
+    /// database, keeping their IDs, UUIDs and timestamps.
+    pub fn from_export(file: &Path) -> Result<Self> {
+        let contents = fs::read(file).with_context(|| format!("Failed to read {:?}", file))?;
+        let rows: Vec<ExportRow> = serde_json::from_slice(&contents).with_context(|| {
+            format!(
+                "{:?} is not an invy export (invy export --target json)",
+                file
+            )
+        })?;
+
+        let sandbox = Self::empty()?;
+        let conn = db::open(Some(&sandbox.db_path))?;
+        let ids: HashSet<i64> = rows.iter().map(|row| row.id).collect();
+        let mut rows: Vec<&ExportRow> = rows.iter().collect();
+        // Containers before their contents
+        rows.sort_by_key(|row| row.depth);
+        db::with_tx(&conn, |tx| {
+            for row in &rows {
+                // The container of an exported subtree was not exported
+                let container_id = row.container_id.filter(|id| ids.contains(id));
+                db::insert_export_row(tx, row, container_id)?;
+            }
+            Ok(())
+        })?;
+        writeln!(
+            io::stderr(),
+            "Reading {} items from {}; nothing will be saved",
+            rows.len(),
+            file.display()
+        )?;
+        Ok(sandbox)
+    }
+
+    /// A new temporary directory for the database, with nothing in it yet.
+    fn empty() -> Result<Self> {
+        let mut bytes = [0u8; 6];
+        getrandom::fill(&mut bytes)
+            .map_err(|e| anyhow!("Failed to name sandbox directory: {}", e))?;
+        let suffix: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        let dir = std::env::temp_dir().join(format!("invy-sandbox-{}", suffix));
+        fs::create_dir(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        Ok(Self {
+            db_path: dir.join("invy.db"),
+            dir,
+        })
+    }
+
+    /// Path of the temporary database.
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push("-wal");
+    PathBuf::from(path)
+}
+
+/// Create a database at `path` holding the tutorial inventory.
+fn seed_tutorial(path: &Path) -> Result<()> {
+    let conn = db::open(Some(path))?;
+    db::with_tx(&conn, |tx| {
+        for &(container, name, desc, quantity) in TUTORIAL {
+            let container_id = match container {
+                "/" => None,
+                path => {
+                    Some(db::resolve_or_create_container(tx, path, db::Placement::default())?.id)
+                }
+            };
+            let item = db::insert_item(tx, &db::unescape_name(name), desc, container_id)?;
+            let quantity = Quantity::parse(quantity)?;
+            if quantity != Quantity::count(1) {
+                db::update_item_quantity(tx, item.id, &quantity)?;
+            }
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,272 @@
+//! Suggestions for mistyped commands and flags.
+//!
+//! clap already points out close matches ("a similar subcommand exists");
+//! this adds common synonyms from other tools (`remove`, `search`,
+//! `--description`), spells out the corrected invocation and links the SPEC
+//! section for the command.
+
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Command, CommandFactory};
+
+use crate::cli::Cli;
+use crate::fuzzy;
+
+/// Words people reach for, mapped to invy's subcommands.
+const COMMAND_SYNONYMS: &[(&str, &str)] = &[
+    ("remove", "rm"),
+    ("delete", "rm"),
+    ("del", "rm"),
+    ("search", "find"),
+    ("grep", "find"),
+    ("locate", "find"),
+    ("move", "mv"),
+    ("copy", "cp"),
+    ("ls", "list"),
+    ("tree", "list"),
+    ("new", "add"),
+    ("create", "add"),
+    ("info", "show"),
+    ("get", "show"),
+    ("rename", "edit"),
+    ("use", "consume"),
+    ("take", "consume"),
+];
+
+/// Flag spellings from other tools, mapped to invy's.
+const FLAG_SYNONYMS: &[(&str, &str)] = &[
+    ("--description", "--desc"),
+    ("--container", "--in"),
+    ("--into", "--in"),
+    ("--to", "--in"),
+    ("--parent", "--in"),
+    ("--quantity", "--qty"),
+    ("--count", "--qty"),
+    ("--recurse", "--recursive"),
+];
+
+/// A corrected command line and where it is documented.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub invocation: String,
+    /// SPEC.md section, e.g. `SPEC.md#invy-rm-item`
+    pub docs: Option<String>,
+}
+
+/// Suggest a fix for a parse error of `args` (including the program name).
+pub fn suggest(err: &clap::Error, args: &[String]) -> Option<Suggestion> {
+    let root = Cli::command();
+    let (bad, subcommand, replacement) = match err.kind() {
+        ErrorKind::InvalidSubcommand => {
+            let bad = context(err, ContextKind::InvalidSubcommand)?;
+            let name = suggest_command(&root, &bad)?;
+            (bad, name.clone(), name)
+        }
+        ErrorKind::UnknownArgument => {
+            let bad = context(err, ContextKind::InvalidArg)?;
+            let subcommand = args
+                .iter()
+                .skip(1)
+                .find_map(|a| find_subcommand(&root, a))?;
+            let bad = bad.split('=').next().unwrap_or(&bad).to_string();
+            let fixed = suggest_flag(root.find_subcommand(&subcommand)?, &bad)?;
+            (bad, subcommand, fixed)
+        }
+        _ => return None,
+    };
+
+    let mut replaced = false;
+    let words: Vec<String> = args
+        .iter()
+        .skip(1)
+        .map(|arg| {
+            // A flag may carry its value: --container=garage
+            let value = arg
+                .strip_prefix(bad.as_str())
+                .filter(|rest| rest.is_empty() || rest.starts_with('='));
+            match value {
+                Some(value) if !replaced => {
+                    replaced = true;
+                    match value.strip_prefix('=') {
+                        Some(value) => format!("{}={}", replacement, quote(value)),
+                        None => replacement.clone(),
+                    }
+                }
+                _ => quote(arg),
+            }
+        })
+        .collect();
+
+    Some(Suggestion {
+        invocation: format!("invy {}", words.join(" ")),
+        docs: root.find_subcommand(&subcommand).and_then(spec_section),
+    })
+}
+
+/// Print a parse error with any suggestion and exit like clap does.
+pub fn exit(err: clap::Error) -> ! {
+    let args: Vec<String> = std::env::args().collect();
+    // `--help` and `--version` also arrive here, on stdout
+    if !err.use_stderr() {
+        err.exit()
+    }
+    // Strict mode drops clap's own "similar subcommands" tip as well
+    if strict_requested(&args) {
+        eprintln!("{}", without_tips(&err));
+        std::process::exit(err.exit_code());
+    }
+    let Some(suggestion) = suggest(&err, &args) else {
+        err.exit()
+    };
+
+    // Replace clap's own tip with the full corrected command
+    let message = without_tips(&err);
+    let (head, usage) = message.split_once("\nUsage:").unwrap_or((&message, ""));
+
+    eprintln!("{}", head.trim_end());
+    eprintln!();
+    eprintln!("  tip: did you mean `{}`?", suggestion.invocation);
+    if let Some(docs) = &suggestion.docs {
+        eprintln!("  docs: {}", docs);
+    }
+    if !usage.is_empty() {
+        eprintln!();
+        eprintln!("Usage:{}", usage);
+    }
+    std::process::exit(err.exit_code());
+}
+
+/// The rendered error minus clap's `tip:` lines.
+fn without_tips(err: &clap::Error) -> String {
+    let rendered = err.render().to_string();
+    let mut lines: Vec<&str> = rendered
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("tip:"))
+        .collect();
+    lines.dedup_by(|a, b| a.is_empty() && b.is_empty());
+    lines.join("\n")
+}
+
+/// Whether `--strict` or `INVY_STRICT` asks for strict mode, which turns
+/// suggestions off. Read directly since the arguments did not parse.
+fn strict_requested(args: &[String]) -> bool {
+    let env = std::env::var("INVY_STRICT").unwrap_or_default();
+    args.iter().any(|arg| arg == "--strict")
+        || !matches!(
+            env.to_ascii_lowercase().as_str(),
+            "" | "0" | "false" | "no" | "off" | "n" | "f"
+        )
+}
+
+fn context(err: &clap::Error, kind: ContextKind) -> Option<String> {
+    match err.get(kind)? {
+        ContextValue::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// Name of the subcommand `word` refers to, including visible aliases.
+fn find_subcommand(root: &Command, word: &str) -> Option<String> {
+    root.find_subcommand(word)
+        .map(|cmd| cmd.get_name().to_string())
+}
+
+fn suggest_command(root: &Command, bad: &str) -> Option<String> {
+    let names: Vec<&str> = root
+        .get_subcommands()
+        .flat_map(|cmd| std::iter::once(cmd.get_name()).chain(cmd.get_visible_aliases()))
+        .collect();
+    let synonyms = COMMAND_SYNONYMS.iter().map(|&(word, _)| word);
+
+    let word = fuzzy::closest(bad, names.iter().copied().chain(synonyms))?;
+    let target = COMMAND_SYNONYMS
+        .iter()
+        .find(|&&(synonym, _)| synonym == word)
+        .map_or(word, |&(_, target)| target);
+    find_subcommand(root, target)
+}
+
+fn suggest_flag(cmd: &Command, bad: &str) -> Option<String> {
+    let longs: Vec<String> = cmd
+        .get_arguments()
+        .filter_map(|arg| arg.get_long())
+        .map(|long| format!("--{}", long))
+        .collect();
+    let synonyms = FLAG_SYNONYMS
+        .iter()
+        .filter(|&&(_, target)| longs.iter().any(|l| l == target))
+        .map(|&(word, _)| word);
+
+    let word = fuzzy::closest(bad, longs.iter().map(String::as_str).chain(synonyms))?;
+    Some(
+        FLAG_SYNONYMS
+            .iter()
+            .find(|&&(synonym, _)| synonym == word)
+            .map_or(word, |&(_, target)| target)
+            .to_string(),
+    )
+}
+
+/// The `See SPEC.md#...` reference in a command's doc comment.
+fn spec_section(cmd: &Command) -> Option<String> {
+    let about = cmd.get_long_about()?.to_string();
+    let start = about.find("SPEC.md#")?;
+    let section = about[start..].split_whitespace().next()?;
+    Some(section.to_string())
+}
+
+/// Quote an argument for display if the shell would split it.
+fn quote(arg: &str) -> String {
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || "'\"$\\".contains(c)) {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    } else {
+        arg.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn suggest_for(line: &str) -> Option<Suggestion> {
+        let args: Vec<String> = line.split(' ').map(String::from).collect();
+        let err = Cli::try_parse_from(&args).expect_err("should not parse");
+        suggest(&err, &args)
+    }
+
+    #[test]
+    fn suggests_synonyms_and_typos() {
+        assert_eq!(
+            suggest_for("invy remove hammer"),
+            Some(Suggestion {
+                invocation: "invy rm hammer".to_string(),
+                docs: Some("SPEC.md#invy-rm-item".to_string()),
+            })
+        );
+        assert_eq!(
+            suggest_for("invy serach drill").unwrap().invocation,
+            "invy find drill"
+        );
+        assert_eq!(
+            suggest_for("invy lsit -r").unwrap().invocation,
+            "invy list -r"
+        );
+        assert_eq!(suggest_for("invy frobnicate"), None);
+    }
+
+    #[test]
+    fn suggests_flags() {
+        assert_eq!(
+            suggest_for("invy add drill --description cordless")
+                .unwrap()
+                .invocation,
+            "invy add drill --desc cordless"
+        );
+        assert_eq!(
+            suggest_for("invy add drill --container=garage")
+                .unwrap()
+                .invocation,
+            "invy add drill --in=garage"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Plain-text tables for human output.
+//!
+//! Widths are measured in terminal columns rather than bytes, so CJK names
+//! and emoji line up. When a table is wider than the terminal, its
+//! shrinkable columns are narrowed (widest first) and their cells truncated
+//! with `…`.
+
+use std::io::{self, IsTerminal};
+use unicode_width::UnicodeWidthStr;
+
+/// Columns a shrinkable column is never narrowed below.
+const MIN_WIDTH: usize = 8;
+
+/// Horizontal alignment of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug)]
+struct Column {
+    title: &'static str,
+    align: Align,
+    shrink: bool,
+}
+
+/// A table of text cells, rendered with one space between columns.
+#[derive(Debug)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// A table with the given column titles, all left-aligned and fixed.
+    pub fn new(titles: &[&'static str]) -> Self {
+        Table {
+            columns: titles
+                .iter()
+                .map(|&title| Column {
+                    title,
+                    align: Align::Left,
+                    shrink: false,
+                })
+                .collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Right-align column `index`.
+    pub fn align_right(mut self, index: usize) -> Self {
+        self.columns[index].align = Align::Right;
+        self
+    }
+
+    /// Let column `index` be truncated to fit the terminal.
+    pub fn shrink(mut self, index: usize) -> Self {
+        self.columns[index].shrink = true;
+        self
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    /// Render the header and rows as lines, fitting `max_width` columns
+    /// where possible. The last column is not padded.
+    pub fn render(&self, max_width: Option<usize>) -> Vec<String> {
+        let mut widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                self.rows
+                    .iter()
+                    .map(|row| width(&row[i]))
+                    .chain([width(col.title)])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        if let Some(max_width) = max_width {
+            self.fit(&mut widths, max_width);
+        }
+
+        let header: Vec<String> = self.columns.iter().map(|c| c.title.to_string()).collect();
+        std::iter::once(&header)
+            .chain(&self.rows)
+            .map(|row| self.render_row(row, &widths))
+            .collect()
+    }
+
+    /// Narrow shrinkable columns, widest first, until the table fits.
+    fn fit(&self, widths: &mut [usize], max_width: usize) {
+        let gaps = widths.len().saturating_sub(1);
+        while widths.iter().sum::<usize>() + gaps > max_width {
+            let widest = (0..widths.len())
+                .filter(|&i| self.columns[i].shrink && widths[i] > MIN_WIDTH)
+                .max_by_key(|&i| (widths[i], std::cmp::Reverse(i)));
+            match widest {
+                Some(i) => widths[i] -= 1,
+                None => break,
+            }
+        }
+    }
+
+    fn render_row(&self, row: &[String], widths: &[usize]) -> String {
+        let last = row.len() - 1;
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&self.columns)
+            .zip(widths)
+            .enumerate()
+            .map(|(i, ((cell, col), &w))| {
+                let cell = truncate(cell, w);
+                let fill = " ".repeat(w - width(&cell));
+                match col.align {
+                    Align::Right => format!("{}{}", fill, cell),
+                    Align::Left if i == last => cell,
+                    Align::Left => format!("{}{}", cell, fill),
+                }
+            })
+            .collect();
+        cells.join(" ")
+    }
+}
+
+/// Display width of `s` in terminal columns.
+pub fn width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// Cut `s` to at most `max` columns, marking the cut with `…`.
+pub fn truncate(s: &str, max: usize) -> String {
+    if width(s) <= max {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+        if used + w + 1 > max {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out.push('…');
+    out
+}
+
+/// Width of the terminal stdout is attached to, or `None` when output goes
+/// to a file or pipe (nothing is truncated then).
+pub fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_size::terminal_size().map(|(w, _)| w.0 as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_wide_characters() {
+        let mut table = Table::new(&["NAME", "N"]).align_right(1);
+        table.push(vec!["工具箱".to_string(), "3".to_string()]);
+        table.push(vec!["🔨 hammer".to_string(), "12".to_string()]);
+
+        assert_eq!(
+            table.render(None),
+            ["NAME       N", "工具箱     3", "🔨 hammer 12"]
+        );
+    }
+
+    #[test]
+    fn shrinks_widest_column_to_fit() {
+        let mut table = Table::new(&["NAME", "DESCRIPTION", "ITEMS"])
+            .shrink(0)
+            .shrink(1);
+        table.push(vec![
+            "toolbox".to_string(),
+            "red metal box with two trays".to_string(),
+            "3".to_string(),
+        ]);
+
+        let lines = table.render(Some(30));
+        assert_eq!(lines[1], "toolbox red metal box w… 3");
+        assert!(lines.iter().all(|l| width(l) <= 30));
+    }
+
+    #[test]
+    fn truncate_respects_double_width() {
+        assert_eq!(truncate("工具箱", 6), "工具箱");
+        assert_eq!(truncate("工具箱", 5), "工具…");
+        assert_eq!(truncate("工具箱", 4), "工…");
+    }
+}
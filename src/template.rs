@@ -0,0 +1,106 @@
+//! Output templates for `--template` and the `[templates]` config table.
+//!
+//! Templates use minijinja syntax (`{{ name }} :: {{ path }}`) and are
+//! rendered once per item in the human output of `find`, `list` and `show`,
+//! so scripts can shape lines without post-processing JSON.
+//!
+//! See SPEC.md#output-templates
+
+use anyhow::{anyhow, Context, Result};
+use minijinja::Environment;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::db;
+use crate::output::Format;
+
+/// A parsed template, ready to render items.
+#[derive(Debug)]
+pub struct Template {
+    env: Environment<'static>,
+    source: String,
+}
+
+impl Template {
+    /// Parse `source`, failing on syntax errors before any output is written.
+    pub fn new(source: &str) -> Result<Self> {
+        let env = Environment::new();
+        env.template_from_str(source)
+            .with_context(|| format!("invalid template '{}'", source))?;
+        Ok(Self {
+            env,
+            source: source.to_string(),
+        })
+    }
+
+    /// The template named by `--template`: a template from `[templates]`
+    /// when one has that name, the value itself otherwise. Templates only
+    /// shape human output.
+    pub fn from_arg(
+        value: Option<&str>,
+        named: &HashMap<String, String>,
+        format: Format,
+    ) -> Result<Option<Self>> {
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        if !matches!(format, Format::Human) {
+            return Err(anyhow!("--template applies only to human output"));
+        }
+        let source = named.get(value).map_or(value, String::as_str);
+        Self::new(source).map(Some)
+    }
+
+    /// Render one item. Its fields are available by their JSON names, except
+    /// that `path` is the slash-separated path and `container` the path of
+    /// the container it is in (empty at root).
+    pub fn render(&self, item: &impl Serialize) -> Result<String> {
+        let mut context = serde_json::to_value(item)?;
+        if let Some(fields) = context.as_object_mut() {
+            let path: Option<Vec<String>> = fields
+                .get("path")
+                .and_then(|path| serde_json::from_value(path.clone()).ok());
+            if let Some(path) = path {
+                let container = &path[..path.len().saturating_sub(1)];
+                fields.insert("container".into(), db::join_path(container).into());
+                fields.insert("path".into(), db::join_path(&path).into());
+            }
+        }
+        self.env
+            .render_str(&self.source, context)
+            .with_context(|| format!("failed to render template '{}'", self.source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_fields_and_joined_path() {
+        let template = Template::new("{{ name }} :: {{ path }} in {{ container }}").unwrap();
+        let item = serde_json::json!({"name": "hammer", "path": ["garage", "a/b", "hammer"]});
+        assert_eq!(
+            template.render(&item).unwrap(),
+            "hammer :: garage/a\\/b/hammer in garage/a\\/b"
+        );
+    }
+
+    #[test]
+    fn missing_fields_render_empty() {
+        let template = Template::new("{{ name }}|{{ description }}").unwrap();
+        let item = serde_json::json!({"name": "hammer"});
+        assert_eq!(template.render(&item).unwrap(), "hammer|");
+    }
+
+    #[test]
+    fn named_templates_win_over_literals() {
+        let named = HashMap::from([("short".to_string(), "{{ id }}".to_string())]);
+        let template = Template::from_arg(Some("short"), &named, Format::Human)
+            .unwrap()
+            .unwrap();
+        assert_eq!(template.render(&serde_json::json!({"id": 3})).unwrap(), "3");
+        assert!(Template::from_arg(Some("{{ id }}"), &named, Format::Json).is_err());
+        assert!(Template::new("{{ name").is_err());
+    }
+}
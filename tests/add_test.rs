@@ -38,6 +38,23 @@ fn add_item_into_container_auto_creates() {
         .stdout(predicate::str::contains("toolbox"));
 }
 
+/// Test: auto_create = false in the config refuses a missing container,
+/// like --no-create
+#[test]
+fn add_item_auto_create_off() {
+    let env = common::TestEnv::new();
+    env.add_into("saw", "toolbox").success();
+    env.write_config("auto_create = false\n");
+
+    env.add_into("hammer", "tolbox")
+        .failure()
+        .stderr(predicate::str::contains(
+            "container 'tolbox' not found; did you mean 'toolbox'?",
+        ));
+    env.run(&["show", "tolbox"]).failure();
+    env.add_into("hammer", "toolbox").success();
+}
+
 /// Test: add item into nested container path
 #[test]
 fn add_item_into_nested_container() {
@@ -100,3 +117,197 @@ fn add_with_csv_output() {
         .stdout(predicate::str::contains("id,name,description,container"))
         .stdout(predicate::str::contains("hammer"));
 }
+
+/// Test: --qty accepts a unit
+#[test]
+fn add_item_with_unit() {
+    let env = common::TestEnv::new();
+
+    env.run(&["add", "solder", "--qty", "250 g"]).success();
+    env.run(&["show", "solder", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""quantity":250,"unit":"g""#));
+
+    env.run(&["add", "wire", "--qty", "1.5m"])
+        .failure()
+        .stderr(predicate::str::contains("invalid quantity"));
+}
+
+/// Test: a name containing '/' is added with a warning showing how to
+/// refer to it, and is reachable with the slash escaped
+#[test]
+fn add_name_with_slash() {
+    let env = common::TestEnv::new();
+    env.add_into("A/V cables", "office")
+        .success()
+        .stderr(predicate::str::contains(
+            r"Warning: 'A/V cables' contains '/'; refer to it as 'A\/V cables'",
+        ));
+
+    env.run(&["show", r"A\/V cables"])
+        .success()
+        .stdout(predicate::str::contains("A/V cables"));
+    env.run(&["add", "hdmi", "--in", r"office/A\/V cables"])
+        .success();
+    env.run(&["where", "hdmi"])
+        .success()
+        .stdout("office/A\\/V cables/hdmi\n");
+}
+
+/// Test: --editor takes the description from $VISUAL
+#[cfg(unix)]
+#[test]
+fn add_with_editor() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = common::TestEnv::new();
+    let script = env.temp_dir.path().join("editor.sh");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\nprintf 'line one\\nline two\\n\\n' > \"$1\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    env.cmd()
+        .env("VISUAL", &script)
+        .args(["add", "manual", "--editor"])
+        .assert()
+        .success();
+    env.run(&["show", "manual", "--json"])
+        .success()
+        .stdout(predicate::str::contains(
+            r#""description":"line one\nline two""#,
+        ));
+
+    env.run(&["add", "guide", "--editor", "--desc", "x"])
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+/// Test: --kind marks the new item, and containers created by --in are containers
+#[test]
+fn add_sets_kind() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "garage", "--kind", "location"]).success();
+    env.add_into("hammer", "garage/toolbox").success();
+
+    env.run(&["show", "garage"])
+        .success()
+        .stdout(predicate::str::contains("Kind:        location"));
+    env.run(&["show", "toolbox"])
+        .success()
+        .stdout(predicate::str::contains("Kind:        container"));
+    env.run(&["show", "hammer"])
+        .success()
+        .stdout(predicate::str::contains("Kind:").not());
+    env.run(&["show", "hammer", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""kind":"item""#));
+}
+
+/// Test: adding into a plain item warns but still adds
+#[test]
+fn add_into_plain_item_warns() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.add_into("sticker", "hammer")
+        .success()
+        .stderr(predicate::str::contains(
+            "Warning: 'hammer' is a plain item, not a container",
+        ));
+    env.run(&["edit", "hammer", "--kind", "container"])
+        .success();
+    env.add_into("label", "hammer")
+        .success()
+        .stderr(predicate::str::contains("Warning").not());
+}
+
+/// Test: without --in, a [classify] rule matching the name or description
+/// files the item, ahead of default_container
+#[test]
+fn add_files_by_keyword_rules() {
+    let env = common::TestEnv::new();
+    env.write_config(concat!(
+        "default_container = \"inbox\"\n",
+        "[classify.rules]\n",
+        "batter = \"electronics-bin\"\n",
+        "passport = \"office/documents\"\n",
+    ));
+
+    env.add("AA batteries")
+        .success()
+        .stderr(predicate::str::contains(
+            "Filing in electronics-bin (rule 'batter'); use --in to choose another place",
+        ));
+    env.add_with_desc("blue folder", "passport and visas")
+        .success();
+    env.add("stapler")
+        .success()
+        .stderr(predicate::str::contains("Filing").not());
+    env.add_into("old batteries", "garage")
+        .success()
+        .stderr(predicate::str::contains("Filing").not());
+
+    env.run(&["show", "electronics-bin/AA batteries"]).success();
+    env.run(&["show", "office/documents/blue folder"]).success();
+    env.run(&["show", "inbox/stapler"]).success();
+    env.run(&["show", "garage/old batteries"]).success();
+}
+
+/// Test: with ask = true and no one to ask, the rule is only a hint
+#[test]
+fn add_classify_ask_without_terminal() {
+    let env = common::TestEnv::new();
+    env.write_config("[classify]\nask = true\n[classify.rules]\nbattery = \"electronics-bin\"\n");
+
+    env.add("battery")
+        .success()
+        .stderr(predicate::str::contains(
+            "hint: rule 'battery' suggests --in electronics-bin",
+        ));
+    env.run(&["show", "battery", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""path":["battery"]"#));
+}
+
+/// Test: --count adds numbered containers that share a group
+#[test]
+fn add_count_creates_numbered_group() {
+    let env = common::TestEnv::new();
+
+    env.run(&["add", "storage bin", "--count", "3", "--in", "shelf", "-d", "clear, 30l"])
+        .success()
+        .stdout(
+            "Added 3 to group 'storage bin':\n  - storage bin-1\n  - storage bin-2\n  - storage bin-3\n  -> shelf\n",
+        );
+    env.run(&["show", "shelf/storage bin-2"])
+        .success()
+        .stdout(predicate::str::contains("Kind:        container"))
+        .stdout(predicate::str::contains("Group:       storage bin"))
+        .stdout(predicate::str::contains("clear, 30l"));
+
+    env.run(&[
+        "add", "crate", "--count", "2", "--group", "crates", "--json",
+    ])
+    .success()
+    .stdout(predicate::str::contains(r#""group":"crates""#))
+    .stdout(predicate::str::contains(r#""name":"crate-2""#));
+}
+
+/// Test: --count fails as a whole when a numbered name is taken
+#[test]
+fn add_count_rejects_taken_names() {
+    let env = common::TestEnv::new();
+    env.add_into("bin-2", "shelf").success();
+
+    env.run(&["add", "bin", "--count", "3", "--in", "shelf"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "item 'bin-2' already exists in shelf",
+        ));
+    env.run(&["show", "shelf/bin-1"]).failure();
+    env.run(&["add", "bin", "--count", "0"]).code(2);
+    env.run(&["add", "bin", "--group", "bins"]).code(2);
+}
@@ -0,0 +1,102 @@
+//! Integration tests for the `alias` command.
+//!
+//! See SPEC.md#invy-alias
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: an alias resolves in show and matches in find
+#[test]
+fn alias_resolves_in_show_and_find() {
+    let env = common::TestEnv::new();
+    env.add_into("multimeter", "garage").success();
+
+    env.run(&["alias", "add", "multimeter", "DMM"])
+        .success()
+        .stdout(predicate::str::contains("Alias: DMM -> garage/multimeter"));
+
+    env.run(&["show", "dmm"])
+        .success()
+        .stdout(predicate::str::contains("multimeter"))
+        .stdout(predicate::str::contains("Aliases:     DMM"));
+
+    env.run(&["find", "DMM"])
+        .success()
+        .stdout(predicate::str::contains("garage/multimeter"));
+}
+
+/// Test: an item's real name wins over another item's alias
+#[test]
+fn real_name_wins_over_alias() {
+    let env = common::TestEnv::new();
+    env.add_with_desc("multimeter", "Fluke").success();
+    env.add_with_desc("meter", "Folding ruler").success();
+
+    env.run(&["alias", "add", "multimeter", "meter"]).success();
+
+    env.run(&["show", "meter"])
+        .success()
+        .stdout(predicate::str::contains("Folding ruler"));
+}
+
+/// Test: duplicate, empty and self-named aliases are rejected
+#[test]
+fn alias_add_rejects_bad_aliases() {
+    let env = common::TestEnv::new();
+    env.add("multimeter").success();
+    env.run(&["alias", "add", "multimeter", "DMM"]).success();
+
+    env.run(&["alias", "add", "multimeter", "dmm"])
+        .failure()
+        .stderr(predicate::str::contains("already has the alias"));
+    env.run(&["alias", "add", "multimeter", " "])
+        .failure()
+        .stderr(predicate::str::contains("alias cannot be empty"));
+    env.run(&["alias", "add", "multimeter", "Multimeter"])
+        .failure()
+        .stderr(predicate::str::contains("already the item's name"));
+    env.run(&["alias", "add", "multimeter", "a/b"])
+        .failure()
+        .stderr(predicate::str::contains("cannot contain '/'"));
+}
+
+/// Test: list shows aliases and rm removes them
+#[test]
+fn alias_list_and_rm() {
+    let env = common::TestEnv::new();
+    env.add("multimeter").success();
+
+    env.run(&["alias", "list", "multimeter"])
+        .success()
+        .stdout(predicate::str::contains("multimeter has no aliases"));
+
+    env.run(&["alias", "add", "multimeter", "DMM"]).success();
+    env.run(&["alias", "add", "multimeter", "tester"]).success();
+    env.run(&["alias", "list", "multimeter"])
+        .success()
+        .stdout(predicate::str::contains("DMM"))
+        .stdout(predicate::str::contains("tester"));
+
+    env.run(&["alias", "rm", "DMM", "DMM"])
+        .success()
+        .stdout(predicate::str::contains("Removed alias: DMM"));
+    env.run(&["show", "DMM"]).failure();
+    env.run(&["alias", "rm", "multimeter", "DMM"])
+        .failure()
+        .stderr(predicate::str::contains("has no alias 'DMM'"));
+}
+
+/// Test: removing an item removes its aliases
+#[test]
+fn alias_removed_with_item() {
+    let env = common::TestEnv::new();
+    env.add("multimeter").success();
+    env.run(&["alias", "add", "multimeter", "DMM"]).success();
+    env.run(&["rm", "multimeter"]).success();
+
+    env.add("DMM").success();
+    env.run(&["alias", "list", "DMM"])
+        .success()
+        .stdout(predicate::str::contains("no aliases"));
+}
@@ -0,0 +1,184 @@
+//! Integration tests for `invy --api` (machine mode).
+//!
+//! See SPEC.md#machine-mode
+
+mod common;
+
+use predicates::prelude::*;
+use serde_json::{json, Value};
+
+/// Send `requests` as lines on stdin and return the response lines.
+fn call(env: &common::TestEnv, requests: &[Value]) -> Vec<Value> {
+    let input: String = requests.iter().map(|r| format!("{}\n", r)).collect();
+    let output = env
+        .cmd()
+        .arg("--api")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+/// Test: one process answers several requests in order over one connection
+#[test]
+fn api_answers_requests() {
+    let env = common::TestEnv::new();
+    let responses = call(
+        &env,
+        &[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "add",
+                   "params": {"name": "drill", "container": "garage", "quantity": "2"}}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "find", "params": {"query": "dri"}}),
+            json!({"jsonrpc": "2.0", "id": "three", "method": "edit",
+                   "params": {"item": "drill", "description": "cordless"}}),
+            json!({"jsonrpc": "2.0", "id": 4, "method": "list", "params": {"in": "garage"}}),
+            json!({"jsonrpc": "2.0", "id": 5, "method": "rm", "params": {"item": "garage/drill"}}),
+        ],
+    );
+
+    assert_eq!(responses.len(), 5);
+    assert_eq!(responses[0]["jsonrpc"], "2.0");
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["result"]["path"], json!(["garage", "drill"]));
+    assert_eq!(responses[0]["result"]["quantity"], 2);
+    assert_eq!(responses[1]["result"][0]["name"], "drill");
+    assert_eq!(responses[2]["id"], "three");
+    assert_eq!(responses[2]["result"]["description"], "cordless");
+    assert_eq!(responses[3]["result"][0]["description"], "cordless");
+    assert_eq!(responses[4]["result"]["removed"], "drill");
+
+    env.run(&["find", "drill"]).success().stdout("");
+}
+
+/// Test: show takes a name, path, #ID or a plain number
+#[test]
+fn api_show_item_refs() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+
+    let responses = call(
+        &env,
+        &[
+            json!({"id": 1, "method": "show", "params": {"item": "hammer"}}),
+            json!({"id": 2, "method": "show", "params": {"item": 2}}),
+            json!({"id": 3, "method": "show", "params": {"item": "#1"}}),
+        ],
+    );
+    assert_eq!(responses[0]["result"]["name"], "hammer");
+    assert_eq!(responses[1]["result"]["name"], "hammer");
+    assert_eq!(responses[2]["result"]["stats"]["descendants"], 1);
+}
+
+/// Test: failures are JSON-RPC errors, and later requests still run
+#[test]
+fn api_reports_errors() {
+    let env = common::TestEnv::new();
+    env.add("drill").success();
+
+    let output = env
+        .cmd()
+        .arg("--api")
+        .write_stdin(concat!(
+            "not json\n",
+            "\n",
+            "{\"id\": 1}\n",
+            "{\"id\": 2, \"method\": \"frobnicate\"}\n",
+            "{\"id\": 3, \"method\": \"show\", \"params\": {\"item\": \"saw\"}}\n",
+            "{\"id\": 4, \"method\": \"add\", \"params\": {\"nmae\": \"saw\"}}\n",
+            "{\"id\": 5, \"method\": \"add\", \"params\": {\"name\": \"drill\"}}\n",
+            "{\"id\": 6, \"method\": \"show\", \"params\": {\"item\": \"drill\"}}\n",
+        ))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let responses: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let codes: Vec<Value> = responses
+        .iter()
+        .map(|r| r["error"]["code"].clone())
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            json!(-32700),
+            json!(-32600),
+            json!(-32601),
+            json!(-32001),
+            json!(-32602),
+            json!(-32000),
+            Value::Null,
+        ]
+    );
+    assert_eq!(responses[0]["id"], Value::Null);
+    assert_eq!(responses[3]["error"]["message"], "item 'saw' not found");
+    assert!(responses[5]["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("already exists"));
+    assert_eq!(responses[6]["result"]["name"], "drill");
+}
+
+/// Test: --api cannot be combined with a subcommand
+#[test]
+fn api_conflicts_with_subcommand() {
+    let env = common::TestEnv::new();
+    env.run(&["--api", "list"])
+        .code(2)
+        .stderr(predicate::str::contains(
+            "cannot be combined with a subcommand",
+        ));
+    env.run(&["--api", "--json"]).code(2);
+}
+
+/// Test: mv moves an item, creating the destination
+#[test]
+fn api_moves_items() {
+    let env = common::TestEnv::new();
+    env.add_into("drill", "garage").success();
+
+    let responses = call(
+        &env,
+        &[
+            json!({"id": 1, "method": "mv", "params": {"item": "drill", "to": "shed/shelf"}}),
+            json!({"id": 2, "method": "mv", "params": {"item": "drill"}}),
+        ],
+    );
+    assert_eq!(
+        responses[0]["result"]["path"],
+        json!(["shed", "shelf", "drill"])
+    );
+    assert_eq!(responses[1]["error"]["code"], -32602);
+    env.run(&["show", "shed/shelf/drill"]).success();
+}
+
+/// Test: auto_create = false in the config applies to machine mode too
+#[test]
+fn api_no_create() {
+    let env = common::TestEnv::new();
+    env.add_into("drill", "garage").success();
+    env.write_config("auto_create = false\n");
+
+    let responses = call(
+        &env,
+        &[
+            json!({"id": 1, "method": "mv", "params": {"item": "drill", "to": "shed"}}),
+            json!({"id": 2, "method": "add", "params": {"name": "saw", "container": "shed"}}),
+        ],
+    );
+    assert_eq!(responses[0]["error"]["code"], -32000);
+    assert_eq!(responses[1]["error"]["code"], -32000);
+    env.run(&["show", "shed"]).failure();
+}
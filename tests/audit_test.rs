@@ -0,0 +1,84 @@
+//! Integration tests for the `audit` command.
+//!
+//! See SPEC.md#invy-audit
+
+mod common;
+
+use predicates::prelude::*;
+
+/// A garage with three things to find and a kitchen outside the audit.
+fn garage(env: &common::TestEnv) {
+    env.add_into("hammer", "garage/toolbox").success();
+    env.add_into("tape", "garage/shelf").success();
+    env.add_into("kettle", "kitchen").success();
+}
+
+/// Test: start counts what is to check, check ticks items off and report
+/// lists the rest
+#[test]
+fn audit_tracks_progress() {
+    let env = common::TestEnv::new();
+    garage(&env);
+
+    env.run(&["audit", "start", "garage"])
+        .success()
+        .stdout("Started audit of garage: 4 item(s) to check\n");
+    env.run(&["audit", "check", "hammer", "toolbox"])
+        .success()
+        .stdout("Checked: garage/toolbox/hammer\nChecked: garage/toolbox\n2 of 4 checked\n");
+    env.run(&["audit", "report"]).success().stdout(
+        predicate::str::is_match(
+            r"^Audit of garage started \S+ \S+: 2 of 4 checked\nPATH\s+LAST VERIFIED\ngarage/shelf\s+never\ngarage/shelf/tape\s+never\n$",
+        )
+        .unwrap(),
+    );
+    env.run(&["audit", "report", "-q"])
+        .success()
+        .stdout("4\n5\n");
+}
+
+/// Test: a new audit keeps old verifications as "last verified"
+#[test]
+fn audit_report_shows_last_verified() {
+    let env = common::TestEnv::new();
+    garage(&env);
+    env.run(&["audit", "start"]).success();
+    env.run(&["audit", "check", "tape"]).success();
+    env.run(&["show", "tape"])
+        .success()
+        .stdout(predicate::str::contains("Verified:    "));
+
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    env.run(&["audit", "start", "garage/shelf"]).success();
+    env.run(&["audit", "report", "--csv"]).success().stdout(
+        predicate::str::is_match(r"^id,path,verified_at\n5,garage/shelf/tape,\S+ \S+\n$").unwrap(),
+    );
+    env.run(&["audit", "check", "tape"]).success();
+    env.run(&["audit", "report"])
+        .success()
+        .stdout(predicate::str::contains(
+            "1 of 1 checked\nEverything has been checked\n",
+        ));
+}
+
+/// Test: items outside the audit and audits that were never started
+#[test]
+fn audit_errors() {
+    let env = common::TestEnv::new();
+    garage(&env);
+
+    env.run(&["audit", "report"])
+        .failure()
+        .stderr(predicate::str::contains("no audit running"));
+    env.run(&["audit", "start", "garage"]).success();
+    env.run(&["audit", "check", "kettle"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "'kitchen/kettle' is not inside 'garage'",
+        ));
+    env.run(&["audit", "check", "garage"]).failure();
+    env.run(&["rm", "garage"]).success();
+    env.run(&["audit", "report"])
+        .failure()
+        .stderr(predicate::str::contains("was removed"));
+}
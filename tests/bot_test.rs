@@ -0,0 +1,189 @@
+//! Integration tests for `invy bot`.
+//!
+//! Only built with the `bot` cargo feature. A scripted HTTP listener on
+//! localhost stands in for the Telegram Bot API.
+//!
+//! See SPEC.md#invy-bot
+
+#![cfg(feature = "bot")]
+
+mod common;
+
+use predicates::prelude::*;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+
+/// Answer one request per response body, in order, and return the raw
+/// requests.
+fn scripted_server(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                head.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+            requests.push(head + &String::from_utf8(body).unwrap());
+        }
+        requests
+    });
+    (url, handle)
+}
+
+/// A `getUpdates` result holding one text message per `(user, text)`,
+/// numbered from `first_id`.
+fn updates(first_id: i64, messages: &[(i64, &str)]) -> String {
+    let updates: Vec<_> = messages
+        .iter()
+        .zip(first_id..)
+        .map(|((user, text), id)| {
+            serde_json::json!({
+                "update_id": id,
+                "message": {
+                    "message_id": id,
+                    "from": { "id": user, "is_bot": false, "first_name": "Sam" },
+                    "chat": { "id": user, "type": "private" },
+                    "text": text,
+                },
+            })
+        })
+        .collect();
+    serde_json::json!({ "ok": true, "result": updates }).to_string()
+}
+
+fn sent() -> String {
+    r#"{"ok":true,"result":{}}"#.to_string()
+}
+
+/// The reply text in a `sendMessage` request.
+fn reply_text(request: &str) -> String {
+    assert!(
+        request.starts_with("POST /bot42:abc/sendMessage "),
+        "{}",
+        request
+    );
+    let body = &request[request.find("\r\n\r\n").unwrap() + 4..];
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    body["text"].as_str().unwrap().to_string()
+}
+
+fn run_bot(env: &common::TestEnv, url: &str) -> assert_cmd::assert::Assert {
+    env.write_config(&format!("[bot]\nallow = [7]\napi_url = \"{}\"\n", url));
+    env.run(&["bot", "--telegram-token", "42:abc", "--once"])
+}
+
+/// Test: a plain question is answered with the matching paths
+#[test]
+fn bot_answers_where_is_questions() {
+    let env = common::TestEnv::new();
+    env.add_into("passport", "office/desk").success();
+    let (url, server) =
+        scripted_server(vec![updates(10, &[(7, "Where is the passport?")]), sent()]);
+
+    run_bot(&env, &url).success();
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("POST /bot42:abc/getUpdates "));
+    assert_eq!(reply_text(&requests[1]), "office/desk/passport");
+}
+
+/// Test: /show and /add work like their commands
+#[test]
+fn bot_shows_and_adds_items() {
+    let env = common::TestEnv::new();
+    env.add_with_desc("passport", "expires 2031").success();
+    let (url, server) = scripted_server(vec![
+        updates(
+            1,
+            &[
+                (7, "/show passport"),
+                (7, "/add hammer in garage/toolbox"),
+                (7, "/find@house_bot nothing-like-this"),
+            ],
+        ),
+        sent(),
+        sent(),
+        sent(),
+    ]);
+
+    run_bot(&env, &url).success();
+
+    let requests = server.join().unwrap();
+    assert!(reply_text(&requests[1]).contains("Description: expires 2031"));
+    assert_eq!(
+        reply_text(&requests[2]),
+        "Added: hammer\n  -> garage -> toolbox"
+    );
+    assert_eq!(
+        reply_text(&requests[3]),
+        "Nothing matches 'nothing-like-this'"
+    );
+    env.run(&["where", "hammer"])
+        .success()
+        .stdout("garage/toolbox/hammer\n");
+}
+
+/// Test: users outside the allow list only learn their ID
+#[test]
+fn bot_refuses_unknown_users() {
+    let env = common::TestEnv::new();
+    env.add("passport").success();
+    let (url, server) = scripted_server(vec![updates(1, &[(99, "/find passport")]), sent()]);
+
+    run_bot(&env, &url)
+        .success()
+        .stderr(predicate::str::contains("Ignored a message from user 99"));
+
+    let requests = server.join().unwrap();
+    let reply = reply_text(&requests[1]);
+    assert!(reply.contains("not on this bot's allow list"), "{}", reply);
+    assert!(reply.contains("99"));
+    assert!(!reply.contains("passport"));
+}
+
+/// Test: handled messages are not fetched again on the next run
+#[test]
+fn bot_remembers_handled_updates() {
+    let env = common::TestEnv::new();
+    let (url, server) = scripted_server(vec![updates(41, &[(7, "/help")]), sent()]);
+    run_bot(&env, &url).success();
+    server.join().unwrap();
+
+    let (url, server) = scripted_server(vec![updates(42, &[])]);
+    run_bot(&env, &url).success();
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].contains(r#""offset": 42"#), "{}", requests[0]);
+}
+
+/// Test: the bot refuses to start without an allow list
+#[test]
+fn bot_requires_allow_list() {
+    let env = common::TestEnv::new();
+    env.run(&["bot", "--telegram-token", "42:abc", "--once"])
+        .failure()
+        .stderr(predicate::str::contains("no users allowed"));
+}
@@ -0,0 +1,81 @@
+//! Integration tests for the `cd` and `pwd` commands.
+//!
+//! See SPEC.md#invy-cd-container
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Set up two boxes named "box", one in garage and one in attic.
+fn two_boxes(env: &common::TestEnv) {
+    env.add_into("box", "garage").success();
+    env.add_into("box", "attic").success();
+}
+
+/// Test: cd sets the context shown by pwd
+#[test]
+fn cd_sets_context() {
+    let env = common::TestEnv::new();
+    env.add_into("toolbox", "garage").success();
+
+    env.run(&["cd", "garage/toolbox"])
+        .success()
+        .stdout(predicate::str::contains("Context: garage/toolbox"));
+
+    env.run(&["pwd"])
+        .success()
+        .stdout(predicate::str::contains("Context: garage/toolbox"));
+}
+
+/// Test: cd without argument clears the context
+#[test]
+fn cd_without_argument_clears() {
+    let env = common::TestEnv::new();
+    env.add("garage").success();
+    env.run(&["cd", "garage"]).success();
+
+    env.run(&["cd"])
+        .success()
+        .stdout(predicate::str::contains("Context: (root)"));
+}
+
+/// Test: ambiguous name resolves inside the current context with a note
+#[test]
+fn context_resolves_ambiguous_name() {
+    let env = common::TestEnv::new();
+    two_boxes(&env);
+
+    env.run(&["show", "box"])
+        .failure()
+        .stderr(predicate::str::contains("ambiguous"));
+
+    env.run(&["cd", "attic"]).success();
+
+    env.run(&["show", "box"])
+        .success()
+        .stdout(predicate::str::contains("Location:    attic"))
+        .stderr(predicate::str::contains("note: 'box' is ambiguous"));
+}
+
+/// Test: still ambiguous within the context is an error
+#[test]
+fn context_still_ambiguous_fails() {
+    let env = common::TestEnv::new();
+    env.add_into("box", "house/garage").success();
+    env.add_into("box", "house/attic").success();
+    env.run(&["cd", "house"]).success();
+
+    env.run(&["show", "box"])
+        .failure()
+        .stderr(predicate::str::contains("ambiguous"));
+}
+
+/// Test: cd to a missing container fails
+#[test]
+fn cd_missing_container_fails() {
+    let env = common::TestEnv::new();
+
+    env.run(&["cd", "nowhere"])
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+}
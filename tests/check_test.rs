@@ -0,0 +1,65 @@
+//! Integration tests for the `check` command.
+//!
+//! See SPEC.md#invy-check-container
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: a container without a checklist passes with a hint
+#[test]
+fn check_without_checklist() {
+    let env = common::TestEnv::new();
+    env.add("first-aid-kit").success();
+
+    env.run(&["check", "first-aid-kit"])
+        .success()
+        .stdout(predicate::str::contains("has no checklist"));
+}
+
+/// Test: missing entries are reported and fail the check
+#[test]
+fn check_reports_missing_items() {
+    let env = common::TestEnv::new();
+    env.add_into("bandages", "first-aid-kit").success();
+    env.add_into("scissors", "first-aid-kit/pouch").success();
+
+    env.run(&[
+        "check",
+        "first-aid-kit",
+        "--require",
+        "bandages",
+        "--require",
+        "antiseptic",
+        "--require",
+        "Scissors",
+    ])
+    .failure()
+    .stdout(predicate::str::contains("first-aid-kit: 2 of 3 present"))
+    .stdout(predicate::str::contains("[ ] antiseptic"))
+    .stdout(predicate::str::is_match(r"\[x\] Scissors\s+first-aid-kit/pouch/scissors").unwrap())
+    .stderr(predicate::str::contains(
+        "1 item(s) missing from 'first-aid-kit'",
+    ));
+
+    // The checklist is remembered
+    env.add_into("antiseptic", "first-aid-kit").success();
+    env.run(&["check", "first-aid-kit"])
+        .success()
+        .stdout(predicate::str::contains("3 of 3 present"));
+}
+
+/// Test: entries can be removed; unknown entries are an error
+#[test]
+fn check_unrequire() {
+    let env = common::TestEnv::new();
+    env.add("kit").success();
+    env.run(&["check", "kit", "--require", "tape"]).failure();
+
+    env.run(&["check", "kit", "--unrequire", "TAPE", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""entries":[]"#));
+    env.run(&["check", "kit", "--unrequire", "tape"])
+        .failure()
+        .stderr(predicate::str::contains("'tape' is not on the checklist"));
+}
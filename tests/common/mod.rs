@@ -1,5 +1,8 @@
 //! Common test utilities and helpers.
 
+#![allow(dead_code)]
+
+use assert_cmd::cargo::cargo_bin_cmd;
 use assert_cmd::Command;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -20,11 +23,41 @@ impl TestEnv {
 
     /// Get a Command configured to use this test environment's database.
     pub fn cmd(&self) -> Command {
-        let mut cmd = Command::cargo_bin("invy").expect("Failed to find invy binary");
+        let mut cmd = cargo_bin_cmd!("invy");
         cmd.arg("--db").arg(&self.db_path);
+        // Keep the user's real config file and environment out of tests
+        cmd.env("XDG_CONFIG_HOME", self.temp_dir.path());
+        cmd.env_remove("INVY_DB")
+            .env_remove("INVY_FORMAT")
+            .env_remove("INVY_LANG")
+            .env_remove("INVY_PASSPHRASE")
+            .env_remove("INVY_STRICT");
         cmd
     }
 
+    /// Path of the config file picked up by default in this environment.
+    pub fn config_path(&self) -> PathBuf {
+        self.temp_dir.path().join("invy").join("config.toml")
+    }
+
+    /// Write the default config file for this environment.
+    pub fn write_config(&self, contents: &str) {
+        let path = self.config_path();
+        std::fs::create_dir_all(path.parent().unwrap()).expect("Failed to create config dir");
+        std::fs::write(path, contents).expect("Failed to write config");
+    }
+
+    /// Set an item's created_at/updated_at directly in the database, for
+    /// tests of date-based behavior.
+    pub fn backdate(&self, name: &str, timestamp: &str) {
+        let conn = rusqlite::Connection::open(&self.db_path).expect("Failed to open test db");
+        conn.execute(
+            "UPDATE items SET created_at = ?1, updated_at = ?1 WHERE name = ?2",
+            [timestamp, name],
+        )
+        .expect("Failed to backdate item");
+    }
+
     /// Run invy with the given arguments.
     pub fn run(&self, args: &[&str]) -> assert_cmd::assert::Assert {
         self.cmd().args(args).assert()
@@ -0,0 +1,177 @@
+//! Integration tests for config file support and the `config` command.
+//!
+//! See SPEC.md#configuration and SPEC.md#invy-config
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: default output format comes from config
+#[test]
+fn config_sets_default_format() {
+    let env = common::TestEnv::new();
+    env.write_config("format = \"json\"\n");
+
+    env.add("hammer")
+        .success()
+        .stdout(predicate::str::starts_with("{"));
+}
+
+/// Test: CLI flags win over config format
+#[test]
+fn cli_flag_overrides_config_format() {
+    let env = common::TestEnv::new();
+    env.write_config("format = \"json\"\n");
+
+    env.run(&["add", "hammer", "--csv"])
+        .success()
+        .stdout(predicate::str::starts_with("id,name"));
+}
+
+/// Test: list order and color come from config, and flags win
+#[test]
+fn config_sets_sort_and_color() {
+    let env = common::TestEnv::new();
+    env.add("alpha").success();
+    env.add("bravo").success();
+    env.backdate("bravo", "2020-01-01 00:00:00");
+    env.write_config("sort = \"updated\"\ncolor = \"always\"\n");
+
+    let output = env.run(&["list"]).success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.find("bravo") < output.find("alpha"));
+    assert!(output.contains("\x1b["));
+
+    let output = env
+        .run(&["list", "--sort", "name", "--color", "never"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.find("alpha") < output.find("bravo"));
+    assert!(!output.contains("\x1b["));
+}
+
+/// Test: database path comes from config when --db is absent
+#[test]
+fn config_sets_database_path() {
+    let env = common::TestEnv::new();
+    let db_path = env.temp_dir.path().join("from-config.db");
+    env.write_config(&format!("db = {:?}\n", db_path.to_str().unwrap()));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("invy");
+    cmd.env("XDG_CONFIG_HOME", env.temp_dir.path())
+        .args(["add", "hammer"])
+        .assert()
+        .success();
+
+    assert!(db_path.exists());
+}
+
+/// Test: explicit --config file is used
+#[test]
+fn explicit_config_file() {
+    let env = common::TestEnv::new();
+    let path = env.temp_dir.path().join("custom.toml");
+    std::fs::write(&path, "format = \"csv\"\n").unwrap();
+
+    env.run(&["--config", path.to_str().unwrap(), "add", "hammer"])
+        .success()
+        .stdout(predicate::str::starts_with("id,name"));
+}
+
+/// Test: missing explicit --config file is an error
+#[test]
+fn missing_explicit_config_fails() {
+    let env = common::TestEnv::new();
+
+    env.run(&["--config", "/nonexistent/invy.toml", "list"])
+        .failure()
+        .stderr(predicate::str::contains("config"));
+}
+
+/// Test: malformed config reports an error
+#[test]
+fn invalid_config_fails() {
+    let env = common::TestEnv::new();
+    env.write_config("format = \"yaml\"\n");
+
+    env.run(&["list"])
+        .failure()
+        .stderr(predicate::str::contains("Invalid config"));
+}
+
+/// Test: export prints the config file, or converts it with --json
+#[test]
+fn config_export() {
+    let env = common::TestEnv::new();
+    env.write_config("format = \"json\"\ndefault_container = \"inbox\"\n");
+
+    env.run(&["config", "export"])
+        .success()
+        .stdout("format = \"json\"\ndefault_container = \"inbox\"\n");
+    env.run(&["config", "export", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""default_container":"inbox""#));
+
+    let file = env.temp_dir.path().join("invy-config.toml");
+    env.run(&["config", "export", "-o", file.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::contains("Exported config"));
+    assert!(std::fs::read_to_string(file).unwrap().contains("inbox"));
+}
+
+/// Test: export without a config file is an error
+#[test]
+fn config_export_missing() {
+    let env = common::TestEnv::new();
+    env.run(&["config", "export"])
+        .failure()
+        .stderr(predicate::str::contains("no config file at"));
+}
+
+/// Test: import installs a file, refusing to replace a different one
+/// without --force
+#[test]
+fn config_import() {
+    let env = common::TestEnv::new();
+    let file = env.temp_dir.path().join("exported.toml");
+    std::fs::write(&file, "lang = \"es\"\n").unwrap();
+    let file = file.to_str().unwrap();
+
+    env.run(&["config", "import", file])
+        .success()
+        .stdout(predicate::str::contains("Imported config"));
+    assert_eq!(
+        std::fs::read_to_string(env.config_path()).unwrap(),
+        "lang = \"es\"\n"
+    );
+    env.run(&["config", "import", file])
+        .success()
+        .stdout(predicate::str::contains("already up to date"));
+
+    env.write_config("format = \"csv\"\n");
+    env.run(&["config", "import", file])
+        .failure()
+        .stderr(predicate::str::contains("use --force to replace it"));
+    env.run(&["config", "import", file, "--force"]).success();
+    let backup = env.config_path().with_extension("toml.bak");
+    assert_eq!(
+        std::fs::read_to_string(backup).unwrap(),
+        "format = \"csv\"\n"
+    );
+}
+
+/// Test: an invalid file is not imported
+#[test]
+fn config_import_invalid() {
+    let env = common::TestEnv::new();
+    let file = env.temp_dir.path().join("bad.toml");
+    std::fs::write(&file, "format = \"yaml\"\n").unwrap();
+
+    env.run(&["config", "import", file.to_str().unwrap()])
+        .failure()
+        .stderr(predicate::str::contains("Invalid config file"));
+    assert!(!env.config_path().exists());
+}
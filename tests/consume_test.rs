@@ -0,0 +1,124 @@
+//! Integration tests for the `consume` and `consumed` commands.
+//!
+//! See SPEC.md#invy-consume-item
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: consuming part of an item decrements its quantity
+#[test]
+fn consume_decrements_quantity() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "AA batteries", "--in", "drawer", "--qty", "8"])
+        .success();
+
+    env.run(&["consume", "AA batteries", "--qty", "3"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Consumed: 3 x AA batteries (5 left)",
+        ));
+
+    env.run(&["show", "drawer/AA batteries", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""quantity":5"#));
+}
+
+/// Test: consuming the last one archives the item but keeps the record
+#[test]
+fn consume_last_archives_item() {
+    let env = common::TestEnv::new();
+    env.add_into("lightbulb", "closet").success();
+
+    env.run(&["consume", "lightbulb"])
+        .success()
+        .stdout(predicate::str::contains("none left, archived"));
+
+    env.run(&["show", "lightbulb"]).failure();
+    env.run(&["consumed", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""path":["closet","lightbulb"]"#));
+}
+
+/// Test: cannot consume more than is left
+#[test]
+fn consume_more_than_available_fails() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "nails", "--qty", "2"]).success();
+
+    env.run(&["consume", "nails", "--qty", "3"])
+        .failure()
+        .stderr(predicate::str::contains("only 2 left"));
+}
+
+/// Test: a container with contents cannot be consumed away
+#[test]
+fn consume_container_with_contents_fails() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "toolbox").success();
+
+    env.run(&["consume", "toolbox"])
+        .failure()
+        .stderr(predicate::str::contains("still contains 1 items"));
+}
+
+/// Test: consumed --since filters the report
+#[test]
+fn consumed_since_filters_report() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "coffee", "--qty", "5"]).success();
+    env.run(&["consume", "coffee", "--qty", "2"]).success();
+
+    env.run(&["consumed", "--since", "30d", "--csv"])
+        .success()
+        .stdout(predicate::str::contains(
+            "id,item_id,name,path,quantity,consumed_at",
+        ))
+        .stdout(predicate::str::contains(",coffee,coffee,2,"));
+
+    env.run(&["consumed", "--since", "2999-01-01", "--json"])
+        .success()
+        .stdout("[]\n");
+}
+
+/// Test: invalid --since value fails
+#[test]
+fn consumed_invalid_since_fails() {
+    let env = common::TestEnv::new();
+
+    env.run(&["consumed", "--since", "soon"])
+        .failure()
+        .stderr(predicate::str::contains("invalid time 'soon'"));
+}
+
+/// Test: units convert within their family and mismatches are rejected
+#[test]
+fn consume_with_units() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "cable", "--qty", "3m"]).success();
+
+    env.run(&["consume", "cable", "--qty", "30cm"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Consumed: 30 cm of cable (270 cm left)",
+        ));
+
+    env.run(&["consume", "cable", "--qty", "70cm"])
+        .success()
+        .stdout(predicate::str::contains("(200 cm left)"));
+    env.run(&["show", "cable"])
+        .success()
+        .stdout(predicate::str::contains("Quantity:    200 cm"));
+
+    env.run(&["consume", "cable", "--qty", "5g"])
+        .failure()
+        .stderr(predicate::str::contains("units do not match"));
+    env.run(&["consume", "cable", "--qty", "3m"])
+        .failure()
+        .stderr(predicate::str::contains("only 200 cm left"));
+
+    // A bare number is in the item's unit
+    env.run(&["consume", "cable", "--qty", "200"])
+        .success()
+        .stdout(predicate::str::contains("none left, archived"));
+}
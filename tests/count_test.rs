@@ -0,0 +1,58 @@
+//! Integration tests for the `count` command and `find --count`.
+//!
+//! See SPEC.md#invy-count-container
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: count prints direct children, or everything below with -r
+#[test]
+fn count_container() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+    env.add_into("saw", "garage/toolbox").success();
+    env.add_into("bike", "garage").success();
+    env.add("desk").success();
+
+    env.run(&["count", "garage"]).success().stdout("2\n");
+    env.run(&["count", "garage", "-r"]).success().stdout("4\n");
+    env.run(&["count"]).success().stdout("2\n");
+    env.run(&["count", "--recursive"]).success().stdout("6\n");
+    env.run(&["count", "garage/toolbox", "--format", "json"])
+        .success()
+        .stdout("{\"count\":2}\n");
+}
+
+/// Test: a count of zero still prints 0 but exits 1, with nothing on stderr
+#[test]
+fn count_zero_exit_code() {
+    let env = common::TestEnv::new();
+    env.add("desk").success();
+
+    env.run(&["count", "desk"]).code(1).stdout("0\n").stderr("");
+    env.run(&["count", "shed"])
+        .code(1)
+        .stderr(predicate::str::contains("container 'shed' not found"));
+}
+
+/// Test: find --count prints the number of filtered matches
+#[test]
+fn find_count() {
+    let env = common::TestEnv::new();
+    env.add_into("AA battery", "garage").success();
+    env.add_into("AAA battery", "kitchen").success();
+
+    env.run(&["find", "battery", "--count"])
+        .success()
+        .stdout("2\n");
+    env.run(&["find", "battery", "--count", "--path", "kitchen"])
+        .success()
+        .stdout("1\n");
+    env.run(&["find", "drill", "--count"])
+        .code(1)
+        .stdout("0\n")
+        .stderr("");
+    env.run(&["find", "battery", "--count", "--limit", "1"])
+        .code(2);
+}
@@ -0,0 +1,93 @@
+//! Integration tests for the `cp` command.
+//!
+//! See SPEC.md#invy-cp-item-destination
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: copy item into another container
+#[test]
+fn copy_item_to_container() {
+    let env = common::TestEnv::new();
+
+    // Setup
+    env.add_with_desc("hammer", "claw hammer").success();
+    env.add("workshop").success();
+
+    env.run(&["cp", "hammer", "workshop"])
+        .success()
+        .stdout(predicate::str::contains("Copied: hammer"));
+
+    // Original stays in place, copy keeps the description
+    env.run(&["show", "/hammer"]).success();
+    env.run(&["show", "workshop/hammer"])
+        .success()
+        .stdout(predicate::str::contains("claw hammer"));
+}
+
+/// Test: non-recursive copy leaves contents behind
+#[test]
+fn copy_without_recursive_skips_contents() {
+    let env = common::TestEnv::new();
+
+    env.add_into("hammer", "toolbox").success();
+    env.add("garage").success();
+
+    env.run(&["cp", "toolbox", "garage"]).success();
+
+    env.run(&["list", "garage/toolbox", "--json"])
+        .success()
+        .stdout(predicate::str::contains("hammer").not());
+}
+
+/// Test: recursive copy duplicates the whole subtree
+#[test]
+fn copy_recursive_duplicates_subtree() {
+    let env = common::TestEnv::new();
+
+    env.add_into("hammer", "toolbox").success();
+    env.add_into("nails", "toolbox/tray").success();
+    env.add("garage").success();
+
+    env.run(&["cp", "--recursive", "toolbox", "garage"])
+        .success()
+        .stdout(predicate::str::contains("4 items copied"));
+
+    env.run(&["show", "garage/toolbox/hammer"]).success();
+    env.run(&["show", "garage/toolbox/tray/nails"]).success();
+}
+
+/// Test: recursive copy into own descendant fails
+#[test]
+fn copy_recursive_into_descendant_fails() {
+    let env = common::TestEnv::new();
+
+    env.add_into("shelf", "garage").success();
+
+    env.run(&["cp", "-r", "garage", "shelf"])
+        .failure()
+        .stderr(predicate::str::contains("cannot copy"));
+}
+
+/// Test: copy with name conflict fails
+#[test]
+fn copy_name_conflict_fails() {
+    let env = common::TestEnv::new();
+
+    env.add("hammer").success();
+
+    env.run(&["cp", "hammer", "/"])
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+}
+
+/// Test: copy non-existent item fails
+#[test]
+fn copy_nonexistent_item_fails() {
+    let env = common::TestEnv::new();
+
+    env.run(&["cp", "nonexistent", "somewhere"])
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+}
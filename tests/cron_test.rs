@@ -0,0 +1,89 @@
+//! Integration tests for the cron command.
+//!
+//! See SPEC.md#invy-cron-run
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: without a [cron] section nothing runs
+#[test]
+fn cron_run_without_schedules() {
+    let env = common::TestEnv::new();
+    env.run(&["cron", "run"])
+        .success()
+        .stdout(predicate::str::contains("No jobs scheduled"));
+}
+
+/// Test: due jobs run once, then wait for their interval
+#[test]
+fn cron_run_only_runs_due_jobs() {
+    let env = common::TestEnv::new();
+    let backups = env.temp_dir.path().join("backups");
+    env.write_config(&format!(
+        "[cron]\nbackup = \"1d\"\nbackup_dir = {:?}\nprune_shares = \"1d\"\n",
+        backups
+    ));
+    env.add("hammer").success();
+
+    env.run(&["cron", "run"])
+        .success()
+        .stdout(predicate::str::is_match(r"backup\s+ran\s+wrote").unwrap())
+        .stdout(predicate::str::is_match(r"prune_shares\s+ran\s+removed 0").unwrap());
+    assert_eq!(std::fs::read_dir(&backups).unwrap().count(), 1);
+
+    env.run(&["cron", "run"])
+        .success()
+        .stdout(predicate::str::is_match(r"backup\s+skipped\s+not due").unwrap());
+
+    // The backup is a working database
+    let copy = std::fs::read_dir(&backups)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let conn = rusqlite::Connection::open(copy.path()).unwrap();
+    let name: String = conn
+        .query_row("SELECT name FROM items", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(name, "hammer");
+}
+
+/// Test: --force runs jobs that are not due
+#[test]
+fn cron_run_force() {
+    let env = common::TestEnv::new();
+    env.write_config("[cron]\nprune_shares = \"1w\"\n");
+
+    env.run(&["cron", "run"]).success();
+    env.run(&["cron", "run", "--force", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""status":"ran""#));
+}
+
+/// Test: a failing job is reported and fails the run
+#[test]
+fn cron_run_reports_failures() {
+    let env = common::TestEnv::new();
+    env.write_config("[cron]\nverify_hashes = \"1d\"\n");
+    env.add_into("hammer", "toolbox").success();
+    env.run(&["hash", "toolbox"]).success();
+    env.add_into("saw", "toolbox").success();
+
+    env.run(&["cron", "run"])
+        .failure()
+        .stdout(predicate::str::contains(
+            "FAILED   1 container(s) changed: toolbox",
+        ))
+        .stderr(predicate::str::contains("1 job(s) failed"));
+}
+
+/// Test: an invalid schedule is an error
+#[test]
+fn cron_run_invalid_schedule() {
+    let env = common::TestEnv::new();
+    env.write_config("[cron]\nbackup = \"daily\"\n");
+    env.run(&["cron", "run"])
+        .failure()
+        .stderr(predicate::str::contains("invalid [cron] backup schedule"));
+}
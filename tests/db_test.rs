@@ -0,0 +1,96 @@
+//! Integration tests for the `db` maintenance commands.
+//!
+//! See SPEC.md#invy-db
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: db path prints the database location
+#[test]
+fn db_path_prints_location() {
+    let env = common::TestEnv::new();
+
+    env.run(&["db", "path"])
+        .success()
+        .stdout(predicate::str::contains(env.db_path.to_str().unwrap()));
+}
+
+/// Test: db info reports version, count and journal mode
+#[test]
+fn db_info_reports_details() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.add("saw").success();
+
+    env.run(&["db", "info", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""item_count":2"#))
+        .stdout(predicate::str::contains(r#""journal_mode":"wal""#))
+        .stdout(predicate::str::contains(r#""schema_version""#));
+}
+
+/// Test: db migrate --to steps the schema forward
+#[test]
+fn db_migrate_to_version() {
+    let env = common::TestEnv::new();
+
+    env.run(&["db", "migrate", "--to", "1"])
+        .success()
+        .stdout(predicate::str::contains("Migrated schema: 0 -> 1"));
+
+    env.run(&["db", "info"])
+        .success()
+        .stdout(predicate::str::contains("Schema version: 1"));
+
+    env.run(&["db", "migrate"])
+        .success()
+        .stdout(predicate::str::contains("Migrated schema: 1 ->"));
+}
+
+/// Test: db migrate refuses to downgrade
+#[test]
+fn db_migrate_downgrade_fails() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.run(&["db", "migrate", "--to", "1"])
+        .failure()
+        .stderr(predicate::str::contains("cannot downgrade"));
+}
+
+/// Test: db migrate is a no-op when already current
+#[test]
+fn db_migrate_when_current() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.run(&["db", "migrate"])
+        .success()
+        .stdout(predicate::str::contains("already at version"));
+}
+
+/// Test: parallel invocations against one database all succeed
+#[test]
+fn db_concurrent_adds_succeed() {
+    let env = common::TestEnv::new();
+    env.add("shelf").success();
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let mut cmd = env.cmd();
+            std::thread::spawn(move || {
+                cmd.args(["add", &format!("item{}", i), "--in", "shelf"])
+                    .assert()
+                    .success();
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    env.run(&["list", "shelf", "--json"])
+        .success()
+        .stdout(predicate::str::contains("item7"));
+}
@@ -0,0 +1,112 @@
+//! Integration tests for the `doctor` command.
+//!
+//! See SPEC.md#invy-doctor
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Break the hierarchy the way another tool could: drop the safeguards,
+/// then add a cycle, a dangling container and a duplicate name.
+fn damage(env: &common::TestEnv) {
+    env.add_into("hammer", "garage").success(); // 1 garage, 2 hammer
+    env.add_into("tape", "shelf/box").success(); // 3 shelf, 4 box, 5 tape
+    env.add("saw").success(); // 6 saw
+
+    let conn = rusqlite::Connection::open(&env.db_path).unwrap();
+    conn.execute_batch(
+        "PRAGMA foreign_keys = OFF;
+         DROP TRIGGER items_no_cycle_update;
+         DROP INDEX idx_items_name_container;
+         UPDATE items SET container_id = 4 WHERE id = 3;
+         UPDATE items SET container_id = 999 WHERE id = 6;
+         INSERT INTO items (name, container_id) VALUES ('hammer', 1);
+         INSERT INTO items (name) VALUES ('saw');",
+    )
+    .unwrap();
+}
+
+/// Test: a healthy database reports nothing
+#[test]
+fn doctor_healthy() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+
+    env.run(&["doctor"]).success().stdout("No problems found\n");
+}
+
+/// Test: cycles, dangling containers and duplicate names are reported
+/// without changing anything
+#[test]
+fn doctor_reports_problems() {
+    let env = common::TestEnv::new();
+    damage(&env);
+
+    env.run(&["doctor"])
+        .failure()
+        .stdout(predicate::str::contains(
+            "dangling: 'saw' (#6) is in container #999, which does not exist",
+        ))
+        .stdout(predicate::str::contains(
+            "cycle: 'shelf' (#3) is inside its own contents",
+        ))
+        .stdout(predicate::str::contains(
+            "duplicate: 'hammer' (#7) has the same name as another item in its container",
+        ))
+        .stderr(predicate::str::contains(
+            "3 problem(s) found (run with --fix to repair)",
+        ));
+    env.run(&["doctor", "-q"]).failure().stdout("6\n3\n7\n");
+}
+
+/// Test: --fix moves broken items to root and renames duplicates
+#[test]
+fn doctor_fix() {
+    let env = common::TestEnv::new();
+    damage(&env);
+
+    env.run(&["doctor", "--fix"])
+        .success()
+        .stdout(predicate::str::contains(
+            "fixed: moved to root as 'saw (2)'",
+        ))
+        .stdout(predicate::str::contains("fixed: moved to root\n"))
+        .stdout(predicate::str::contains("fixed: renamed to 'hammer (2)'"))
+        .stdout(predicate::str::contains("Found 3 problem(s), fixed 3"));
+
+    env.run(&["doctor"]).success();
+    env.run(&["where", "tape"])
+        .success()
+        .stdout("shelf/box/tape\n");
+    env.run(&["show", "garage/hammer (2)"]).success();
+}
+
+/// Test: views installed by an older version are reported and reinstalled
+#[test]
+fn doctor_outdated_views() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.run(&["doctor"]).success();
+
+    env.run(&["views", "install"]).success();
+    let conn = rusqlite::Connection::open(&env.db_path).unwrap();
+    conn.execute_batch(
+        "DROP VIEW expiring_soon;
+         DROP VIEW container_totals;
+         CREATE VIEW container_totals AS SELECT id FROM items;",
+    )
+    .unwrap();
+
+    env.run(&["doctor"])
+        .failure()
+        .stdout(predicate::str::contains(
+            "view: view container_totals is out of date",
+        ))
+        .stdout(predicate::str::contains(
+            "view: view expiring_soon is not installed",
+        ));
+    env.run(&["doctor", "--fix"])
+        .success()
+        .stdout(predicate::str::contains("fixed: reinstalled"));
+    env.run(&["doctor"]).success();
+}
@@ -0,0 +1,51 @@
+//! Integration tests for the `dupes` command.
+//!
+//! See SPEC.md#invy-dupes
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: identical and near-identical names are grouped
+#[test]
+fn dupes_groups_exact_and_similar_names() {
+    let env = common::TestEnv::new();
+    env.add_into("Hammer", "garage").success();
+    env.add_into("hammer", "shed").success();
+    env.add_into("phillips screwdriver", "garage/toolbox")
+        .success();
+    env.add_into("philips screwdriver", "office/drawer")
+        .success();
+    env.add_into("cup", "kitchen").success();
+    env.add_into("cap", "closet").success();
+
+    env.run(&["dupes"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Same name: Hammer, hammer\n  garage/Hammer\n  shed/hammer\n",
+        ))
+        .stdout(predicate::str::contains(
+            "Similar: philips screwdriver, phillips screwdriver\n  \
+             garage/toolbox/phillips screwdriver\n  office/drawer/philips screwdriver\n",
+        ))
+        // Short names must match exactly by default
+        .stdout(predicate::str::contains("cup").not());
+
+    env.run(&["dupes", "--max-distance", "1", "--csv"])
+        .success()
+        .stdout(predicate::str::contains("similar,"))
+        .stdout(predicate::str::contains("kitchen/cup"));
+
+    env.run(&["dupes", "--exact", "--json"])
+        .success()
+        .stdout(predicate::str::contains("screwdriver").not())
+        .stdout(predicate::str::contains(r#""kind":"exact""#));
+}
+
+/// Test: nothing is printed without duplicates
+#[test]
+fn dupes_empty() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.run(&["dupes"]).success().stdout("");
+}
@@ -144,3 +144,238 @@ fn edit_with_json_output() {
         .success()
         .stdout(predicate::str::contains(r#""name":"new_hammer""#));
 }
+
+/// Test: an item can be edited by #ID
+#[test]
+fn edit_by_id() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.run(&["edit", "#1", "--name", "mallet"])
+        .success()
+        .stdout(predicate::str::contains("Updated: mallet (was: hammer)"));
+}
+
+/// Test: --expected-updated-at rejects an edit if the item changed since
+#[test]
+fn edit_expected_updated_at() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.backdate("hammer", "2024-01-15 10:30:00");
+
+    env.run(&[
+        "edit",
+        "hammer",
+        "--desc",
+        "claw",
+        "--expected-updated-at",
+        "2024-01-15 10:30:00",
+    ])
+    .success();
+
+    // The first edit moved updated_at on, so a second writer with the old
+    // timestamp loses
+    env.run(&[
+        "edit",
+        "hammer",
+        "--desc",
+        "ball-peen",
+        "--expected-updated-at",
+        "2024-01-15 10:30:00",
+    ])
+    .failure()
+    .stderr(predicate::str::contains(
+        "conflict: 'hammer' was updated at",
+    ));
+    env.run(&["show", "hammer"])
+        .success()
+        .stdout(predicate::str::contains("claw"));
+}
+
+/// Test: --in moves the item in the same edit as a rename
+#[test]
+fn edit_in_moves_and_renames() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+
+    env.run(&["edit", "hammer", "--name", "mallet", "--in", "workshop"])
+        .success()
+        .stdout(predicate::str::contains("Updated: mallet (was: hammer)"))
+        .stdout(predicate::str::contains(
+            "location: garage/toolbox -> workshop",
+        ));
+    env.run(&["show", "workshop/mallet"]).success();
+
+    env.run(&["edit", "mallet", "--in", "/"])
+        .success()
+        .stdout(predicate::str::contains("location: workshop -> (root)"));
+}
+
+/// Test: --in shares mv's cycle and name-conflict checks
+#[test]
+fn edit_in_conflicts() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "toolbox").success();
+    env.add_into("mallet", "workshop").success();
+
+    env.run(&["edit", "toolbox", "--in", "toolbox/hammer"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "cannot move 'toolbox' into itself or its descendants",
+        ));
+
+    env.run(&["edit", "hammer", "--name", "mallet", "--in", "workshop"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "item 'mallet' already exists in workshop",
+        ));
+    env.run(&["show", "toolbox/hammer"]).success();
+}
+
+/// Test: --editor edits the current description in $EDITOR
+#[cfg(unix)]
+#[test]
+fn edit_with_editor() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = common::TestEnv::new();
+    env.add_with_desc("hammer", "claw hammer").success();
+    let script = env.temp_dir.path().join("editor.sh");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\nprintf '\\n16oz, wooden handle\\n' >> \"$1\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    env.cmd()
+        .env("EDITOR", &script)
+        .env_remove("VISUAL")
+        .args(["edit", "hammer", "--editor"])
+        .assert()
+        .success();
+    env.run(&["show", "hammer", "--json"])
+        .success()
+        .stdout(predicate::str::contains(
+            r#""description":"claw hammer\n16oz, wooden handle""#,
+        ));
+
+    // A failing editor changes nothing
+    env.cmd()
+        .env("EDITOR", "false")
+        .env_remove("VISUAL")
+        .args(["edit", "hammer", "--editor"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nothing was changed"));
+}
+
+/// Test: Ctrl-C while the editor is open waits for it, then changes nothing
+#[cfg(unix)]
+#[test]
+fn edit_with_editor_interrupted() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = common::TestEnv::new();
+    env.add_with_desc("hammer", "claw hammer").success();
+    let script = env.temp_dir.path().join("editor.sh");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\nkill -INT $PPID\nsleep 1\necho 16oz >> \"$1\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    env.cmd()
+        .env("EDITOR", &script)
+        .env_remove("VISUAL")
+        .args(["edit", "hammer", "--editor"])
+        .assert()
+        .code(130)
+        .stderr(predicate::str::contains("interrupted; nothing was changed"));
+    env.run(&["show", "hammer", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""description":"claw hammer""#));
+}
+
+/// Test: --append-desc keeps the existing description
+#[test]
+fn edit_append_desc() {
+    let env = common::TestEnv::new();
+    env.add_with_desc("wrench", "13mm").success();
+    env.add("bolt").success();
+
+    env.run(&["edit", "wrench", "--append-desc", "also fits M5 bolts"])
+        .success()
+        .stdout(predicate::str::contains(
+            r#"description: "13mm" -> "13mm; also fits M5 bolts""#,
+        ));
+
+    // Nothing to append to: the text becomes the description
+    env.run(&["edit", "bolt", "--append-desc", "M5"]).success();
+    env.run(&["show", "bolt"])
+        .success()
+        .stdout(predicate::str::contains("Description: M5"));
+
+    env.run(&["edit", "bolt", "--append-desc", "x", "--desc", "y"])
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+/// Test: several items get the same change in one transaction
+#[test]
+fn edit_many_items() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+    env.add_into("saw", "garage").success();
+    env.add("drill").success();
+
+    env.run(&[
+        "edit",
+        "garage/hammer",
+        "garage/saw",
+        "--id",
+        "4",
+        "--desc",
+        "tools",
+    ])
+    .success()
+    .stdout(predicate::str::contains("Updated 3 items:"))
+    .stdout(predicate::str::contains("  garage/saw"));
+    env.run(&["show", "drill"])
+        .success()
+        .stdout(predicate::str::contains("Description: tools"));
+
+    // One missing item rolls back the rest
+    env.run(&["edit", "hammer", "ladder", "--desc", "shed"])
+        .failure()
+        .stderr(predicate::str::contains("item 'ladder' not found"));
+    env.run(&["show", "hammer"])
+        .success()
+        .stdout(predicate::str::contains("Description: tools"));
+
+    env.run(&["edit", "hammer", "saw", "--name", "x"])
+        .failure()
+        .stderr(predicate::str::contains("--name edits one item at a time"));
+}
+
+/// Test: --kind changes what an item is, for several items at once
+#[test]
+fn edit_kind() {
+    let env = common::TestEnv::new();
+    env.add("attic").success();
+    env.add("cellar").success();
+
+    env.run(&["edit", "attic", "--kind", "location"])
+        .success()
+        .stdout(predicate::str::contains("kind: item -> location"));
+    env.run(&["edit", "attic", "cellar", "--kind", "location"])
+        .success();
+    env.run(&["show", "cellar"])
+        .success()
+        .stdout(predicate::str::contains("Kind:        location"));
+
+    env.run(&["edit", "attic", "--kind", "shelf"])
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'shelf'"));
+}
@@ -0,0 +1,81 @@
+//! Integration tests for environment variable configuration.
+//!
+//! See SPEC.md#environment-variables
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: INVY_DB selects the database when --db is absent
+#[test]
+fn invy_db_selects_database() {
+    let env = common::TestEnv::new();
+    let db_path = env.temp_dir.path().join("from-env.db");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("invy");
+    cmd.env("XDG_CONFIG_HOME", env.temp_dir.path())
+        .env("INVY_DB", &db_path)
+        .args(["add", "hammer"])
+        .assert()
+        .success();
+
+    assert!(db_path.exists());
+}
+
+/// Test: INVY_DB wins over the config file
+#[test]
+fn invy_db_overrides_config() {
+    let env = common::TestEnv::new();
+    let config_db = env.temp_dir.path().join("from-config.db");
+    let env_db = env.temp_dir.path().join("from-env.db");
+    env.write_config(&format!("db = {:?}\n", config_db.to_str().unwrap()));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("invy");
+    cmd.env("XDG_CONFIG_HOME", env.temp_dir.path())
+        .env("INVY_DB", &env_db)
+        .args(["add", "hammer"])
+        .assert()
+        .success();
+
+    assert!(env_db.exists());
+    assert!(!config_db.exists());
+}
+
+/// Test: INVY_FORMAT sets the default output format
+#[test]
+fn invy_format_sets_default() {
+    let env = common::TestEnv::new();
+
+    env.cmd()
+        .env("INVY_FORMAT", "json")
+        .args(["add", "hammer"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("{"));
+}
+
+/// Test: explicit --csv wins over INVY_FORMAT
+#[test]
+fn csv_flag_overrides_invy_format() {
+    let env = common::TestEnv::new();
+
+    env.cmd()
+        .env("INVY_FORMAT", "json")
+        .args(["add", "hammer", "--csv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("id,name"));
+}
+
+/// Test: invalid INVY_FORMAT is rejected
+#[test]
+fn invalid_invy_format_fails() {
+    let env = common::TestEnv::new();
+
+    env.cmd()
+        .env("INVY_FORMAT", "yaml")
+        .args(["list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("yaml"));
+}
@@ -0,0 +1,96 @@
+//! Integration tests for expiry dates and `invy expiring`.
+//!
+//! See SPEC.md#invy-expiring
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: add --expires and edit --expires set the date shown by show
+#[test]
+fn expiry_dates_are_set_and_shown() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "flour", "--in", "pantry", "--expires", "2099-06-30"])
+        .success();
+    env.run(&["show", "flour"])
+        .success()
+        .stdout(predicate::str::contains("Expires:     2099-06-30\n"));
+
+    env.run(&["edit", "flour", "--expires", "2020-01-01"])
+        .success()
+        .stdout(predicate::str::contains("  expires: 2020-01-01"));
+    env.run(&["show", "flour"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Expires:     2020-01-01 (expired)",
+        ));
+
+    env.run(&["edit", "flour", "--expires", ""])
+        .success()
+        .stdout(predicate::str::contains("  expires: (none)"));
+    env.run(&["show", "flour", "--json"])
+        .success()
+        .stdout(predicate::str::contains("expires_at").not());
+}
+
+/// Test: expiring lists expired and soon-expiring items, soonest first
+#[test]
+fn expiring_lists_items_within_window() {
+    let env = common::TestEnv::new();
+    env.run(&[
+        "add",
+        "aspirin",
+        "--in",
+        "cabinet",
+        "--expires",
+        "2020-01-01",
+    ])
+    .success();
+    env.run(&["add", "milk", "--in", "fridge", "--expires", "5d"])
+        .success();
+    env.run(&["add", "extinguisher", "--in", "hall", "--expires", "2y"])
+        .success();
+    env.add("hammer").success();
+
+    env.run(&["expiring"])
+        .success()
+        .stdout(predicate::str::contains("expired cabinet/aspirin"))
+        .stdout(predicate::str::contains("5d fridge/milk"))
+        .stdout(predicate::str::contains("extinguisher").not());
+
+    env.run(&["-q", "expiring", "--within", "3y"])
+        .success()
+        .stdout("2\n4\n6\n");
+}
+
+/// Test: CSV output has the expiry dates
+#[test]
+fn expiring_csv() {
+    let env = common::TestEnv::new();
+    env.run(&[
+        "add",
+        "aspirin",
+        "--in",
+        "cabinet",
+        "--expires",
+        "2020-01-01",
+    ])
+    .success();
+    env.run(&["--format", "csv", "expiring"])
+        .success()
+        .stdout("id,name,path,expires_at\n2,aspirin,cabinet/aspirin,2020-01-01\n");
+}
+
+/// Test: nothing expiring says so, and bad dates are rejected
+#[test]
+fn expiring_empty_and_invalid() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "extinguisher", "--expires", "2099-01-01"])
+        .success();
+    env.run(&["expiring", "--within", "2w"])
+        .success()
+        .stdout("Nothing expires within 2w\n");
+    env.run(&["add", "milk", "--expires", "soon"])
+        .failure()
+        .stderr(predicate::str::contains("invalid time 'soon'"));
+}
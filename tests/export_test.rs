@@ -0,0 +1,383 @@
+//! Integration tests for the `export` command.
+//!
+//! See SPEC.md#invy-export
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: json target prints flat rows with string paths
+#[test]
+fn export_json_rows() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+
+    let output = env.run(&["export"]).success().get_output().stdout.clone();
+    let rows: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let rows = rows.as_array().unwrap();
+    assert_eq!(rows.len(), 3);
+
+    let hammer = &rows[2];
+    assert_eq!(hammer["path"], "garage/toolbox/hammer");
+    assert_eq!(hammer["container"], "garage/toolbox");
+    assert_eq!(hammer["depth"], 2);
+    assert_eq!(hammer["quantity"], 1);
+    assert_eq!(hammer["uuid"].as_str().unwrap().len(), 36);
+    assert_eq!(rows[0]["container"], "");
+    assert_eq!(rows[0]["child_count"], 1);
+}
+
+/// Test: datasette target writes items.json and metadata.json
+#[test]
+fn export_datasette_directory() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "toolbox").success();
+    let dir = env.temp_dir.path().join("site");
+
+    env.run(&[
+        "export",
+        "--target",
+        "datasette",
+        "-o",
+        dir.to_str().unwrap(),
+    ])
+    .success()
+    .stdout(predicate::str::contains("Exported 2 items"));
+
+    let items: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("items.json")).unwrap()).unwrap();
+    assert_eq!(items.as_array().unwrap().len(), 2);
+
+    let metadata: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("metadata.json")).unwrap()).unwrap();
+    let table = &metadata["databases"]["invy"]["tables"]["items"];
+    assert_eq!(table["label_column"], "name");
+    assert!(table["columns"]["path"].is_string());
+}
+
+/// Test: datasette target needs an output directory
+#[test]
+fn export_datasette_requires_output() {
+    let env = common::TestEnv::new();
+
+    env.run(&["export", "--target", "datasette"])
+        .failure()
+        .stderr(predicate::str::contains("--output"));
+}
+
+/// Test: --include and --exclude keep or drop whole subtrees
+#[test]
+fn export_include_exclude_globs() {
+    let env = common::TestEnv::new();
+    env.add_into("toolbox", "garage").success();
+    env.add_into("hammer", "garage/toolbox").success();
+    env.add_into("private papers", "garage").success();
+    env.add_into("deed", "garage/private papers").success();
+    env.add_into("stapler", "office").success();
+
+    let output = env
+        .run(&[
+            "export",
+            "--include",
+            "garage/**",
+            "--exclude",
+            "**/private*",
+        ])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let rows: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let paths: Vec<&str> = rows
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, ["garage", "garage/toolbox", "garage/toolbox/hammer"]);
+}
+
+/// Test: an empty pattern is rejected
+#[test]
+fn export_rejects_empty_glob() {
+    let env = common::TestEnv::new();
+
+    env.run(&["export", "--exclude", "/"])
+        .failure()
+        .stderr(predicate::str::contains("empty path pattern"));
+}
+
+/// Test: a container argument exports only that subtree
+#[test]
+fn export_subtree() {
+    let env = common::TestEnv::new();
+    env.add_into("ring", "home/valuables").success();
+    env.add_into("hammer", "home/garage").success();
+
+    let output = env
+        .run(&["export", "home/valuables"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let rows: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let paths: Vec<&str> = rows
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, ["home/valuables", "home/valuables/ring"]);
+
+    env.run(&["export", "attic"])
+        .failure()
+        .stderr(predicate::str::contains("container 'attic' not found"));
+}
+
+/// A shell script standing in for age: "encrypts" by prepending a header
+/// line naming its arguments and "decrypts" by dropping it again.
+#[cfg(unix)]
+fn fake_age(env: &common::TestEnv) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script = env.temp_dir.path().join("fake-age");
+    std::fs::write(
+        &script,
+        concat!(
+            "#!/bin/sh\n",
+            "case \"$1\" in\n",
+            "  --encrypt) shift; echo \"fake-age $*\"; cat ;;\n",
+            "  --decrypt) shift; [ \"$1\" = --identity ] && shift 2; tail -n +2 \"$1\" ;;\n",
+            "esac\n",
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+/// Test: an encrypted export goes through age and imports back with --decrypt
+#[cfg(unix)]
+#[test]
+fn export_encrypted_round_trip() {
+    let env = common::TestEnv::new();
+    let age = fake_age(&env);
+    env.run(&["add", "ring", "--in", "valuables", "-d", "gold"])
+        .success();
+    env.add_into("hammer", "garage").success();
+    let file = env.temp_dir.path().join("valuables.age");
+
+    env.cmd()
+        .env("INVY_AGE", &age)
+        .args(["export", "valuables", "--encrypt", "-r", "age1alice"])
+        .args(["-r", "age1bob", "-o"])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 items"));
+    let encrypted = std::fs::read_to_string(&file).unwrap();
+    assert!(encrypted.starts_with("fake-age --recipient age1alice --recipient age1bob\n"));
+
+    let other = common::TestEnv::new();
+    other
+        .cmd()
+        .env("INVY_AGE", &age)
+        .args(["import", "--decrypt", "--identity", "key.txt"])
+        .arg(&file)
+        .assert()
+        .success();
+    other
+        .run(&["show", "valuables/ring"])
+        .success()
+        .stdout(predicate::str::contains("gold"));
+    other.run(&["show", "hammer"]).failure();
+}
+
+/// Test: --encrypt needs a recipient and the json target
+#[test]
+fn export_encrypt_requires_recipient() {
+    let env = common::TestEnv::new();
+    env.add("ring").success();
+
+    env.run(&["export", "--encrypt"]).code(2);
+    env.run(&["export", "-r", "age1alice"]).code(2);
+    env.run(&[
+        "export",
+        "--encrypt",
+        "-r",
+        "age1alice",
+        "--target",
+        "datasette",
+        "-o",
+        "site",
+    ])
+    .failure()
+    .stderr(predicate::str::contains("only works with the json target"));
+}
+
+/// Test: a missing age program is reported with how to fix it
+#[test]
+fn export_encrypt_without_age() {
+    let env = common::TestEnv::new();
+    env.add("ring").success();
+
+    env.cmd()
+        .env("INVY_AGE", env.temp_dir.path().join("missing"))
+        .args(["export", "--encrypt", "-r", "age1alice", "-o", "out.age"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("install age or set INVY_AGE"));
+}
+
+/// Test: when age exits without reading the export, its own error is
+/// reported rather than the broken pipe
+#[cfg(unix)]
+#[test]
+fn export_encrypt_reports_age_error() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let env = common::TestEnv::new();
+    // Larger than a pipe buffer, so writing it fails once age has exited
+    env.run(&["add", "ring", "-d", &"x".repeat(100_000)])
+        .success();
+    let age = env.temp_dir.path().join("failing-age");
+    std::fs::write(
+        &age,
+        "#!/bin/sh\necho 'age: error: malformed recipient' >&2\nexit 1\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&age, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    env.cmd()
+        .env("INVY_AGE", &age)
+        .args(["export", "--encrypt", "-r", "nonsense", "-o", "out.age"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("malformed recipient"))
+        .stderr(predicate::str::contains("Failed to write").not());
+}
+
+/// Test: markdown-vault writes a note per container with front matter and
+/// wiki-links to its contents
+#[test]
+fn export_markdown_vault() {
+    let env = common::TestEnv::new();
+    env.add_full("hammer", "claw hammer", "garage/toolbox")
+        .success();
+    env.run(&["add", "nails", "--in", "garage/toolbox", "--qty", "200"])
+        .success();
+    env.add("passport").success();
+    let vault = env.temp_dir.path().join("vault");
+
+    env.run(&["export", "--target", "markdown-vault", "-o"])
+        .failure();
+    env.cmd()
+        .args(["export", "--target", "markdown-vault", "--output"])
+        .arg(&vault)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 5 items"));
+
+    let index = std::fs::read_to_string(vault.join("_Inventory.md")).unwrap();
+    assert!(index.contains("- [[garage|garage]]\n- passport\n"));
+    let garage = std::fs::read_to_string(vault.join("garage.md")).unwrap();
+    assert!(garage.contains("- [[garage/toolbox|toolbox]]"));
+    let toolbox = std::fs::read_to_string(vault.join("garage/toolbox.md")).unwrap();
+    assert!(toolbox.starts_with("---\ngenerator: invy\n"));
+    assert!(toolbox.contains("path: \"garage/toolbox\"\n"));
+    assert!(toolbox.contains("Inside [[garage|garage]]"));
+    assert!(toolbox.contains("- hammer: claw hammer\n- nails (200)\n"));
+    assert!(!vault.join("passport.md").exists());
+}
+
+/// Test: exporting the vault again updates changed notes, removes notes of
+/// removed containers and keeps the user's own files
+#[test]
+fn export_markdown_vault_regenerates() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+    env.add_into("lamp", "attic/box").success();
+    let vault = env.temp_dir.path().join("vault");
+    let export = || {
+        env.cmd()
+            .args(["export", "--target", "markdown-vault", "--output"])
+            .arg(&vault)
+            .assert()
+            .success();
+    };
+    export();
+    std::fs::write(vault.join("my notes.md"), "# Mine\n").unwrap();
+    std::fs::write(vault.join("attic/ideas.md"), "# Mine too\n").unwrap();
+    let garage = std::fs::read_to_string(vault.join("garage.md")).unwrap();
+
+    env.run(&["rm", "attic/box/lamp"]).success();
+    env.run(&["rm", "attic/box"]).success();
+    env.run(&["rm", "garage/toolbox/hammer"]).success();
+    export();
+
+    assert!(!vault.join("attic/box.md").exists());
+    assert!(vault.join("attic/ideas.md").exists());
+    assert!(vault.join("my notes.md").exists());
+    assert_eq!(
+        std::fs::read_to_string(vault.join("garage.md")).unwrap(),
+        garage
+    );
+    let toolbox = std::fs::read_to_string(vault.join("garage/toolbox.md")).unwrap();
+    assert!(toolbox.contains("_Empty_"));
+}
+
+/// Test: ical target emits an all-day event on each expiry date, with
+/// stable UIDs and escaped text
+#[test]
+fn export_ical_expiry_events() {
+    let env = common::TestEnv::new();
+    env.run(&[
+        "add",
+        "aspirin",
+        "--in",
+        "bathroom/cabinet",
+        "--expires",
+        "2030-05-01",
+        "--desc",
+        "500mg; 20 tablets",
+    ])
+    .success();
+    env.run(&["add", "milk", "--in", "fridge", "--expires", "2030-01-31"])
+        .success();
+    env.add_into("hammer", "garage").success();
+
+    let output = env
+        .run(&["export", "--target", "ical"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let feed = String::from_utf8(output).unwrap();
+    assert!(feed.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(feed.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(feed.matches("BEGIN:VEVENT").count(), 2);
+    assert!(feed.contains("DTSTART;VALUE=DATE:20300501\r\nDTEND;VALUE=DATE:20300502\r\n"));
+    assert!(feed.contains("DTSTART;VALUE=DATE:20300131\r\nDTEND;VALUE=DATE:20300201\r\n"));
+    assert!(feed.contains("SUMMARY:aspirin expires\r\n"));
+    assert!(feed.contains("DESCRIPTION:bathroom/cabinet/aspirin\\n500mg\\; 20 tablets\r\n"));
+    assert!(feed.contains("LOCATION:bathroom/cabinet\r\n"));
+    assert!(!feed.contains("hammer"));
+
+    // The same UIDs every time, so subscribed calendars update in place
+    let uids = |feed: &str| -> Vec<String> {
+        feed.lines()
+            .filter(|line| line.starts_with("UID:"))
+            .map(String::from)
+            .collect()
+    };
+    let path = env.temp_dir.path().join("invy.ics");
+    env.cmd()
+        .args(["export", "--target", "ical", "--include", "fridge/**", "-o"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 1 items"));
+    let filtered = std::fs::read_to_string(&path).unwrap();
+    assert!(filtered.contains("SUMMARY:milk expires"));
+    assert!(uids(&feed).contains(&uids(&filtered)[0]));
+}
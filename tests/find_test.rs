@@ -65,6 +65,21 @@ fn find_returns_full_path() {
         .stdout(predicate::str::contains("garage"));
 }
 
+/// Test: find prints a `/` inside a name escaped, so the path can be given
+/// back to other commands
+#[test]
+fn find_escapes_slashes_in_paths() {
+    let env = common::TestEnv::new();
+    env.add_into("A/V cables", "office").success();
+
+    env.run(&["find", "cables"])
+        .success()
+        .stdout(predicate::str::contains(r"office/A\/V cables"));
+    env.run(&["find", "cables", "--csv"])
+        .success()
+        .stdout(predicate::str::contains(r"office/A\/V cables"));
+}
+
 /// Test: find with no results
 #[test]
 fn find_with_no_results() {
@@ -119,3 +134,170 @@ fn find_with_csv_output() {
         .success()
         .stdout(predicate::str::contains("id,name,description,path"));
 }
+
+/// Test: find honors date filters
+#[test]
+fn find_filters_by_updated_date() {
+    let env = common::TestEnv::new();
+    env.add("red drill").success();
+    env.add("blue drill").success();
+    env.backdate("red drill", "2019-03-01 08:00:00");
+
+    env.run(&["find", "drill", "--updated-since", "1y"])
+        .success()
+        .stdout(predicate::str::contains("blue drill"))
+        .stdout(predicate::str::contains("red drill").not());
+
+    env.run(&["find", "drill", "--created-after", "whenever"])
+        .failure()
+        .stderr(predicate::str::contains("invalid time"));
+}
+
+/// Test: find pages through matches in name order
+#[test]
+fn find_limit_and_offset() {
+    let env = common::TestEnv::new();
+    env.add("red box").success();
+    env.add("blue box").success();
+    env.add("green box").success();
+
+    env.run(&["find", "box", "--limit", "1", "--offset", "1"])
+        .success()
+        .stdout(predicate::str::contains("green box"))
+        .stdout(predicate::str::contains("red box").not())
+        .stdout(predicate::str::contains("Showing 2-2 of 3"));
+
+    // Without paging, output is unchanged
+    env.run(&["find", "box"])
+        .success()
+        .stdout(predicate::str::contains("Showing").not());
+}
+
+/// Test: --in limits a search to one container's contents
+#[test]
+fn find_within_container() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "garage/shelf").success();
+    env.add_into("tape", "office").success();
+
+    env.run(&["find", "tape", "--in", "garage", "--csv"])
+        .success()
+        .stdout(predicate::str::contains("garage/shelf/tape"))
+        .stdout(predicate::str::contains("office/tape").not());
+}
+
+/// Test: --missing-child lists containers lacking an item
+#[test]
+fn find_missing_child() {
+    let env = common::TestEnv::new();
+    env.add_into("smoke detector", "house/kitchen").success();
+    env.add_into("lamp", "house/bedroom").success();
+    env.add_into("smoke detector", "house/hall/ceiling")
+        .success();
+    env.add_into("bed", "house/attic").success();
+
+    env.run(&[
+        "find",
+        "--missing-child",
+        "Smoke Detector",
+        "--in",
+        "house",
+        "--csv",
+    ])
+    .success()
+    .stdout(predicate::str::contains("house/attic"))
+    .stdout(predicate::str::contains("house/bedroom"))
+    .stdout(predicate::str::contains("kitchen").not())
+    .stdout(predicate::str::contains("hall").not());
+
+    // Without --in every container is checked
+    env.run(&["find", "--missing-child", "lamp", "--csv"])
+        .success()
+        .stdout(predicate::str::contains("house/hall/ceiling"))
+        .stdout(predicate::str::contains("house/bedroom").not());
+
+    env.run(&["find", "lamp", "--missing-child", "bed"])
+        .failure();
+}
+
+/// Test: --incomplete lists containers missing checklist entries
+#[test]
+fn find_incomplete_checklists() {
+    let env = common::TestEnv::new();
+    env.add_into("bandages", "car-kit").success();
+    env.add_into("bandages", "home-kit").success();
+    env.run(&["check", "car-kit", "--require", "bandages"])
+        .success();
+    env.run(&[
+        "check",
+        "home-kit",
+        "--require",
+        "bandages",
+        "--require",
+        "scissors",
+    ])
+    .failure();
+
+    env.run(&["find", "--incomplete", "--csv"])
+        .success()
+        .stdout(predicate::str::contains("home-kit"))
+        .stdout(predicate::str::contains("car-kit").not());
+}
+
+/// Test: --save-as-checklist puts the results' names on a container's
+/// checklist, leaving the items where they are
+#[test]
+fn find_save_as_checklist() {
+    let env = common::TestEnv::new();
+    env.add_full("tent", "camping tent", "garage").success();
+    env.add_full("stove", "camping stove", "garage/shelf")
+        .success();
+    env.add_into("rucksack", "hall").success();
+
+    env.run(&["find", "camping", "--save-as-checklist", "hall/rucksack"])
+        .success()
+        .stdout(predicate::str::contains("garage/shelf/stove"))
+        .stderr(predicate::str::contains(
+            "Added 2 names to the checklist of hall/rucksack",
+        ));
+
+    env.run(&["check", "hall/rucksack"])
+        .failure()
+        .stdout(predicate::str::contains("[ ] stove"))
+        .stdout(predicate::str::contains("[ ] tent"));
+    env.run(&["where", "tent"])
+        .success()
+        .stdout(predicate::str::contains("garage/tent"));
+
+    env.run(&["find", "kayak", "--save-as-checklist", "hall/rucksack"])
+        .failure()
+        .stderr(predicate::str::contains("no results to save"));
+}
+
+/// Test: --path and --not-path keep or drop whole areas by glob
+#[test]
+fn find_path_globs() {
+    let env = common::TestEnv::new();
+    env.add_into("AA battery", "garage/shelf").success();
+    env.add_into("9V battery", "garage/attic box").success();
+    env.add_into("AAA battery", "kitchen/drawer").success();
+
+    env.run(&["find", "battery", "--path", "garage/**", "-q"])
+        .success()
+        .stdout("5\n3\n");
+    env.run(&["find", "battery", "--not-path", "**/attic*", "-q"])
+        .success()
+        .stdout("3\n8\n");
+    env.run(&[
+        "find",
+        "battery",
+        "--path",
+        "Garage/shelf",
+        "--path",
+        "kitchen",
+    ])
+    .success()
+    .stdout(predicate::str::contains("garage/shelf/AA battery"))
+    .stdout(predicate::str::contains("kitchen/drawer/AAA battery"))
+    .stdout(predicate::str::contains("9V").not());
+}
@@ -0,0 +1,83 @@
+//! Integration tests for the `hash` command.
+//!
+//! See SPEC.md#invy-hash-container
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: hashing records a 64-character hex digest
+#[test]
+fn hash_records_digest() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "box-3").success();
+
+    env.run(&["hash", "box-3"])
+        .success()
+        .stdout(predicate::str::is_match(r"^box-3  [0-9a-f]{64}\n$").unwrap());
+}
+
+/// Test: verify passes when nothing changed
+#[test]
+fn verify_unchanged_container() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "box-3").success();
+    env.run(&["hash", "box-3"]).success();
+
+    env.run(&["hash", "box-3", "--verify"])
+        .success()
+        .stdout(predicate::str::contains("box-3  OK"));
+}
+
+/// Test: verify fails after contents change
+#[test]
+fn verify_detects_drift() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "box-3").success();
+    env.run(&["hash", "box-3"]).success();
+
+    env.run(&["edit", "tape", "--desc", "duct"]).success();
+
+    env.run(&["hash", "box-3", "--verify"])
+        .failure()
+        .stdout(predicate::str::contains("CHANGED"))
+        .stderr(predicate::str::contains("do not match"));
+}
+
+/// Test: verify without a container checks every recorded container
+#[test]
+fn verify_all_recorded_containers() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "box-1").success();
+    env.add_into("glue", "box-2").success();
+    env.run(&["hash", "box-1"]).success();
+    env.run(&["hash", "box-2"]).success();
+
+    env.add_into("scissors", "box-2").success();
+
+    env.run(&["hash", "--verify", "--json"])
+        .failure()
+        .stdout(predicate::str::contains(r#""status":"ok""#))
+        .stdout(predicate::str::contains(r#""status":"changed""#));
+}
+
+/// Test: verify on a never-hashed container fails
+#[test]
+fn verify_unrecorded_container_fails() {
+    let env = common::TestEnv::new();
+    env.add("box-3").success();
+
+    env.run(&["hash", "box-3", "--verify"])
+        .failure()
+        .stdout(predicate::str::contains("NO RECORDED HASH"));
+}
+
+/// Test: hash without container or --verify fails
+#[test]
+fn hash_requires_container() {
+    let env = common::TestEnv::new();
+
+    env.run(&["hash"])
+        .failure()
+        .stderr(predicate::str::contains("no container specified"));
+}
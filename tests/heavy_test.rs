@@ -0,0 +1,105 @@
+//! Integration tests for the `heavy` command.
+//!
+//! See SPEC.md#invy-heavy
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Build a moving box of books, a light box and a bag of flour.
+fn pack(env: &common::TestEnv) {
+    env.add_into("books", "garage/box").success();
+    env.add_into("pillows", "garage/light box").success();
+    env.run(&["edit", "books", "--weight", "18kg"])
+        .success()
+        .stdout(predicate::str::contains("weight: 18 kg"));
+    env.run(&["edit", "box", "--weight", "3kg"]).success();
+    env.run(&["edit", "pillows", "--weight", "900g"]).success();
+    env.run(&["add", "flour", "--in", "pantry", "--qty", "2kg"])
+        .success();
+}
+
+/// Test: containers over the threshold are listed heaviest first, with
+/// weights summed over their contents
+#[test]
+fn heavy_lists_containers_over_threshold() {
+    let env = common::TestEnv::new();
+    pack(&env);
+
+    env.run(&["heavy"]).success().stdout(
+        predicate::str::is_match(r"21\.9 kg\s+2\s+garage\n\s*21 kg\s+1\s+garage/box\n$").unwrap(),
+    );
+    env.run(&["heavy", "--over", "1kg", "-q"])
+        .success()
+        .stdout("1\n2\n6\n");
+    env.run(&["heavy", "--over", "1kg", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""weight_g":2000"#));
+    env.run(&["heavy", "--over", "50kg"])
+        .success()
+        .stdout("No containers over 50 kg\n");
+}
+
+/// Test: heavy_over in the config changes the default threshold
+#[test]
+fn heavy_threshold_from_config() {
+    let env = common::TestEnv::new();
+    pack(&env);
+    env.write_config("heavy_over = \"25kg\"\n");
+
+    env.run(&["heavy"])
+        .success()
+        .stdout("No containers over 25 kg\n");
+}
+
+/// Test: a bad heavy_over only breaks the commands that use it
+#[test]
+fn heavy_bad_config_value() {
+    let env = common::TestEnv::new();
+    pack(&env);
+    env.write_config("heavy_over = \"lots\"\n");
+
+    env.run(&["heavy"]).failure();
+    env.run(&["list"]).failure();
+    env.run(&["doctor"]).success();
+    env.run(&["show", "garage"]).success();
+}
+
+/// Test: list shows weights and flags heavy containers
+#[test]
+fn list_flags_heavy_containers() {
+    let env = common::TestEnv::new();
+    pack(&env);
+
+    env.run(&["list", "garage"])
+        .success()
+        .stdout(predicate::str::is_match(r"box\s+-\s+1\s+21 kg \(heavy\)").unwrap())
+        .stdout(predicate::str::is_match(r"light box\s+-\s+1\s+900 g\n").unwrap());
+    env.run(&["list", "garage", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""weight_g":21000,"heavy":true"#));
+    env.run(&["show", "garage"])
+        .success()
+        .stdout(predicate::str::contains("Weight:      21.9 kg"));
+}
+
+/// Test: weights must be masses, and "" clears one
+#[test]
+fn edit_weight() {
+    let env = common::TestEnv::new();
+    env.add("anvil").success();
+
+    env.run(&["edit", "anvil", "--weight", "50"])
+        .failure()
+        .stderr(predicate::str::contains("invalid weight '50'"));
+    env.run(&["edit", "anvil", "--weight", "50kg"]).success();
+    env.run(&["show", "anvil", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""weight_g":50000"#));
+    env.run(&["edit", "anvil", "--weight", ""])
+        .success()
+        .stdout(predicate::str::contains("weight: (none)"));
+    env.run(&["show", "anvil"])
+        .success()
+        .stdout(predicate::str::contains("Weight:").not());
+}
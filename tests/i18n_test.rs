@@ -0,0 +1,88 @@
+//! Integration tests for the `i18n` command and `--lang`.
+//!
+//! See SPEC.md#invy-i18n
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: a name set in another language is listed and found by find
+#[test]
+fn i18n_set_and_find() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+
+    env.run(&["i18n", "set", "hammer", "ES", "martillo"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Named: garage/hammer (es: martillo)",
+        ));
+
+    env.run(&["i18n", "list", "hammer"])
+        .success()
+        .stdout(predicate::str::is_match(r"es\s+martillo").unwrap());
+
+    env.run(&["find", "martil"])
+        .success()
+        .stdout(predicate::str::contains("hammer"));
+}
+
+/// Test: --lang shows translated names, falling back to the original
+#[test]
+fn lang_selects_display_name() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+    env.add_into("saw", "garage").success();
+    env.run(&["i18n", "set", "garage", "es", "garaje"])
+        .success();
+    env.run(&["i18n", "set", "hammer", "es", "martillo"])
+        .success();
+
+    env.run(&["find", "hammer", "--lang", "es"])
+        .success()
+        .stdout(predicate::str::contains("martillo"))
+        .stdout(predicate::str::contains("garaje"));
+
+    env.run(&["list", "garage", "--lang", "es"])
+        .success()
+        .stdout(predicate::str::contains("martillo"))
+        .stdout(predicate::str::contains("saw"));
+
+    env.run(&["list", "-r", "--lang", "es"])
+        .success()
+        .stdout(predicate::str::contains("garaje"));
+
+    env.write_config("lang = \"es\"\n");
+    env.run(&["show", "hammer"])
+        .success()
+        .stdout(predicate::str::contains("martillo"));
+
+    // Without a language the original names are shown
+    env.run(&["list", "garage", "--lang", "fr"])
+        .success()
+        .stdout(predicate::str::contains("hammer"));
+}
+
+/// Test: names can be removed; invalid codes and unknown names are errors
+#[test]
+fn i18n_unset_and_errors() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.run(&["i18n", "set", "hammer", "es", "martillo"])
+        .success();
+
+    env.run(&["i18n", "unset", "hammer", "es", "--json"])
+        .success()
+        .stdout(predicate::str::contains("\"name\":\"martillo\""));
+    env.run(&["i18n", "unset", "hammer", "es"])
+        .failure()
+        .stderr(predicate::str::contains("'hammer' has no 'es' name"));
+
+    env.run(&["i18n", "set", "hammer", "e s", "martillo"])
+        .failure()
+        .stderr(predicate::str::contains("invalid language code"));
+
+    env.run(&["i18n", "list", "hammer"])
+        .success()
+        .stdout(predicate::str::contains("has no other names"));
+}
@@ -0,0 +1,316 @@
+//! Integration tests for the `import` command.
+//!
+//! See SPEC.md#invy-import-file
+
+mod common;
+
+use predicates::prelude::*;
+use std::path::PathBuf;
+
+fn write_csv(env: &common::TestEnv, contents: &str) -> PathBuf {
+    let path = env.temp_dir.path().join("items.csv");
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+/// Test: valid rows are imported, creating containers along their paths
+#[test]
+fn import_creates_items_and_containers() {
+    let env = common::TestEnv::new();
+    let file = write_csv(
+        &env,
+        "path,description,quantity\n\
+         garage/toolbox/hammer,claw hammer,\n\
+         garage,the big one,\n\
+         pantry/beans,,4\n",
+    );
+
+    env.run(&["import", file.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::contains("Imported 3 items"));
+
+    env.run(&["show", "garage/toolbox/hammer"])
+        .success()
+        .stdout(predicate::str::contains("claw hammer"));
+    env.run(&["show", "garage", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""description":"the big one""#));
+    env.run(&["show", "beans"])
+        .success()
+        .stdout(predicate::str::contains("Quantity:    4"));
+
+    // Listed or implied, what holds something is a container
+    env.run(&["list", "-r", "--containers-only"])
+        .success()
+        .stdout(predicate::str::contains("toolbox"))
+        .stdout(predicate::str::contains("pantry"))
+        .stdout(predicate::str::contains("beans").not());
+    env.run(&["--strict", "add", "saw", "--in", "garage"])
+        .success();
+}
+
+/// Test: any error aborts the import and reports line, column and fix
+#[test]
+fn import_errors_abort_before_writes() {
+    let env = common::TestEnv::new();
+    let file = write_csv(
+        &env,
+        "path,quantity\n\
+         shelf/jar,2\n\
+         shelf/can,two\n\
+         shelf/jar,1\n",
+    );
+
+    env.run(&["import", file.to_str().unwrap()])
+        .failure()
+        .stdout(predicate::str::contains(
+            "line 3, quantity: error: quantity is not a whole number ('two')",
+        ))
+        .stdout(predicate::str::contains("fix:"))
+        .stdout(predicate::str::contains("first seen on line 2"))
+        .stderr(predicate::str::contains("nothing was written"));
+
+    env.run(&["list"])
+        .success()
+        .stdout(predicate::str::contains("shelf").not());
+}
+
+/// Test: --report-only validates without writing and reports as JSON
+#[test]
+fn import_report_only() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    let file = write_csv(&env, "path,colour\nhammer,\nwrench,\n");
+
+    let output = env
+        .run(&["import", file.to_str().unwrap(), "--report-only", "--json"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["rows"], 2);
+    assert_eq!(report["errors"], 0);
+    assert_eq!(report["warnings"], 2);
+    assert_eq!(report["imported"], 0);
+    assert_eq!(report["issues"][0]["column"], "colour");
+    assert_eq!(report["issues"][1]["line"], 2);
+    assert_eq!(report["issues"][1]["severity"], "warning");
+
+    env.run(&["show", "wrench"]).failure();
+}
+
+/// Test: a file without a path column is rejected
+#[test]
+fn import_requires_path_column() {
+    let env = common::TestEnv::new();
+    let file = write_csv(&env, "name\nhammer\n");
+
+    env.run(&["import", file.to_str().unwrap(), "--csv"])
+        .failure()
+        .stdout(predicate::str::contains(
+            "error,1,,,missing required column 'path'",
+        ));
+}
+
+/// Test: UUIDs from an export are kept, and rows whose UUID is already in
+/// the inventory are skipped even after a rename
+#[test]
+fn import_keeps_uuids() {
+    let env = common::TestEnv::new();
+    let uuid = "3f2a9c1e-7b4d-4e8a-9f00-12ab34cd56ef";
+    let file = write_csv(
+        &env,
+        &format!("path,uuid\ngarage/hammer,{}\n", uuid.to_uppercase()),
+    );
+
+    env.run(&["import", file.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::contains("Imported 1 item"));
+    env.run(&["show", "uuid:3f2a", "--json"])
+        .success()
+        .stdout(predicate::str::contains(format!(r#""uuid":"{}""#, uuid)));
+
+    env.run(&["edit", "hammer", "--name", "mallet"]).success();
+    env.run(&["import", file.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::contains("already in the inventory"));
+    env.run(&["show", "garage/hammer"]).failure();
+}
+
+/// Test: malformed and repeated UUIDs are errors
+#[test]
+fn import_rejects_bad_uuids() {
+    let env = common::TestEnv::new();
+    let file = write_csv(
+        &env,
+        "path,uuid\n\
+         hammer,not-a-uuid\n\
+         saw,3f2a9c1e-7b4d-4e8a-9f00-12ab34cd56ef\n\
+         drill,3f2a9c1e-7b4d-4e8a-9f00-12ab34cd56ef\n",
+    );
+
+    env.run(&["import", file.to_str().unwrap()])
+        .failure()
+        .stdout(predicate::str::contains("uuid is not a valid UUID"))
+        .stdout(predicate::str::contains(
+            "duplicate uuid, first seen on line 3",
+        ));
+}
+
+/// Test: an `invy export` file imports with its names, quantities and UUIDs
+#[test]
+fn import_reads_export_json() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "A/V cables", "--in", "shelf", "-d", "hdmi"])
+        .success();
+    env.run(&["add", "rope", "--in", "shelf", "--qty", "20m"])
+        .success();
+    env.run(&["edit", "shelf", "--kind", "location"]).success();
+    let export = env.run(&["export"]).success().get_output().stdout.clone();
+    let file = env.temp_dir.path().join("items.json");
+    std::fs::write(&file, &export).unwrap();
+
+    let other = common::TestEnv::new();
+    other
+        .cmd()
+        .arg("import")
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 3 items"));
+    other
+        .run(&["show", "shelf/A\\/V cables"])
+        .success()
+        .stdout(predicate::str::contains("hdmi"));
+    let reexport = other.run(&["export"]).success().get_output().stdout.clone();
+    let uuids = |bytes: &[u8]| -> Vec<serde_json::Value> {
+        let rows: serde_json::Value = serde_json::from_slice(bytes).unwrap();
+        rows.as_array()
+            .unwrap()
+            .iter()
+            .map(|row| row["uuid"].clone())
+            .collect()
+    };
+    assert_eq!(uuids(&reexport), uuids(&export));
+    other
+        .run(&["show", "rope"])
+        .success()
+        .stdout(predicate::str::contains("20"));
+    other
+        .run(&["show", "shelf", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""kind":"location""#));
+}
+
+/// Test: HomeBox JSON places items in nested locations, with labels in the
+/// description and its item IDs as UUIDs
+#[test]
+fn import_from_homebox_json() {
+    let env = common::TestEnv::new();
+    let file = env.temp_dir.path().join("homebox.json");
+    std::fs::write(
+        &file,
+        r#"{
+          "locations": [
+            {"id": "loc-1", "name": "Garage", "children": [
+              {"id": "loc-2", "name": "Shelf", "children": []}
+            ]}
+          ],
+          "items": [
+            {"id": "0b6f5a3e-1c2d-4e5f-8a9b-0c1d2e3f4a5b", "name": "Drill",
+             "description": "cordless", "quantity": 1,
+             "location": {"id": "loc-2", "name": "Shelf"},
+             "labels": [{"id": "l1", "name": "Tools"}, {"id": "l2", "name": "Power"}]},
+            {"id": "x", "name": "Drill", "quantity": 2,
+             "location": {"id": "loc-2", "name": "Shelf"}, "labels": []},
+            {"id": "y", "name": "Old saw", "archived": true,
+             "location": {"id": "loc-1", "name": "Garage"}}
+          ]
+        }"#,
+    )
+    .unwrap();
+
+    env.run(&["import", "--from", "homebox", file.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::contains("Imported 2 items"));
+
+    env.run(&["show", "Garage/Shelf/Drill", "--json"])
+        .success()
+        .stdout(predicate::str::contains(
+            r#""description":"cordless; labels: Tools, Power""#,
+        ))
+        .stdout(predicate::str::contains(
+            "0b6f5a3e-1c2d-4e5f-8a9b-0c1d2e3f4a5b",
+        ));
+    env.run(&["show", "Garage/Shelf/Drill (2)"])
+        .success()
+        .stdout(predicate::str::contains("Quantity:    2"));
+    env.run(&["show", "Old saw"]).failure();
+}
+
+/// Test: HomeBox's own CSV export is read by its HB. columns
+#[test]
+fn import_from_homebox_csv() {
+    let env = common::TestEnv::new();
+    let file = write_csv(
+        &env,
+        "HB.import_ref,HB.location,HB.labels,HB.name,HB.quantity,HB.description,HB.archived\n\
+         ,Kitchen / Drawer,Cooking;Metal,Whisk,3,balloon,false\n",
+    );
+
+    env.run(&["import", "--from", "homebox", file.to_str().unwrap()])
+        .success();
+    env.run(&["show", "Kitchen/Drawer/Whisk"])
+        .success()
+        .stdout(predicate::str::contains("balloon; labels: Cooking, Metal"))
+        .stdout(predicate::str::contains("Quantity:    3"));
+
+    let file = write_csv(&env, "path\nshelf/jar\n");
+    env.run(&["import", "--from", "homebox", file.to_str().unwrap()])
+        .failure()
+        .stderr(predicate::str::contains("no HB.name column"));
+}
+
+/// Test: Grocy stock becomes one item per product and location, with its
+/// amount and quantity unit
+#[test]
+fn import_from_grocy() {
+    let env = common::TestEnv::new();
+    let file = env.temp_dir.path().join("grocy.json");
+    std::fs::write(
+        &file,
+        r#"{
+          "locations": [{"id": 1, "name": "Pantry"}, {"id": "2", "name": "Fridge"}],
+          "quantity_units": [{"id": 1, "name": "Piece"}, {"id": 2, "name": "Pack"}],
+          "products": [
+            {"id": 10, "name": "Pasta", "description": "<p>Penne</p>",
+             "location_id": 1, "qu_id_stock": 2},
+            {"id": 11, "name": "Eggs", "location_id": 2, "qu_id_stock": 1},
+            {"id": 12, "name": "Flour", "location_id": 1, "qu_id_stock": 1}
+          ],
+          "stock": [
+            {"product_id": 10, "amount": 2, "location_id": 1},
+            {"product_id": 10, "amount": "1", "location_id": 1},
+            {"product_id": 11, "amount": 6, "location_id": 2},
+            {"product_id": 11, "amount": 4, "location_id": 1}
+          ]
+        }"#,
+    )
+    .unwrap();
+
+    env.run(&["import", "--from", "grocy", file.to_str().unwrap()])
+        .success()
+        .stdout(predicate::str::contains("Imported 3 items"));
+
+    env.run(&["show", "Pantry/Pasta"])
+        .success()
+        .stdout(predicate::str::contains("Penne"))
+        .stdout(predicate::str::contains("Quantity:    3 Pack"));
+    env.run(&["show", "Fridge/Eggs"])
+        .success()
+        .stdout(predicate::str::contains("Quantity:    6"));
+    env.run(&["show", "Pantry/Eggs"]).success();
+    // Out of stock
+    env.run(&["show", "Flour"]).failure();
+}
@@ -0,0 +1,68 @@
+//! Integration tests for the `init` command.
+//!
+//! See SPEC.md#invy-init
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: init creates a new database
+#[test]
+fn init_creates_database() {
+    let env = common::TestEnv::new();
+
+    env.run(&["init"])
+        .success()
+        .stdout(predicate::str::contains("Initialized database"));
+
+    assert!(env.db_path.exists());
+}
+
+/// Test: init refuses to overwrite an existing database
+#[test]
+fn init_existing_database_fails() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.run(&["init"])
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+}
+
+/// Test: --encrypted without the encryption feature is rejected
+#[cfg(not(feature = "encryption"))]
+#[test]
+fn init_encrypted_requires_feature() {
+    let env = common::TestEnv::new();
+
+    env.run(&["init", "--encrypted"])
+        .failure()
+        .stderr(predicate::str::contains("no encryption support"));
+
+    assert!(!env.db_path.exists());
+}
+
+/// Test: encrypted database round-trips with INVY_PASSPHRASE
+#[cfg(feature = "encryption")]
+#[test]
+fn init_encrypted_round_trip() {
+    let env = common::TestEnv::new();
+
+    env.cmd()
+        .env("INVY_PASSPHRASE", "s3cret")
+        .args(["init", "--encrypted"])
+        .assert()
+        .success();
+    env.cmd()
+        .env("INVY_PASSPHRASE", "s3cret")
+        .args(["add", "hammer"])
+        .assert()
+        .success();
+
+    env.cmd()
+        .env("INVY_PASSPHRASE", "wrong")
+        .args(["list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("wrong passphrase"));
+}
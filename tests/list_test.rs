@@ -212,3 +212,310 @@ fn list_recursive_alphabetical_order() {
         "middle should come before zebra"
     );
 }
+
+/// Test: date filters keep matching items
+#[test]
+fn list_filters_by_created_date() {
+    let env = common::TestEnv::new();
+    env.add("old lamp").success();
+    env.add("new lamp").success();
+    env.backdate("old lamp", "2020-05-01 12:00:00");
+
+    env.run(&["list", "--created-after", "2021-01-01"])
+        .success()
+        .stdout(predicate::str::contains("new lamp"))
+        .stdout(predicate::str::contains("old lamp").not());
+
+    env.run(&["list", "--created-before", "30d"])
+        .success()
+        .stdout(predicate::str::contains("old lamp"))
+        .stdout(predicate::str::contains("new lamp").not());
+}
+
+/// Test: filtered recursive listing keeps ancestors of matches
+#[test]
+fn list_recursive_date_filter_keeps_ancestors() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+    env.add("attic").success();
+    env.backdate("garage", "2020-01-01 00:00:00");
+    env.backdate("toolbox", "2020-01-01 00:00:00");
+    env.backdate("attic", "2020-01-01 00:00:00");
+
+    env.run(&["list", "-r", "--updated-since", "7d"])
+        .success()
+        .stdout(predicate::str::contains("garage"))
+        .stdout(predicate::str::contains("hammer"))
+        .stdout(predicate::str::contains("attic").not());
+}
+
+/// Test: --sort orders every output format, --reverse flips it
+#[test]
+fn list_sort_and_reverse() {
+    let env = common::TestEnv::new();
+    env.add("bin").success();
+    env.add_into("lid", "bin").success();
+    env.add("shelf").success();
+    env.add_into("jar", "shelf").success();
+    env.add_into("can", "shelf").success();
+    env.add("attic").success();
+    env.backdate("shelf", "2020-01-01 00:00:00");
+
+    let names = |args: &[&str]| {
+        let output = env.run(args).success().get_output().stdout.clone();
+        let rows: Vec<serde_json::Value> = serde_json::from_slice(&output).unwrap();
+        rows.iter()
+            .map(|row| row["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(&["list", "--json"]), ["attic", "bin", "shelf"]);
+    assert_eq!(
+        names(&["list", "--json", "--sort", "children", "--reverse"]),
+        ["shelf", "bin", "attic"]
+    );
+    assert_eq!(names(&["list", "--json", "--sort", "created"])[0], "shelf");
+
+    let csv = env
+        .run(&["list", "--csv", "--sort", "name", "--reverse"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let csv = String::from_utf8_lossy(&csv);
+    assert!(csv.find("shelf").unwrap() < csv.find("attic").unwrap());
+}
+
+/// Test: --limit/--offset page through results with totals
+#[test]
+fn list_limit_and_offset() {
+    let env = common::TestEnv::new();
+    for name in ["a", "b", "c", "d", "e"] {
+        env.add(name).success();
+    }
+
+    env.run(&["list", "--limit", "2", "--offset", "1"])
+        .success()
+        .stdout(predicate::str::contains("b"))
+        .stdout(predicate::str::contains("c"))
+        .stdout(predicate::str::contains("\nd ").not())
+        .stdout(predicate::str::contains("Showing 2-3 of 5"));
+
+    env.run(&["list", "--limit", "2", "--offset", "4", "--json"])
+        .success()
+        .stdout(predicate::str::starts_with(
+            r#"{"total":5,"offset":4,"limit":2,"items":[{"id":5,"name":"e""#,
+        ));
+
+    env.run(&["list", "-r", "--limit", "2"]).failure();
+}
+
+/// Test: --long adds IDs, paths and timestamps in every format
+#[test]
+fn list_long_format() {
+    let env = common::TestEnv::new();
+    env.add_full("hammer", "claw hammer", "garage/toolbox")
+        .success();
+    env.backdate("hammer", "2024-01-15 10:30:00");
+
+    env.run(&["list", "garage/toolbox", "--long"])
+        .success()
+        .stdout(predicate::str::contains("ID ITEMS CREATED"))
+        .stdout(predicate::str::contains(
+            " 3     - 2024-01-15 10:30:00 2024-01-15 10:30:00 garage/toolbox/hammer claw hammer",
+        ));
+
+    env.run(&["list", "garage/toolbox", "-l", "--json"])
+        .success()
+        .stdout(predicate::str::contains(
+            r#""path":["garage","toolbox","hammer"],"created_at":"2024-01-15 10:30:00""#,
+        ));
+
+    env.run(&["list", "garage/toolbox", "-l", "--csv"])
+        .success()
+        .stdout(predicate::str::contains(
+            "id,name,description,child_count,path,created_at,updated_at",
+        ));
+
+    // The default stays compact
+    env.run(&["list", "garage/toolbox", "--json"])
+        .success()
+        .stdout(predicate::str::contains("created_at").not());
+}
+
+/// Test: --color always colors rows by age, using configured thresholds
+#[test]
+fn list_colors_rows_by_age() {
+    let env = common::TestEnv::new();
+    env.add("new lamp").success();
+    env.add("old lamp").success();
+    env.add("mid lamp").success();
+    env.backdate("old lamp", "2000-01-01 00:00:00");
+    env.backdate("mid lamp", "2020-01-01 00:00:00");
+    env.write_config("color_fresh = \"7d\"\ncolor_stale = \"2010-01-01\"\n");
+
+    env.run(&["list", "--color", "always"])
+        .success()
+        .stdout(predicate::str::contains("\x1b[32mnew lamp"))
+        .stdout(predicate::str::contains("\x1b[90mmid lamp"))
+        .stdout(predicate::str::contains("\x1b[31mold lamp"));
+
+    // Never colored when piped (auto) or for machine formats
+    env.run(&["list"])
+        .success()
+        .stdout(predicate::str::contains("\x1b[").not());
+    env.run(&["list", "--color", "always", "--json"])
+        .success()
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+/// Test: --markdown renders a table, and a bullet tree with --recursive
+#[test]
+fn list_markdown_output() {
+    let env = common::TestEnv::new();
+    env.add_full("hammer", "claw hammer", "garage/toolbox")
+        .success();
+
+    env.run(&["list", "garage", "--markdown"])
+        .success()
+        .stdout(predicate::str::starts_with(
+            "| Name | Description | Items |\n|---|---|---|\n| toolbox |  | 1 |\n",
+        ));
+
+    env.run(&["list", "-r", "--markdown"])
+        .success()
+        .stdout("- garage [1]\n  - toolbox [1]\n    - hammer (claw hammer)\n");
+}
+
+/// Test: columns line up for double-width names
+#[test]
+fn list_aligns_wide_characters() {
+    let env = common::TestEnv::new();
+    env.add_with_desc("工具箱", "tools").success();
+    env.add_with_desc("box", "spare").success();
+
+    env.run(&["list"])
+        .success()
+        .stdout(predicate::str::contains("box    spare"))
+        .stdout(predicate::str::contains("工具箱 tools"));
+}
+
+/// Test: --containers-only hides plain items, keeping their places in the tree
+#[test]
+fn list_containers_only() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "kitchen", "--kind", "location"]).success();
+    env.add_into("drawer", "kitchen").success();
+    env.run(&["edit", "drawer", "--kind", "container"])
+        .success();
+    env.add_into("scissors", "kitchen/drawer").success();
+    env.add("hammer").success();
+
+    env.run(&["list", "--containers-only"])
+        .success()
+        .stdout(predicate::str::contains("kitchen"))
+        .stdout(predicate::str::contains("hammer").not());
+
+    env.run(&["list", "-r", "--containers-only"])
+        .success()
+        .stdout(predicate::str::contains("drawer"))
+        .stdout(predicate::str::contains("scissors").not())
+        .stdout(predicate::str::contains("hammer").not());
+}
+
+/// Test: --empty lists only containers and locations holding nothing
+#[test]
+fn list_empty_containers() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "garage", "--kind", "location"]).success();
+    env.run(&["add", "old box", "--kind", "container", "--in", "garage"])
+        .success();
+    env.add_into("drill", "garage/toolbox").success();
+    env.add_into("hammer", "garage").success();
+    env.run(&["add", "attic", "--kind", "location"]).success();
+
+    // Nested empty boxes are found without -r, shown with their paths
+    env.run(&["list", "--empty"])
+        .success()
+        .stdout(predicate::str::contains("garage/old box"))
+        .stdout(predicate::str::contains("attic"))
+        .stdout(predicate::str::contains("toolbox").not())
+        .stdout(predicate::str::contains("hammer").not());
+    env.run(&["list", "--empty", "-q"])
+        .success()
+        .stdout("6\n2\n");
+
+    env.run(&["list", "-r", "--empty"])
+        .success()
+        .stdout(predicate::str::contains("old box"))
+        .stdout(predicate::str::contains("attic"))
+        .stdout(predicate::str::contains("toolbox").not())
+        .stdout(predicate::str::contains("hammer").not());
+
+    env.run(&["list", "garage", "--empty"])
+        .success()
+        .stdout(predicate::str::contains("garage/old box"))
+        .stdout(predicate::str::contains("attic").not());
+}
+
+/// Test: --group lists only the containers added together with add --count
+#[test]
+fn list_group_members() {
+    let env = common::TestEnv::new();
+    env.run(&[
+        "add", "bin", "--count", "2", "--group", "bins", "--in", "shelf",
+    ])
+    .success();
+    env.add_into("tape", "shelf").success();
+    env.add_into("gloves", "shelf/bin-1").success();
+
+    env.run(&["list", "shelf", "--group", "BINS", "-q"])
+        .success()
+        .stdout("2\n3\n");
+    env.run(&["list", "-r", "--group", "bins"])
+        .success()
+        .stdout(predicate::str::contains("bin-2"))
+        .stdout(predicate::str::contains("tape").not())
+        .stdout(predicate::str::contains("gloves").not());
+    env.run(&["list", "shelf", "--group", "crates"])
+        .failure()
+        .stderr(predicate::str::contains("group 'crates' not found"));
+}
+
+/// Test: --save-as-kit moves the listed items into a new container with a
+/// checklist of them
+#[test]
+fn list_save_as_kit() {
+    let env = common::TestEnv::new();
+    env.add_into("plasters", "bathroom/cabinet").success();
+    env.add_into("scissors", "bathroom/cabinet").success();
+
+    env.run(&[
+        "list",
+        "bathroom/cabinet",
+        "--save-as-kit",
+        "car/first-aid kit",
+    ])
+    .success()
+    .stderr(predicate::str::contains(
+        "Moved 2 items into the new kit car/first-aid kit",
+    ));
+
+    env.run(&["check", "car/first-aid kit"])
+        .success()
+        .stdout(predicate::str::contains("2 of 2 present"));
+    env.run(&["list", "bathroom/cabinet"]).success().stdout("");
+
+    env.run(&["list", "car", "--save-as-kit", "car/first-aid kit"])
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+    env.run(&[
+        "list",
+        "car",
+        "--save-as-kit",
+        "spare",
+        "--save-as-checklist",
+        "car",
+    ])
+    .code(2);
+}
@@ -0,0 +1,136 @@
+//! Integration tests for `invy mcp` (Model Context Protocol server).
+//!
+//! See SPEC.md#invy-mcp
+
+mod common;
+
+use serde_json::{json, Value};
+
+/// Send `messages` as lines on stdin and return the response lines.
+fn session(env: &common::TestEnv, messages: &[Value]) -> Vec<Value> {
+    let input: String = messages.iter().map(|m| format!("{}\n", m)).collect();
+    let output = env
+        .cmd()
+        .arg("mcp")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn call(id: i64, tool: &str, arguments: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "method": "tools/call",
+           "params": {"name": tool, "arguments": arguments}})
+}
+
+/// The JSON a tool call returned as its text content.
+fn tool_result(response: &Value) -> Value {
+    assert_eq!(response["result"]["isError"], false, "{}", response);
+    serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap()).unwrap()
+}
+
+/// Test: the handshake negotiates a version and lists the tools
+#[test]
+fn mcp_initialize_and_list_tools() {
+    let env = common::TestEnv::new();
+    let responses = session(
+        &env,
+        &[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize",
+                   "params": {"protocolVersion": "2024-11-05", "capabilities": {},
+                              "clientInfo": {"name": "test", "version": "1"}}}),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "ping"}),
+        ],
+    );
+
+    assert_eq!(responses.len(), 3, "notifications get no response");
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "invy");
+    assert!(responses[0]["result"]["capabilities"]["tools"].is_object());
+    let tools: Vec<&str> = responses[1]["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(tools, ["find", "list", "add", "move"]);
+    assert_eq!(
+        responses[1]["result"]["tools"][0]["inputSchema"]["required"],
+        json!(["query"])
+    );
+    assert_eq!(responses[2]["result"], json!({}));
+}
+
+/// Test: tools add, move, list and find items in the database
+#[test]
+fn mcp_tools_answer_where_things_are() {
+    let env = common::TestEnv::new();
+    let responses = session(
+        &env,
+        &[
+            call(
+                1,
+                "add",
+                json!({"name": "passport", "container": "office/drawer"}),
+            ),
+            call(2, "move", json!({"item": "passport", "to": "office/safe"})),
+            call(3, "list", json!({"in": "office"})),
+            call(4, "find", json!({"query": "PASS"})),
+        ],
+    );
+
+    assert_eq!(
+        tool_result(&responses[0])["path"],
+        json!(["office", "drawer", "passport"])
+    );
+    assert_eq!(
+        tool_result(&responses[1])["path"],
+        json!(["office", "safe", "passport"])
+    );
+    let listed: Vec<Value> = tool_result(&responses[2])
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].clone())
+        .collect();
+    assert_eq!(listed, [json!("drawer"), json!("safe")]);
+    assert_eq!(
+        tool_result(&responses[3])[0]["path"],
+        json!(["office", "safe", "passport"])
+    );
+
+    env.run(&["show", "office/safe/passport"]).success();
+}
+
+/// Test: tool failures are results with isError; protocol mistakes are errors
+#[test]
+fn mcp_reports_errors() {
+    let env = common::TestEnv::new();
+    let responses = session(
+        &env,
+        &[
+            call(1, "move", json!({"item": "passport", "to": "safe"})),
+            call(2, "find", json!({})),
+            call(3, "shred", json!({})),
+            json!({"jsonrpc": "2.0", "id": 4, "method": "resources/list"}),
+        ],
+    );
+
+    assert_eq!(responses[0]["result"]["isError"], true);
+    assert_eq!(
+        responses[0]["result"]["content"][0]["text"],
+        "item 'passport' not found"
+    );
+    assert_eq!(responses[1]["result"]["isError"], true);
+    assert_eq!(responses[2]["error"]["code"], -32602);
+    assert_eq!(responses[3]["error"]["code"], -32601);
+}
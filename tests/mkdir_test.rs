@@ -0,0 +1,44 @@
+//! Integration tests for the `mkdir` command.
+//!
+//! See SPEC.md#invy-mkdir-path
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: mkdir creates each missing level and prints only those
+#[test]
+fn mkdir_creates_missing_levels() {
+    let env = common::TestEnv::new();
+    env.add("garage").success();
+
+    env.run(&["mkdir", "garage/shelf2/bin-a"])
+        .success()
+        .stdout("Created: garage/shelf2\nCreated: garage/shelf2/bin-a\n");
+
+    env.run(&["show", "garage/shelf2/bin-a"])
+        .success()
+        .stdout(predicate::str::contains("container"));
+}
+
+/// Test: mkdir of a path that already exists fails
+#[test]
+fn mkdir_existing_path() {
+    let env = common::TestEnv::new();
+    env.run(&["mkdir", "garage"]).success();
+
+    env.run(&["mkdir", "garage"])
+        .failure()
+        .stderr(predicate::str::contains("'garage' already exists"));
+}
+
+/// Test: mkdir is explicit creation, so --no-create does not apply; -q
+/// prints the new IDs
+#[test]
+fn mkdir_ignores_no_create() {
+    let env = common::TestEnv::new();
+
+    env.run(&["mkdir", "attic/box", "--no-create", "-q"])
+        .success()
+        .stdout("1\n2\n");
+}
@@ -118,3 +118,179 @@ fn move_name_conflict_fails() {
         .failure()
         .stderr(predicate::str::contains("already exists"));
 }
+
+/// Test: a rejected move does not leave a half-created destination behind
+#[test]
+fn mv_failure_rolls_back_created_containers() {
+    let env = common::TestEnv::new();
+    env.add("toolbox").success();
+
+    env.run(&["mv", "toolbox", "toolbox/drawer"])
+        .failure()
+        .stderr(predicate::str::contains("cannot move"));
+
+    env.run(&["show", "toolbox/drawer"]).failure();
+}
+
+/// Test: both the item and the destination can be given as #ID
+#[test]
+fn mv_by_id() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.add("garage").success();
+
+    env.run(&["mv", "#1", "#2"])
+        .success()
+        .stdout(predicate::str::contains("garage"));
+    env.run(&["show", "garage/hammer"]).success();
+
+    // An unknown ID is not created as a container
+    env.run(&["mv", "hammer", "#99"])
+        .failure()
+        .stderr(predicate::str::contains("container '#99' not found"));
+}
+
+/// Test: --id names the item, leaving the destination as the only argument
+#[test]
+fn mv_id_flag() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.add("garage").success();
+
+    env.run(&["mv", "--id", "1", "garage"]).success();
+    env.run(&["show", "garage/hammer"]).success();
+
+    env.run(&["mv", "--id", "1", "garage", "shed"]).code(2);
+    env.run(&["mv", "hammer"])
+        .code(2)
+        .stderr(predicate::str::contains("a destination is required"));
+}
+
+/// Test: --contents (or container/*) moves the children, not the container
+#[test]
+fn mv_contents() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+    env.add_into("wrench", "garage/toolbox").success();
+
+    env.run(&["mv", "garage/toolbox", "workshop", "--contents"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Moved 2 items: garage/toolbox -> workshop",
+        ))
+        .stdout(predicate::str::contains("  - hammer"));
+    env.run(&["show", "workshop/hammer"]).success();
+    env.run(&["show", "workshop/wrench"]).success();
+    env.run(&["show", "garage/toolbox"]).success();
+
+    env.run(&["mv", "workshop/*", "garage/toolbox", "--quiet"])
+        .success()
+        .stdout("3\n4\n");
+    env.run(&["show", "garage/toolbox/wrench"]).success();
+}
+
+/// Test: moving contents checks every name and cycle before moving any
+#[test]
+fn mv_contents_conflicts() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "toolbox").success();
+    env.add_into("wrench", "toolbox").success();
+    env.add_into("wrench", "workshop").success();
+
+    env.run(&["mv", "toolbox/*", "workshop"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "item 'wrench' already exists in workshop",
+        ));
+    env.run(&["show", "toolbox/hammer"]).success();
+
+    env.run(&["mv", "toolbox", "toolbox/hammer", "--contents"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "cannot move the contents of 'toolbox' into itself",
+        ));
+
+    env.run(&["mv", "toolbox/hammer/*", "/"])
+        .failure()
+        .stderr(predicate::str::contains("'hammer' has no contents to move"));
+}
+
+/// Test: mv --expected-updated-at refuses to move an item changed since
+#[test]
+fn mv_expected_updated_at() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.backdate("hammer", "2024-01-15 10:30:00");
+
+    env.run(&[
+        "mv",
+        "hammer",
+        "garage",
+        "--expected-updated-at",
+        "2024-01-15 09:00:00",
+    ])
+    .failure()
+    .stderr(predicate::str::contains(
+        "was updated at 2024-01-15 10:30:00, not 2024-01-15 09:00:00",
+    ));
+    env.run(&["show", "hammer"]).success();
+
+    env.run(&[
+        "mv",
+        "hammer",
+        "garage",
+        "--expected-updated-at",
+        "2024-01-15 10:30:00",
+    ])
+    .success();
+    env.run(&["show", "garage/hammer"]).success();
+}
+
+/// Test: --no-create refuses a missing destination and suggests the closest
+/// container instead of creating a new one
+#[test]
+fn mv_no_create() {
+    let env = common::TestEnv::new();
+    env.add_into("saw", "garage/toolbox").success();
+    env.add("hammer").success();
+
+    env.run(&["mv", "hammer", "tooblox", "--no-create"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "container 'tooblox' not found; did you mean 'garage/toolbox'?",
+        ));
+    env.run(&["mv", "hammer", "garage/tolbox", "--no-create"])
+        .failure()
+        .stderr(predicate::str::contains("did you mean 'garage/toolbox'?"));
+    env.run(&["show", "tooblox"]).failure();
+
+    env.run(&["mv", "hammer", "toolbox", "--no-create"])
+        .success();
+    env.run(&["show", "garage/toolbox/hammer"]).success();
+}
+
+/// Test: moving into a plain item warns like add, and strict mode refuses it
+#[test]
+fn mv_into_plain_item_warns() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.add("sticker").success();
+    env.add("label").success();
+
+    env.run(&["mv", "sticker", "hammer"])
+        .success()
+        .stderr(predicate::str::contains(
+            "Warning: 'hammer' is a plain item, not a container",
+        ));
+    env.run(&["edit", "label", "--in", "hammer"])
+        .success()
+        .stderr(predicate::str::contains("is a plain item"));
+    env.run(&["--strict", "mv", "hammer/label", "/"]).success();
+    env.run(&["--strict", "mv", "label", "hammer"])
+        .code(3)
+        .stderr(predicate::str::contains("is a plain item, not a container"));
+    env.run(&["where", "label"]).success().stdout(
+        "label
+",
+    );
+}
@@ -0,0 +1,78 @@
+//! Integration tests for the `near` command.
+//!
+//! See SPEC.md#invy-near-item
+
+mod common;
+
+use predicates::prelude::*;
+
+/// A garage with a toolbox and a shelf, and a kitchen next door.
+fn garage(env: &common::TestEnv) {
+    env.add_into("hammer", "house/garage/toolbox").success();
+    env.add_into("bits", "house/garage/toolbox/case").success();
+    env.add_into("saw", "house/garage/toolbox").success();
+    env.add_into("tape", "house/garage/shelf").success();
+    env.add_into("kettle", "house/kitchen").success();
+}
+
+/// Test: radius 1 covers the item's container, boxes inside it included
+#[test]
+fn near_lists_the_same_container() {
+    let env = common::TestEnv::new();
+    garage(&env);
+
+    env.run(&["near", "hammer"])
+        .success()
+        .stdout(predicate::str::is_match(
+            r"^DIST\s+PATH\n\s+1\s+house/garage/toolbox/case\n\s+1\s+house/garage/toolbox/saw\n\s+1\s+house/garage/toolbox/case/bits\n$",
+        )
+        .unwrap());
+}
+
+/// Test: a larger radius adds the rings around it, nearest first
+#[test]
+fn near_ranks_by_distance() {
+    let env = common::TestEnv::new();
+    garage(&env);
+
+    env.run(&["near", "hammer", "--radius", "2", "--csv"])
+        .success()
+        .stdout(
+            "id,path,distance\n\
+             5,house/garage/toolbox/case,1\n\
+             7,house/garage/toolbox/saw,1\n\
+             6,house/garage/toolbox/case/bits,1\n\
+             8,house/garage/shelf,2\n\
+             9,house/garage/shelf/tape,2\n",
+        );
+    let all = env.run(&["near", "hammer", "--radius", "9", "--json"]);
+    all.success()
+        .stdout(predicate::str::contains(r#""distance":3"#))
+        .stdout(predicate::str::contains("kettle"));
+}
+
+/// Test: a container's own contents are at distance 0
+#[test]
+fn near_includes_contents() {
+    let env = common::TestEnv::new();
+    garage(&env);
+
+    env.run(&["near", "case", "-q"])
+        .success()
+        .stdout("6\n4\n7\n");
+}
+
+/// Test: nothing nearby and unknown items
+#[test]
+fn near_empty_and_missing() {
+    let env = common::TestEnv::new();
+    env.add("lonely").success();
+
+    env.run(&["near", "lonely"])
+        .success()
+        .stdout("Nothing within 1 of 'lonely'\n");
+    env.run(&["near", "ghost"])
+        .failure()
+        .stderr(predicate::str::contains("item 'ghost' not found"));
+    env.run(&["near", "lonely", "--radius", "0"]).failure();
+}
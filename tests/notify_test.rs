@@ -0,0 +1,123 @@
+//! Integration tests for notifications (`invy notify` and `[notify]`).
+//!
+//! Only built with the `notify` cargo feature. A one-shot HTTP listener on
+//! localhost stands in for the ntfy and Telegram servers.
+//!
+//! See SPEC.md#notifications
+
+#![cfg(feature = "notify")]
+
+mod common;
+
+use predicates::prelude::*;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread::{self, JoinHandle};
+
+/// Accept one HTTP request, answer 200 and return the raw request.
+fn one_request_server() -> (String, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = String::new();
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            head.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        reader
+            .get_mut()
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}")
+            .unwrap();
+        head + &String::from_utf8(body).unwrap()
+    });
+    (url, handle)
+}
+
+/// Test: notify test posts to the ntfy topic with a title and token
+#[test]
+fn notify_test_posts_to_ntfy() {
+    let env = common::TestEnv::new();
+    let (url, server) = one_request_server();
+    env.write_config(&format!(
+        "[notify.ntfy]\nurl = \"{}/invy-home\"\ntoken = \"tk_123\"\n",
+        url
+    ));
+
+    env.run(&["notify", "test", "hello from the shed"])
+        .success()
+        .stdout("Sent to ntfy\n");
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /invy-home "));
+    let lower = request.to_ascii_lowercase();
+    assert!(lower.contains("\r\ntitle: invy\r\n"), "{}", request);
+    assert!(
+        lower.contains("authorization: bearer tk_123"),
+        "{}",
+        request
+    );
+    assert!(request.ends_with("hello from the shed"));
+}
+
+/// Test: Telegram messages go to the bot API with the chat ID
+#[test]
+fn notify_test_sends_telegram_message() {
+    let env = common::TestEnv::new();
+    let (url, server) = one_request_server();
+    env.write_config(&format!(
+        "[notify.telegram]\ntoken = \"42:abc\"\nchat_id = 1234\napi_url = \"{}\"\n",
+        url
+    ));
+
+    env.run(&["notify", "test"])
+        .success()
+        .stdout("Sent to telegram\n");
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /bot42:abc/sendMessage "));
+    assert!(request.contains(r#""chat_id": 1234"#), "{}", request);
+}
+
+/// Test: without backends notify test explains what to configure
+#[test]
+fn notify_test_without_backends() {
+    let env = common::TestEnv::new();
+    env.run(&["notify", "test"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "no notification backends configured",
+        ));
+}
+
+/// Test: failed cron jobs are pushed, and the run still fails
+#[test]
+fn cron_failures_are_pushed() {
+    let env = common::TestEnv::new();
+    let (url, server) = one_request_server();
+    env.write_config(&format!(
+        "[cron]\nverify_hashes = \"1d\"\n\n[notify.ntfy]\nurl = \"{}/invy\"\n",
+        url
+    ));
+    env.add_into("hammer", "toolbox").success();
+    env.run(&["hash", "toolbox"]).success();
+    env.add_into("saw", "toolbox").success();
+
+    env.run(&["cron", "run"])
+        .failure()
+        .stderr(predicate::str::contains("1 job(s) failed"));
+
+    let request = server.join().unwrap();
+    assert!(request.ends_with("verify_hashes: 1 container(s) changed: toolbox"));
+}
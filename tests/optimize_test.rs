@@ -0,0 +1,94 @@
+//! Integration tests for `invy optimize`.
+//!
+//! See SPEC.md#invy-optimize
+
+mod common;
+
+use predicates::prelude::*;
+use serde_json::Value;
+
+/// Look `name` up with `invy where` `times` times.
+fn look_up(env: &common::TestEnv, name: &str, times: usize) {
+    for _ in 0..times {
+        env.run(&["where", name]).success();
+    }
+}
+
+/// Test: a frequently used item deep down is suggested for the busiest
+/// shallow container
+#[test]
+fn optimize_suggests_shallower_place() {
+    let env = common::TestEnv::new();
+    env.add_into("tape measure", "garage/shelf/box/bag")
+        .success();
+    env.add_into("keys", "hall/drawer").success();
+    env.add_into("old tent", "attic/crate/bag").success();
+    look_up(&env, "tape measure", 4);
+    look_up(&env, "keys", 5);
+    env.run(&["show", "old tent"]).success();
+
+    let output = env
+        .run(&["optimize", "--json"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let suggestions: Value = serde_json::from_slice(&output).unwrap();
+    let suggestions = suggestions.as_array().unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0]["path"][4], "tape measure");
+    assert_eq!(suggestions[0]["uses"], 4);
+    assert_eq!(suggestions[0]["depth"], 4);
+    assert_eq!(suggestions[0]["destination"][1], "drawer");
+
+    env.run(&["optimize"])
+        .success()
+        .stdout(predicate::str::contains(
+            "garage/shelf/box/bag/tape measure: used 4 times, 4 levels deep in garage; consider hall/drawer",
+        ));
+
+    // Fewer uses than the threshold leaves it alone
+    env.run(&["optimize", "--min-uses", "10"])
+        .success()
+        .stdout("Nothing to reorganize\n");
+}
+
+/// Test: an item that a [classify.rules] keyword files elsewhere is
+/// suggested for that container
+#[test]
+fn optimize_follows_classify_rules() {
+    let env = common::TestEnv::new();
+    env.write_config("[classify.rules]\nbattery = \"electronics-bin\"\n");
+    env.add("electronics-bin").success();
+    env.add_into("9V battery", "kitchen/drawer").success();
+    env.add_into("AA battery", "electronics-bin").success();
+
+    env.run(&["optimize"])
+        .success()
+        .stdout(predicate::str::contains(
+            "kitchen/drawer/9V battery: rule 'battery' files it elsewhere; consider electronics-bin",
+        ))
+        .stdout(predicate::str::contains("AA battery").not());
+}
+
+/// Test: --script prints invy mv commands that carry out the suggestions
+#[test]
+fn optimize_script() {
+    let env = common::TestEnv::new();
+    env.add_into("tape measure", "garage/shelf/box").success();
+    env.add_into("keys", "hall").success();
+    look_up(&env, "tape measure", 3);
+    look_up(&env, "keys", 3);
+
+    env.run(&["optimize", "--script"])
+        .success()
+        .stdout(predicate::str::starts_with("#!/bin/sh\n"))
+        .stdout(predicate::str::contains(
+            "invy mv 'garage/shelf/box/tape measure' hall\n",
+        ));
+    env.run(&["mv", "garage/shelf/box/tape measure", "hall"])
+        .success();
+    env.run(&["optimize"])
+        .success()
+        .stdout("Nothing to reorganize\n");
+}
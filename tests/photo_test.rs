@@ -0,0 +1,82 @@
+//! Integration tests for the `photo ocr` command.
+//!
+//! See SPEC.md#invy-photo-ocr
+//!
+//! Only built with the `ocr` feature. A shell script stands in for
+//! tesseract, printing the "photo" file itself.
+
+#![cfg(all(feature = "ocr", unix))]
+
+mod common;
+
+use predicates::prelude::*;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+/// Write a fake tesseract and a "photo" holding the text it will report.
+fn fake_ocr(env: &common::TestEnv, text: &str) -> (PathBuf, PathBuf) {
+    let script = env.temp_dir.path().join("fake-tesseract");
+    std::fs::write(&script, "#!/bin/sh\ncat \"$1\"\n").unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let photo = env.temp_dir.path().join("bin.jpg");
+    std::fs::write(&photo, text).unwrap();
+    (script, photo)
+}
+
+/// Test: without --suggest-items the extracted text is printed
+#[test]
+fn photo_ocr_prints_text() {
+    let env = common::TestEnv::new();
+    let (script, photo) = fake_ocr(&env, "SCREWS M4\n");
+
+    env.cmd()
+        .env("INVY_TESSERACT", &script)
+        .args(["photo", "ocr", photo.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("SCREWS M4\n");
+}
+
+/// Test: suggested names are added, edited or skipped as answered
+#[test]
+fn photo_ocr_suggests_items() {
+    let env = common::TestEnv::new();
+    let (script, photo) = fake_ocr(
+        &env,
+        "AA batteries 8pk   4.99\n- duct tape x2\n12/03/2024\nTOTAL $17.48\naa BATTERIES 8pk\n",
+    );
+
+    env.cmd()
+        .env("INVY_TESSERACT", &script)
+        .args(["photo", "ocr", photo.to_str().unwrap(), "--suggest-items"])
+        .args(["--in", "drawer"])
+        .write_stdin("y\ne\ngaffer tape\nn\n")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Add 'AA batteries 8pk'?"))
+        .stderr(predicate::str::contains("Add 'TOTAL'?"))
+        .stderr(predicate::str::contains("atteries").count(1))
+        .stdout(predicate::str::contains("Added: AA batteries 8pk"))
+        .stdout(predicate::str::contains("Added: gaffer tape"));
+
+    env.run(&["list", "drawer"])
+        .success()
+        .stdout(predicate::str::contains("gaffer tape"))
+        .stdout(predicate::str::contains("TOTAL").not());
+}
+
+/// Test: a missing OCR program is reported
+#[test]
+fn photo_ocr_without_backend() {
+    let env = common::TestEnv::new();
+    let (_, photo) = fake_ocr(&env, "x");
+
+    env.cmd()
+        .env("INVY_TESSERACT", env.temp_dir.path().join("missing"))
+        .args(["photo", "ocr", photo.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "install tesseract or set INVY_TESSERACT",
+        ));
+}
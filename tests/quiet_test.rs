@@ -0,0 +1,45 @@
+//! Integration tests for the global `--quiet` flag.
+//!
+//! See SPEC.md#global-flags
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: commands that touch items print only their IDs
+#[test]
+fn quiet_prints_ids() {
+    let env = common::TestEnv::new();
+
+    env.run(&["add", "hammer", "--in", "toolbox", "-q"])
+        .success()
+        .stdout("2\n");
+    env.run(&["show", "hammer", "--quiet"])
+        .success()
+        .stdout("2\n");
+    env.run(&["list", "-r", "-q"]).success().stdout("1\n2\n");
+    env.run(&["find", "ham", "-q"]).success().stdout("2\n");
+    env.run(&["rm", "hammer", "-q"]).success().stdout("2\n");
+}
+
+/// Test: commands without item IDs print nothing
+#[test]
+fn quiet_suppresses_reports() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.run(&["stats", "-q"]).success().stdout("");
+    env.run(&["views", "install", "-q"]).success().stdout("");
+}
+
+/// Test: --quiet wins over a configured format but not over explicit flags
+#[test]
+fn quiet_and_formats() {
+    let env = common::TestEnv::new();
+    env.write_config("format = \"json\"\n");
+
+    env.run(&["add", "hammer", "-q"]).success().stdout("1\n");
+    env.run(&["show", "hammer", "-q", "--json"])
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
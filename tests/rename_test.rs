@@ -0,0 +1,96 @@
+//! Integration tests for the `rename` command.
+//!
+//! See SPEC.md#invy-rename
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: matches are renamed in name order with a padded counter
+#[test]
+fn rename_with_counter() {
+    let env = common::TestEnv::new();
+    env.add_into("box b", "garage").success();
+    env.add_into("box a", "garage").success();
+    env.add_into("bike", "garage").success();
+
+    env.run(&[
+        "rename",
+        "--matching",
+        "box*",
+        "--template",
+        "moving-box-{n:02}",
+        "--in",
+        "garage",
+    ])
+    .success()
+    .stdout(predicate::str::contains("box a -> moving-box-01"))
+    .stdout(predicate::str::contains("box b -> moving-box-02"))
+    .stdout(predicate::str::contains("Renamed 2 items"));
+
+    env.run(&["show", "garage/moving-box-01"]).success();
+    env.run(&["show", "garage/bike"]).success();
+}
+
+/// Test: captures fill {1}, and --dry-run writes nothing
+#[test]
+fn rename_captures_and_dry_run() {
+    let env = common::TestEnv::new();
+    env.add("crate 7 (tools)").success();
+
+    env.run(&[
+        "rename",
+        "--matching",
+        "crate ? (*)",
+        "--template",
+        "{2} crate #{1}",
+        "--dry-run",
+    ])
+    .success()
+    .stdout(predicate::str::contains(
+        "crate 7 (tools) -> tools crate #7",
+    ))
+    .stdout(predicate::str::contains("Would rename 1 items (dry run)"));
+    env.run(&["show", "crate 7 (tools)"]).success();
+
+    env.run(&["rename", "--matching", "crate*", "--template", "{2}"])
+        .failure()
+        .stderr(predicate::str::contains("has 1 wildcard(s)"));
+    env.run(&["rename", "--matching", "crate*", "--template", "{x}"])
+        .failure()
+        .stderr(predicate::str::contains("unknown placeholder '{x}'"));
+}
+
+/// Test: names that would clash are rejected, but swaps are fine
+#[test]
+fn rename_conflicts_and_swaps() {
+    let env = common::TestEnv::new();
+    env.add("box 1").success();
+    env.add("box 2").success();
+    env.add("bin").success();
+
+    env.run(&["rename", "--matching", "box*", "--template", "bin"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "'box 1' would be renamed to 'bin', which is already taken",
+        ));
+
+    // box 1 -> box 2 and box 2 -> box 3 at once
+    env.run(&[
+        "rename",
+        "--matching",
+        "box ?",
+        "--template",
+        "box {n}",
+        "--start",
+        "2",
+    ])
+    .success();
+    env.run(&["show", "box 3"]).success();
+
+    env.run(&["rename", "--matching", "crate*", "--template", "x"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "no items named like 'crate*' in (root)",
+        ));
+}
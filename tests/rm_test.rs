@@ -107,3 +107,16 @@ fn remove_nested_container_orphans_to_root() {
     // Garage should still exist
     env.run(&["show", "garage"]).success();
 }
+
+/// Test: --id removes exactly the given item
+#[test]
+fn rm_by_id() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "office").success();
+    env.add_into("tape", "garage").success();
+
+    env.run(&["rm", "--id", "4"]).success();
+    env.run(&["where", "tape"])
+        .success()
+        .stdout("office/tape\n");
+}
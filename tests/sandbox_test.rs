@@ -0,0 +1,112 @@
+//! Integration tests for the `--sandbox` and `--from-export` flags.
+//!
+//! See SPEC.md#sandbox
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: changes made in a sandbox do not reach the real database
+#[test]
+fn sandbox_discards_changes() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+
+    env.run(&["--sandbox", "rm", "garage/hammer"])
+        .success()
+        .stdout(predicate::str::contains("Removed: hammer"))
+        .stderr(predicate::str::contains("Sandbox: using a copy of"));
+    env.run(&["--sandbox", "list", "garage"])
+        .success()
+        .stdout(predicate::str::contains("hammer"));
+
+    env.run(&["show", "garage/hammer"]).success();
+}
+
+/// Test: without a database the sandbox holds the tutorial inventory
+#[test]
+fn sandbox_tutorial_inventory() {
+    let env = common::TestEnv::new();
+
+    env.run(&["--sandbox", "find", "hammer"])
+        .success()
+        .stdout(predicate::str::contains("garage/toolbox/hammer"))
+        .stderr(predicate::str::contains("using the tutorial inventory"));
+    env.run(&["--sandbox", "show", r"office/desk/A\/V cables"])
+        .success();
+
+    assert!(!env.db_path.exists());
+}
+
+/// Write an export of `env` to a file in it and return the path.
+fn export_file(env: &common::TestEnv) -> std::path::PathBuf {
+    let path = env.temp_dir.path().join("snapshot.json");
+    env.cmd()
+        .args(["export", "-o"])
+        .arg(&path)
+        .assert()
+        .success();
+    path
+}
+
+/// Test: --from-export reads an export as it was, with its IDs, without
+/// touching the database
+#[test]
+fn from_export_reads_snapshot() {
+    let env = common::TestEnv::new();
+    env.add_full("passport", "blue folder", "office/desk")
+        .success();
+    env.run(&["add", "batteries", "--in", "drawer", "--qty", "8"])
+        .success();
+    let snapshot = export_file(&env);
+    env.run(&["rm", "office/desk/passport"]).success();
+
+    let from = snapshot.to_str().unwrap();
+    env.run(&["--from-export", from, "find", "passport"])
+        .success()
+        .stdout(predicate::str::contains("office/desk/passport"))
+        .stderr(predicate::str::contains("Reading 5 items from"));
+    env.run(&["--from-export", from, "show", "#3"])
+        .success()
+        .stdout(predicate::str::contains("blue folder"));
+    env.run(&["--from-export", from, "show", "batteries"])
+        .success()
+        .stdout(predicate::str::contains("8"));
+    env.run(&["--from-export", from, "list", "--containers-only"])
+        .success()
+        .stdout(predicate::str::contains("office"));
+
+    env.run(&["show", "passport"]).failure();
+}
+
+/// Test: --from-export refuses commands that change the inventory, and files
+/// that are not exports
+#[test]
+fn from_export_is_read_only() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+    let snapshot = export_file(&env);
+    let from = snapshot.to_str().unwrap();
+
+    env.run(&["--from-export", from, "rm", "garage/hammer"])
+        .failure()
+        .stderr(predicate::str::contains("--from-export is read-only"));
+    env.run(&[
+        "--from-export",
+        from,
+        "find",
+        "hammer",
+        "--save-as-kit",
+        "kit",
+    ])
+    .failure()
+    .stderr(predicate::str::contains("--from-export is read-only"));
+    env.run(&["--from-export", from, "--sandbox", "list"])
+        .code(2);
+
+    let not_export = env.temp_dir.path().join("items.csv");
+    std::fs::write(&not_export, "path\nhammer\n").unwrap();
+    env.run(&["--from-export", not_export.to_str().unwrap(), "list"])
+        .failure()
+        .stderr(predicate::str::contains("is not an invy export"));
+}
@@ -0,0 +1,310 @@
+//! Integration tests for `invy serve`.
+//!
+//! Only built with the `serve` cargo feature. Each test starts the server
+//! on a free port and talks raw HTTP to it.
+//!
+//! See SPEC.md#invy-serve
+
+#![cfg(feature = "serve")]
+
+mod common;
+
+use common::TestEnv;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+/// A running `invy serve`, stopped when dropped.
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    fn start(env: &TestEnv, extra: &[&str]) -> Self {
+        let mut child = Command::new(assert_cmd::cargo::cargo_bin!("invy"))
+            .arg("--db")
+            .arg(&env.db_path)
+            .args(["serve", "--port", "0"])
+            .args(extra)
+            .env("XDG_CONFIG_HOME", env.temp_dir.path())
+            .env_remove("INVY_SERVE_TOKEN")
+            .env_remove("INVY_STRICT")
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(child.stderr.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let addr = line
+            .split("http://")
+            .nth(1)
+            .and_then(|rest| rest.split(';').next())
+            .unwrap_or_else(|| panic!("unexpected startup line: {}", line))
+            .to_string();
+        Server { child, addr }
+    }
+
+    /// Send a request and return the status, the raw head and the JSON body.
+    fn request(&self, method: &str, path: &str, body: Option<&str>) -> (u16, String, Value) {
+        self.request_with(method, path, body, "")
+    }
+
+    fn request_with(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+        headers: &str,
+    ) -> (u16, String, Value) {
+        let (status, head, body) = self.fetch(method, path, body, headers);
+        (status, head, serde_json::from_str(&body).unwrap())
+    }
+
+    /// Send a request and return the status, the raw head and the raw body.
+    fn fetch(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&str>,
+        headers: &str,
+    ) -> (u16, String, String) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        let body = body.unwrap_or("");
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\n{}Content-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            self.addr,
+            headers,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, head.to_string(), body.to_string())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Test: GET /items lists root items like `invy list --json`
+#[test]
+fn test_list_items() {
+    let env = TestEnv::new();
+    env.add_into("hammer", "garage").success();
+    env.add("passport").success();
+    let server = Server::start(&env, &[]);
+
+    let (status, head, body) = server.request("GET", "/items", None);
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: application/json"));
+    let names: Vec<&str> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["garage", "passport"]);
+    assert_eq!(body[0]["child_count"], 1);
+
+    let (status, _, body) = server.request("GET", "/items?in=garage", None);
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["name"], "hammer");
+    assert_eq!(body[0]["path"], serde_json::json!(["garage", "hammer"]));
+}
+
+/// Test: POST /items adds an item and GET /items/{id} shows it
+#[test]
+fn test_create_and_show() {
+    let env = TestEnv::new();
+    env.add("garage").success();
+    let server = Server::start(&env, &[]);
+
+    let (status, head, body) = server.request(
+        "POST",
+        "/items",
+        Some(
+            r#"{"name": "drill", "container": "garage", "quantity": 2, "description": "cordless"}"#,
+        ),
+    );
+    assert_eq!(status, 201);
+    let id = body["id"].as_i64().unwrap();
+    assert!(head.contains(&format!("Location: /items/{}", id)));
+    assert_eq!(body["path"], serde_json::json!(["garage", "drill"]));
+
+    let (status, _, body) = server.request("GET", &format!("/items/{}", id), None);
+    assert_eq!(status, 200);
+    assert_eq!(body["name"], "drill");
+    assert_eq!(body["description"], "cordless");
+    assert_eq!(body["quantity"], 2);
+
+    env.run(&["where", "drill"])
+        .success()
+        .stdout(predicates::str::contains("garage"));
+}
+
+/// Test: PATCH changes and clears fields; DELETE removes the item
+#[test]
+fn test_update_and_delete() {
+    let env = TestEnv::new();
+    env.add_with_desc("drill", "cordless").success();
+    env.run(&["add", "garage", "--kind", "container"]).success();
+    let server = Server::start(&env, &[]);
+
+    let (status, _, body) = server.request(
+        "PATCH",
+        "/items/1",
+        Some(r#"{"container": "garage", "description": null}"#),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["path"], serde_json::json!(["garage", "drill"]));
+    assert!(body.get("description").is_none() || body["description"].is_null());
+
+    let (status, _, body) = server.request("DELETE", "/items/1", None);
+    assert_eq!(status, 200);
+    assert_eq!(body["removed"], "drill");
+
+    let (status, _, body) = server.request("GET", "/items/1", None);
+    assert_eq!(status, 404);
+    assert_eq!(body["error"], "item 1 not found");
+}
+
+/// Test: GET /search finds items like `invy find`
+#[test]
+fn test_search() {
+    let env = TestEnv::new();
+    env.add_with_desc("drill", "cordless").success();
+    env.add("hammer").success();
+    let server = Server::start(&env, &[]);
+
+    let (status, _, body) = server.request("GET", "/search?q=cord%20less", None);
+    assert_eq!(status, 200);
+    assert_eq!(body, serde_json::json!([]));
+
+    let (_, _, body) = server.request("GET", "/search?q=cordless", None);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["name"], "drill");
+
+    let (status, _, _) = server.request("GET", "/search", None);
+    assert_eq!(status, 400);
+}
+
+/// Test: invalid requests get a JSON error and a matching status
+#[test]
+fn test_errors() {
+    let env = TestEnv::new();
+    env.add("drill").success();
+    let server = Server::start(&env, &[]);
+
+    let (status, _, body) = server.request("POST", "/items", Some(r#"{"nmae": "saw"}"#));
+    assert_eq!(status, 400);
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("unknown field 'nmae'"));
+
+    let (status, _, _) = server.request("POST", "/items", Some("not json"));
+    assert_eq!(status, 400);
+
+    // A failed check is the request's fault, not the server's
+    let (status, _, body) = server.request("POST", "/items", Some(r#"{"name": "drill"}"#));
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("already exists"));
+
+    let (status, _, _) = server.request("PUT", "/items/1", Some("{}"));
+    assert_eq!(status, 405);
+
+    let (status, _, _) = server.request("GET", "/nowhere", None);
+    assert_eq!(status, 404);
+}
+
+/// Test: --token requires a bearer token on API requests
+#[test]
+fn test_token() {
+    let env = TestEnv::new();
+    env.add("drill").success();
+    let server = Server::start(&env, &["--token", "s3cret"]);
+
+    let (status, head, _) = server.request("GET", "/items", None);
+    assert_eq!(status, 401);
+    assert!(head.contains("WWW-Authenticate: Bearer"));
+
+    let (status, _, body) =
+        server.request_with("GET", "/items", None, "Authorization: Bearer s3cret\r\n");
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["name"], "drill");
+}
+
+/// Test: a client that connects and sends nothing does not block others
+#[test]
+fn test_slow_client() {
+    let env = TestEnv::new();
+    env.add("drill").success();
+    let server = Server::start(&env, &[]);
+
+    let _idle = TcpStream::connect(&server.addr).unwrap();
+    let started = std::time::Instant::now();
+    let (status, _, body) = server.request("GET", "/items", None);
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["name"], "drill");
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
+
+/// Test: / serves the web UI, without a token
+#[test]
+fn test_web_ui() {
+    let env = TestEnv::new();
+    let server = Server::start(&env, &["--token", "s3cret"]);
+
+    let (status, head, body) = server.fetch("GET", "/", None, "");
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: text/html; charset=utf-8"));
+    assert!(body.starts_with("<!doctype html>"));
+    assert!(body.contains("/search?q="));
+
+    let (status, _, _) = server.fetch("POST", "/", Some("{}"), "");
+    assert_eq!(status, 405);
+}
+
+/// Test: share links serve the shared subtree only, without a token
+#[test]
+fn test_share_link() {
+    let env = TestEnv::new();
+    env.add_into("hammer", "garage").success();
+    env.add("passport").success();
+    let output = env
+        .run(&["--json", "share", "create", "garage"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let share: Value = serde_json::from_slice(&output).unwrap();
+    let token = share["token"].as_str().unwrap();
+    let server = Server::start(&env, &["--token", "s3cret"]);
+
+    let (status, _, body) = server.request("GET", &format!("/share/{}", token), None);
+    assert_eq!(status, 200);
+    let names: Vec<&str> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["garage", "hammer"]);
+
+    env.run(&["share", "revoke", token]).success();
+    let (status, _, _) = server.request("GET", &format!("/share/{}", token), None);
+    assert_eq!(status, 404);
+}
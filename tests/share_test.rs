@@ -0,0 +1,69 @@
+//! Integration tests for the `share` command.
+//!
+//! See SPEC.md#invy-share
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: create prints a tokenized link with an expiry
+#[test]
+fn share_create_prints_link() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/shelf").success();
+
+    env.run(&["share", "create", "garage/shelf", "--expires", "7d"])
+        .success()
+        .stdout(predicate::str::contains("Shared: garage/shelf"))
+        .stdout(
+            predicate::str::is_match(r"URL:\s+http://localhost:8080/share/[0-9a-f]{32}\n").unwrap(),
+        );
+}
+
+/// Test: base_url from config is used in links
+#[test]
+fn share_uses_configured_base_url() {
+    let env = common::TestEnv::new();
+    env.write_config("base_url = \"https://inventory.example.org/\"\n");
+    env.add("garage").success();
+
+    env.run(&["share", "create", "garage", "--expires", "never", "--json"])
+        .success()
+        .stdout(predicate::str::contains(
+            r#""url":"https://inventory.example.org/share/"#,
+        ))
+        .stdout(predicate::str::contains("expires_at").not());
+}
+
+/// Test: list shows shares, revoke removes them
+#[test]
+fn share_list_and_revoke() {
+    let env = common::TestEnv::new();
+    env.add("garage").success();
+    env.run(&["share", "create", "garage"]).success();
+
+    let output = env.run(&["share", "list", "--csv"]).success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let token = stdout.lines().nth(1).unwrap().split(',').next().unwrap();
+
+    env.run(&["share", "revoke", token])
+        .success()
+        .stdout(predicate::str::contains(format!("Revoked: {}", token)));
+    env.run(&["share", "list", "--json"])
+        .success()
+        .stdout("[]\n");
+    env.run(&["share", "revoke", token])
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+}
+
+/// Test: invalid expiry is rejected
+#[test]
+fn share_invalid_expiry_fails() {
+    let env = common::TestEnv::new();
+    env.add("garage").success();
+
+    env.run(&["share", "create", "garage", "--expires", "later"])
+        .failure()
+        .stderr(predicate::str::contains("invalid time 'later'"));
+}
@@ -0,0 +1,96 @@
+//! Integration tests for restocking thresholds and `invy shopping`.
+//!
+//! See SPEC.md#invy-shopping
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: edit --min-qty sets a threshold shown by show
+#[test]
+fn edit_sets_minimum_quantity() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "coffee", "--qty", "500g"]).success();
+
+    env.run(&["edit", "coffee", "--min-qty", "1kg"])
+        .success()
+        .stdout(predicate::str::contains("  min qty: 1 kg"));
+    env.run(&["show", "coffee"])
+        .success()
+        .stdout(predicate::str::contains("Minimum:     1 kg"));
+
+    env.run(&["edit", "coffee", "--min-qty", ""])
+        .success()
+        .stdout(predicate::str::contains("  min qty: (none)"));
+    env.run(&["show", "coffee"])
+        .success()
+        .stdout(predicate::str::contains("Minimum:").not());
+}
+
+/// Test: a threshold must be comparable with the item's quantity
+#[test]
+fn edit_rejects_incomparable_minimum() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "cable", "--qty", "3m"]).success();
+    env.run(&["edit", "cable", "--min-qty", "2kg"])
+        .failure()
+        .stderr(predicate::str::contains("cannot be compared"));
+}
+
+/// Test: shopping lists only items below their threshold, with what to buy
+#[test]
+fn shopping_lists_items_below_minimum() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "AA batteries", "--in", "drawer", "--qty", "2"])
+        .success();
+    env.run(&["add", "coffee", "--in", "kitchen", "--qty", "250g"])
+        .success();
+    env.run(&["add", "tape", "--in", "drawer", "--qty", "4"])
+        .success();
+    env.run(&["edit", "AA batteries", "--min-qty", "8"])
+        .success();
+    env.run(&["edit", "coffee", "--min-qty", "1kg"]).success();
+    env.run(&["edit", "tape", "--min-qty", "4"]).success();
+
+    env.run(&["shopping"])
+        .success()
+        .stdout(predicate::str::contains("drawer/AA batteries"))
+        .stdout(predicate::str::contains("750 g"))
+        .stdout(predicate::str::contains("tape").not());
+
+    env.run(&["--format", "csv", "shopping"]).success().stdout(
+        "id,name,path,quantity,min_quantity,need,unit\n\
+         2,AA batteries,drawer/AA batteries,2,8,6,\n\
+         4,coffee,kitchen/coffee,250,1000,750,g\n",
+    );
+
+    env.run(&["--format", "csv", "shopping", "--in", "kitchen"])
+        .success()
+        .stdout(predicate::str::contains("coffee"))
+        .stdout(predicate::str::contains("batteries").not());
+}
+
+/// Test: the markdown list can be pasted into a note
+#[test]
+fn shopping_exports_markdown() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "coffee", "--in", "kitchen", "--qty", "250g"])
+        .success();
+    env.run(&["edit", "coffee", "--min-qty", "1kg"]).success();
+
+    env.run(&["--format", "markdown", "shopping"])
+        .success()
+        .stdout(predicate::str::contains(
+            "| coffee | 750 g | 250 g | kitchen |",
+        ));
+}
+
+/// Test: nothing below its threshold says so
+#[test]
+fn shopping_empty() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "coffee", "--qty", "2"]).success();
+    env.run(&["shopping"])
+        .success()
+        .stdout("Nothing is running low\n");
+}
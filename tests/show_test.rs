@@ -117,3 +117,139 @@ fn show_ambiguous_name_fails() {
         .failure()
         .stderr(predicate::str::contains("ambiguous"));
 }
+
+/// Test: --locations-history lists every container the item has lived in
+#[test]
+fn show_locations_history() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+    env.add("kitchen").success();
+    env.run(&["mv", "hammer", "kitchen"]).success();
+
+    env.run(&["show", "hammer", "--locations-history"])
+        .success()
+        .stdout(predicate::str::contains("Locations:"))
+        .stdout(predicate::str::contains("toolbox -> garage"))
+        .stdout(predicate::str::contains("kitchen"));
+
+    env.run(&["show", "hammer", "--locations-history", "--json"])
+        .success()
+        .stdout(predicate::str::contains(
+            r#""path":["garage","toolbox"],"moved_at""#,
+        ));
+
+    // Without the flag, JSON output is unchanged
+    env.run(&["show", "hammer", "--json"])
+        .success()
+        .stdout(predicate::str::contains("locations").not());
+}
+
+/// Test: #ID and --id pick one of several items with the same name
+#[test]
+fn show_by_id() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "office").success();
+    env.add_into("tape", "garage").success();
+    let id = String::from_utf8(
+        env.run(&["where", "garage/tape", "-q"])
+            .get_output()
+            .stdout
+            .clone(),
+    )
+    .unwrap();
+    let id = id.trim();
+
+    env.run(&["show", &format!("#{}", id)])
+        .success()
+        .stdout(predicate::str::is_match(r"Location:\s+garage").unwrap());
+    env.run(&["show", "--id", id])
+        .success()
+        .stdout(predicate::str::is_match(r"Location:\s+garage").unwrap());
+
+    env.run(&["show", "#999"])
+        .failure()
+        .stderr(predicate::str::contains("item '#999' not found"));
+    env.run(&["show", "tape", "--id", id]).failure();
+}
+
+/// Test: --by-desc finds the one item whose description contains the text
+#[test]
+fn show_by_description() {
+    let env = common::TestEnv::new();
+    env.add_full("drill", "blue Makita, 18V", "garage")
+        .success();
+    env.add_full("saw", "Makita circular saw", "shed").success();
+
+    env.run(&["show", "--by-desc", "BLUE makita"])
+        .success()
+        .stdout(predicate::str::is_match(r"Name:\s+drill").unwrap());
+
+    env.run(&["show", "--by-desc", "makita"])
+        .failure()
+        .stderr(predicate::str::contains(
+        "description 'makita' matches more than one item. Use full path: garage/drill, shed/saw",
+    ));
+    env.run(&["show", "--by-desc", "dewalt"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "no item's description contains 'dewalt'",
+        ));
+    env.run(&["show", "drill", "--by-desc", "blue"]).failure();
+}
+
+/// Test: uuid:<prefix> finds an item, and the UUID survives renames and moves
+#[test]
+fn show_by_uuid() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+    let output = env
+        .run(&["show", "hammer", "--json"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let item: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let uuid = item["uuid"].as_str().unwrap().to_string();
+
+    env.run(&["edit", "hammer", "--name", "mallet"]).success();
+    env.run(&["mv", &format!("uuid:{}", &uuid[..8]), "shed"])
+        .success();
+    env.run(&["show", &format!("uuid:{}", uuid), "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""name":"mallet""#))
+        .stdout(predicate::str::contains(format!(r#""uuid":"{}""#, uuid)));
+
+    env.run(&["show", "uuid:ffffffff-0"])
+        .failure()
+        .stderr(predicate::str::contains("item 'uuid:ffffffff-0' not found"));
+}
+
+/// Test: show --json on a container includes totals over its contents
+#[test]
+fn show_container_stats_json() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+    env.run(&["add", "screws", "--in", "garage", "--qty", "40"])
+        .success();
+
+    let output = env
+        .run(&["show", "garage", "--json"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let item: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let stats = &item["stats"];
+    assert_eq!(stats["direct_children"], 2);
+    assert_eq!(stats["descendants"], 3);
+    assert_eq!(stats["total_quantity"], 42);
+    assert_eq!(
+        stats["deepest_path"],
+        serde_json::json!(["garage", "toolbox", "hammer"])
+    );
+    assert!(stats["last_activity"].is_string());
+
+    env.run(&["show", "garage/toolbox/hammer", "--json"])
+        .success()
+        .stdout(predicate::str::contains("stats").not());
+}
@@ -0,0 +1,116 @@
+//! Integration tests for the `stats` command.
+//!
+//! See SPEC.md#invy-stats
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: summary counts items, containers and root items
+#[test]
+fn stats_summary_counts() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+    env.add("lamp").success();
+
+    env.run(&["stats", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""item_count":4"#))
+        .stdout(predicate::str::contains(r#""container_count":2"#))
+        .stdout(predicate::str::contains(r#""root_count":2"#));
+}
+
+/// Test: series buckets additions, moves and consumption per period
+#[test]
+fn stats_series_buckets_activity() {
+    let env = common::TestEnv::new();
+    env.add("shelf").success();
+    env.run(&["add", "batteries", "--qty", "2"]).success();
+    env.run(&["mv", "batteries", "shelf"]).success();
+    env.run(&["consume", "batteries", "--qty", "2"]).success();
+
+    // shelf and batteries added, batteries moved then archived, 2 consumed
+    env.run(&["stats", "--series", "--period", "year", "--csv"])
+        .success()
+        .stdout(predicate::str::contains(
+            "period,added,archived,net,moved,consumed",
+        ))
+        .stdout(predicate::str::is_match(r"\n\d{4},2,1,1,1,2\n").unwrap());
+}
+
+/// Test: churn reports moves in and out of each container
+#[test]
+fn stats_churn_by_container() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage").success();
+    env.add("kitchen").success();
+    env.run(&["mv", "hammer", "kitchen"]).success();
+
+    env.run(&["stats", "--churn", "--csv"])
+        .success()
+        .stdout(predicate::str::contains(
+            "period,container,moved_in,moved_out",
+        ))
+        .stdout(predicate::str::is_match(r",garage,0,1\n").unwrap())
+        .stdout(predicate::str::is_match(r",kitchen,1,0\n").unwrap());
+}
+
+/// Test: --since excludes older activity
+#[test]
+fn stats_series_since_future_is_empty() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.run(&["stats", "--series", "--since", "2999-01-01", "--json"])
+        .success()
+        .stdout("[]\n");
+}
+
+/// Test: --health counts described, stale and ambiguous items overall and
+/// per top-level container, worst container first
+#[test]
+fn stats_health_report() {
+    let env = common::TestEnv::new();
+    env.add_full("drill", "cordless", "garage/shelf").success();
+    env.add_into("tape", "garage").success();
+    env.add_into("tape", "office").success();
+    env.add("lamp").success();
+    env.backdate("lamp", "2020-01-01 00:00:00");
+
+    let output = env
+        .run(&["stats", "--health", "--json"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["items"], 7);
+    assert_eq!(report["described"], 1);
+    assert_eq!(report["stale"], 1);
+    assert_eq!(report["ambiguous"], 2);
+    // (1 + 6 + 5) of 21
+    assert_eq!(report["score"], 57);
+    let containers = report["containers"].as_array().unwrap();
+    assert_eq!(containers.len(), 2);
+    assert_eq!(containers[0]["path"][0], "office");
+    assert_eq!(containers[0]["score"], 33);
+    assert_eq!(containers[1]["path"][0], "garage");
+    assert_eq!(containers[1]["items"], 3);
+}
+
+/// Test: --health compares the score with the previous run
+#[test]
+fn stats_health_trend() {
+    let env = common::TestEnv::new();
+    env.add("lamp").success();
+
+    env.run(&["stats", "--health"])
+        .success()
+        .stdout(predicate::str::starts_with("Data quality: 67/100\n"));
+    env.run(&["edit", "lamp", "--desc", "desk lamp"]).success();
+    env.run(&["stats", "--health"])
+        .success()
+        .stdout(predicate::str::is_match(r"^Data quality: 100/100 \(up 33 since \d{4}-").unwrap());
+
+    env.run(&["stats", "--stale-after", "2y"]).failure();
+}
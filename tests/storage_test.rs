@@ -0,0 +1,112 @@
+//! Integration tests for the `storage` and `storage-check` commands.
+//!
+//! See SPEC.md#invy-storage
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: conditions and requirements are recorded and listed
+#[test]
+fn storage_set_and_list() {
+    let env = common::TestEnv::new();
+    env.add("attic").success();
+    env.add_into("chocolate", "pantry").success();
+
+    env.run(&[
+        "storage",
+        "set",
+        "attic",
+        "--temp",
+        "5..40",
+        "--note",
+        "hot in summer",
+    ])
+    .success()
+    .stdout(predicate::str::is_match(r"attic\s+has\s+5\.\.40°C\s+hot in summer").unwrap());
+
+    // Later changes keep the other values
+    env.run(&["storage", "set", "attic", "--humidity", "20..70%"])
+        .success();
+    env.run(&["storage", "require", "chocolate", "--temp", "..30C"])
+        .success();
+
+    env.run(&["storage", "list"])
+        .success()
+        .stdout(
+            predicate::str::is_match(r"attic\s+has\s+5\.\.40°C\s+20\.\.70%\s+hot in summer")
+                .unwrap(),
+        )
+        .stdout(predicate::str::is_match(r"pantry/chocolate\s+needs\s+\.\.30°C").unwrap());
+
+    env.run(&["storage", "set", "attic", "--temp", "40..5"])
+        .failure()
+        .stderr(predicate::str::contains("invalid range '40..5'"));
+}
+
+/// Test: items in a container outside their needs are reported
+#[test]
+fn storage_check_reports_unsuitable_items() {
+    let env = common::TestEnv::new();
+    env.add_into("candles", "attic/box").success();
+    env.add_into("chocolate", "attic/box").success();
+    env.add_into("wine", "cellar").success();
+    env.run(&[
+        "storage",
+        "set",
+        "attic",
+        "--temp",
+        "-5..40",
+        "--note",
+        "hot in summer",
+    ])
+    .success();
+    env.run(&["storage", "require", "candles", "--temp", "..35"])
+        .success();
+    env.run(&["storage", "require", "chocolate", "--temp", "..30"])
+        .success();
+    // No conditions recorded for the cellar: nothing to compare
+    env.run(&["storage", "require", "wine", "--temp", "10..15"])
+        .success();
+
+    env.run(&["storage-check"])
+        .failure()
+        .stdout(predicate::str::contains(
+            "attic/box/candles in attic (hot in summer)",
+        ))
+        .stdout(predicate::str::contains("reaches 40°C, needs at most 35°C"))
+        .stdout(predicate::str::contains("reaches 40°C, needs at most 30°C"))
+        .stdout(predicate::str::contains("wine").not())
+        .stderr(predicate::str::contains(
+            "2 item(s) stored in unsuitable conditions",
+        ));
+
+    env.run(&["mv", "chocolate", "cellar"]).success();
+    env.run(&["rm", "candles"]).success();
+    env.run(&["storage-check"])
+        .success()
+        .stdout(predicate::str::contains("All items are stored in suitable"));
+}
+
+/// Test: clearing forgets both conditions and requirements
+#[test]
+fn storage_clear() {
+    let env = common::TestEnv::new();
+    env.add("attic").success();
+    env.run(&["storage", "set", "attic", "--temp", "5..40"])
+        .success();
+
+    env.run(&["storage", "clear", "attic"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Cleared storage conditions: attic",
+        ));
+    env.run(&["storage", "clear", "attic"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "'attic' has no storage conditions",
+        ));
+    env.run(&["storage", "list"])
+        .success()
+        .stdout(predicate::str::contains("No storage conditions recorded"));
+}
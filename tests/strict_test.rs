@@ -0,0 +1,78 @@
+//! Integration tests for `--strict`.
+//!
+//! See SPEC.md#strict-mode
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: strict mode does not create missing containers
+#[test]
+fn strict_does_not_create_containers() {
+    let env = common::TestEnv::new();
+    env.run(&["--strict", "add", "hammer", "--in", "garage"])
+        .code(3)
+        .stderr(predicate::str::contains(
+            "container 'garage' not found; create it first",
+        ));
+    env.run(&["list"])
+        .success()
+        .stdout(predicate::str::contains("garage").not());
+
+    env.run(&["add", "garage", "--kind", "container"]).success();
+    env.run(&["--strict", "add", "hammer", "--in", "garage"])
+        .success();
+    env.run(&["--strict", "mv", "hammer", "shed"]).code(3);
+    env.run(&["where", "hammer"])
+        .success()
+        .stdout("garage/hammer\n");
+}
+
+/// Test: ambiguous names are errors even inside the current context
+#[test]
+fn strict_rejects_ambiguous_names() {
+    let env = common::TestEnv::new();
+    env.add_into("box", "attic").success();
+    env.add_into("box", "garage").success();
+    env.run(&["cd", "attic"]).success();
+
+    env.run(&["show", "box"]).success();
+    env.run(&["--strict", "show", "box"])
+        .code(3)
+        .stderr(predicate::str::contains("'box' is ambiguous"));
+}
+
+/// Test: what would be a warning fails the command and changes nothing
+#[test]
+fn strict_turns_warnings_into_errors() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.run(&["--strict", "add", "nail", "--in", "hammer"])
+        .code(3)
+        .stderr(predicate::str::contains("is a plain item, not a container"));
+    env.run(&["--strict", "add", "A/V cables"])
+        .code(3)
+        .stderr(predicate::str::contains("contains '/'"));
+    env.run(&["-q", "list", "-r"]).success().stdout("1\n");
+}
+
+/// Test: no "did you mean" suggestions, and the config file can turn it on
+#[test]
+fn strict_skips_suggestions() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+    env.run(&["show", "hamm"])
+        .failure()
+        .stderr(predicate::str::contains("Did you mean"));
+
+    env.write_config("strict = true\n");
+    env.run(&["show", "hamm"])
+        .code(1)
+        .stderr(predicate::str::contains("Did you mean").not());
+    env.cmd()
+        .env("INVY_STRICT", "1")
+        .args(["remove", "hammer"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("tip:").not());
+}
@@ -0,0 +1,29 @@
+//! Integration tests for suggestions on mistyped commands.
+//!
+//! See SPEC.md#error-messages
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: a synonym suggests the invy command and its SPEC section
+#[test]
+fn suggests_command_for_synonym() {
+    let env = common::TestEnv::new();
+    env.run(&["remove", "claw hammer"])
+        .code(2)
+        .stderr(predicate::str::contains("tip: did you mean `invy "))
+        .stderr(predicate::str::contains("rm 'claw hammer'`?"))
+        .stderr(predicate::str::contains("tip: did you mean `invy "))
+        .stderr(predicate::str::contains("docs: SPEC.md#invy-rm-item"));
+}
+
+/// Test: a misspelled flag suggests the corrected invocation
+#[test]
+fn suggests_flag_for_typo() {
+    let env = common::TestEnv::new();
+    env.run(&["add", "drill", "--quantiy", "2"])
+        .code(2)
+        .stderr(predicate::str::contains("tip: did you mean `invy "))
+        .stderr(predicate::str::contains("add drill --qty 2`?"));
+}
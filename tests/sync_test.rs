@@ -0,0 +1,203 @@
+//! Integration tests for `invy sync`.
+//!
+//! See SPEC.md#invy-sync
+
+mod common;
+
+use predicates::prelude::*;
+use serde_json::Value;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Run `invy sync` on `env` against `folder` and return its JSON report.
+fn sync(env: &common::TestEnv, folder: &Path) -> Value {
+    let output = env
+        .cmd()
+        .args(["sync", "--json"])
+        .arg(folder)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    serde_json::from_slice(&output).unwrap()
+}
+
+/// Test: items added on one machine appear on the other, with their paths
+#[test]
+fn sync_copies_new_items() {
+    let folder = TempDir::new().unwrap();
+    let laptop = common::TestEnv::new();
+    let desktop = common::TestEnv::new();
+    laptop.add_into("drill", "garage/shelf").success();
+    laptop.run(&["add", "screws", "--qty", "40"]).success();
+
+    let report = sync(&laptop, folder.path());
+    assert_eq!(report["sent"], 4);
+    let report = sync(&desktop, folder.path());
+    assert_eq!(report["received"], 4);
+    assert_eq!(report["applied"], 4);
+    assert_eq!(report["sent"], 0);
+
+    desktop
+        .run(&["where", "drill"])
+        .success()
+        .stdout(predicate::str::contains("garage/shelf/drill"));
+    desktop
+        .run(&["show", "screws"])
+        .success()
+        .stdout(predicate::str::contains("40"));
+
+    // Nothing new the second time around
+    let report = sync(&laptop, folder.path());
+    assert_eq!(report["received"], 0);
+    assert_eq!(report["sent"], 0);
+}
+
+/// Test: edits, moves and deletes travel back and forth
+#[test]
+fn sync_propagates_changes() {
+    let folder = TempDir::new().unwrap();
+    let laptop = common::TestEnv::new();
+    let desktop = common::TestEnv::new();
+    laptop.add_into("drill", "garage").success();
+    laptop.add("shed").success();
+    laptop.add("old lamp").success();
+    // Older than anything the desktop will do
+    for name in ["drill", "garage", "shed", "old lamp"] {
+        laptop.backdate(name, "2020-01-01 00:00:00");
+    }
+    sync(&laptop, folder.path());
+    sync(&desktop, folder.path());
+
+    desktop
+        .run(&["edit", "drill", "--desc", "cordless"])
+        .success();
+    desktop.run(&["mv", "drill", "shed"]).success();
+    desktop.run(&["rm", "old lamp"]).success();
+    let report = sync(&desktop, folder.path());
+    assert_eq!(report["sent"], 2);
+
+    let report = sync(&laptop, folder.path());
+    assert_eq!(report["applied"], 2);
+    laptop
+        .run(&["show", "shed/drill"])
+        .success()
+        .stdout(predicate::str::contains("cordless"));
+    laptop.run(&["show", "old lamp"]).failure();
+}
+
+/// Test: when both machines changed an item, the later change wins
+#[test]
+fn sync_keeps_latest_change() {
+    let folder = TempDir::new().unwrap();
+    let laptop = common::TestEnv::new();
+    let desktop = common::TestEnv::new();
+    laptop.add("drill").success();
+    laptop.backdate("drill", "2020-01-01 00:00:00");
+    sync(&laptop, folder.path());
+    sync(&desktop, folder.path());
+
+    laptop.run(&["edit", "drill", "--desc", "older"]).success();
+    laptop.backdate("drill", "2021-01-01 00:00:00");
+    desktop.run(&["edit", "drill", "--desc", "newer"]).success();
+
+    sync(&laptop, folder.path());
+    let report = sync(&desktop, folder.path());
+    assert_eq!(report["received"], 1);
+    assert_eq!(report["applied"], 0);
+    sync(&laptop, folder.path());
+
+    for env in [&laptop, &desktop] {
+        env.run(&["show", "drill"])
+            .success()
+            .stdout(predicate::str::contains("newer"));
+    }
+}
+
+/// Test: a delete newer than a received edit wins, so the edit does not
+/// bring the item back
+#[test]
+fn sync_keeps_later_delete() {
+    let folder = TempDir::new().unwrap();
+    let laptop = common::TestEnv::new();
+    let desktop = common::TestEnv::new();
+    laptop.add("drill").success();
+    laptop.backdate("drill", "2020-01-01 00:00:00");
+    sync(&laptop, folder.path());
+    sync(&desktop, folder.path());
+
+    desktop.run(&["edit", "drill", "--desc", "older"]).success();
+    desktop.backdate("drill", "2021-01-01 00:00:00");
+    laptop.run(&["rm", "drill"]).success();
+
+    sync(&desktop, folder.path());
+    let report = sync(&laptop, folder.path());
+    assert_eq!(report["received"], 1);
+    assert_eq!(report["applied"], 0);
+    let report = sync(&desktop, folder.path());
+    assert_eq!(report["applied"], 1);
+    // Both machines agree; nothing is left to send
+    let report = sync(&laptop, folder.path());
+    assert_eq!(report["sent"], 0);
+
+    for env in [&laptop, &desktop] {
+        env.run(&["show", "drill"]).failure();
+    }
+}
+
+/// Test: two different items with one name in one place are both kept
+#[test]
+fn sync_renames_on_name_conflict() {
+    let folder = TempDir::new().unwrap();
+    let laptop = common::TestEnv::new();
+    let desktop = common::TestEnv::new();
+    laptop.add("drill").success();
+    desktop.add("drill").success();
+
+    sync(&laptop, folder.path());
+    let report = sync(&desktop, folder.path());
+    let conflicts = report["conflicts"].as_array().unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert!(conflicts[0].as_str().unwrap().contains("drill (2)"));
+    sync(&laptop, folder.path());
+
+    // Both machines renamed the same one
+    let uuids = |env: &common::TestEnv| -> Vec<Value> {
+        ["drill", "drill (2)"]
+            .iter()
+            .map(|name| {
+                let output = env.run(&["show", name, "--json"]).success();
+                let item: Value = serde_json::from_slice(&output.get_output().stdout).unwrap();
+                item["uuid"].clone()
+            })
+            .collect()
+    };
+    assert_eq!(uuids(&laptop), uuids(&desktop));
+    let report = sync(&desktop, folder.path());
+    assert_eq!(report["sent"], 0);
+}
+
+/// Test: the machine name comes from the host name, and a missing folder is
+/// an error
+#[test]
+fn sync_names_machine() {
+    let folder = TempDir::new().unwrap();
+    let env = common::TestEnv::new();
+    env.add("drill").success();
+
+    env.cmd()
+        .env("HOSTNAME", "Work Laptop")
+        .arg("sync")
+        .arg(folder.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("Synced as work-laptop-"))
+        .stdout(predicate::str::contains("sent 1, received 0 (0 applied)"));
+    let files: Vec<_> = std::fs::read_dir(folder.path()).unwrap().collect();
+    assert_eq!(files.len(), 1);
+
+    env.run(&["sync", "/no/such/folder"])
+        .failure()
+        .stderr(predicate::str::contains("not found"));
+}
@@ -0,0 +1,71 @@
+//! Integration tests for output templates (`--template` and `[templates]`).
+//!
+//! See SPEC.md#output-templates
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: find renders each match through the template
+#[test]
+fn find_with_template() {
+    let env = common::TestEnv::new();
+    env.add_full("hammer", "claw", "garage/toolbox").success();
+    env.add_into("hamster cage", "attic").success();
+
+    env.run(&["find", "ham", "--template", "{{ name }} :: {{ path }}"])
+        .success()
+        .stdout("hammer :: garage/toolbox/hammer\nhamster cage :: attic/hamster cage\n");
+}
+
+/// Test: list renders flat and recursive listings, with depth in trees
+#[test]
+fn list_with_template() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+
+    env.run(&[
+        "list",
+        "garage",
+        "--template",
+        "{{ id }} {{ path }} ({{ child_count }})",
+    ])
+    .success()
+    .stdout("2 garage/toolbox (1)\n");
+    env.run(&[
+        "list",
+        "-r",
+        "--template",
+        "{{ '  ' * depth }}{{ name }} in {{ container or '/' }}",
+    ])
+    .success()
+    .stdout("garage in /\n  toolbox in garage\n    hammer in garage/toolbox\n");
+}
+
+/// Test: named templates come from the config; show uses them too
+#[test]
+fn show_with_named_template() {
+    let env = common::TestEnv::new();
+    env.write_config("[templates]\nlabel = \"{{ name | upper }}: {{ description or '-' }}\"\n");
+    env.add_with_desc("hammer", "claw hammer").success();
+
+    env.run(&["show", "hammer", "--template", "label"])
+        .success()
+        .stdout("HAMMER: claw hammer\n");
+}
+
+/// Test: templates are for human output and must parse
+#[test]
+fn template_errors() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.run(&["find", "ham", "--json", "--template", "{{ name }}"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "--template applies only to human output",
+        ));
+    env.run(&["show", "hammer", "--template", "{{ name"])
+        .failure()
+        .stderr(predicate::str::contains("invalid template"));
+}
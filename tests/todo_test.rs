@@ -0,0 +1,111 @@
+//! Integration tests for the `todo` command.
+//!
+//! See SPEC.md#invy-todo
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: a task is added to an item and listed with its path
+#[test]
+fn todo_add_and_list() {
+    let env = common::TestEnv::new();
+    env.add_into("bike", "garage").success();
+
+    env.run(&["todo", "add", "bike", "replace brake pads"])
+        .success()
+        .stdout("Added: #1 replace brake pads (garage/bike)\n");
+    env.run(&["todo", "list"])
+        .success()
+        .stdout(predicate::str::is_match(r"1\s+garage/bike\s+replace brake pads").unwrap());
+    env.run(&["todo", "list", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""text":"replace brake pads""#))
+        .stdout(predicate::str::contains(r#""done_at":null"#));
+}
+
+/// Test: done tasks drop out of the list unless --all is given
+#[test]
+fn todo_done() {
+    let env = common::TestEnv::new();
+    env.add("bike").success();
+    env.run(&["todo", "add", "bike", "oil chain"]).success();
+
+    env.run(&["todo", "done", "1"])
+        .success()
+        .stdout("Done: #1 oil chain (bike)\n");
+    env.run(&["todo", "list"])
+        .success()
+        .stdout("No open todos\n");
+    env.run(&["todo", "list", "--all"])
+        .success()
+        .stdout(predicate::str::contains("DONE"))
+        .stdout(predicate::str::contains("oil chain"));
+
+    env.run(&["todo", "done", "1"])
+        .failure()
+        .stderr(predicate::str::contains("todo #1 is already done"));
+    env.run(&["todo", "done", "9"])
+        .failure()
+        .stderr(predicate::str::contains("todo #9 not found"));
+}
+
+/// Test: --in keeps tasks about the container and anything inside it
+#[test]
+fn todo_list_in_container() {
+    let env = common::TestEnv::new();
+    env.add_into("bike", "garage").success();
+    env.add_into("smoke alarm", "hallway").success();
+    env.run(&["todo", "add", "garage", "sweep"]).success();
+    env.run(&["todo", "add", "bike", "pump tires"]).success();
+    env.run(&["todo", "add", "smoke alarm", "test battery"])
+        .success();
+
+    env.run(&["todo", "list", "--in", "garage", "-q"])
+        .success()
+        .stdout("1\n2\n");
+}
+
+/// Test: show and list display open task counts
+#[test]
+fn todo_counts_in_show_and_list() {
+    let env = common::TestEnv::new();
+    env.add("bike").success();
+    env.add("car").success();
+    env.run(&["todo", "add", "bike", "pump tires"]).success();
+    env.run(&["todo", "add", "bike", "oil chain"]).success();
+
+    env.run(&["show", "bike"])
+        .success()
+        .stdout(predicate::str::contains("Todos:       2 open"));
+    env.run(&["show", "car"])
+        .success()
+        .stdout(predicate::str::contains("Todos:").not());
+    env.run(&["list"])
+        .success()
+        .stdout(predicate::str::contains("TODOS"))
+        .stdout(predicate::str::is_match(r"bike\s+-\s+-\s+2").unwrap());
+    env.run(&["list", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""open_todos":2"#));
+
+    env.run(&["todo", "done", "1"]).success();
+    env.run(&["todo", "done", "2"]).success();
+    env.run(&["list"])
+        .success()
+        .stdout(predicate::str::contains("TODOS").not());
+}
+
+/// Test: tasks need text and an existing item
+#[test]
+fn todo_add_errors() {
+    let env = common::TestEnv::new();
+    env.add("bike").success();
+
+    env.run(&["todo", "add", "bike", "  "])
+        .failure()
+        .stderr(predicate::str::contains("todo text cannot be empty"));
+    env.run(&["todo", "add", "boat", "paint"])
+        .failure()
+        .stderr(predicate::str::contains("item 'boat' not found"));
+}
@@ -0,0 +1,59 @@
+//! Integration tests for the default container and `triage` command.
+//!
+//! See SPEC.md#invy-triage
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: adds without --in land in the configured default container
+#[test]
+fn add_uses_default_container() {
+    let env = common::TestEnv::new();
+    env.write_config("default_container = \"inbox\"\n");
+
+    env.add("stapler").success();
+    env.add_into("hammer", "garage").success();
+    env.run(&["add", "lamp", "--in", "/"]).success();
+
+    env.run(&["show", "inbox/stapler"]).success();
+    env.run(&["show", "garage/hammer"]).success();
+    env.run(&["list"])
+        .success()
+        .stdout(predicate::str::contains("lamp"))
+        .stdout(predicate::str::contains("stapler").not());
+}
+
+/// Test: triage lists the inbox and file moves items out of it
+#[test]
+fn triage_and_file() {
+    let env = common::TestEnv::new();
+    env.write_config("default_container = \"inbox\"\n");
+
+    env.run(&["triage"])
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    env.add("stapler").success();
+    env.add("tape").success();
+    env.run(&["triage"])
+        .success()
+        .stdout(predicate::str::contains("stapler"))
+        .stdout(predicate::str::contains("tape"));
+
+    env.run(&["file", "stapler", "office/desk"]).success();
+    env.run(&["triage"])
+        .success()
+        .stdout(predicate::str::contains("stapler").not())
+        .stdout(predicate::str::contains("tape"));
+}
+
+/// Test: triage needs a default container
+#[test]
+fn triage_without_config_fails() {
+    let env = common::TestEnv::new();
+
+    env.run(&["triage"])
+        .failure()
+        .stderr(predicate::str::contains("default_container"));
+}
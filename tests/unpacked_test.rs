@@ -0,0 +1,122 @@
+//! Integration tests for the `unpacked` and `unpacking-status` commands.
+//!
+//! See SPEC.md#invy-unpacked
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Three boxes in a new flat: two in the kitchen, one inside the other, and
+/// one in the study.
+fn moved(env: &common::TestEnv) {
+    env.run(&["add", "kitchen", "--kind", "location"]).success();
+    env.run(&["add", "study", "--kind", "location"]).success();
+    env.run(&["add", "box-1", "--in", "kitchen", "--kind", "container"])
+        .success();
+    env.run(&[
+        "add",
+        "box-2",
+        "--in",
+        "kitchen/box-1",
+        "--kind",
+        "container",
+    ])
+    .success();
+    env.run(&["add", "box-3", "--in", "study", "--kind", "container"])
+        .success();
+    env.add_into("kettle", "kitchen/box-1").success();
+    env.add_into("mugs", "kitchen/box-1/box-2").success();
+    env.add_into("books", "study/box-3").success();
+    env.add_into("lamp", "study").success();
+}
+
+/// Test: status counts packed boxes and the items still inside them
+#[test]
+fn status_reports_progress() {
+    let env = common::TestEnv::new();
+    moved(&env);
+
+    env.run(&["unpacking-status"]).success().stdout(
+        predicate::str::is_match(
+            r"^Boxes unpacked: 0 of 3 \(0%\)\nItems unpacked: 1 of 4 \(25%\)\n\nITEMS\s+STILL PACKED\n\s+2 kitchen/box-1\n\s+1 kitchen/box-1/box-2\n\s+1 study/box-3\n$",
+        )
+        .unwrap(),
+    );
+}
+
+/// Test: unpacking a box frees its items unless an inner box is still packed
+#[test]
+fn unpacked_marks_boxes() {
+    let env = common::TestEnv::new();
+    moved(&env);
+
+    env.run(&["unpacked", "box-1", "box-3"])
+        .success()
+        .stdout("Unpacked: kitchen/box-1\nUnpacked: study/box-3\n2 of 3 boxes unpacked\n");
+    env.run(&["unpacking-status"])
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Boxes unpacked: 2 of 3 (66%)\nItems unpacked: 3 of 4 (75%)\n",
+        ))
+        .stdout(predicate::str::contains("kitchen/box-1/box-2"));
+    env.run(&["unpacking-status", "-q"]).success().stdout("4\n");
+
+    env.run(&["unpacked", "box-2"]).success();
+    env.run(&["unpacking-status"]).success().stdout(
+        "Boxes unpacked: 3 of 3 (100%)\nItems unpacked: 4 of 4 (100%)\nEverything is unpacked\n",
+    );
+
+    env.run(&["unpacked", "--undo", "box-3"])
+        .success()
+        .stdout("Packed: study/box-3\n2 of 3 boxes unpacked\n");
+}
+
+/// Test: --in only counts what is inside the given location
+#[test]
+fn status_within_location() {
+    let env = common::TestEnv::new();
+    moved(&env);
+
+    let output = env
+        .run(&["--json", "unpacking-status", "--in", "study"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let status: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(status["boxes"], 1);
+    assert_eq!(status["items"], 2);
+    assert_eq!(status["unpacked_items"], 1);
+    assert_eq!(
+        status["packed"][0]["path"],
+        serde_json::json!(["study", "box-3"])
+    );
+    assert_eq!(status["packed"][0]["items"], 1);
+}
+
+/// Test: only containers can be marked unpacked
+#[test]
+fn unpacked_rejects_non_boxes() {
+    let env = common::TestEnv::new();
+    moved(&env);
+
+    env.run(&["unpacked", "kitchen"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "'kitchen' is a location, not a box",
+        ));
+    env.run(&["unpacked", "nowhere"])
+        .failure()
+        .stderr(predicate::str::contains("box 'nowhere' not found"));
+}
+
+/// Test: with no boxes there is nothing to report
+#[test]
+fn status_without_boxes() {
+    let env = common::TestEnv::new();
+    env.add("lamp").success();
+
+    env.run(&["unpacking-status"])
+        .success()
+        .stdout("No boxes to unpack\n");
+}
@@ -0,0 +1,39 @@
+//! Integration tests for the `views` commands.
+//!
+//! See SPEC.md#invy-views
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: install creates queryable views and status reports them
+#[test]
+fn views_install_and_status() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+
+    env.run(&["views", "status"])
+        .success()
+        .stdout(predicate::str::contains("items_with_paths  NOT INSTALLED"));
+
+    env.run(&["views", "install"])
+        .success()
+        .stdout(predicate::str::contains("items_with_paths  OK"))
+        .stdout(predicate::str::contains("container_totals  OK"))
+        .stdout(predicate::str::contains("expiring_soon  OK"));
+
+    let conn = rusqlite::Connection::open(&env.db_path).unwrap();
+    let path: String = conn
+        .query_row(
+            "SELECT path FROM items_with_paths WHERE name = 'hammer'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(path, "garage/toolbox/hammer");
+
+    // Installing again is harmless
+    env.run(&["views", "install", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#""status":"ok""#));
+}
@@ -0,0 +1,78 @@
+//! Integration tests for the `where` command.
+//!
+//! See SPEC.md#invy-where-item
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: prints only the path of a unique item
+#[test]
+fn where_prints_path() {
+    let env = common::TestEnv::new();
+    env.add_full("hammer", "claw", "garage/toolbox").success();
+
+    env.run(&["where", "hammer"])
+        .success()
+        .stdout("garage/toolbox/hammer\n");
+    env.run(&["where", "garage/toolbox"])
+        .success()
+        .stdout("garage/toolbox\n");
+}
+
+/// Test: a shared name prints every match, one per line
+#[test]
+fn where_prints_every_match() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "office").success();
+    env.add_into("tape", "garage").success();
+
+    env.run(&["where", "tape"])
+        .success()
+        .stdout("garage/tape\noffice/tape\n");
+    env.run(&["where", "tape", "--json"])
+        .success()
+        .stdout(predicate::str::contains(r#"["garage/tape","office/tape"]"#));
+}
+
+/// Test: nothing matched exits 1 with nothing on stdout
+#[test]
+fn where_not_found() {
+    let env = common::TestEnv::new();
+    env.add("hammer").success();
+
+    env.run(&["where", "drill"])
+        .failure()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("item 'drill' not found"));
+}
+
+/// Test: the match looked up most often comes first, then the one looked up
+/// most recently
+#[test]
+fn where_ranks_by_lookups() {
+    let env = common::TestEnv::new();
+    env.add_into("tape", "office").success();
+    env.add_into("tape", "garage").success();
+
+    env.run(&["where", "office/tape"]).success();
+    env.run(&["show", "office/tape"]).success();
+    env.run(&["where", "garage/tape"]).success();
+    env.run(&["where", "tape"])
+        .success()
+        .stdout("office/tape\ngarage/tape\n");
+
+    // Equally often now, and garage's lookup is the only recent one
+    env.run(&["where", "garage/tape"]).success();
+    let conn = rusqlite::Connection::open(&env.db_path).unwrap();
+    conn.execute(
+        "UPDATE item_lookups SET looked_up_at = '2020-01-01 00:00:00'
+         WHERE id < (SELECT MAX(id) FROM item_lookups)",
+        [],
+    )
+    .unwrap();
+    env.run(&["where", "tape"])
+        .success()
+        .stdout("garage/tape\noffice/tape\n");
+}
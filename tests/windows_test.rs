@@ -0,0 +1,65 @@
+//! Windows-specific integration tests.
+//!
+//! Only compiled on Windows; covers drive-letter and backslash paths that
+//! the cross-platform suites never exercise.
+
+#![cfg(windows)]
+
+mod common;
+
+use predicates::prelude::*;
+
+/// Test: --db accepts a backslash path containing spaces
+#[test]
+fn db_path_with_backslashes_and_spaces() {
+    let env = common::TestEnv::new();
+    let dir = env.temp_dir.path().join("My Inventory");
+    std::fs::create_dir_all(&dir).unwrap();
+    let db = dir.join("home.db");
+
+    env.cmd()
+        .arg("--db")
+        .arg(&db)
+        .args(["add", "hammer"])
+        .assert()
+        .success();
+
+    assert!(db.exists());
+}
+
+/// Test: item paths keep forward slashes on Windows
+#[test]
+fn item_paths_use_forward_slashes() {
+    let env = common::TestEnv::new();
+    env.add_into("hammer", "garage/toolbox").success();
+
+    env.run(&["find", "hammer"])
+        .success()
+        .stdout(predicate::str::contains("garage/toolbox/hammer"));
+}
+
+/// Test: backslashes in item names are not path separators
+#[test]
+fn backslash_in_name_is_literal() {
+    let env = common::TestEnv::new();
+    env.add("C:\\scans").success();
+
+    env.run(&["show", "C:\\scans"]).success();
+}
+
+/// Test: config `db` accepts a drive-letter path
+#[test]
+fn config_db_accepts_windows_path() {
+    let env = common::TestEnv::new();
+    let db = env.temp_dir.path().join("cfg.db");
+    env.write_config(&format!("db = '{}'\n", db.display()));
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("invy");
+    cmd.env("XDG_CONFIG_HOME", env.temp_dir.path())
+        .env_remove("INVY_DB")
+        .args(["add", "hammer"])
+        .assert()
+        .success();
+
+    assert!(db.exists());
+}